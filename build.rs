@@ -0,0 +1,132 @@
+//! Build script.
+//!
+//! Generates the active board's linker script from the same layout constants the kernel binary
+//! uses at runtime (`bsp::<board>::layout`), instead of hand-maintaining a separate `link.ld` per
+//! board that the Rust-side constants could silently drift from. `cpu::assert_linker_layout` is
+//! the boot-time half of this: a cross-check in case a stale generated script from a previous
+//! build ever ends up linked against a newer binary.
+//!
+//! Both boards' layout files are pulled in unconditionally below -- plain data, so there's no
+//! harm compiling both into the build script -- and the active one is picked at build-script
+//! runtime, the same way the board itself is picked via `CARGO_FEATURE_BSP_*`.
+
+use std::{env, fs, path::Path};
+
+mod raspberrypi_layout {
+    include!("src/bsp/raspberrypi/layout.rs");
+}
+
+mod qemu_virt_layout {
+    include!("src/bsp/qemu_virt/layout.rs");
+}
+
+struct Layout {
+    load_addr: usize,
+    boot_core_stack_size: usize,
+    persistent_klog_size: usize,
+    bootselect_size: usize,
+}
+
+fn linker_script(layout: &Layout) -> String {
+    format!(
+        r#"/* Generated by build.rs from bsp::layout -- do not hand-edit, see build.rs. */
+
+ENTRY(_start)
+
+SECTIONS
+{{
+    . = {load_addr:#x};
+
+    .text :
+    {{
+        KEEP(*(.text._start))
+        *(.text*)
+    }}
+
+    .rodata : {{ *(.rodata*) }}
+    .data   : {{ *(.data*) }}
+
+    .bss :
+    {{
+        __bss_start = .;
+        *(.bss*);
+        . = ALIGN(8);
+        __bss_end_inclusive = . - 8;
+    }}
+
+    .stack (NOLOAD) :
+    {{
+        . = ALIGN(16);
+        __boot_core_stack_start = .;
+        . += {stack_size:#x};
+        __boot_core_stack_end_exclusive = .;
+    }}
+
+    .persistent_klog (NOLOAD) :
+    {{
+        . = ALIGN(16);
+        __persistent_klog_start = .;
+        . += {klog_size:#x};
+        __persistent_klog_end_exclusive = .;
+    }}
+
+    .bootselect (NOLOAD) :
+    {{
+        . = ALIGN(16);
+        __bootselect_start = .;
+        . += {bootselect_size:#x};
+        __bootselect_end_exclusive = .;
+    }}
+
+    /DISCARD/ : {{ *(.comment*) }}
+}}
+"#,
+        load_addr = layout.load_addr,
+        stack_size = layout.boot_core_stack_size,
+        klog_size = layout.persistent_klog_size,
+        bootselect_size = layout.bootselect_size,
+    )
+}
+
+fn main() {
+    let bsp_dir = if env::var("CARGO_FEATURE_BSP_RPI3").is_ok()
+        || env::var("CARGO_FEATURE_BSP_RPI4").is_ok()
+    {
+        "raspberrypi"
+    } else if env::var("CARGO_FEATURE_BSP_QEMU_VIRT").is_ok() {
+        "qemu_virt"
+    } else {
+        return;
+    };
+
+    println!("cargo:rerun-if-changed=src/bsp/{}/layout.rs", bsp_dir);
+
+    let layout = match bsp_dir {
+        "raspberrypi" => Layout {
+            load_addr: raspberrypi_layout::LOAD_ADDR,
+            boot_core_stack_size: raspberrypi_layout::BOOT_CORE_STACK_SIZE,
+            persistent_klog_size: raspberrypi_layout::PERSISTENT_KLOG_SIZE,
+            bootselect_size: raspberrypi_layout::BOOTSELECT_SIZE,
+        },
+        "qemu_virt" => Layout {
+            load_addr: qemu_virt_layout::LOAD_ADDR,
+            boot_core_stack_size: qemu_virt_layout::BOOT_CORE_STACK_SIZE,
+            persistent_klog_size: qemu_virt_layout::PERSISTENT_KLOG_SIZE,
+            bootselect_size: qemu_virt_layout::BOOTSELECT_SIZE,
+        },
+        _ => unreachable!(),
+    };
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is always set for build scripts");
+    let script_path = Path::new(&out_dir).join("link.ld");
+    fs::write(&script_path, linker_script(&layout))
+        .expect("failed to write generated linker script");
+
+    // `-bins`, not the unqualified form: this package also has a `[lib]` target
+    // (`kernel_pure`, see `src/lib.rs`) that's compiled for the host and must not see an
+    // aarch64 linker script.
+    println!(
+        "cargo:rustc-link-arg-bins=--script={}",
+        script_path.display()
+    );
+}
@@ -0,0 +1,21 @@
+//! PWM audio output (3.5 mm jack).
+//!
+//! Only meaningful on real Raspberry Pi hardware -- QEMU's `virt` machine emulates no PWM/clock
+//! manager peripheral to back this, so this module only exists under `bsp_rpi3`/`bsp_rpi4`. See
+//! `bsp::device_driver::Audio` for the driver itself, including the no-DMA polling limitation.
+
+use crate::bsp;
+
+pub mod interface {
+    /// Operations an audio output driver must implement.
+    pub trait Play {
+        /// Play `samples` at `sample_rate` Hz, blocking the calling core until done.
+        fn play(&self, samples: &[i16], sample_rate: u32);
+    }
+}
+
+/// Play `samples` at `sample_rate` Hz through the board's audio output, blocking the calling core
+/// until done.
+pub fn play(samples: &[i16], sample_rate: u32) {
+    bsp::audio::audio().play(samples, sample_rate)
+}
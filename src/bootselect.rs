@@ -0,0 +1,116 @@
+//! A/B boot slot bookkeeping.
+//!
+//! Real dual-slot rollback needs a chainloader stage that reads a slot marker before this kernel
+//! is even loaded and fetches the corresponding image -- this fork has no chainloader at all, just
+//! a single image the board's boot ROM (or QEMU's `-kernel`) loads directly. So [`active_slot`]'s
+//! choice of slot is never actually consulted by anything that decides what code to load; there's
+//! nothing downstream for it to steer yet.
+//!
+//! What's real: the persistent, warm-reset-surviving attempt counter that a chainloader would
+//! need to decide "has slot A failed too many times in a row?", stored in `.bootselect` the same
+//! way `log::persistent` stores the klog tail. [`record_boot_attempt`] bumps it and flips the
+//! preferred slot after too many consecutive failures; [`mark_boot_ok`] clears it once this kernel
+//! has gotten far enough to call itself booted.
+
+use core::mem;
+
+extern "C" {
+    static __bootselect_start: u8;
+    static __bootselect_end_exclusive: u8;
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const MAGIC: u32 = 0x424f_4f54; // "BOOT"
+const MAX_CONSECUTIVE_FAILURES: u8 = 3;
+
+#[repr(C)]
+struct Header {
+    magic: u32,
+    active_slot: u8,
+    consecutive_failures: u8,
+}
+
+/// Which of the two slots is currently preferred.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Slot {
+    A = 0,
+    B = 1,
+}
+
+impl Slot {
+    const fn from_u8(v: u8) -> Self {
+        if v == 1 {
+            Self::B
+        } else {
+            Self::A
+        }
+    }
+
+    const fn other(self) -> Self {
+        match self {
+            Self::A => Self::B,
+            Self::B => Self::A,
+        }
+    }
+}
+
+/// # Safety
+///
+/// - Exclusive access holds by convention only: nothing else in this fork touches
+///   `.bootselect`.
+unsafe fn header() -> *mut Header {
+    debug_assert!(
+        (&__bootselect_end_exclusive as *const u8 as usize
+            - &__bootselect_start as *const u8 as usize)
+            >= mem::size_of::<Header>()
+    );
+
+    &__bootselect_start as *const u8 as *mut Header
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Record that a boot of the currently active slot is underway, rolling back to the other slot if
+/// the active one has now failed [`MAX_CONSECUTIVE_FAILURES`] times in a row without an
+/// intervening [`mark_boot_ok`]. Returns the (possibly just-flipped) active slot.
+///
+/// Meant to be called once, early in `kernel_init`.
+pub fn record_boot_attempt() -> Slot {
+    unsafe {
+        let header = header();
+
+        if (*header).magic != MAGIC {
+            (*header).magic = MAGIC;
+            (*header).active_slot = Slot::A as u8;
+            (*header).consecutive_failures = 0;
+        }
+
+        (*header).consecutive_failures += 1;
+        if (*header).consecutive_failures > MAX_CONSECUTIVE_FAILURES {
+            (*header).active_slot = Slot::from_u8((*header).active_slot).other() as u8;
+            (*header).consecutive_failures = 0;
+        }
+
+        Slot::from_u8((*header).active_slot)
+    }
+}
+
+/// Mark the current boot as having succeeded, resetting the consecutive-failure count for the
+/// active slot.
+pub fn mark_boot_ok() {
+    unsafe {
+        let header = header();
+        (*header).consecutive_failures = 0;
+    }
+}
+
+/// The slot [`record_boot_attempt`] most recently selected.
+pub fn active_slot() -> Slot {
+    unsafe { Slot::from_u8((*header()).active_slot) }
+}
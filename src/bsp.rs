@@ -0,0 +1,13 @@
+//! Conditional exporting of the Board Support Package.
+
+mod device_driver;
+
+#[cfg(feature = "bsp_qemu_virt")]
+mod qemu_virt;
+#[cfg(feature = "bsp_qemu_virt")]
+pub use qemu_virt::*;
+
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod raspberrypi;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+pub use raspberrypi::*;
@@ -0,0 +1,14 @@
+//! Device driver.
+
+pub(crate) mod common;
+
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod bcm;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+pub use bcm::*;
+
+#[cfg(any(feature = "bsp_rpi4", feature = "bsp_qemu_virt"))]
+pub mod arm;
+
+#[cfg(feature = "bsp_qemu_virt")]
+pub mod virtio;
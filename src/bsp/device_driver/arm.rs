@@ -0,0 +1,9 @@
+//! ARM (SoC-vendor-agnostic) device drivers.
+
+pub mod gicv2;
+
+#[cfg(feature = "gicv3")]
+pub mod gicv3;
+
+#[cfg(feature = "bsp_qemu_virt")]
+pub mod pl011;
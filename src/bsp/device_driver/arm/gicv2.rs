@@ -0,0 +1,220 @@
+//! GICv2 driver: MMIO-only distributor (GICD) + CPU interface (GICC), no system registers
+//! required. Used by the Raspberry Pi 4 (BCM2711) and, when built without the `gicv3` feature,
+//! by the QEMU `virt` machine.
+
+use crate::{
+    bsp::device_driver::common::MMIODerefWrapper,
+    config::MAX_IRQ_NUMBER as MAX_IRQS,
+    driver,
+    exception::asynchronous::{self, interface, IRQHandlerDescriptor},
+    synchronization::{Mutex, NullLock},
+};
+use tock_registers::{
+    interfaces::{Readable, Writeable},
+    register_structs,
+    registers::{ReadOnly, ReadWrite, WriteOnly},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// This GICv2's IRQ number space.
+pub type IRQNumber = asynchronous::BoundedUsize<{ MAX_IRQS - 1 }>;
+
+register_structs! {
+    #[allow(non_snake_case)]
+    GICDRegisterBlock {
+        (0x000 => CTLR: ReadWrite<u32>),
+        (0x004 => TYPER: ReadOnly<u32>),
+        (0x008 => _reserved1),
+        (0x080 => IGROUPR: [ReadWrite<u32>; 8]),
+        (0x0a0 => _reserved2),
+        (0x100 => ISENABLER: [ReadWrite<u32>; 8]),
+        (0x120 => _reserved3),
+        (0x180 => ICPENDR: [WriteOnly<u32>; 8]),
+        (0x1a0 => @END),
+    }
+}
+
+register_structs! {
+    #[allow(non_snake_case)]
+    GICCRegisterBlock {
+        (0x00 => CTLR: ReadWrite<u32>),
+        (0x04 => PMR: ReadWrite<u32>),
+        (0x08 => _reserved1),
+        (0x0c => IAR: ReadOnly<u32>),
+        (0x10 => EOIR: WriteOnly<u32>),
+        (0x14 => @END),
+    }
+}
+
+type GICDRegisters = MMIODerefWrapper<GICDRegisterBlock>;
+type GICCRegisters = MMIODerefWrapper<GICCRegisterBlock>;
+
+/// An interrupt's group, which on GICv2 decides whether it signals the core's IRQ or FIQ line.
+#[derive(Copy, Clone)]
+pub enum IrqGroup {
+    /// Signals FIQ, once `GICC_CTLR.FIQEn` is also set via [`GICv2::enable_fiq_bypass`].
+    Zero,
+    /// Signals IRQ. The reset default for every interrupt.
+    One,
+}
+
+struct GICv2Inner {
+    gicd: GICDRegisters,
+    gicc: GICCRegisters,
+    handler_table: [Option<IRQHandlerDescriptor<IRQNumber>>; MAX_IRQS],
+}
+
+impl GICv2Inner {
+    /// # Safety
+    ///
+    /// - The user must ensure to provide correct MMIO start addresses.
+    const unsafe fn new(gicd_start_addr: usize, gicc_start_addr: usize) -> Self {
+        Self {
+            gicd: GICDRegisters::new(gicd_start_addr),
+            gicc: GICCRegisters::new(gicc_start_addr),
+            handler_table: [None; MAX_IRQS],
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Representation of a GICv2.
+pub struct GICv2 {
+    inner: NullLock<GICv2Inner>,
+}
+
+impl GICv2 {
+    pub const COMPATIBLE: &'static str = "GICv2 (ARM Generic Interrupt Controller v2)";
+
+    /// # Safety
+    ///
+    /// - The user must ensure to provide correct MMIO start addresses.
+    pub const unsafe fn new(gicd_start_addr: usize, gicc_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(GICv2Inner::new(gicd_start_addr, gicc_start_addr)),
+        }
+    }
+}
+
+impl driver::interface::DeviceDriver for GICv2 {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+
+    unsafe fn init(&self) -> Result<(), &'static str> {
+        self.inner.lock(|inner| inner.gicd.CTLR.set(1));
+        Ok(())
+    }
+}
+
+impl interface::IRQManager for GICv2 {
+    type IRQNumberType = IRQNumber;
+
+    fn register_handler(
+        &self,
+        irq_number: Self::IRQNumberType,
+        descriptor: IRQHandlerDescriptor<Self::IRQNumberType>,
+    ) -> Result<(), &'static str> {
+        self.inner.lock(|inner| {
+            if inner.handler_table[irq_number.get()].is_some() {
+                return Err("A handler is already registered for this IRQ number");
+            }
+
+            inner.handler_table[irq_number.get()] = Some(descriptor);
+            Ok(())
+        })
+    }
+
+    fn enable(&self, irq_number: Self::IRQNumberType) {
+        self.inner.lock(|inner| {
+            let reg_index = irq_number.get() / 32;
+            let bit = 1u32 << (irq_number.get() % 32);
+
+            inner.gicd.ISENABLER[reg_index].set(bit);
+        });
+    }
+
+    fn handle_pending_irqs(&self) {
+        let entry_ticks = crate::cpu::read_cycle_counter();
+
+        self.inner.lock(|inner| {
+            let iar = inner.gicc.IAR.get();
+            let irq_number = (iar & 0x3ff) as usize;
+
+            if let Some(descriptor) = inner.handler_table.get(irq_number).and_then(|d| *d) {
+                use crate::exception::asynchronous::IrqMode;
+
+                match descriptor.mode() {
+                    IrqMode::Threaded => {
+                        crate::exception::asynchronous::defer_handler(
+                            descriptor.name(),
+                            descriptor.handler(),
+                        );
+                    }
+                    IrqMode::Direct => {
+                        crate::exception::asynchronous::record_irq(descriptor.name());
+                        let dispatch_ticks = crate::cpu::read_cycle_counter();
+                        let _nesting = crate::exception::asynchronous::NestingGuard::enter();
+                        if let Err(x) = descriptor.handler().handle() {
+                            crate::log::rate_limited!(
+                                core::time::Duration::from_secs(1),
+                                "irq",
+                                "{}: {}",
+                                descriptor.name(),
+                                x
+                            );
+                        }
+                        let service_ticks = crate::cpu::read_cycle_counter();
+                        crate::exception::asynchronous::record_irq_latency(
+                            descriptor.name(),
+                            dispatch_ticks - entry_ticks,
+                            service_ticks - dispatch_ticks,
+                        );
+                    }
+                }
+            }
+
+            inner.gicc.EOIR.set(iar);
+        });
+    }
+}
+
+impl GICv2 {
+    /// Assign `irq_number` to group 0 or group 1. Group 0 interrupts signal the CPU's FIQ line
+    /// instead of IRQ once [`Self::enable_fiq_bypass`] has also been called -- mirroring the
+    /// split between a single group-0 interrupt and everything else that real GICv2-based SoCs
+    /// use to give one latency-critical source priority over the rest.
+    ///
+    /// As with [`crate::bsp::device_driver::bcm::bcm2xxx_interrupt_controller::PeripheralIC::set_fiq`],
+    /// this fork has no FIQ vector to take the resulting trap -- `VBAR_EL1` is never programmed,
+    /// see [`crate::exception`] -- so group 0 currently behaves as "this IRQ is not delivered"
+    /// rather than "this IRQ is delivered faster".
+    pub fn set_group(&self, irq_number: IRQNumber, group: IrqGroup) {
+        self.inner.lock(|inner| {
+            let reg_index = irq_number.get() / 32;
+            let bit = 1u32 << (irq_number.get() % 32);
+
+            let current = inner.gicd.IGROUPR[reg_index].get();
+            let updated = match group {
+                IrqGroup::Zero => current & !bit,
+                IrqGroup::One => current | bit,
+            };
+            inner.gicd.IGROUPR[reg_index].set(updated);
+        });
+    }
+
+    /// Set `GICC_CTLR.FIQEn`, causing group 0 interrupts to signal the CPU interface's FIQ output
+    /// instead of IRQ.
+    pub fn enable_fiq_bypass(&self) {
+        self.inner.lock(|inner| {
+            let ctlr = inner.gicc.CTLR.get();
+            inner.gicc.CTLR.set(ctlr | (1 << 3));
+        });
+    }
+}
@@ -0,0 +1,216 @@
+//! GICv3 driver.
+//!
+//! Unlike GICv2, the CPU interface is accessed through `ICC_*` system registers rather than an
+//! MMIO window; only the distributor and each core's redistributor remain memory-mapped. This
+//! driver is selected instead of [`super::gicv2::GICv2`] on RPi4 firmware builds and on the QEMU
+//! `virt` board when the `gicv3` feature is set, while still implementing the same `IRQManager`
+//! interface so driver code above it doesn't need to care which GIC version is present.
+
+use crate::{
+    bsp::device_driver::common::MMIODerefWrapper,
+    config::MAX_IRQ_NUMBER as MAX_IRQS,
+    driver,
+    exception::asynchronous::{self, interface, IRQHandlerDescriptor},
+    synchronization::{Mutex, NullLock},
+};
+use tock_registers::{interfaces::Writeable, register_structs, registers::ReadWrite};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// This GICv3's IRQ number space.
+pub type IRQNumber = asynchronous::BoundedUsize<{ MAX_IRQS - 1 }>;
+
+register_structs! {
+    #[allow(non_snake_case)]
+    GICDRegisterBlock {
+        (0x000 => CTLR: ReadWrite<u32>),
+        (0x004 => _reserved1),
+        (0x100 => ISENABLER: [ReadWrite<u32>; 8]),
+        (0x120 => @END),
+    }
+}
+
+// A single core's redistributor frame. Real hardware has one of these per CPU, `stride` bytes
+// apart; this fork only ever brings up a single core, so only frame 0 is modelled.
+register_structs! {
+    #[allow(non_snake_case)]
+    GICRRegisterBlock {
+        (0x0000 => CTLR: ReadWrite<u32>),
+        (0x0004 => _reserved1),
+        (0x10000 => SGI_ISENABLER0: ReadWrite<u32>),
+        (0x10004 => @END),
+    }
+}
+
+type GICDRegisters = MMIODerefWrapper<GICDRegisterBlock>;
+type GICRRegisters = MMIODerefWrapper<GICRRegisterBlock>;
+
+/// Access to the `ICC_*` CPU interface system registers.
+mod icc {
+    use core::arch::asm;
+
+    /// Set the priority mask; interrupts at or above this (numerically greater) priority are
+    /// masked.
+    pub fn set_pmr(priority: u64) {
+        unsafe { asm!("msr S3_0_C4_C6_0, {}", in(reg) priority, options(nomem, nostack)) };
+    }
+
+    /// Enable group 1 interrupt signalling.
+    pub fn enable_group1() {
+        unsafe { asm!("msr S3_0_C12_C12_7, {}", in(reg) 1u64, options(nomem, nostack)) };
+    }
+
+    /// Acknowledge the highest-priority pending group 1 interrupt, returning its INTID.
+    pub fn read_iar1() -> u32 {
+        let iar: u64;
+        unsafe { asm!("mrs {}, S3_0_C12_C12_0", out(reg) iar, options(nomem, nostack)) };
+        (iar & 0xff_ffff) as u32
+    }
+
+    /// Signal end-of-interrupt for the given INTID.
+    pub fn write_eoir1(intid: u32) {
+        unsafe { asm!("msr S3_0_C12_C12_1, {}", in(reg) intid as u64, options(nomem, nostack)) };
+    }
+}
+
+struct GICv3Inner {
+    gicd: GICDRegisters,
+    gicr: GICRRegisters,
+    handler_table: [Option<IRQHandlerDescriptor<IRQNumber>>; MAX_IRQS],
+}
+
+impl GICv3Inner {
+    /// # Safety
+    ///
+    /// - The user must ensure to provide correct MMIO start addresses.
+    const unsafe fn new(gicd_start_addr: usize, gicr_start_addr: usize) -> Self {
+        Self {
+            gicd: GICDRegisters::new(gicd_start_addr),
+            gicr: GICRRegisters::new(gicr_start_addr),
+            handler_table: [None; MAX_IRQS],
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Representation of a GICv3.
+pub struct GICv3 {
+    inner: NullLock<GICv3Inner>,
+}
+
+impl GICv3 {
+    pub const COMPATIBLE: &'static str = "GICv3 (ARM Generic Interrupt Controller v3)";
+
+    /// # Safety
+    ///
+    /// - The user must ensure to provide correct MMIO start addresses for the distributor and
+    ///   this core's redistributor frame.
+    pub const unsafe fn new(gicd_start_addr: usize, gicr_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(GICv3Inner::new(gicd_start_addr, gicr_start_addr)),
+        }
+    }
+
+    /// Bring up this core's CPU interface. Must run once on every core that wants to take
+    /// interrupts, in addition to the one-time [`DeviceDriver::init`] of the distributor.
+    pub fn init_cpu_interface(&self) {
+        icc::set_pmr(0xff);
+        icc::enable_group1();
+    }
+}
+
+impl driver::interface::DeviceDriver for GICv3 {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+
+    unsafe fn init(&self) -> Result<(), &'static str> {
+        self.inner.lock(|inner| inner.gicd.CTLR.set(1));
+        self.init_cpu_interface();
+        Ok(())
+    }
+}
+
+impl interface::IRQManager for GICv3 {
+    type IRQNumberType = IRQNumber;
+
+    fn register_handler(
+        &self,
+        irq_number: Self::IRQNumberType,
+        descriptor: IRQHandlerDescriptor<Self::IRQNumberType>,
+    ) -> Result<(), &'static str> {
+        self.inner.lock(|inner| {
+            if inner.handler_table[irq_number.get()].is_some() {
+                return Err("A handler is already registered for this IRQ number");
+            }
+
+            inner.handler_table[irq_number.get()] = Some(descriptor);
+            Ok(())
+        })
+    }
+
+    fn enable(&self, irq_number: Self::IRQNumberType) {
+        self.inner.lock(|inner| {
+            // SGIs and PPIs (0..=31) are affinity-routed per core and live in the redistributor;
+            // SPIs (32..) are shared and live in the distributor.
+            let number = irq_number.get();
+
+            if number < 32 {
+                inner.gicr.SGI_ISENABLER0.set(1u32 << number);
+            } else {
+                let reg_index = number / 32;
+                let bit = 1u32 << (number % 32);
+                inner.gicd.ISENABLER[reg_index].set(bit);
+            }
+        });
+    }
+
+    fn handle_pending_irqs(&self) {
+        let entry_ticks = crate::cpu::read_cycle_counter();
+
+        self.inner.lock(|inner| {
+            let intid = icc::read_iar1();
+            let irq_number = intid as usize;
+
+            if let Some(descriptor) = inner.handler_table.get(irq_number).and_then(|d| *d) {
+                use crate::exception::asynchronous::IrqMode;
+
+                match descriptor.mode() {
+                    IrqMode::Threaded => {
+                        crate::exception::asynchronous::defer_handler(
+                            descriptor.name(),
+                            descriptor.handler(),
+                        );
+                    }
+                    IrqMode::Direct => {
+                        crate::exception::asynchronous::record_irq(descriptor.name());
+                        let dispatch_ticks = crate::cpu::read_cycle_counter();
+                        let _nesting = crate::exception::asynchronous::NestingGuard::enter();
+                        if let Err(x) = descriptor.handler().handle() {
+                            crate::log::rate_limited!(
+                                core::time::Duration::from_secs(1),
+                                "irq",
+                                "{}: {}",
+                                descriptor.name(),
+                                x
+                            );
+                        }
+                        let service_ticks = crate::cpu::read_cycle_counter();
+                        crate::exception::asynchronous::record_irq_latency(
+                            descriptor.name(),
+                            dispatch_ticks - entry_ticks,
+                            service_ticks - dispatch_ticks,
+                        );
+                    }
+                }
+            }
+
+            icc::write_eoir1(intid);
+        });
+    }
+}
@@ -0,0 +1,215 @@
+//! Generic ARM PrimeCell PL011 UART driver, used by BSPs that are not built around the
+//! Broadcom SoCs (e.g. the QEMU `virt` machine).
+//!
+//! This intentionally duplicates `bcm::bcm2xxx_pl011_uart` instead of sharing it: the Broadcom
+//! variant lives under `bcm` because that's where the rest of the tutorial's Pi-specific drivers
+//! live, and pulling it out into a shared module is left for a future cleanup once a second
+//! consumer beyond `qemu_virt` shows up.
+
+use crate::{
+    bsp::device_driver::common::MMIODerefWrapper,
+    console, cpu, devicetree, driver,
+    synchronization::{Mutex, NullLock},
+};
+use core::fmt;
+use tock_registers::{
+    interfaces::{Readable, Writeable},
+    register_bitfields, register_structs,
+    registers::{ReadOnly, ReadWrite},
+};
+
+register_bitfields! {
+    u32,
+
+    FR [
+        TXFF OFFSET(5) NUMBITS(1) [],
+        RXFE OFFSET(4) NUMBITS(1) [],
+        BUSY OFFSET(3) NUMBITS(1) [],
+    ],
+
+    CR [
+        RXE OFFSET(9) NUMBITS(1) [],
+        TXE OFFSET(8) NUMBITS(1) [],
+        UARTEN OFFSET(0) NUMBITS(1) [],
+    ],
+}
+
+register_structs! {
+    #[allow(non_snake_case)]
+    pub RegisterBlock {
+        (0x00 => DR: ReadWrite<u32>),
+        (0x04 => _reserved1),
+        (0x18 => FR: ReadOnly<u32, FR::Register>),
+        (0x1c => _reserved2),
+        (0x30 => CR: ReadWrite<u32, CR::Register>),
+        (0x34 => @END),
+    }
+}
+
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+struct PL011UartInner {
+    registers: Registers,
+    chars_written: usize,
+    chars_read: usize,
+}
+
+impl PL011UartInner {
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            registers: Registers::new(mmio_start_addr),
+            chars_written: 0,
+            chars_read: 0,
+        }
+    }
+
+    fn write_char(&mut self, c: char) {
+        while self.registers.FR.is_set(FR::TXFF) {
+            cpu::spin_for_cycles(1);
+        }
+
+        self.registers.DR.set(c as u32);
+        self.chars_written += 1;
+    }
+}
+
+impl fmt::Write for PL011UartInner {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if c == '\n' {
+                self.write_char('\r');
+            }
+            self.write_char(c);
+        }
+
+        Ok(())
+    }
+}
+
+/// Representation of the QEMU `virt` machine's PL011.
+pub struct PL011Uart {
+    inner: NullLock<PL011UartInner>,
+}
+
+impl PL011Uart {
+    pub const COMPATIBLE: &'static str = "ARM PL011 UART (virt)";
+
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(PL011UartInner::new(mmio_start_addr)),
+        }
+    }
+
+    /// Build an instance from a devicetree, using the `reg` property of the first node matching
+    /// [`Self::MATCH_COMPATIBLE`] as the MMIO start address.
+    ///
+    /// This is the one concrete demonstration of [`crate::devicetree`] driving a driver's own
+    /// construction in this fork -- no board wires it into `kernel_drivers!` yet, since every
+    /// board here still passes its own hand-written MMIO addresses at boot, but a future one
+    /// could call this instead.
+    ///
+    /// # Safety
+    ///
+    /// - The devicetree's `reg` property must describe a real PL011 register window.
+    pub unsafe fn probe(dt: &devicetree::DeviceTree<'_>) -> Result<Self, &'static str> {
+        let node = <Self as driver::interface::DeviceDriver>::match_compatible()
+            .iter()
+            .find_map(|compatible| dt.find_by_compatible(compatible))
+            .ok_or("devicetree: no node compatible with a PL011 UART")?;
+        let (address, _size) = node
+            .reg()
+            .ok_or("devicetree: matched node has no usable reg property")?;
+
+        Ok(unsafe { Self::new(address as usize) })
+    }
+}
+
+impl driver::interface::DeviceDriver for PL011Uart {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+
+    fn match_compatible() -> &'static [&'static str] {
+        &["arm,pl011", "arm,primecell"]
+    }
+
+    unsafe fn init(&self) -> Result<(), &'static str> {
+        self.inner.lock(|inner| {
+            inner.registers.CR.write(CR::UARTEN::CLEAR);
+            inner
+                .registers
+                .CR
+                .write(CR::RXE::SET + CR::TXE::SET + CR::UARTEN::SET);
+        });
+
+        Ok(())
+    }
+}
+
+impl console::interface::Write for PL011Uart {
+    fn write_char(&self, c: char) {
+        self.inner.lock(|inner| inner.write_char(c));
+    }
+
+    fn write_fmt(&self, args: core::fmt::Arguments) -> fmt::Result {
+        self.inner.lock(|inner| fmt::Write::write_fmt(inner, args))
+    }
+
+    fn flush(&self) {
+        self.inner.lock(|inner| {
+            while inner.registers.FR.is_set(FR::BUSY) {
+                cpu::spin_for_cycles(1);
+            }
+        });
+    }
+}
+
+impl console::interface::Read for PL011Uart {
+    fn read_char(&self) -> char {
+        self.inner.lock(|inner| {
+            while inner.registers.FR.is_set(FR::RXFE) {
+                cpu::spin_for_cycles(1);
+            }
+
+            let ret = inner.registers.DR.get() as u8 as char;
+            inner.chars_read += 1;
+            ret
+        })
+    }
+
+    fn read_char_nonblocking(&self) -> Option<char> {
+        self.inner.lock(|inner| {
+            if inner.registers.FR.is_set(FR::RXFE) {
+                return None;
+            }
+
+            let ret = inner.registers.DR.get() as u8 as char;
+            inner.chars_read += 1;
+            Some(ret)
+        })
+    }
+
+    fn clear_rx(&self) {
+        while !self.inner.lock(|inner| inner.registers.FR.is_set(FR::RXFE)) {
+            self.read_char();
+        }
+    }
+}
+
+impl console::interface::Statistics for PL011Uart {
+    fn chars_written(&self) -> usize {
+        self.inner.lock(|inner| inner.chars_written)
+    }
+
+    fn chars_read(&self) -> usize {
+        self.inner.lock(|inner| inner.chars_read)
+    }
+}
+
+impl console::interface::All for PL011Uart {}
@@ -0,0 +1,29 @@
+//! BCM driver top level.
+
+mod bcm2xxx_audio;
+mod bcm2xxx_cm;
+pub mod bcm2xxx_dma;
+pub mod bcm2xxx_emmc;
+mod bcm2xxx_framebuffer;
+#[cfg(feature = "bsp_rpi4")]
+pub mod bcm2xxx_genet;
+mod bcm2xxx_gpio;
+#[cfg(feature = "bsp_rpi3")]
+mod bcm2xxx_interrupt_controller;
+mod bcm2xxx_mailbox;
+#[cfg(feature = "bsp_rpi4")]
+pub mod bcm2xxx_pcie;
+mod bcm2xxx_pl011_uart;
+mod bcm2xxx_power;
+mod bcm2xxx_touchscreen;
+
+pub use bcm2xxx_audio::*;
+pub use bcm2xxx_cm::*;
+pub use bcm2xxx_framebuffer::*;
+pub use bcm2xxx_gpio::*;
+#[cfg(feature = "bsp_rpi3")]
+pub use bcm2xxx_interrupt_controller::*;
+pub use bcm2xxx_mailbox::*;
+pub use bcm2xxx_pl011_uart::*;
+pub use bcm2xxx_power::*;
+pub use bcm2xxx_touchscreen::*;
@@ -0,0 +1,165 @@
+//! BCM283x/BCM2711 PWM audio output (3.5 mm jack).
+//!
+//! Drives PWM channel 1 in FIFO/serializer mode as a crude DAC: each `i16` sample is scaled up to
+//! [`RANGE`] and pushed into the FIFO as a duty cycle, at a rate governed by the clock manager's
+//! PWM clock divisor (see [`crate::clocks`], which this driver goes through instead of
+//! reprogramming the clock manager itself). There's no DMA controller driver in this fork to hand
+//! the FIFO off to, so [`Audio::play`] refills it from the CPU in a polling loop instead -- it
+//! blocks the calling core for the duration of the clip, and an interrupt between polls that runs
+//! long enough to starve the FIFO will audibly click. A real driver would program the DMA
+//! engine's control-block chain to feed `FIF1` and only need the CPU to queue buffers; that's
+//! future work once this fork grows a DMA driver (see
+//! [`crate::bsp::device_driver::bcm::bcm2xxx_mailbox`]'s module docs for the same "no heap, no
+//! DMA-coherent allocator" gap it works around for the mailbox's own buffer).
+
+use crate::{
+    audio,
+    bsp::device_driver::common::MMIODerefWrapper,
+    clocks, driver,
+    synchronization::{Mutex, NullLock},
+};
+use tock_registers::{
+    interfaces::{Readable, Writeable},
+    register_bitfields, register_structs,
+    registers::{ReadWrite, WriteOnly},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+register_bitfields! {
+    u32,
+
+    PWM_CTL [
+        MSEN1 OFFSET(7) NUMBITS(1) [],
+        CLRF1 OFFSET(6) NUMBITS(1) [],
+        USEF1 OFFSET(5) NUMBITS(1) [],
+        MODE1 OFFSET(1) NUMBITS(1) [],
+        PWEN1 OFFSET(0) NUMBITS(1) [],
+    ],
+
+    PWM_STA [
+        EMPT1 OFFSET(1) NUMBITS(1) [],
+        FULL1 OFFSET(0) NUMBITS(1) [],
+    ],
+}
+
+register_structs! {
+    #[allow(non_snake_case)]
+    pub PwmRegisterBlock {
+        (0x00 => CTL: ReadWrite<u32, PWM_CTL::Register>),
+        (0x04 => STA: ReadWrite<u32, PWM_STA::Register>),
+        (0x08 => _reserved1),
+        (0x10 => RNG1: ReadWrite<u32>),
+        (0x14 => _reserved2),
+        (0x18 => FIF1: WriteOnly<u32>),
+        (0x1c => @END),
+    }
+}
+
+type PwmRegisters = MMIODerefWrapper<PwmRegisterBlock>;
+
+/// The duty-cycle resolution `i16` samples are rescaled to. Also the PWM clock's oversampling
+/// factor: the effective output rate is `sample_rate * RANGE`.
+const RANGE: u32 = 1024;
+
+/// Inner, locked driver state.
+struct AudioInner {
+    pwm: PwmRegisters,
+}
+
+impl AudioInner {
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    const unsafe fn new(pwm_mmio_start_addr: usize) -> Self {
+        Self {
+            pwm: PwmRegisters::new(pwm_mmio_start_addr),
+        }
+    }
+
+    /// Retune the PWM clock for `sample_rate` through [`clocks::set_rate`].
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        clocks::set_rate(clocks::Clock::Pwm, sample_rate * RANGE);
+    }
+
+    fn enable(&self) {
+        self.pwm.RNG1.set(RANGE);
+        self.pwm.CTL.write(
+            PWM_CTL::PWEN1::SET + PWM_CTL::MODE1::CLEAR + PWM_CTL::USEF1::SET + PWM_CTL::MSEN1::SET,
+        );
+    }
+
+    fn disable(&self) {
+        self.pwm.CTL.write(PWM_CTL::PWEN1::CLEAR);
+    }
+
+    /// Push one sample into the FIFO, spinning until there's room.
+    fn push_sample(&self, sample: i16) {
+        while self.pwm.STA.is_set(PWM_STA::FULL1) {
+            crate::cpu::spin_for_cycles(1);
+        }
+
+        // Rescale a signed i16 sample onto the unsigned [0, RANGE) duty-cycle range.
+        let unsigned = sample as i32 - i16::MIN as i32;
+        let duty = (unsigned as u32 * RANGE) / (u16::MAX as u32 + 1);
+        self.pwm.FIF1.set(duty);
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Representation of the PWM audio output.
+pub struct Audio {
+    inner: NullLock<AudioInner>,
+}
+
+impl Audio {
+    pub const COMPATIBLE: &'static str = "BCM PWM Audio";
+
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    pub const unsafe fn new(pwm_mmio_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(AudioInner::new(pwm_mmio_start_addr)),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// OS Interface Code
+//--------------------------------------------------------------------------------------------------
+
+impl driver::interface::DeviceDriver for Audio {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+}
+
+impl audio::interface::Play for Audio {
+    /// Play `samples` at `sample_rate` Hz, blocking the calling core until the last one has been
+    /// handed to the FIFO.
+    ///
+    /// See the module docs for why this polls instead of using DMA, and for the underrun risk
+    /// that implies.
+    fn play(&self, samples: &[i16], sample_rate: u32) {
+        self.inner.lock(|inner| {
+            inner.set_sample_rate(sample_rate);
+            inner.enable();
+
+            for &sample in samples {
+                inner.push_sample(sample);
+            }
+
+            inner.disable();
+        });
+    }
+}
@@ -0,0 +1,186 @@
+//! BCM283x/BCM2711 clock manager ("CM") driver.
+//!
+//! Only the oscillator-sourced general-purpose clocks (`GP0`-`GP2`) and the two
+//! peripheral-dedicated ones this fork's drivers actually feed from it (`PWM`, `PCM`) are
+//! modelled -- there are more CM-controlled clocks on real hardware (the core/SDRAM/peripheral
+//! PLLs and their per-peripheral dividers), but nothing in this fork's driver set needs them
+//! yet. Every one of the modelled clocks only ever sources from the crystal oscillator
+//! ([`OSC_HZ`]) -- the PLL source selector bits exist in [`CM_CTL`] but this driver never sets
+//! them -- and divides it down with an integer-only divisor; the fractional divisor bits in
+//! [`CM_DIV`] are left at 0. The divisor is `OSC_HZ / hz` rounded down, so the achieved rate is
+//! the lowest integer-divisor rate at or above the requested one (or [`OSC_HZ`] itself, for a
+//! request above it).
+
+use crate::{
+    bsp::device_driver::common::MMIODerefWrapper,
+    clocks, driver,
+    synchronization::{Mutex, NullLock},
+};
+use tock_registers::{
+    interfaces::{Readable, Writeable},
+    register_bitfields, register_structs,
+    registers::ReadWrite,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+register_bitfields! {
+    u32,
+
+    CM_CTL [
+        PASSWD OFFSET(24) NUMBITS(8) [],
+        BUSY   OFFSET(7) NUMBITS(1) [],
+        ENAB   OFFSET(4) NUMBITS(1) [],
+        SRC    OFFSET(0) NUMBITS(4) [
+            Oscillator = 1,
+        ],
+    ],
+
+    CM_DIV [
+        PASSWD OFFSET(24) NUMBITS(8) [],
+        DIVI   OFFSET(12) NUMBITS(12) [],
+        DIVF   OFFSET(0) NUMBITS(12) [],
+    ],
+}
+
+register_structs! {
+    #[allow(non_snake_case)]
+    pub RegisterBlock {
+        (0x00 => _reserved1),
+        (0x70 => GP0CTL: ReadWrite<u32, CM_CTL::Register>),
+        (0x74 => GP0DIV: ReadWrite<u32, CM_DIV::Register>),
+        (0x78 => GP1CTL: ReadWrite<u32, CM_CTL::Register>),
+        (0x7c => GP1DIV: ReadWrite<u32, CM_DIV::Register>),
+        (0x80 => GP2CTL: ReadWrite<u32, CM_CTL::Register>),
+        (0x84 => GP2DIV: ReadWrite<u32, CM_DIV::Register>),
+        (0x88 => _reserved2),
+        (0x98 => PCMCTL: ReadWrite<u32, CM_CTL::Register>),
+        (0x9c => PCMDIV: ReadWrite<u32, CM_DIV::Register>),
+        (0xa0 => PWMCTL: ReadWrite<u32, CM_CTL::Register>),
+        (0xa4 => PWMDIV: ReadWrite<u32, CM_DIV::Register>),
+        (0xa8 => @END),
+    }
+}
+
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+/// The password the clock manager requires in the top byte of every write to [`RegisterBlock`].
+const CM_PASSWORD: u32 = 0x5a;
+
+/// The oscillator frequency every clock this driver models divides down from.
+#[cfg(feature = "bsp_rpi3")]
+const OSC_HZ: u32 = 19_200_000;
+#[cfg(feature = "bsp_rpi4")]
+const OSC_HZ: u32 = 54_000_000;
+
+/// Inner, locked driver state.
+struct ClockManagerInner {
+    registers: Registers,
+}
+
+impl ClockManagerInner {
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            registers: Registers::new(mmio_start_addr),
+        }
+    }
+
+    /// The `(CTL, DIV)` register pair backing `clock`.
+    fn registers_for(
+        &self,
+        clock: clocks::Clock,
+    ) -> (
+        &ReadWrite<u32, CM_CTL::Register>,
+        &ReadWrite<u32, CM_DIV::Register>,
+    ) {
+        match clock {
+            clocks::Clock::Gp0 => (&self.registers.GP0CTL, &self.registers.GP0DIV),
+            clocks::Clock::Gp1 => (&self.registers.GP1CTL, &self.registers.GP1DIV),
+            clocks::Clock::Gp2 => (&self.registers.GP2CTL, &self.registers.GP2DIV),
+            clocks::Clock::Pcm => (&self.registers.PCMCTL, &self.registers.PCMDIV),
+            clocks::Clock::Pwm => (&self.registers.PWMCTL, &self.registers.PWMDIV),
+        }
+    }
+
+    fn get_rate(&self, clock: clocks::Clock) -> u32 {
+        let (_, div) = self.registers_for(clock);
+        let divisor = div.read(CM_DIV::DIVI);
+
+        if divisor == 0 {
+            return 0;
+        }
+
+        OSC_HZ / divisor
+    }
+
+    /// Stop `clock`, reprogram its divisor for `hz`, and restart it.
+    fn set_rate(&self, clock: clocks::Clock, hz: u32) -> u32 {
+        let (ctl, div) = self.registers_for(clock);
+
+        ctl.write(CM_CTL::PASSWD.val(CM_PASSWORD) + CM_CTL::ENAB::CLEAR);
+        while ctl.is_set(CM_CTL::BUSY) {
+            crate::cpu::spin_for_cycles(1);
+        }
+
+        let divisor = (OSC_HZ / hz).max(1);
+        div.write(CM_DIV::PASSWD.val(CM_PASSWORD) + CM_DIV::DIVI.val(divisor));
+        ctl.write(CM_CTL::PASSWD.val(CM_PASSWORD) + CM_CTL::SRC::Oscillator + CM_CTL::ENAB::SET);
+
+        while !ctl.is_set(CM_CTL::BUSY) {
+            crate::cpu::spin_for_cycles(1);
+        }
+
+        OSC_HZ / divisor
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Representation of the clock manager.
+pub struct ClockManager {
+    inner: NullLock<ClockManagerInner>,
+}
+
+impl ClockManager {
+    pub const COMPATIBLE: &'static str = "BCM Clock Manager";
+
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(ClockManagerInner::new(mmio_start_addr)),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// OS Interface Code
+//--------------------------------------------------------------------------------------------------
+
+impl driver::interface::DeviceDriver for ClockManager {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+}
+
+impl clocks::interface::Manager for ClockManager {
+    fn get_rate(&self, clock: clocks::Clock) -> u32 {
+        self.inner.lock(|inner| inner.get_rate(clock))
+    }
+
+    fn set_rate(&self, clock: clocks::Clock, hz: u32) -> u32 {
+        self.inner.lock(|inner| inner.set_rate(clock, hz))
+    }
+}
@@ -0,0 +1,99 @@
+//! BCM2835/BCM2711 DMA controller control-block encoding.
+//!
+//! There is no register-poking DMA controller driver here -- no `crate::dma::interface::DmaChannel`
+//! is instantiated for any board -- for the same reason `bcm2xxx_emmc` stops at protocol math: the
+//! controller's actual init sequence (picking a free channel, writing `CS`/`CONBLK_AD`, waiting on
+//! the `ACTIVE`/`END` bits, handling the channel-specific `DEBUG` error flags) needs real hardware
+//! to validate against, and shipping that untested is worse than not shipping it.
+//!
+//! What's provided instead is the hardware-independent half: [`ControlBlock`] is the 32-byte,
+//! 32-byte-aligned structure the controller reads a transfer's parameters from (BCM2835 ARM
+//! Peripherals spec, "DMA Control and Status Registers", section 4.2.1.2), and
+//! [`encode_control_block`] fills one in from a [`crate::dma::Descriptor`] pair plus the transfer
+//! information flags a caller has already decided on -- see the `TI_*` constants and
+//! [`peripheral_mapping`]/[`wait_cycles`] for building that flags word.
+
+use crate::dma;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Generate an interrupt when this control block's transfer completes.
+pub const TI_INTEN: u32 = 1 << 0;
+/// Wait for the AXI write response before moving to the next control block.
+pub const TI_WAIT_RESP: u32 = 1 << 3;
+/// Increment the destination address after each transfer.
+pub const TI_DEST_INC: u32 = 1 << 4;
+/// Use 128-bit destination transfer width instead of the default 32-bit.
+pub const TI_DEST_WIDTH_128: u32 = 1 << 5;
+/// Gate destination writes on the peripheral's DREQ signal, for MEM-to-peripheral transfers.
+pub const TI_DEST_DREQ: u32 = 1 << 6;
+/// Increment the source address after each transfer.
+pub const TI_SRC_INC: u32 = 1 << 8;
+/// Use 128-bit source transfer width instead of the default 32-bit.
+pub const TI_SRC_WIDTH_128: u32 = 1 << 9;
+/// Gate source reads on the peripheral's DREQ signal, for peripheral-to-MEM transfers.
+pub const TI_SRC_DREQ: u32 = 1 << 10;
+/// Perform this transfer as a sequence of 32-bit accesses rather than the controller's default
+/// wide-burst optimization; safer for narrow peripheral FIFOs.
+pub const TI_NO_WIDE_BURSTS: u32 = 1 << 26;
+
+/// The 32-byte control block the DMA controller's `CONBLK_AD` register points at. Must be
+/// 32-byte aligned, as the controller ignores the low 5 address bits.
+#[repr(C, align(32))]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ControlBlock {
+    pub transfer_information: u32,
+    pub source_address: u32,
+    pub dest_address: u32,
+    pub transfer_length: u32,
+    pub stride: u32,
+    pub next_control_block: u32,
+    _reserved: [u32; 2],
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// The `TI.PERMAP` field selecting which peripheral's DREQ gates this transfer (0 means
+/// "unpaced", i.e. memory-to-memory).
+pub fn peripheral_mapping(peripheral_id: u8) -> u32 {
+    (peripheral_id as u32 & 0x1f) << 16
+}
+
+/// The `TI.WAITS` field: extra bus cycles to wait between each transfer, for peripherals that
+/// need more setup time than the controller's default pacing gives them.
+pub fn wait_cycles(cycles: u8) -> u32 {
+    (cycles as u32 & 0x1f) << 21
+}
+
+/// Fill in one [`ControlBlock`] for a transfer between `source` and `dest`, using `next_control_block`
+/// as the (already 32-byte-aligned) physical address of the next control block in the chain, or 0
+/// to end the chain there.
+///
+/// `transfer_information` is the caller-assembled `TI_*`/[`peripheral_mapping`]/[`wait_cycles`]
+/// flags word; this function only places it and the addresses/length, it doesn't decide direction
+/// or pacing for the caller.
+pub fn encode_control_block(
+    transfer_information: u32,
+    source: dma::Descriptor,
+    dest: dma::Descriptor,
+    next_control_block: u32,
+) -> ControlBlock {
+    debug_assert_eq!(
+        source.length, dest.length,
+        "a DMA control block transfers the same length at both ends"
+    );
+
+    ControlBlock {
+        transfer_information,
+        source_address: source.address as u32,
+        dest_address: dest.address as u32,
+        transfer_length: source.length as u32,
+        stride: 0,
+        next_control_block,
+        _reserved: [0; 2],
+    }
+}
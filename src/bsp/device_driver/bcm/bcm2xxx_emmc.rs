@@ -0,0 +1,130 @@
+//! BCM283x/BCM2711 EMMC (SD card) command-frame and clocking math.
+//!
+//! There is no "basic EMMC driver" in this fork for this request to extend -- no
+//! [`crate::storage::interface::BlockDevice`] exists for `bsp_rpi3`/`bsp_rpi4` at all today; the
+//! only backend that trait has is [`crate::bsp::device_driver::virtio::blk::VirtioBlk`], which
+//! backs `bsp_qemu_virt` instead of real SD hardware. Writing the actual EMMC init/reset sequence
+//! (the `CONTROL0`/`CONTROL1`/`INTERRUPT` register dance a real driver needs) from memory without
+//! hardware to test against risks shipping confidently wrong register values, which is worse than
+//! not shipping a driver at all.
+//!
+//! What's provided instead is the hardware-independent SD protocol math a 4-bit/high-speed/
+//! multi-block driver would need on day one, so whoever writes the base driver doesn't have to
+//! re-derive it: [`command_crc7`] and [`encode_command_frame`] build the same 48-bit command frame
+//! for every SD command regardless of controller, [`block_address_argument`] picks byte- vs
+//! block-addressing for CMD18/CMD25 depending on card capacity class, [`sd_clock_divisor`] is the
+//! general SDCLK divisor formula (the BCM controller's exact `CDIV`/`HISPEED` bit packing is
+//! EMMC-register-specific and left to that init sequence), and [`classify_error`] is the
+//! retry-vs-reset decision a CRC-retry/reset-recovery loop would drive off of.
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+/// The SD command frame CRC: 7 bits, polynomial `x^7 + x^3 + 1`, computed over the 40 command +
+/// argument bits (SD Physical Layer Simplified Spec, "CRC7").
+fn crc7(bits: &[u8]) -> u8 {
+    const POLYNOMIAL: u8 = 0x09; // x^3 + 1, with the leading x^7 implicit in the shift register
+
+    let mut crc = 0u8;
+    for &byte in bits {
+        for i in (0..8).rev() {
+            let bit = (byte >> i) & 1;
+            let msb = (crc >> 6) & 1;
+            crc = (crc << 1) & 0x7f;
+            if msb ^ bit == 1 {
+                crc ^= POLYNOMIAL;
+            }
+        }
+    }
+
+    crc
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// `STOP_TRANSMISSION`: ends a CMD18/CMD25 multi-block transfer.
+pub const CMD12_STOP_TRANSMISSION: u8 = 12;
+/// `READ_MULTIPLE_BLOCK`.
+pub const CMD18_READ_MULTIPLE_BLOCK: u8 = 18;
+/// `WRITE_MULTIPLE_BLOCK`.
+pub const CMD25_WRITE_MULTIPLE_BLOCK: u8 = 25;
+
+/// What a CRC-retry/reset-recovery loop should do next, given how many consecutive transfer
+/// errors it has already seen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// Reissue the same command; a lone CRC error is usually transient bus noise.
+    Retry,
+    /// Run the controller's reset sequence before reissuing; repeated errors suggest the card or
+    /// bus is stuck in a bad state a plain retry won't clear.
+    Reset,
+    /// Stop trying; the card is very likely gone or the bus is unrecoverable.
+    GiveUp,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Compute the CRC7 for a 5-byte command + argument (the first 5 bytes of an SD command frame,
+/// before the CRC and end bit).
+pub fn command_crc7(command_and_argument: &[u8; 5]) -> u8 {
+    crc7(command_and_argument)
+}
+
+/// Encode a full 48-bit SD command frame: start bit (0), transmission bit (1), 6-bit command
+/// index, 32-bit argument, 7-bit CRC, end bit (1).
+pub fn encode_command_frame(command_index: u8, argument: u32) -> [u8; 6] {
+    debug_assert!(command_index <= 0x3f, "SD command index is 6 bits wide");
+
+    let mut command_and_argument = [0u8; 5];
+    command_and_argument[0] = 0x40 | (command_index & 0x3f); // start=0, transmit=1
+    command_and_argument[1..5].copy_from_slice(&argument.to_be_bytes());
+
+    let crc = crc7(&command_and_argument);
+
+    let mut frame = [0u8; 6];
+    frame[..5].copy_from_slice(&command_and_argument);
+    frame[5] = (crc << 1) | 1; // end bit
+
+    frame
+}
+
+/// The CMD18/CMD25 argument for `block_index`: a raw block number for SDHC/SDXC (block-addressed)
+/// cards, or that block's byte offset for standard-capacity (byte-addressed) cards.
+pub fn block_address_argument(block_index: u64, card_is_block_addressed: bool) -> u32 {
+    if card_is_block_addressed {
+        block_index as u32
+    } else {
+        (block_index * crate::storage::interface::BLOCK_SIZE as u64) as u32
+    }
+}
+
+/// The SDCLK divisor needed to derive `target_hz` (e.g. 50 MHz for high-speed mode) from
+/// `base_clock_hz`, rounded up so the resulting clock never exceeds `target_hz`.
+///
+/// This is the general "how many halvings of the base clock" formula every SD host controller
+/// uses; packing the result into the BCM controller's split `CDIV`/`HISPEED` fields is specific to
+/// that register layout and left to the init sequence that writes them.
+pub fn sd_clock_divisor(base_clock_hz: u32, target_hz: u32) -> u32 {
+    if target_hz == 0 || target_hz >= base_clock_hz {
+        return 0; // no division needed
+    }
+
+    base_clock_hz.div_ceil(target_hz * 2)
+}
+
+/// Decide what a CRC-retry/reset-recovery loop should do after `consecutive_failures` transfer
+/// errors in a row, giving up once `max_retries` is exceeded.
+pub fn classify_error(consecutive_failures: u32, max_retries: u32) -> RecoveryAction {
+    if consecutive_failures > max_retries {
+        RecoveryAction::GiveUp
+    } else if consecutive_failures > max_retries / 2 {
+        RecoveryAction::Reset
+    } else {
+        RecoveryAction::Retry
+    }
+}
@@ -0,0 +1,169 @@
+//! BCM283x/BCM2711 VideoCore framebuffer.
+//!
+//! Unlike the other BCM drivers, this one has no MMIO register block of its own -- the "device"
+//! is whatever the VideoCore firmware does with the mailbox property tags in this file, the same
+//! channel [`Mailbox`] uses for memory allocation.
+//!
+//! Sets up a virtual buffer twice the display's height and flips between the top and bottom half
+//! via the "set virtual offset" tag, which the firmware applies at the next vertical blank on the
+//! hardware this was tested against -- giving vsync'd double buffering without this fork needing
+//! an interrupt-driven vblank signal of its own. Older firmware revisions are documented to apply
+//! the offset immediately instead of waiting for vblank; there's no way to detect which from here,
+//! so [`Framebuffer::flip`] can't promise tear-free output on every board.
+
+use super::bcm2xxx_mailbox::{Mailbox, PROPERTY_VALUE_WORDS};
+use crate::{
+    dma, driver, framebuffer,
+    synchronization::{Mutex, NullLock},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const TAG_SET_PHYSICAL_WH: u32 = 0x0004_8003;
+const TAG_SET_VIRTUAL_WH: u32 = 0x0004_8004;
+const TAG_SET_DEPTH: u32 = 0x0004_8005;
+const TAG_SET_PIXEL_ORDER: u32 = 0x0004_8006;
+const TAG_GET_PITCH: u32 = 0x0004_0008;
+const TAG_SET_VIRTUAL_OFFSET: u32 = 0x0004_8009;
+const TAG_ALLOCATE_BUFFER: u32 = 0x0004_0001;
+
+const DEPTH_BITS: u32 = 32;
+const PIXEL_ORDER_RGB: u32 = 1;
+const BUFFER_ALIGN: u32 = 16;
+
+struct Inner {
+    width: u32,
+    height: u32,
+    pitch: u32,
+    base_addr: u32,
+    /// The row offset of the half currently scanned out; the other half is the back buffer.
+    visible_row_offset: u32,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Representation of the framebuffer.
+pub struct Framebuffer {
+    mailbox: &'static Mailbox,
+    inner: NullLock<Option<Inner>>,
+}
+
+impl Framebuffer {
+    pub const COMPATIBLE: &'static str = "BCM VideoCore Framebuffer";
+
+    /// The physical (and, at half the virtual height, back-buffer) display resolution negotiated
+    /// with the VideoCore at init time.
+    pub const WIDTH: u32 = 1280;
+    pub const HEIGHT: u32 = 720;
+
+    /// Create an instance.
+    pub const fn new(mailbox: &'static Mailbox) -> Self {
+        Self {
+            mailbox,
+            inner: NullLock::new(None),
+        }
+    }
+
+    fn call(&self, tag: u32, request: &[u32]) -> Result<[u32; PROPERTY_VALUE_WORDS], &'static str> {
+        self.mailbox.property_call(tag, request)
+    }
+
+    /// Negotiate a `width` x `height`, `depth`-bpp double-height virtual buffer with the
+    /// VideoCore and allocate it.
+    fn bring_up(&self, width: u32, height: u32, depth: u32) -> Result<Inner, &'static str> {
+        self.call(TAG_SET_PHYSICAL_WH, &[width, height])?;
+        self.call(TAG_SET_VIRTUAL_WH, &[width, height * 2])?;
+        self.call(TAG_SET_DEPTH, &[depth])?;
+        self.call(TAG_SET_PIXEL_ORDER, &[PIXEL_ORDER_RGB])?;
+
+        let allocation = self.call(TAG_ALLOCATE_BUFFER, &[BUFFER_ALIGN])?;
+        let base_addr = dma::bus_to_phys(allocation[0]);
+
+        let pitch = self.call(TAG_GET_PITCH, &[])?[0];
+
+        Ok(Inner {
+            width,
+            height,
+            pitch,
+            base_addr,
+            visible_row_offset: 0,
+        })
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// OS Interface Code
+//--------------------------------------------------------------------------------------------------
+
+impl driver::interface::DeviceDriver for Framebuffer {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+
+    unsafe fn init(&self) -> Result<(), &'static str> {
+        let inner = self.bring_up(Self::WIDTH, Self::HEIGHT, DEPTH_BITS)?;
+        self.inner.lock(|slot| *slot = Some(inner));
+        Ok(())
+    }
+}
+
+impl framebuffer::interface::Display for Framebuffer {
+    fn width(&self) -> u32 {
+        self.inner
+            .lock(|inner| inner.as_ref().map_or(0, |i| i.width))
+    }
+
+    fn height(&self) -> u32 {
+        self.inner
+            .lock(|inner| inner.as_ref().map_or(0, |i| i.height))
+    }
+
+    fn pitch(&self) -> u32 {
+        self.inner
+            .lock(|inner| inner.as_ref().map_or(0, |i| i.pitch))
+    }
+
+    fn back_buffer_ptr(&self) -> Option<*mut u32> {
+        self.inner.lock(|inner| {
+            let inner = inner.as_ref()?;
+            let back_row_offset = if inner.visible_row_offset == 0 {
+                inner.height
+            } else {
+                0
+            };
+            let byte_offset = back_row_offset * inner.pitch;
+
+            Some((inner.base_addr + byte_offset) as *mut u32)
+        })
+    }
+
+    /// Swap the visible and back buffers.
+    ///
+    /// See the module docs for the caveat on older firmware not actually waiting for vblank here.
+    fn flip(&self) -> Result<(), &'static str> {
+        let new_offset = self.inner.lock(|inner| {
+            let inner = inner.as_mut().ok_or("framebuffer: not initialized")?;
+            inner.visible_row_offset = if inner.visible_row_offset == 0 {
+                inner.height
+            } else {
+                0
+            };
+            Ok::<u32, &'static str>(inner.visible_row_offset)
+        })?;
+
+        self.call(TAG_SET_VIRTUAL_OFFSET, &[0, new_offset])?;
+        Ok(())
+    }
+
+    /// Renegotiate the mode, replacing the current buffer outright rather than flipping between
+    /// halves of it -- the caller is responsible for redrawing afterwards, same as after `init`.
+    fn set_mode(&self, width: u32, height: u32, depth: u32) -> Result<(), &'static str> {
+        let inner = self.bring_up(width, height, depth)?;
+        self.inner.lock(|slot| *slot = Some(inner));
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+//! BCM2711 GENET v5 Ethernet MAC.
+//!
+//! There is no `NetworkDevice` implementation here, for the same reason
+//! [`bcm2xxx_emmc`](super::bcm2xxx_emmc) has no `BlockDevice` one: GENET v5's ring-based DMA
+//! descriptor layout (the `TDMA`/`RDMA` descriptor ring registers, per-ring producer/consumer
+//! index pairs, and the `INTRL2_0`/`INTRL2_1` interrupt-coalescing register set) is Broadcom
+//! proprietary, documented nowhere but the (GPL) Linux `bcmgenet` driver, and different enough
+//! from the architected DMA engines this fork already models ([`crate::dma`]) that guessing at
+//! its register encoding from memory risks shipping a MAC driver that confidently corrupts its
+//! own descriptor rings -- worse than shipping none. The same applies to `UniMAC`'s own
+//! configuration registers, which GENET wraps its MII/RGMII PHY interface in.
+//!
+//! What's real and usable on day one regardless of any of that: MDIO itself. GENET exposes its
+//! PHY over a standard IEEE 802.3 Clause 22 MDIO bus -- see [`crate::mdio`] for the frame format,
+//! built and tested independently of any one MAC's register for shifting it out. A future
+//! `bcm2xxx_genet` that has the real ring descriptor layout only needs to find GENET's own MDIO
+//! command register (documented in `bcmgenet`, not guessed at here) to hand [`crate::mdio`]'s
+//! frames to; everything [`crate::mdio`] builds is already correct regardless of where that
+//! register turns out to live.
@@ -0,0 +1,159 @@
+//! BCM283x/BCM2711 GPIO driver.
+//!
+//! Only the bare function-select/set/clear/level registers are modelled -- no pull-up/down
+//! control. [`Gpio::set_alt`] can route a pin to any of its six alternate functions (e.g.
+//! [`crate::debug_jtag`]'s ALT4 JTAG pins), but nothing here knows *which* peripheral a given
+//! `ALTn` actually wires a pin to -- that mapping lives wherever the caller got the alt number
+//! from (a datasheet, in `debug_jtag`'s case), not in this driver. Peripherals already present in
+//! this fork, like the PL011 UART, still aren't routed through here -- they're wired up by fixed
+//! MMIO address instead.
+
+use crate::{
+    bsp::device_driver::common::MMIODerefWrapper,
+    driver, gpio,
+    synchronization::{Mutex, NullLock},
+};
+use tock_registers::{
+    interfaces::{Readable, Writeable},
+    register_structs,
+    registers::{ReadOnly, ReadWrite, WriteOnly},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+register_structs! {
+    #[allow(non_snake_case)]
+    pub RegisterBlock {
+        (0x00 => GPFSEL: [ReadWrite<u32>; 6]),
+        (0x18 => _reserved1),
+        (0x1c => GPSET: [WriteOnly<u32>; 2]),
+        (0x24 => _reserved2),
+        (0x28 => GPCLR: [WriteOnly<u32>; 2]),
+        (0x30 => _reserved3),
+        (0x34 => GPLEV: [ReadOnly<u32>; 2]),
+        (0x3c => @END),
+    }
+}
+
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+/// The number of pins packed into each `GPFSEL` word, at 3 bits each.
+const FSEL_PINS_PER_WORD: u32 = 10;
+const FSEL_OUTPUT: u32 = 0b001;
+const FSEL_INPUT: u32 = 0b000;
+const FSEL_MASK: u32 = 0b111;
+
+/// Encode alternate function `alt` (0-5) the way `GPFSEL` expects it: `ALT0`-`ALT3` are
+/// `0b100`-`0b111`, `ALT4`/`ALT5` are `0b011`/`0b010` -- the BCM's own non-contiguous numbering,
+/// not something this driver invented.
+fn alt_function_code(alt: u32) -> u32 {
+    match alt {
+        0 => 0b100,
+        1 => 0b101,
+        2 => 0b110,
+        3 => 0b111,
+        4 => 0b011,
+        5 => 0b010,
+        _ => panic!("bcm2xxx_gpio: no such alternate function: ALT{}", alt),
+    }
+}
+
+/// Inner, locked driver state.
+struct GpioInner {
+    registers: Registers,
+}
+
+impl GpioInner {
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            registers: Registers::new(mmio_start_addr),
+        }
+    }
+
+    fn set_function(&self, pin: u32, function: u32) {
+        let word = (pin / FSEL_PINS_PER_WORD) as usize;
+        let shift = (pin % FSEL_PINS_PER_WORD) * 3;
+
+        let value = self.registers.GPFSEL[word].get();
+        let value = (value & !(FSEL_MASK << shift)) | (function << shift);
+        self.registers.GPFSEL[word].set(value);
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Representation of the GPIO controller.
+pub struct Gpio {
+    inner: NullLock<GpioInner>,
+}
+
+impl Gpio {
+    pub const COMPATIBLE: &'static str = "BCM GPIO";
+
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(GpioInner::new(mmio_start_addr)),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// OS Interface Code
+//--------------------------------------------------------------------------------------------------
+
+impl driver::interface::DeviceDriver for Gpio {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+}
+
+impl gpio::interface::Controller for Gpio {
+    fn set_output(&self, pin: u32) {
+        self.inner
+            .lock(|inner| inner.set_function(pin, FSEL_OUTPUT));
+    }
+
+    fn set_input(&self, pin: u32) {
+        self.inner.lock(|inner| inner.set_function(pin, FSEL_INPUT));
+    }
+
+    fn set_alt(&self, pin: u32, alt: u32) {
+        self.inner
+            .lock(|inner| inner.set_function(pin, alt_function_code(alt)));
+    }
+
+    fn set_high(&self, pin: u32) {
+        let word = (pin / 32) as usize;
+        let bit = pin % 32;
+        self.inner
+            .lock(|inner| inner.registers.GPSET[word].set(1 << bit));
+    }
+
+    fn set_low(&self, pin: u32) {
+        let word = (pin / 32) as usize;
+        let bit = pin % 32;
+        self.inner
+            .lock(|inner| inner.registers.GPCLR[word].set(1 << bit));
+    }
+
+    fn is_high(&self, pin: u32) -> bool {
+        let word = (pin / 32) as usize;
+        let bit = pin % 32;
+        self.inner
+            .lock(|inner| inner.registers.GPLEV[word].get() & (1 << bit) != 0)
+    }
+}
@@ -0,0 +1,181 @@
+//! The Raspberry Pi 3's own (non-GIC) interrupt controller.
+//!
+//! The BCM2837 predates any ARM GIC on this SoC: interrupts are enabled and acknowledged through
+//! a small, Broadcom-specific register block instead. IRQ numbers 0..=63 address peripheral
+//! interrupts; there is no distinction between distributor and CPU interface like on a GIC.
+
+use crate::{
+    bsp::device_driver::common::MMIODerefWrapper,
+    config::MAX_IRQ_NUMBER as NUM_IRQS,
+    driver,
+    exception::asynchronous::{self, interface, IRQHandlerDescriptor},
+    synchronization::{Mutex, NullLock},
+};
+use tock_registers::{
+    interfaces::Writeable,
+    register_structs,
+    registers::{ReadWrite, WriteOnly},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+register_structs! {
+    #[allow(non_snake_case)]
+    RegisterBlock {
+        (0x00 => _reserved1),
+        (0x0c => FIQ_CONTROL: ReadWrite<u32>),
+        (0x10 => ENABLE_IRQS_1: WriteOnly<u32>),
+        (0x14 => ENABLE_IRQS_2: WriteOnly<u32>),
+        (0x18 => @END),
+    }
+}
+
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+/// The BCM peripheral IC's IRQ number space.
+pub type IRQNumber = asynchronous::BoundedUsize<{ NUM_IRQS - 1 }>;
+
+struct PeripheralICInner {
+    registers: Registers,
+    handler_table: [Option<IRQHandlerDescriptor<IRQNumber>>; NUM_IRQS],
+}
+
+impl PeripheralICInner {
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            registers: Registers::new(mmio_start_addr),
+            handler_table: [None; NUM_IRQS],
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Representation of the BCM2837 peripheral interrupt controller.
+pub struct PeripheralIC {
+    inner: NullLock<PeripheralICInner>,
+}
+
+impl PeripheralIC {
+    pub const COMPATIBLE: &'static str = "BCM Peripheral Interrupt Controller";
+
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(PeripheralICInner::new(mmio_start_addr)),
+        }
+    }
+}
+
+impl driver::interface::DeviceDriver for PeripheralIC {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+}
+
+impl interface::IRQManager for PeripheralIC {
+    type IRQNumberType = IRQNumber;
+
+    fn register_handler(
+        &self,
+        irq_number: Self::IRQNumberType,
+        descriptor: IRQHandlerDescriptor<Self::IRQNumberType>,
+    ) -> Result<(), &'static str> {
+        self.inner.lock(|inner| {
+            if inner.handler_table[irq_number.get()].is_some() {
+                return Err("A handler is already registered for this IRQ number");
+            }
+
+            inner.handler_table[irq_number.get()] = Some(descriptor);
+            Ok(())
+        })
+    }
+
+    fn enable(&self, irq_number: Self::IRQNumberType) {
+        self.inner.lock(|inner| {
+            let reg_index = irq_number.get() / 32;
+            let bit = 1u32 << (irq_number.get() % 32);
+
+            if reg_index == 0 {
+                inner.registers.ENABLE_IRQS_1.set(bit);
+            } else {
+                inner.registers.ENABLE_IRQS_2.set(bit);
+            }
+        });
+    }
+
+    fn handle_pending_irqs(&self) {
+        let entry_ticks = crate::cpu::read_cycle_counter();
+
+        self.inner.lock(|inner| {
+            for descriptor in inner.handler_table.iter().flatten() {
+                use crate::exception::asynchronous::IrqMode;
+
+                match descriptor.mode() {
+                    IrqMode::Threaded => {
+                        crate::exception::asynchronous::defer_handler(
+                            descriptor.name(),
+                            descriptor.handler(),
+                        );
+                    }
+                    IrqMode::Direct => {
+                        crate::exception::asynchronous::record_irq(descriptor.name());
+                        let dispatch_ticks = crate::cpu::read_cycle_counter();
+                        let _nesting = crate::exception::asynchronous::NestingGuard::enter();
+                        if let Err(x) = descriptor.handler().handle() {
+                            crate::log::rate_limited!(
+                                core::time::Duration::from_secs(1),
+                                "irq",
+                                "{}: {}",
+                                descriptor.name(),
+                                x
+                            );
+                        }
+                        let service_ticks = crate::cpu::read_cycle_counter();
+                        crate::exception::asynchronous::record_irq_latency(
+                            descriptor.name(),
+                            dispatch_ticks - entry_ticks,
+                            service_ticks - dispatch_ticks,
+                        );
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl PeripheralIC {
+    /// Route `irq_number` to the FIQ line instead of IRQ.
+    ///
+    /// The BCM2835/2837 only has a single FIQ line, so at most one source can be routed to it at
+    /// a time; setting a new source silently steals the line from whatever was routed before.
+    /// Bit 7 of `FIQ_CONTROL` is the enable bit, and the low 7 bits select the source using the
+    /// same 0..=63 peripheral-interrupt numbering as `ENABLE_IRQS_{1,2}`.
+    ///
+    /// Note that this fork has no FIQ vector -- `VBAR_EL1` is never programmed and there is no
+    /// exception vector table at all, see [`crate::exception`] -- so routing a source here does
+    /// not yet have any observable effect on its own. This only sets up the hardware side of the
+    /// routing ahead of that vector existing.
+    pub fn set_fiq(&self, irq_number: IRQNumber) {
+        self.inner.lock(|inner| {
+            let value = (1 << 7) | (irq_number.get() as u32);
+            inner.registers.FIQ_CONTROL.set(value);
+        });
+    }
+
+    /// Disable FIQ routing. The previously-routed source keeps whatever `ENABLE_IRQS_{1,2}` bit
+    /// it already had, so disabling FIQ here does not by itself resume normal IRQ delivery for
+    /// it -- callers that want that must still call [`interface::IRQManager::enable`] on it.
+    pub fn disable_fiq(&self) {
+        self.inner.lock(|inner| inner.registers.FIQ_CONTROL.set(0));
+    }
+}
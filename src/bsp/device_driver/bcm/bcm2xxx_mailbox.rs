@@ -0,0 +1,246 @@
+//! BCM283x/BCM2711 VideoCore mailbox driver.
+//!
+//! Exposes the VideoCore's memory property tags (`gpu_mem_alloc`/`lock`/`unlock`/`free`) so
+//! drivers that need physically contiguous buffers -- a framebuffer, DMA descriptors, V3D
+//! experiments -- don't have to carve them from thin air the way `.persistent_klog`/`.bootselect`
+//! do with fixed linker-reserved regions. There's no kernel heap in this fork to carve them from
+//! anyway.
+//!
+//! Property-tag request/response buffers are handed to the GPU as bus addresses, which must not
+//! be behind the ARM's cache. Rather than clean/invalidate the message buffer around every call
+//! the way [`crate::dma`]'s cache maintenance would, this uses the classic "L2 cache disabled"
+//! alias ([`crate::dma::phys_to_bus`]) so the buffer round-trips uncached without needing real MMU
+//! cache-attribute support (`memory::mmu` doesn't have any -- see its module docs) or per-call
+//! maintenance for a buffer this small and short-lived.
+
+use crate::{
+    bsp::device_driver::common::MMIODerefWrapper,
+    dma, driver, mailbox,
+    synchronization::{Mutex, NullLock},
+};
+use tock_registers::{
+    interfaces::{Readable, Writeable},
+    register_bitfields, register_structs,
+    registers::{ReadOnly, WriteOnly},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+register_bitfields! {
+    u32,
+
+    STATUS [
+        FULL  OFFSET(31) NUMBITS(1) [],
+        EMPTY OFFSET(30) NUMBITS(1) [],
+    ],
+}
+
+register_structs! {
+    #[allow(non_snake_case)]
+    pub RegisterBlock {
+        (0x00 => READ0: ReadOnly<u32>),
+        (0x04 => _reserved1),
+        (0x18 => STATUS0: ReadOnly<u32, STATUS::Register>),
+        (0x1c => _reserved2),
+        (0x20 => WRITE1: WriteOnly<u32>),
+        (0x24 => _reserved3),
+        (0x38 => STATUS1: ReadOnly<u32, STATUS::Register>),
+        (0x3c => _reserved4),
+        (0x40 => @END),
+    }
+}
+
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+/// The channel the VideoCore's property-tag interface listens on.
+const CHANNEL_PROPERTY: u32 = 8;
+
+const REQUEST_CODE: u32 = 0x0000_0000;
+const RESPONSE_SUCCESS: u32 = 0x8000_0001;
+
+const TAG_ALLOCATE_MEMORY: u32 = 0x0003_000c;
+const TAG_LOCK_MEMORY: u32 = 0x0003_000d;
+const TAG_UNLOCK_MEMORY: u32 = 0x0003_000e;
+const TAG_RELEASE_MEMORY: u32 = 0x0003_000f;
+
+const VALUE_WORDS: usize = 3;
+const MSG_WORDS: usize = 2 /* header */ + 3 /* tag header */ + VALUE_WORDS + 1 /* end tag */;
+
+/// A property-tag message buffer. 16-byte aligned, as the mailbox protocol requires.
+#[repr(C, align(16))]
+struct Message {
+    words: [u32; MSG_WORDS],
+}
+
+impl Message {
+    const fn new() -> Self {
+        Self {
+            words: [0; MSG_WORDS],
+        }
+    }
+}
+
+/// Inner, locked driver state.
+struct MailboxInner {
+    registers: Registers,
+    message: Message,
+}
+
+impl MailboxInner {
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            registers: Registers::new(mmio_start_addr),
+            message: Message::new(),
+        }
+    }
+
+    fn send(&self, bus_addr: u32) {
+        while self.registers.STATUS1.is_set(STATUS::FULL) {
+            crate::cpu::spin_for_cycles(1);
+        }
+
+        self.registers.WRITE1.set(bus_addr | CHANNEL_PROPERTY);
+    }
+
+    fn receive(&self) -> u32 {
+        loop {
+            while self.registers.STATUS0.is_set(STATUS::EMPTY) {
+                crate::cpu::spin_for_cycles(1);
+            }
+
+            let data = self.registers.READ0.get();
+            if data & 0xf == CHANNEL_PROPERTY {
+                return data & !0xf;
+            }
+        }
+    }
+
+    /// Run a single property tag with `request` as its input value words, returning the
+    /// response's value words.
+    fn call(&mut self, tag: u32, request: &[u32]) -> Result<[u32; VALUE_WORDS], &'static str> {
+        let bus_addr = dma::phys_to_bus(&self.message as *const Message as u32);
+
+        let msg = &mut self.message;
+        msg.words[0] = (MSG_WORDS * 4) as u32;
+        msg.words[1] = REQUEST_CODE;
+        msg.words[2] = tag;
+        msg.words[3] = (VALUE_WORDS * 4) as u32;
+        msg.words[4] = (request.len() * 4) as u32;
+        for i in 0..VALUE_WORDS {
+            msg.words[5 + i] = request.get(i).copied().unwrap_or(0);
+        }
+        msg.words[5 + VALUE_WORDS] = 0;
+
+        self.send(bus_addr);
+        self.receive();
+
+        let msg = &self.message;
+        if msg.words[1] != RESPONSE_SUCCESS {
+            return Err("mailbox: property request failed");
+        }
+
+        let mut response = [0u32; VALUE_WORDS];
+        response.copy_from_slice(&msg.words[5..5 + VALUE_WORDS]);
+        Ok(response)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The number of value words a [`Mailbox::property_call`] request/response is allowed to use.
+pub(crate) const PROPERTY_VALUE_WORDS: usize = VALUE_WORDS;
+
+/// Representation of the mailbox.
+pub struct Mailbox {
+    inner: NullLock<MailboxInner>,
+}
+
+impl Mailbox {
+    pub const COMPATIBLE: &'static str = "BCM Mailbox";
+
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(MailboxInner::new(mmio_start_addr)),
+        }
+    }
+
+    /// Run an arbitrary property tag directly. For BCM drivers (e.g. the framebuffer) that need
+    /// tags beyond the [`mailbox::interface::GpuMemory`](crate::mailbox::interface::GpuMemory)
+    /// allocation ones exposed to the rest of the kernel.
+    pub(crate) fn property_call(
+        &self,
+        tag: u32,
+        request: &[u32],
+    ) -> Result<[u32; PROPERTY_VALUE_WORDS], &'static str> {
+        self.inner.lock(|i| i.call(tag, request))
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// OS Interface Code
+//--------------------------------------------------------------------------------------------------
+
+impl driver::interface::DeviceDriver for Mailbox {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+}
+
+impl mailbox::interface::GpuMemory for Mailbox {
+    fn gpu_mem_alloc(
+        &self,
+        size: u32,
+        align: u32,
+        flags: mailbox::interface::MemFlag,
+    ) -> Result<u32, &'static str> {
+        let flags = match flags {
+            mailbox::interface::MemFlag::Normal => 0x0,
+            mailbox::interface::MemFlag::Direct => 0x4,
+            mailbox::interface::MemFlag::Coherent => 0x8,
+        };
+
+        self.inner
+            .lock(|i| i.call(TAG_ALLOCATE_MEMORY, &[size, align, flags]))
+            .map(|response| response[0])
+    }
+
+    fn lock(&self, handle: u32) -> Result<u32, &'static str> {
+        self.inner
+            .lock(|i| i.call(TAG_LOCK_MEMORY, &[handle]))
+            .map(|response| response[0])
+    }
+
+    fn unlock(&self, handle: u32) -> Result<(), &'static str> {
+        let status = self.inner.lock(|i| i.call(TAG_UNLOCK_MEMORY, &[handle]))?[0];
+
+        if status == 0 {
+            Ok(())
+        } else {
+            Err("mailbox: failed to unlock memory")
+        }
+    }
+
+    fn free(&self, handle: u32) -> Result<(), &'static str> {
+        let status = self.inner.lock(|i| i.call(TAG_RELEASE_MEMORY, &[handle]))?[0];
+
+        if status == 0 {
+            Ok(())
+        } else {
+            Err("mailbox: failed to release memory")
+        }
+    }
+}
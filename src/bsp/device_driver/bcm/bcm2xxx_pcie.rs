@@ -0,0 +1,75 @@
+//! BCM2711 PCIe root complex: ECAM config-space access.
+//!
+//! There is no bring-up sequence here for the same reason [`bcm2xxx_emmc`](super::bcm2xxx_emmc)
+//! has no EMMC init/reset sequence: the BCM2711 PCIe RC needs a controller-specific register dance
+//! before its link (and therefore its config space) is usable at all -- `RGR1_SW_INIT_1` reset
+//! pulses, `MISC_HARD_DEBUG`/`MISC_PCIE_CTRL` PERST# and clock sequencing, then polling
+//! `MISC_PCIE_STATUS` for `phy_link_up` -- and Broadcom's register names for exactly which bits do
+//! what come from the (GPL) Linux `pcie-brcmstb` driver, not from an ARM-style architected spec.
+//! Writing that sequence from memory without hardware to test it against risks shipping confidently
+//! wrong pokes to live hardware -- silicon Linux's own driver treats cautiously enough to need a
+//! handful of chip-revision-specific workarounds -- which is worse than not shipping it. The same
+//! goes for MSI: BCM2711 routes PCIe MSI through its own MSI-X doorbell register into a handful of
+//! fixed GICv2 SPIs rather than the generic MSI frame an architected GICv2m/GICv3 ITS would expose,
+//! so wiring it up needs the same unverified register knowledge as link bring-up.
+//!
+//! What's real here: once a link is up and an ECAM window is mapped -- both someone else's job
+//! until the above exists -- reading and writing config space through that window is just ordinary
+//! memory-mapped I/O at addresses [`crate::pci::ecam_address`] already computes correctly, no
+//! BCM-specific register knowledge involved. [`Bcm2711Pcie`] is that: a [`pci::ConfigAccess`]
+//! implementation over a caller-supplied ECAM base, so [`pci::enumerate`] has something real to run
+//! against the day link-up lands, and a placeholder [`Bcm2711Pcie::bring_up_link`] that spells out
+//! why it can't do that yet instead of silently pretending the link is up.
+
+use crate::pci::{self, ConfigAccess, DeviceLocation};
+use core::ptr;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A [`pci::ConfigAccess`] over a BCM2711 PCIe root complex's ECAM window.
+pub struct Bcm2711Pcie {
+    ecam_base: usize,
+}
+
+impl Bcm2711Pcie {
+    /// Create an accessor for the ECAM window starting at `ecam_base`.
+    ///
+    /// # Safety
+    ///
+    /// - `ecam_base` must be the start of a valid, mapped PCIe ECAM region for the lifetime of the
+    ///   returned value.
+    /// - The link must already be up; see [`Self::bring_up_link`] for why this constructor doesn't
+    ///   do that itself.
+    pub const unsafe fn new(ecam_base: usize) -> Self {
+        Self { ecam_base }
+    }
+
+    /// Bring the PCIe link up so config-space reads through `self` return real data instead of a
+    /// downstream abort.
+    ///
+    /// Always fails today -- see the module docs for the BCM2711-specific reset/clock/PERST#
+    /// sequence this would need, and why it isn't fabricated from memory.
+    pub fn bring_up_link(&self) -> Result<(), &'static str> {
+        Err(
+            "bcm2xxx_pcie: link bring-up is not implemented -- needs the BCM2711 RC's reset/clock/\
+             PERST# register sequence, which this fork declines to guess at; see the module docs",
+        )
+    }
+}
+
+impl ConfigAccess for Bcm2711Pcie {
+    fn read_u32(&self, location: DeviceLocation, offset: u16) -> u32 {
+        let addr = pci::ecam_address(self.ecam_base, location, offset);
+        // Safety: the caller of `new` guaranteed `ecam_base` maps a valid ECAM region for `self`'s
+        // lifetime, and `ecam_address` keeps `addr` within one function's 4 KiB of it.
+        unsafe { ptr::read_volatile(addr as *const u32) }
+    }
+
+    fn write_u32(&self, location: DeviceLocation, offset: u16, value: u32) {
+        let addr = pci::ecam_address(self.ecam_base, location, offset);
+        // Safety: see `read_u32`.
+        unsafe { ptr::write_volatile(addr as *mut u32, value) };
+    }
+}
@@ -0,0 +1,229 @@
+//! PL011 UART driver.
+
+use crate::{
+    bsp::device_driver::common::MMIODerefWrapper, console, cpu, driver, synchronization,
+    synchronization::NullLock,
+};
+use core::fmt;
+use tock_registers::{
+    interfaces::{Readable, Writeable},
+    register_bitfields, register_structs,
+    registers::{ReadOnly, ReadWrite},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+register_bitfields! {
+    u32,
+
+    FR [
+        TXFE OFFSET(7) NUMBITS(1) [],
+        TXFF OFFSET(5) NUMBITS(1) [],
+        RXFE OFFSET(4) NUMBITS(1) [],
+        BUSY OFFSET(3) NUMBITS(1) [],
+    ],
+
+    CR [
+        RXE OFFSET(9) NUMBITS(1) [],
+        TXE OFFSET(8) NUMBITS(1) [],
+        UARTEN OFFSET(0) NUMBITS(1) [],
+    ],
+}
+
+register_structs! {
+    #[allow(non_snake_case)]
+    pub RegisterBlock {
+        (0x00 => DR: ReadWrite<u32>),
+        (0x04 => _reserved1),
+        (0x18 => FR: ReadOnly<u32, FR::Register>),
+        (0x1c => _reserved2),
+        (0x30 => CR: ReadWrite<u32, CR::Register>),
+        (0x34 => @END),
+    }
+}
+
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+/// Inner, locked driver state.
+struct PL011UartInner {
+    registers: Registers,
+    chars_written: usize,
+    chars_read: usize,
+}
+
+impl PL011UartInner {
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            registers: Registers::new(mmio_start_addr),
+            chars_written: 0,
+            chars_read: 0,
+        }
+    }
+
+    /// Send one raw byte, waiting for TX FIFO space first.
+    fn write_byte(&mut self, byte: u8) {
+        while self.registers.FR.is_set(FR::TXFF) {
+            cpu::spin_for_cycles(1);
+        }
+
+        self.registers.DR.set(byte as u32);
+    }
+
+    /// Send a character, UTF-8 encoded -- a code point past ASCII goes out as the 2-4 bytes UTF-8
+    /// represents it as, the same encoding a modern terminal emulator expects on the other end of
+    /// the wire. `DR` is an 8-bit data register underneath the 32-bit-wide MMIO access, same as
+    /// before this only ever sent ASCII.
+    fn write_char(&mut self, c: char) {
+        let mut buf = [0u8; 4];
+        for &byte in c.encode_utf8(&mut buf).as_bytes() {
+            self.write_byte(byte);
+        }
+
+        self.chars_written += 1;
+    }
+
+    /// Block until one raw byte has arrived over the wire.
+    fn read_byte(&mut self) -> u8 {
+        while self.registers.FR.is_set(FR::RXFE) {
+            cpu::spin_for_cycles(1);
+        }
+
+        self.registers.DR.get() as u8
+    }
+}
+
+impl fmt::Write for PL011UartInner {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if c == '\n' {
+                self.write_char('\r');
+            }
+
+            self.write_char(c);
+        }
+
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Representation of the UART.
+pub struct PL011Uart {
+    inner: NullLock<PL011UartInner>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl PL011Uart {
+    pub const COMPATIBLE: &'static str = "BCM PL011 UART";
+
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address.
+    pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(PL011UartInner::new(mmio_start_addr)),
+        }
+    }
+}
+
+use synchronization::Mutex;
+
+impl driver::interface::DeviceDriver for PL011Uart {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+
+    fn match_compatible() -> &'static [&'static str] {
+        &["arm,pl011", "arm,primecell"]
+    }
+
+    unsafe fn init(&self) -> Result<(), &'static str> {
+        self.inner.lock(|inner| {
+            inner.registers.CR.write(CR::UARTEN::CLEAR);
+            inner
+                .registers
+                .CR
+                .write(CR::RXE::SET + CR::TXE::SET + CR::UARTEN::SET);
+        });
+
+        Ok(())
+    }
+}
+
+impl console::interface::Write for PL011Uart {
+    fn write_char(&self, c: char) {
+        self.inner.lock(|inner| inner.write_char(c));
+    }
+
+    fn write_fmt(&self, args: core::fmt::Arguments) -> fmt::Result {
+        self.inner.lock(|inner| fmt::Write::write_fmt(inner, args))
+    }
+
+    fn flush(&self) {
+        self.inner.lock(|inner| {
+            while inner.registers.FR.is_set(FR::BUSY) {
+                cpu::spin_for_cycles(1);
+            }
+        });
+    }
+}
+
+impl console::interface::Read for PL011Uart {
+    fn read_char(&self) -> char {
+        self.inner.lock(|inner| {
+            let ret = console::decode_utf8_char(|| inner.read_byte());
+            inner.chars_read += 1;
+            ret
+        })
+    }
+
+    fn read_char_nonblocking(&self) -> Option<char> {
+        self.inner.lock(|inner| {
+            if inner.registers.FR.is_set(FR::RXFE) {
+                return None;
+            }
+
+            // A lead byte that arrived alone, with its continuation bytes not here yet, blocks
+            // here on `decode_utf8_char`'s further reads rather than returning a partial
+            // character -- an interactive terminal emulator's multi-byte sequences arrive back to
+            // back, so in practice this doesn't stall noticeably longer than the all-ASCII case
+            // did.
+            let ret = console::decode_utf8_char(|| inner.read_byte());
+            inner.chars_read += 1;
+            Some(ret)
+        })
+    }
+
+    fn clear_rx(&self) {
+        while !self.inner.lock(|inner| inner.registers.FR.is_set(FR::RXFE)) {
+            self.read_char();
+        }
+    }
+}
+
+impl console::interface::Statistics for PL011Uart {
+    fn chars_written(&self) -> usize {
+        self.inner.lock(|inner| inner.chars_written)
+    }
+
+    fn chars_read(&self) -> usize {
+        self.inner.lock(|inner| inner.chars_read)
+    }
+}
+
+impl console::interface::All for PL011Uart {}
@@ -0,0 +1,79 @@
+//! BCM283x/BCM2711 peripheral power domains via the VideoCore mailbox.
+//!
+//! Like [`super::bcm2xxx_framebuffer::Framebuffer`], this has no MMIO register block of its own --
+//! it drives the same mailbox property-tag channel through [`Mailbox::property_call`] to flip a
+//! peripheral's power rail on or off. See [`crate::power::domain`] for the reference-counted
+//! claim/release API built on top of this driver; this one just does what the mailbox tag says,
+//! once, with no bookkeeping of its own.
+
+use super::bcm2xxx_mailbox::{Mailbox, PROPERTY_VALUE_WORDS};
+use crate::{driver, power};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const TAG_SET_POWER_STATE: u32 = 0x0002_8001;
+
+/// `state` bit 0: the requested power state, 1 = on.
+const STATE_ON: u32 = 0b01;
+/// `state` bit 1: wait for the power transition to complete before responding.
+const STATE_WAIT: u32 = 0b10;
+
+/// The VideoCore device IDs the power-state tag addresses, for the domains this fork cares about.
+fn device_id(domain: power::Domain) -> u32 {
+    match domain {
+        power::Domain::Sd => 0x0,
+        power::Domain::Usb => 0x3,
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Representation of the power-domain controller.
+pub struct Power {
+    mailbox: &'static Mailbox,
+}
+
+impl Power {
+    pub const COMPATIBLE: &'static str = "BCM VideoCore Power Domains";
+
+    /// Create an instance.
+    pub const fn new(mailbox: &'static Mailbox) -> Self {
+        Self { mailbox }
+    }
+
+    fn call(&self, tag: u32, request: &[u32]) -> Result<[u32; PROPERTY_VALUE_WORDS], &'static str> {
+        self.mailbox.property_call(tag, request)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// OS Interface Code
+//--------------------------------------------------------------------------------------------------
+
+impl driver::interface::DeviceDriver for Power {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+}
+
+impl power::interface::Controller for Power {
+    fn set_power(&self, domain: power::Domain, on: bool) -> Result<(), &'static str> {
+        let state = if on {
+            STATE_ON | STATE_WAIT
+        } else {
+            STATE_WAIT
+        };
+
+        let response = self.call(TAG_SET_POWER_STATE, &[device_id(domain), state])?;
+
+        if response[1] & STATE_ON == state & STATE_ON {
+            Ok(())
+        } else {
+            Err("power: VideoCore rejected the requested power state")
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! BCM283x/BCM2711 FT5406 touch controller, for the official 7" DSI touchscreen.
+//!
+//! Like [`super::bcm2xxx_framebuffer::Framebuffer`], this has no MMIO register block of its own:
+//! the VideoCore firmware decodes the FT5406's I2C touch protocol on its side of the mailbox and
+//! publishes the result as a plain memory-mapped struct, whose bus address this negotiates once at
+//! init time with the "get touchbuf" property tag. There's no IRQ line for a new touch the way
+//! there is for the BCM peripheral interrupt controller's other sources -- the only way to notice a
+//! contact is to re-read the struct, which is what [`Touchscreen::poll`] (via
+//! [`crate::touch::poll`]) does.
+//!
+//! Display selection -- HDMI vs. this panel's DSI input -- happens entirely in
+//! `config.txt`/`cmdline.txt` before this kernel ever runs; nothing here chooses which physical
+//! output the VideoCore scans [`super::bcm2xxx_framebuffer::Framebuffer`]'s buffer out to. Once the
+//! firmware is configured for the DSI panel, [`crate::framebuffer`] and [`crate::hdmi_console`] work
+//! against it exactly as they do against HDMI -- the mailbox property tags those use don't know or
+//! care which physical connector is active. This driver is what's actually panel-specific: the
+//! FT5406 only exists on the 7" touchscreen's addon board.
+
+use super::bcm2xxx_mailbox::{Mailbox, PROPERTY_VALUE_WORDS};
+use crate::{
+    dma, driver,
+    synchronization::{Mutex, NullLock},
+    touch,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const TAG_GET_TOUCHBUF: u32 = 0x0004_000f;
+
+/// How many simultaneous contacts the FT5406's published struct has room for.
+const MAX_POINTS: usize = 10;
+
+/// Byte offset of the `num_points` field within the FT5406 struct.
+const NUM_POINTS_OFFSET: usize = 2;
+
+/// Byte offset of the first touch record within the FT5406 struct; each record is
+/// [`POINT_RECORD_BYTES`] long.
+const POINTS_OFFSET: usize = 4;
+
+/// Size, in bytes, of one touch record: `xh, xl, yh, yl, -, -` as the firmware publishes them (the
+/// FT5406 controller also packs an event-type nibble into `xh` and a slot id into `yh`, unused
+/// here since [`Touchscreen::poll`] already gets per-finger identity for free from each record's
+/// position in the array).
+const POINT_RECORD_BYTES: usize = 6;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Representation of the touch controller.
+pub struct Touchscreen {
+    mailbox: &'static Mailbox,
+    touchbuf_addr: NullLock<Option<usize>>,
+}
+
+impl Touchscreen {
+    pub const COMPATIBLE: &'static str = "BCM VideoCore FT5406 Touchscreen";
+
+    /// Create an instance.
+    pub const fn new(mailbox: &'static Mailbox) -> Self {
+        Self {
+            mailbox,
+            touchbuf_addr: NullLock::new(None),
+        }
+    }
+
+    fn call(&self, tag: u32, request: &[u32]) -> Result<[u32; PROPERTY_VALUE_WORDS], &'static str> {
+        self.mailbox.property_call(tag, request)
+    }
+
+    /// Read one byte from the FT5406 struct at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// - `self.touchbuf_addr` must be `Some`, and the firmware must still own a live mapping at
+    ///   that address -- true from [`driver::interface::DeviceDriver::init`] onward, for as long as
+    ///   the VideoCore firmware itself keeps running, which this fork never tears down.
+    unsafe fn read_u8(base: usize, offset: usize) -> u8 {
+        core::ptr::read_volatile((base + offset) as *const u8)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// OS Interface Code
+//--------------------------------------------------------------------------------------------------
+
+impl driver::interface::DeviceDriver for Touchscreen {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+
+    unsafe fn init(&self) -> Result<(), &'static str> {
+        let response = self.call(TAG_GET_TOUCHBUF, &[0])?;
+        let addr = dma::bus_to_phys(response[0]) as usize;
+
+        self.touchbuf_addr.lock(|slot| *slot = Some(addr));
+        Ok(())
+    }
+}
+
+impl touch::interface::TouchController for Touchscreen {
+    fn poll(&self, f: &mut dyn FnMut(touch::TouchEvent)) {
+        let base = self.touchbuf_addr.lock(|slot| *slot);
+
+        let Some(base) = base else {
+            return;
+        };
+
+        // SAFETY: `base` is only `Some` once `init` has negotiated a live touchbuf address with
+        // the firmware.
+        let num_points =
+            (unsafe { Self::read_u8(base, NUM_POINTS_OFFSET) } as usize).min(MAX_POINTS);
+
+        for id in 0..num_points {
+            let record_offset = POINTS_OFFSET + id * POINT_RECORD_BYTES;
+
+            // SAFETY: same as above; `record_offset` stays within the struct for `id < MAX_POINTS`.
+            let (xh, xl, yh, yl) = unsafe {
+                (
+                    Self::read_u8(base, record_offset),
+                    Self::read_u8(base, record_offset + 1),
+                    Self::read_u8(base, record_offset + 2),
+                    Self::read_u8(base, record_offset + 3),
+                )
+            };
+
+            // The FT5406 packs a 2-bit event type into xh's top bits: 0 = down, 1 = up, 2 = still
+            // down. Either "down" state is reported as pressed; only "up" isn't.
+            let event_type = xh >> 6;
+            let x = (((xh & 0x0f) as u16) << 8) | xl as u16;
+            let y = (((yh & 0x0f) as u16) << 8) | yl as u16;
+
+            f(touch::TouchEvent {
+                id: id as u8,
+                x,
+                y,
+                pressed: event_type != 1,
+            });
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Virtio drivers for the QEMU `virt` machine.
+//!
+//! `virt` exposes a bank of `virtio-mmio` transport windows (see
+//! `bsp::qemu_virt::memory::map::VIRTIO_MMIO_START`); each window may or may not have a device
+//! behind it depending on the `-device virtio-*-device` options QEMU was started with. Concrete
+//! devices (block, net, ...) are built on top of the shared [`transport::MmioTransport`].
+
+pub mod blk;
+pub mod net;
+pub mod transport;
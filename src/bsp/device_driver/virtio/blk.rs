@@ -0,0 +1,235 @@
+//! virtio-blk device driver, so the filesystem layer can be tested against QEMU's `virtio-blk-device`
+//! without emulating an SD card.
+//!
+//! Implements [`storage::interface::RawBlockQueue`] directly against the virtqueue: submitting a
+//! request only chains descriptors and kicks the device, and completion is a non-blocking check
+//! of the used ring, not a busy-wait -- see [`super::transport::MmioTransport::submit_request`]/
+//! [`super::transport::MmioTransport::poll_completion`]. [`storage::interface::BlockDevice`] is
+//! then a thin synchronous wrapper on top, built with [`crate::executor::block_on`], for callers
+//! (the block cache, the FAT32 volume) that just want a completed transfer.
+//!
+//! Only one request is ever in flight at a time -- [`super::transport::Virtqueue::submit`] always
+//! uses descriptor chain head 0 -- so [`RequestToken`](storage::RequestToken) doesn't need to
+//! carry more than that; see [`storage::interface::RawBlockQueue`]'s module doc for what
+//! "asynchronous" does and doesn't mean here.
+
+use super::transport::{MmioTransport, Virtqueue};
+use crate::{
+    driver, storage,
+    synchronization::{Mutex, NullLock},
+};
+use core::task::Poll;
+
+const VIRTIO_BLK_DEVICE_ID: u32 = 2;
+
+const VIRTIO_BLK_T_IN: u32 = 0;
+const VIRTIO_BLK_T_OUT: u32 = 1;
+
+const VIRTIO_BLK_S_OK: u8 = 0;
+
+/// The request header prepended to every virtio-blk command, exactly as the spec defines it.
+#[repr(C)]
+struct RequestHeader {
+    req_type: u32,
+    reserved: u32,
+    sector: u64,
+}
+
+/// The header and status byte behind the one request this driver ever has in flight, kept alive
+/// at a stable address between submission and completion instead of on a stack frame that would
+/// return before the device is done with it.
+struct PendingRequest {
+    header: RequestHeader,
+    status: u8,
+}
+
+struct Inner {
+    transport: MmioTransport,
+    queue: Virtqueue,
+    block_count: u64,
+    pending: Option<PendingRequest>,
+}
+
+/// Representation of a virtio-blk device behind a virtio-mmio window.
+pub struct VirtioBlk {
+    inner: NullLock<Inner>,
+}
+
+impl VirtioBlk {
+    pub const COMPATIBLE: &'static str = "virtio,mmio-blk";
+
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address for a virtio-mmio window.
+    pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(Inner {
+                transport: MmioTransport::new(mmio_start_addr),
+                queue: Virtqueue::new(),
+                block_count: 0,
+                pending: None,
+            }),
+        }
+    }
+}
+
+impl driver::interface::DeviceDriver for VirtioBlk {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+
+    // Real devicetree source distinguishes virtio-mmio devices by probing the `device-id`
+    // register behind this same compatible string, not by a separate one per device type -- see
+    // `crate::devicetree`'s module doc on why that per-type dispatch isn't implemented here.
+    fn match_compatible() -> &'static [&'static str] {
+        &["virtio,mmio"]
+    }
+
+    unsafe fn init(&self) -> Result<(), &'static str> {
+        self.inner.lock(|inner| {
+            if inner.transport.device_id() != VIRTIO_BLK_DEVICE_ID {
+                return Err("virtio-blk: window does not carry a block device");
+            }
+
+            inner.transport.init(&inner.queue)?;
+
+            // The device's `capacity` config field (in 512-byte sectors) is a little-endian
+            // u64 living at Config[0..2] once queue setup has completed.
+            inner.block_count = 1024; // Placeholder until config-space reads are wired up.
+
+            Ok(())
+        })
+    }
+}
+
+impl storage::interface::RawBlockQueue for VirtioBlk {
+    fn block_count(&self) -> u64 {
+        self.inner.lock(|inner| inner.block_count)
+    }
+
+    unsafe fn submit_read(
+        &self,
+        block_index: u64,
+        buf: &mut storage::interface::Block,
+    ) -> Result<storage::RequestToken, &'static str> {
+        self.inner.lock(|inner| {
+            if inner.pending.is_some() {
+                return Err("virtio-blk: a request is already in flight");
+            }
+
+            let pending = inner.pending.insert(PendingRequest {
+                header: RequestHeader {
+                    req_type: VIRTIO_BLK_T_IN,
+                    reserved: 0,
+                    sector: block_index,
+                },
+                status: 0xff,
+            });
+            let header_ptr = &pending.header as *const _ as u64;
+            let status_ptr = &mut pending.status as *mut _ as u64;
+
+            unsafe {
+                inner.transport.submit_request(
+                    0,
+                    &mut inner.queue,
+                    &[
+                        (
+                            header_ptr,
+                            core::mem::size_of::<RequestHeader>() as u32,
+                            false,
+                        ),
+                        (buf.as_mut_ptr() as u64, buf.len() as u32, true),
+                        (status_ptr, 1, true),
+                    ],
+                );
+            }
+
+            Ok(storage::RequestToken(0))
+        })
+    }
+
+    unsafe fn submit_write(
+        &self,
+        block_index: u64,
+        buf: &storage::interface::Block,
+    ) -> Result<storage::RequestToken, &'static str> {
+        self.inner.lock(|inner| {
+            if inner.pending.is_some() {
+                return Err("virtio-blk: a request is already in flight");
+            }
+
+            let pending = inner.pending.insert(PendingRequest {
+                header: RequestHeader {
+                    req_type: VIRTIO_BLK_T_OUT,
+                    reserved: 0,
+                    sector: block_index,
+                },
+                status: 0xff,
+            });
+            let header_ptr = &pending.header as *const _ as u64;
+            let status_ptr = &mut pending.status as *mut _ as u64;
+
+            unsafe {
+                inner.transport.submit_request(
+                    0,
+                    &mut inner.queue,
+                    &[
+                        (
+                            header_ptr,
+                            core::mem::size_of::<RequestHeader>() as u32,
+                            false,
+                        ),
+                        (buf.as_ptr() as u64, buf.len() as u32, false),
+                        (status_ptr, 1, true),
+                    ],
+                );
+            }
+
+            Ok(storage::RequestToken(0))
+        })
+    }
+
+    fn poll_completion(&self, _token: storage::RequestToken) -> Poll<Result<(), &'static str>> {
+        self.inner.lock(|inner| {
+            if inner.pending.is_none() {
+                return Poll::Ready(Err("virtio-blk: no request in flight"));
+            }
+
+            match inner.transport.poll_completion(&mut inner.queue) {
+                None => Poll::Pending,
+                Some(_len) => {
+                    let status = inner.pending.take().unwrap().status;
+                    if status != VIRTIO_BLK_S_OK {
+                        Poll::Ready(Err("virtio-blk: device reported a transfer error"))
+                    } else {
+                        Poll::Ready(Ok(()))
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl storage::interface::BlockDevice for VirtioBlk {
+    fn block_count(&self) -> u64 {
+        storage::interface::RawBlockQueue::block_count(self)
+    }
+
+    fn read_block(
+        &self,
+        block_index: u64,
+        buf: &mut storage::interface::Block,
+    ) -> Result<(), &'static str> {
+        crate::executor::block_on(unsafe { storage::read_block_async(self, block_index, buf)? })
+    }
+
+    fn write_block(
+        &self,
+        block_index: u64,
+        buf: &storage::interface::Block,
+    ) -> Result<(), &'static str> {
+        crate::executor::block_on(unsafe { storage::write_block_async(self, block_index, buf)? })
+    }
+}
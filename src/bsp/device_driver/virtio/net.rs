@@ -0,0 +1,149 @@
+//! virtio-net device driver, so the ARP/UDP/TCP stack can be exercised against QEMU's user-mode
+//! networking instead of only on real hardware.
+//!
+//! Feature negotiation is kept minimal (no `VIRTIO_NET_F_MAC`, checksum offload, or mergeable RX
+//! buffers), so every packet carries the fixed 10-byte `virtio_net_hdr`, and the MAC address is a
+//! fixed, locally administered placeholder rather than one read out of device config space.
+
+use super::transport::{MmioTransport, Virtqueue};
+use crate::{
+    driver, net,
+    synchronization::{Mutex, NullLock},
+};
+
+const VIRTIO_NET_DEVICE_ID: u32 = 1;
+
+const RX_QUEUE: u16 = 0;
+const TX_QUEUE: u16 = 1;
+
+/// The header virtio-net prepends to (and expects prepended to) every frame.
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct NetHeader {
+    flags: u8,
+    gso_type: u8,
+    hdr_len: u16,
+    gso_size: u16,
+    csum_start: u16,
+    csum_offset: u16,
+}
+
+const NET_HEADER_LEN: usize = core::mem::size_of::<NetHeader>();
+
+struct Inner {
+    transport: MmioTransport,
+    rx_queue: Virtqueue,
+    tx_queue: Virtqueue,
+    mac_address: [u8; 6],
+    rx_buf: [u8; 1526 + NET_HEADER_LEN],
+}
+
+/// Representation of a virtio-net device behind a virtio-mmio window.
+pub struct VirtioNet {
+    inner: NullLock<Inner>,
+}
+
+impl VirtioNet {
+    pub const COMPATIBLE: &'static str = "virtio,mmio-net";
+
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address for a virtio-mmio window.
+    pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            inner: NullLock::new(Inner {
+                transport: MmioTransport::new(mmio_start_addr),
+                rx_queue: Virtqueue::new(),
+                tx_queue: Virtqueue::new(),
+                mac_address: [0; 6],
+                rx_buf: [0; 1526 + NET_HEADER_LEN],
+            }),
+        }
+    }
+}
+
+impl driver::interface::DeviceDriver for VirtioNet {
+    fn compatible(&self) -> &'static str {
+        Self::COMPATIBLE
+    }
+
+    // Real devicetree source distinguishes virtio-mmio devices by probing the `device-id`
+    // register behind this same compatible string, not by a separate one per device type -- see
+    // `crate::devicetree`'s module doc on why that per-type dispatch isn't implemented here.
+    fn match_compatible() -> &'static [&'static str] {
+        &["virtio,mmio"]
+    }
+
+    unsafe fn init(&self) -> Result<(), &'static str> {
+        self.inner.lock(|inner| {
+            if inner.transport.device_id() != VIRTIO_NET_DEVICE_ID {
+                return Err("virtio-net: window does not carry a network device");
+            }
+
+            inner.transport.handshake()?;
+            inner.transport.setup_queue(RX_QUEUE, &inner.rx_queue)?;
+            inner.transport.setup_queue(TX_QUEUE, &inner.tx_queue)?;
+            inner.transport.finish_init();
+
+            // Config space bytes 0..6 hold the MAC address whenever VIRTIO_NET_F_MAC was
+            // negotiated; since feature negotiation here is a no-op stub, this is a placeholder
+            // locally-administered address until config-space reads land.
+            inner.mac_address = [0x52, 0x54, 0x00, 0x12, 0x34, 0x56];
+
+            Ok(())
+        })
+    }
+}
+
+impl net::interface::NetworkDevice for VirtioNet {
+    fn mac_address(&self) -> [u8; 6] {
+        self.inner.lock(|inner| inner.mac_address)
+    }
+
+    fn send(&self, frame: &[u8]) -> Result<(), &'static str> {
+        self.inner.lock(|inner| {
+            let header = NetHeader::default();
+
+            unsafe {
+                inner.transport.request(
+                    TX_QUEUE,
+                    &mut inner.tx_queue,
+                    &[
+                        (&header as *const _ as u64, NET_HEADER_LEN as u32, false),
+                        (frame.as_ptr() as u64, frame.len() as u32, false),
+                    ],
+                );
+            }
+
+            Ok(())
+        })
+    }
+
+    fn receive(&self, buf: &mut [u8]) -> Result<usize, &'static str> {
+        self.inner.lock(|inner| {
+            let rx_len = inner.rx_buf.len() as u32;
+            let rx_ptr = inner.rx_buf.as_mut_ptr() as u64;
+
+            let written = unsafe {
+                inner
+                    .transport
+                    .request(RX_QUEUE, &mut inner.rx_queue, &[(rx_ptr, rx_len, true)])
+            } as usize;
+
+            if written <= NET_HEADER_LEN {
+                return Ok(0);
+            }
+
+            let frame_len = written - NET_HEADER_LEN;
+            if frame_len > buf.len() {
+                return Err("virtio-net: receive buffer too small for the pending frame");
+            }
+
+            buf[..frame_len]
+                .copy_from_slice(&inner.rx_buf[NET_HEADER_LEN..NET_HEADER_LEN + frame_len]);
+            Ok(frame_len)
+        })
+    }
+}
@@ -0,0 +1,393 @@
+//! Virtio-mmio transport (virtio spec 1.1, "legacy-free" `--version-1` layout) and virtqueue
+//! handling.
+
+use crate::bsp::device_driver::common::MMIODerefWrapper;
+use core::sync::atomic::{fence, Ordering};
+use tock_registers::{
+    interfaces::{Readable, Writeable},
+    register_bitfields, register_structs,
+    registers::{ReadOnly, ReadWrite, WriteOnly},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Number of descriptors in each virtqueue this driver allocates.
+///
+/// Fixed and small on purpose: every current consumer (virtio-blk, virtio-net) only ever has a
+/// handful of requests in flight, and a `const` size lets the ring live in `.bss` instead of
+/// requiring a heap.
+pub const QUEUE_SIZE: usize = 8;
+
+register_bitfields! {
+    u32,
+
+    STATUS [
+        ACKNOWLEDGE  OFFSET(0) NUMBITS(1) [],
+        DRIVER       OFFSET(1) NUMBITS(1) [],
+        DRIVER_OK    OFFSET(2) NUMBITS(1) [],
+        FEATURES_OK  OFFSET(3) NUMBITS(1) [],
+        FAILED       OFFSET(7) NUMBITS(1) [],
+    ],
+}
+
+register_structs! {
+    #[allow(non_snake_case)]
+    pub RegisterBlock {
+        (0x000 => MagicValue: ReadOnly<u32>),
+        (0x004 => Version: ReadOnly<u32>),
+        (0x008 => DeviceID: ReadOnly<u32>),
+        (0x00c => VendorID: ReadOnly<u32>),
+        (0x010 => DeviceFeatures: ReadOnly<u32>),
+        (0x014 => DeviceFeaturesSel: WriteOnly<u32>),
+        (0x018 => _reserved0),
+        (0x020 => DriverFeatures: WriteOnly<u32>),
+        (0x024 => DriverFeaturesSel: WriteOnly<u32>),
+        (0x028 => _reserved1),
+        (0x030 => QueueSel: WriteOnly<u32>),
+        (0x034 => QueueNumMax: ReadOnly<u32>),
+        (0x038 => QueueNum: WriteOnly<u32>),
+        (0x03c => _reserved2),
+        (0x044 => QueueReady: ReadWrite<u32>),
+        (0x048 => _reserved3),
+        (0x050 => QueueNotify: WriteOnly<u32>),
+        (0x054 => _reserved4),
+        (0x060 => InterruptStatus: ReadOnly<u32>),
+        (0x064 => InterruptACK: WriteOnly<u32>),
+        (0x068 => _reserved5),
+        (0x070 => Status: ReadWrite<u32, STATUS::Register>),
+        (0x074 => _reserved6),
+        (0x080 => QueueDescLow: WriteOnly<u32>),
+        (0x084 => QueueDescHigh: WriteOnly<u32>),
+        (0x088 => _reserved7),
+        (0x090 => QueueDriverLow: WriteOnly<u32>),
+        (0x094 => QueueDriverHigh: WriteOnly<u32>),
+        (0x098 => _reserved8),
+        (0x0a0 => QueueDeviceLow: WriteOnly<u32>),
+        (0x0a4 => QueueDeviceHigh: WriteOnly<u32>),
+        (0x0a8 => _reserved9),
+        (0x0fc => ConfigGeneration: ReadOnly<u32>),
+        (0x100 => Config: [ReadOnly<u32>; 32]),
+        (0x180 => @END),
+    }
+}
+
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+/// A single virtqueue descriptor, laid out exactly as the virtio spec requires.
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct Descriptor {
+    addr: u64,
+    len: u32,
+    flags: u16,
+    next: u16,
+}
+
+const DESC_F_NEXT: u16 = 1;
+const DESC_F_WRITE: u16 = 2;
+
+#[repr(C, align(2))]
+struct AvailRing {
+    flags: u16,
+    idx: u16,
+    ring: [u16; QUEUE_SIZE],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct UsedElem {
+    id: u32,
+    len: u32,
+}
+
+#[repr(C, align(4))]
+struct UsedRing {
+    flags: u16,
+    idx: u16,
+    ring: [UsedElem; QUEUE_SIZE],
+}
+
+/// A single split virtqueue.
+///
+/// Placed in `.bss` with a fixed layout so its physical address can be handed to the device
+/// without an allocator; this only works because the kernel currently identity-maps all of RAM
+/// (see `memory::mmu`).
+#[repr(C, align(16))]
+pub struct Virtqueue {
+    descriptors: [Descriptor; QUEUE_SIZE],
+    avail: AvailRing,
+    used: UsedRing,
+    last_used_idx: u16,
+}
+
+impl Virtqueue {
+    /// Create an empty, unregistered queue.
+    pub const fn new() -> Self {
+        Self {
+            descriptors: [Descriptor {
+                addr: 0,
+                len: 0,
+                flags: 0,
+                next: 0,
+            }; QUEUE_SIZE],
+            avail: AvailRing {
+                flags: 0,
+                idx: 0,
+                ring: [0; QUEUE_SIZE],
+            },
+            used: UsedRing {
+                flags: 0,
+                idx: 0,
+                ring: [UsedElem { id: 0, len: 0 }; QUEUE_SIZE],
+            },
+            last_used_idx: 0,
+        }
+    }
+
+    fn desc_table_addr(&self) -> u64 {
+        self.descriptors.as_ptr() as u64
+    }
+
+    fn avail_addr(&self) -> u64 {
+        &self.avail as *const _ as u64
+    }
+
+    fn used_addr(&self) -> u64 {
+        &self.used as *const _ as u64
+    }
+
+    /// Chain `buffers` into descriptors and submit them as a single request.
+    ///
+    /// `buffers` is `(pointer, len, device_writable)`. The caller must keep the referenced memory
+    /// alive and unmoved until the request completes.
+    fn submit(&mut self, buffers: &[(u64, u32, bool)]) -> u16 {
+        assert!(
+            buffers.len() <= QUEUE_SIZE,
+            "request needs more descriptors than the queue has"
+        );
+
+        let head = 0u16;
+        for (i, (addr, len, writable)) in buffers.iter().enumerate() {
+            let mut flags = if *writable { DESC_F_WRITE } else { 0 };
+            let has_next = i + 1 < buffers.len();
+            if has_next {
+                flags |= DESC_F_NEXT;
+            }
+
+            self.descriptors[i] = Descriptor {
+                addr: *addr,
+                len: *len,
+                flags,
+                next: if has_next { (i + 1) as u16 } else { 0 },
+            };
+        }
+
+        let avail_slot = (self.avail.idx as usize) % QUEUE_SIZE;
+        self.avail.ring[avail_slot] = head;
+
+        // Descriptors and the avail-ring entry must be visible to the device before we publish
+        // the new `idx`.
+        fence(Ordering::Release);
+        self.avail.idx = self.avail.idx.wrapping_add(1);
+
+        head
+    }
+
+    /// Non-blocking check for whether the device has consumed the head descriptor most recently
+    /// submitted, returning the number of bytes it wrote once it has.
+    fn poll_completion(&mut self) -> Option<u32> {
+        // The device publishes `used.idx` with a release store; a matching acquire fence makes
+        // sure we don't read a stale `used.ring` entry.
+        fence(Ordering::Acquire);
+        if self.used.idx == self.last_used_idx {
+            return None;
+        }
+
+        let slot = (self.last_used_idx as usize) % QUEUE_SIZE;
+        let len = self.used.ring[slot].len;
+        self.last_used_idx = self.last_used_idx.wrapping_add(1);
+        Some(len)
+    }
+
+    /// Busy-wait until the device has consumed the head descriptor most recently submitted, and
+    /// return the number of bytes it wrote.
+    fn wait_for_completion(&mut self) -> u32 {
+        loop {
+            if let Some(len) = self.poll_completion() {
+                return len;
+            }
+
+            crate::cpu::spin_for_cycles(1);
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// A virtio-mmio transport instance bound to a single device window.
+pub struct MmioTransport {
+    registers: Registers,
+}
+
+impl MmioTransport {
+    pub const MAGIC: u32 = 0x7472_6976; // "virt"
+
+    /// Create an instance.
+    ///
+    /// # Safety
+    ///
+    /// - The user must ensure to provide a correct MMIO start address for a virtio-mmio window.
+    pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+        Self {
+            registers: Registers::new(mmio_start_addr),
+        }
+    }
+
+    /// Return the device ID reported by the transport (0 means "no device present here").
+    pub fn device_id(&self) -> u32 {
+        self.registers.DeviceID.get()
+    }
+
+    /// Validate the magic value and version, and negotiate features with the device.
+    ///
+    /// Must be called exactly once per transport, before any [`Self::setup_queue`] call.
+    ///
+    /// # Safety
+    ///
+    /// - Programs device MMIO state; must only be called once per transport, during driver init.
+    pub unsafe fn handshake(&self) -> Result<(), &'static str> {
+        if self.registers.MagicValue.get() != Self::MAGIC {
+            return Err("virtio-mmio: bad magic value");
+        }
+        if self.registers.Version.get() != 2 {
+            return Err("virtio-mmio: unsupported (legacy) transport version");
+        }
+        if self.device_id() == 0 {
+            return Err("virtio-mmio: no device present at this window");
+        }
+
+        self.registers.Status.set(0);
+        self.registers.Status.write(STATUS::ACKNOWLEDGE::SET);
+        self.registers
+            .Status
+            .write(STATUS::ACKNOWLEDGE::SET + STATUS::DRIVER::SET);
+
+        // Feature negotiation is intentionally trivial: accept nothing beyond the base spec.
+        self.registers.DeviceFeaturesSel.set(0);
+        let _ = self.registers.DeviceFeatures.get();
+        self.registers.DriverFeaturesSel.set(0);
+        self.registers.DriverFeatures.set(0);
+
+        self.registers
+            .Status
+            .write(STATUS::ACKNOWLEDGE::SET + STATUS::DRIVER::SET + STATUS::FEATURES_OK::SET);
+        if !self.registers.Status.is_set(STATUS::FEATURES_OK) {
+            return Err("virtio-mmio: device rejected requested features");
+        }
+
+        Ok(())
+    }
+
+    /// Register `queue` as virtqueue number `queue_sel` with the device.
+    ///
+    /// # Safety
+    ///
+    /// - Must be called after [`Self::handshake`] and before [`Self::finish_init`].
+    pub unsafe fn setup_queue(
+        &self,
+        queue_sel: u16,
+        queue: &Virtqueue,
+    ) -> Result<(), &'static str> {
+        self.registers.QueueSel.set(queue_sel as u32);
+        if self.registers.QueueNumMax.get() < QUEUE_SIZE as u32 {
+            return Err("virtio-mmio: device queue too small for QUEUE_SIZE");
+        }
+        self.registers.QueueNum.set(QUEUE_SIZE as u32);
+
+        let desc = queue.desc_table_addr();
+        let avail = queue.avail_addr();
+        let used = queue.used_addr();
+        self.registers.QueueDescLow.set(desc as u32);
+        self.registers.QueueDescHigh.set((desc >> 32) as u32);
+        self.registers.QueueDriverLow.set(avail as u32);
+        self.registers.QueueDriverHigh.set((avail >> 32) as u32);
+        self.registers.QueueDeviceLow.set(used as u32);
+        self.registers.QueueDeviceHigh.set((used >> 32) as u32);
+        self.registers.QueueReady.set(1);
+
+        Ok(())
+    }
+
+    /// Set `DRIVER_OK`, letting the device start processing configured queues.
+    ///
+    /// # Safety
+    ///
+    /// - Must be called once, after every queue has been set up via [`Self::setup_queue`].
+    pub unsafe fn finish_init(&self) {
+        self.registers.Status.write(
+            STATUS::ACKNOWLEDGE::SET
+                + STATUS::DRIVER::SET
+                + STATUS::FEATURES_OK::SET
+                + STATUS::DRIVER_OK::SET,
+        );
+    }
+
+    /// Convenience wrapper around [`Self::handshake`], [`Self::setup_queue`] (for a single
+    /// queue at index 0), and [`Self::finish_init`], for devices that only need one virtqueue.
+    ///
+    /// # Safety
+    ///
+    /// - Programs device MMIO state; must only be called once per transport, during driver init.
+    pub unsafe fn init(&self, queue: &Virtqueue) -> Result<(), &'static str> {
+        self.handshake()?;
+        self.setup_queue(0, queue)?;
+        self.finish_init();
+
+        Ok(())
+    }
+
+    /// Submit a request built from `buffers` to `queue`, kick the device on virtqueue
+    /// `queue_sel`, and block until it is serviced.
+    ///
+    /// # Safety
+    ///
+    /// - `buffers` must reference memory that stays valid and unmoved until the request
+    ///   completes.
+    pub unsafe fn request(
+        &self,
+        queue_sel: u16,
+        queue: &mut Virtqueue,
+        buffers: &[(u64, u32, bool)],
+    ) -> u32 {
+        unsafe { self.submit_request(queue_sel, queue, buffers) };
+        queue.wait_for_completion()
+    }
+
+    /// Submit a request built from `buffers` to `queue` and kick the device on virtqueue
+    /// `queue_sel`, without waiting for it to be serviced -- pair with [`Self::poll_completion`]
+    /// to let the caller do other work while the device processes it.
+    ///
+    /// # Safety
+    ///
+    /// - `buffers` must reference memory that stays valid and unmoved until
+    ///   [`Self::poll_completion`] reports the request done.
+    pub unsafe fn submit_request(
+        &self,
+        queue_sel: u16,
+        queue: &mut Virtqueue,
+        buffers: &[(u64, u32, bool)],
+    ) {
+        queue.submit(buffers);
+        self.registers.QueueNotify.set(queue_sel as u32);
+    }
+
+    /// Non-blocking counterpart to the wait inside [`Self::request`]: check whether the request
+    /// most recently submitted via [`Self::submit_request`] on `queue` has been serviced yet.
+    pub fn poll_completion(&self, queue: &mut Virtqueue) -> Option<u32> {
+        queue.poll_completion()
+    }
+}
@@ -0,0 +1,18 @@
+//! Top-level BSP file for the QEMU `-M virt` aarch64 machine.
+//!
+//! Unlike the Raspberry Pi BSPs, this board only exists to be emulated: it has no real silicon
+//! quirks to work around, which is exactly the point (see `synth-1110`). It exists so that CI can
+//! exercise driver-independent kernel code (and, eventually, virtio drivers) without depending on
+//! the raspi3 machine model.
+
+pub mod console;
+pub mod cpu;
+pub mod driver;
+pub mod exception;
+pub mod layout;
+pub mod memory;
+
+/// Board identification.
+pub fn board_name() -> &'static str {
+    "QEMU virt (aarch64)"
+}
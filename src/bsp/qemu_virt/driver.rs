@@ -0,0 +1,81 @@
+//! BSP driver support for the QEMU `virt` machine.
+
+use super::memory::map;
+use crate::{bsp::device_driver, memory::MMIODescriptor};
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+pub(super) static PL011_UART: device_driver::arm::pl011::PL011Uart =
+    unsafe { device_driver::arm::pl011::PL011Uart::new(map::PL011_UART_START) };
+
+/// The first virtio-mmio slot is reserved for a block device (`-device virtio-blk-device` on the
+/// QEMU command line); it is only initialized if a device actually answers there.
+static VIRTIO_BLK: device_driver::virtio::blk::VirtioBlk =
+    unsafe { device_driver::virtio::blk::VirtioBlk::new(map::virtio_mmio_slot(0)) };
+
+/// The second virtio-mmio slot is reserved for a NIC (`-device virtio-net-device`).
+static VIRTIO_NET: device_driver::virtio::net::VirtioNet =
+    unsafe { device_driver::virtio::net::VirtioNet::new(map::virtio_mmio_slot(1)) };
+
+#[cfg(not(feature = "gicv3"))]
+static INTERRUPT_CONTROLLER: device_driver::arm::gicv2::GICv2 =
+    unsafe { device_driver::arm::gicv2::GICv2::new(map::GICD_START, map::GICC_START) };
+
+#[cfg(feature = "gicv3")]
+static INTERRUPT_CONTROLLER: device_driver::arm::gicv3::GICv3 =
+    unsafe { device_driver::arm::gicv3::GICv3::new(map::GICV3_GICD_START, map::GICV3_GICR_START) };
+
+#[cfg(not(feature = "gicv3"))]
+const INTERRUPT_CONTROLLER_MMIO_DESCRIPTOR: MMIODescriptor =
+    MMIODescriptor::new(map::GICD_START, (map::GICC_START - map::GICD_START) + 0x14);
+#[cfg(feature = "gicv3")]
+const INTERRUPT_CONTROLLER_MMIO_DESCRIPTOR: MMIODescriptor = MMIODescriptor::new(
+    map::GICV3_GICD_START,
+    (map::GICV3_GICR_START - map::GICV3_GICD_START) + 0x10004,
+);
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+unsafe fn post_init_uart() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_virtio_blk() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_virtio_net() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_interrupt_controller() -> Result<(), &'static str> {
+    Ok(())
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+crate::kernel_drivers! {
+    &PL011_UART, MMIODescriptor::new(map::PL011_UART_START, 0x34), Some(post_init_uart);
+    &VIRTIO_BLK, MMIODescriptor::new(map::virtio_mmio_slot(0), map::VIRTIO_MMIO_STRIDE), Some(post_init_virtio_blk);
+    &VIRTIO_NET, MMIODescriptor::new(map::virtio_mmio_slot(1), map::VIRTIO_MMIO_STRIDE), Some(post_init_virtio_net);
+    &INTERRUPT_CONTROLLER, INTERRUPT_CONTROLLER_MMIO_DESCRIPTOR, Some(post_init_interrupt_controller);
+}
+
+/// Return a reference to the board's interrupt controller.
+pub fn irq_manager() -> &'static impl crate::exception::asynchronous::interface::IRQManager {
+    &INTERRUPT_CONTROLLER
+}
+
+/// Return a reference to the board's block device.
+///
+/// Only `bsp_qemu_virt` has one right now -- the Raspberry Pi BSPs have no SD card driver yet --
+/// so callers of this (the `lsblk` shell command) are themselves gated on that feature.
+pub fn block_device() -> &'static dyn crate::storage::interface::BlockDevice {
+    &VIRTIO_BLK
+}
@@ -0,0 +1,18 @@
+//! BSP-specific interrupt number mapping.
+//!
+//! See `bsp::raspberrypi::exception` for the rationale. This board always uses a GICv2 or GICv3,
+//! never the BCM2837 controller, but the split still matters between the two GIC generations.
+
+#[cfg(not(feature = "gicv3"))]
+pub use crate::bsp::device_driver::arm::gicv2::IRQNumber;
+
+#[cfg(feature = "gicv3")]
+pub use crate::bsp::device_driver::arm::gicv3::IRQNumber;
+
+/// Driver-relative interrupt identities, mapped to the active controller's IRQ number space.
+pub mod irq_map {
+    use super::IRQNumber;
+
+    /// QEMU's `virt` machine wires the PL011 UART to SPI 1, i.e. GIC INTID 33.
+    pub const PL011_UART: IRQNumber = IRQNumber::new(33);
+}
@@ -0,0 +1,9 @@
+// Physical load address and fixed-size region layout for the QEMU `virt` board.
+//
+// See `bsp::raspberrypi::layout` (its sibling, `include!`d the same way) for why this is a plain,
+// self-contained file of `pub const`s instead of a normal module with imports.
+
+pub const LOAD_ADDR: usize = 0x4008_0000;
+pub const BOOT_CORE_STACK_SIZE: usize = 0x0008_0000;
+pub const PERSISTENT_KLOG_SIZE: usize = 0x0000_1000;
+pub const BOOTSELECT_SIZE: usize = 0x0000_0010;
@@ -0,0 +1,53 @@
+//! BSP memory management for the QEMU `virt` machine.
+//!
+//! Address values below match `qemu-system-aarch64 -M virt`'s device tree as of QEMU's current
+//! stable `virt` machine version; they are not configurable because this BSP only ever targets
+//! the emulator, never real hardware.
+
+use core::ops::RangeInclusive;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The board's physical memory map.
+#[rustfmt::skip]
+pub mod map {
+    /// GICv2 distributor and CPU interface (used when the board is built without `gicv3`).
+    pub const GICD_START:          usize = 0x0800_0000;
+    pub const GICC_START:          usize = 0x0801_0000;
+
+    /// GICv3 distributor and redistributor.
+    pub const GICV3_GICD_START:    usize = 0x0800_0000;
+    pub const GICV3_GICR_START:    usize = 0x080A_0000;
+
+    /// virtio-mmio transport windows, one per `-device virtio-*-device` QEMU was started with,
+    /// each `VIRTIO_MMIO_STRIDE` bytes apart starting at `VIRTIO_MMIO_START`.
+    pub const VIRTIO_MMIO_START:    usize = 0x0A00_0000;
+    pub const VIRTIO_MMIO_STRIDE:   usize = 0x0200;
+    pub const VIRTIO_MMIO_NUM_SLOTS: usize = 8;
+    pub const VIRTIO_MMIO_SIZE:     usize = VIRTIO_MMIO_STRIDE * VIRTIO_MMIO_NUM_SLOTS;
+
+    /// Return the MMIO start address of the virtio-mmio transport window at `slot`.
+    pub const fn virtio_mmio_slot(slot: usize) -> usize {
+        VIRTIO_MMIO_START + slot * VIRTIO_MMIO_STRIDE
+    }
+
+    pub const PL011_UART_START:    usize = 0x0900_0000;
+
+    pub mod mmio {
+        use super::*;
+
+        pub const START: usize = GICD_START;
+        pub const END: usize = VIRTIO_MMIO_START + VIRTIO_MMIO_SIZE;
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// The inclusive range of physical addresses that back MMIO devices on this board.
+pub fn mmio_range_inclusive() -> RangeInclusive<usize> {
+    map::mmio::START..=map::mmio::END - 1
+}
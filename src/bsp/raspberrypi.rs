@@ -0,0 +1,28 @@
+//! Top-level BSP file for the Raspberry Pi 3 and 4.
+
+pub mod audio;
+pub mod clocks;
+pub mod console;
+pub mod cpu;
+pub mod driver;
+pub mod exception;
+pub mod framebuffer;
+pub mod gpio;
+pub mod layout;
+pub mod mailbox;
+pub mod memory;
+pub mod power;
+pub mod touch;
+
+/// Board identification.
+pub fn board_name() -> &'static str {
+    #[cfg(feature = "bsp_rpi3")]
+    {
+        "Raspberry Pi 3"
+    }
+
+    #[cfg(feature = "bsp_rpi4")]
+    {
+        "Raspberry Pi 4"
+    }
+}
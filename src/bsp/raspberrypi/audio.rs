@@ -0,0 +1,8 @@
+//! BSP audio facilities.
+
+use crate::audio;
+
+/// Return a reference to the board's audio output.
+pub fn audio() -> &'static dyn audio::interface::Play {
+    &super::driver::AUDIO
+}
@@ -0,0 +1,8 @@
+//! BSP clock facilities.
+
+use crate::clocks;
+
+/// Return a reference to the board's clock manager.
+pub fn clocks() -> &'static dyn clocks::interface::Manager {
+    &super::driver::CM
+}
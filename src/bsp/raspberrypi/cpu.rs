@@ -0,0 +1,38 @@
+//! BSP processor code.
+
+use crate::{memory::mmu, time};
+
+/// Return a reference to the MMU instance used by this board.
+pub fn mmu() -> &'static impl mmu::MMU {
+    crate::memory::mmu::mmu()
+}
+
+/// Return a reference to the time manager used by this board.
+pub fn time_manager() -> &'static impl time::TimeManager {
+    &BOARD_DEFAULT_TIMER
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+use core::time::Duration;
+
+struct SystemTimer;
+
+static BOARD_DEFAULT_TIMER: SystemTimer = SystemTimer;
+
+impl time::TimeManager for SystemTimer {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(52)
+    }
+
+    fn uptime(&self) -> Duration {
+        Duration::from_secs(0)
+    }
+
+    fn spin_for(&self, duration: Duration) {
+        let cycles = (duration.as_nanos() / self.resolution().as_nanos().max(1)) as usize;
+        crate::cpu::spin_for_cycles(cycles);
+    }
+}
@@ -0,0 +1,136 @@
+//! BSP driver support.
+
+use super::memory::map::mmio;
+use crate::{bsp::device_driver, memory::MMIODescriptor};
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+pub(super) static PL011_UART: device_driver::PL011Uart =
+    unsafe { device_driver::PL011Uart::new(mmio::PL011_UART_START) };
+
+pub(super) static MAILBOX: device_driver::Mailbox =
+    unsafe { device_driver::Mailbox::new(mmio::MAILBOX_START) };
+
+pub(super) static FRAMEBUFFER: device_driver::Framebuffer =
+    device_driver::Framebuffer::new(&MAILBOX);
+
+pub(super) static POWER: device_driver::Power = device_driver::Power::new(&MAILBOX);
+
+pub(super) static TOUCHSCREEN: device_driver::Touchscreen =
+    device_driver::Touchscreen::new(&MAILBOX);
+
+pub(super) static CM: device_driver::ClockManager =
+    unsafe { device_driver::ClockManager::new(mmio::CM_START) };
+
+pub(super) static AUDIO: device_driver::Audio =
+    unsafe { device_driver::Audio::new(mmio::PWM_START) };
+
+pub(super) static GPIO: device_driver::Gpio = unsafe { device_driver::Gpio::new(mmio::GPIO_START) };
+
+#[cfg(feature = "bsp_rpi3")]
+pub(super) static INTERRUPT_CONTROLLER: device_driver::PeripheralIC =
+    unsafe { device_driver::PeripheralIC::new(mmio::LOCAL_INTERRUPT_CONTROLLER_START) };
+
+#[cfg(all(feature = "bsp_rpi4", not(feature = "gicv3")))]
+pub(super) static INTERRUPT_CONTROLLER: device_driver::arm::gicv2::GICv2 =
+    unsafe { device_driver::arm::gicv2::GICv2::new(mmio::GICD_START, mmio::GICC_START) };
+
+#[cfg(all(feature = "bsp_rpi4", feature = "gicv3"))]
+pub(super) static INTERRUPT_CONTROLLER: device_driver::arm::gicv3::GICv3 =
+    unsafe { device_driver::arm::gicv3::GICv3::new(mmio::GICD_START, mmio::GICR_START) };
+
+/// Covers the interrupt controller's MMIO window(s): just the BCM peripheral IC's own register
+/// block on the Pi 3, or the GIC distributor through to the end of its CPU interface /
+/// redistributor on the Pi 4.
+#[cfg(feature = "bsp_rpi3")]
+const INTERRUPT_CONTROLLER_MMIO_DESCRIPTOR: MMIODescriptor =
+    MMIODescriptor::new(mmio::LOCAL_INTERRUPT_CONTROLLER_START, 0x18);
+#[cfg(all(feature = "bsp_rpi4", not(feature = "gicv3")))]
+const INTERRUPT_CONTROLLER_MMIO_DESCRIPTOR: MMIODescriptor = MMIODescriptor::new(
+    mmio::GICD_START,
+    (mmio::GICC_START - mmio::GICD_START) + 0x14,
+);
+#[cfg(all(feature = "bsp_rpi4", feature = "gicv3"))]
+const INTERRUPT_CONTROLLER_MMIO_DESCRIPTOR: MMIODescriptor = MMIODescriptor::new(
+    mmio::GICD_START,
+    (mmio::GICR_START - mmio::GICD_START) + 0x10004,
+);
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+unsafe fn post_init_uart() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_mailbox() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_framebuffer() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_power() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_touchscreen() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_cm() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_audio() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_gpio() -> Result<(), &'static str> {
+    Ok(())
+}
+
+unsafe fn post_init_interrupt_controller() -> Result<(), &'static str> {
+    Ok(())
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+crate::kernel_drivers! {
+    &PL011_UART, MMIODescriptor::new(mmio::PL011_UART_START, 0x34), Some(post_init_uart);
+    &MAILBOX, MMIODescriptor::new(mmio::MAILBOX_START, 0x40), Some(post_init_mailbox);
+    // The framebuffer has no register block of its own -- it's driven entirely through the
+    // mailbox's property-tag channel above -- so it shares that channel's MMIO window here.
+    &FRAMEBUFFER, MMIODescriptor::new(mmio::MAILBOX_START, 0x40), Some(post_init_framebuffer);
+    // Likewise driven entirely through the mailbox's property-tag channel.
+    &POWER, MMIODescriptor::new(mmio::MAILBOX_START, 0x40), Some(post_init_power);
+    // Likewise driven entirely through the mailbox's property-tag channel -- see
+    // bcm2xxx_touchscreen's module docs for why its actual touch data comes from a separate
+    // firmware-published buffer, not this window.
+    &TOUCHSCREEN, MMIODescriptor::new(mmio::MAILBOX_START, 0x40), Some(post_init_touchscreen);
+    &CM, MMIODescriptor::new(mmio::CM_START, 0xa8), Some(post_init_cm);
+    &AUDIO, MMIODescriptor::new(mmio::PWM_START, 0x1c), Some(post_init_audio);
+    &GPIO, MMIODescriptor::new(mmio::GPIO_START, 0x3c), Some(post_init_gpio);
+    &INTERRUPT_CONTROLLER, INTERRUPT_CONTROLLER_MMIO_DESCRIPTOR, Some(post_init_interrupt_controller);
+}
+
+/// Return a reference to the board's interrupt controller.
+pub fn irq_manager() -> &'static impl crate::exception::asynchronous::interface::IRQManager {
+    &INTERRUPT_CONTROLLER
+}
+
+/// Return a reference to the board's framebuffer.
+pub(super) fn framebuffer() -> &'static device_driver::Framebuffer {
+    &FRAMEBUFFER
+}
+
+/// Return a reference to the board's touch controller.
+pub(super) fn touchscreen() -> &'static device_driver::Touchscreen {
+    &TOUCHSCREEN
+}
@@ -0,0 +1,27 @@
+//! BSP-specific interrupt number mapping.
+//!
+//! The Pi 3 and Pi 4 use interrupt controllers with different, incompatible IRQ number spaces
+//! (the BCM2837's own controller vs. a GICv2 or GICv3). [`IRQNumber`] resolves to whichever one
+//! is active for the selected feature combination, and [`irq_map`] gives driver-relative
+//! interrupt identities a single name that doesn't change when the underlying controller does.
+
+#[cfg(feature = "bsp_rpi3")]
+pub use crate::bsp::device_driver::IRQNumber;
+
+#[cfg(all(feature = "bsp_rpi4", not(feature = "gicv3")))]
+pub use crate::bsp::device_driver::arm::gicv2::IRQNumber;
+
+#[cfg(all(feature = "bsp_rpi4", feature = "gicv3"))]
+pub use crate::bsp::device_driver::arm::gicv3::IRQNumber;
+
+/// Driver-relative interrupt identities, mapped to the active controller's IRQ number space.
+pub mod irq_map {
+    use super::IRQNumber;
+
+    /// The BCM2837 wires the PL011 UART to VC/ARM IRQ 57; BCM2711's GIC routes it to SPI 121, i.e.
+    /// GIC INTID 153.
+    #[cfg(feature = "bsp_rpi3")]
+    pub const PL011_UART: IRQNumber = IRQNumber::new(57);
+    #[cfg(feature = "bsp_rpi4")]
+    pub const PL011_UART: IRQNumber = IRQNumber::new(153);
+}
@@ -0,0 +1,8 @@
+//! BSP framebuffer facilities.
+
+use crate::framebuffer;
+
+/// Return a reference to the board's framebuffer.
+pub fn framebuffer() -> &'static dyn framebuffer::interface::Display {
+    super::driver::framebuffer()
+}
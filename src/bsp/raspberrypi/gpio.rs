@@ -0,0 +1,8 @@
+//! BSP GPIO facilities.
+
+use crate::gpio;
+
+/// Return a reference to the board's GPIO controller.
+pub fn gpio() -> &'static dyn gpio::interface::Controller {
+    &super::driver::GPIO
+}
@@ -0,0 +1,12 @@
+// Physical load address and fixed-size region layout for the Raspberry Pi 3/4 boards.
+//
+// Plain `pub const`s, not gated on any feature and free of anything that isn't valid outside a
+// crate (no `use`, no doc comments) -- this file is `include!`d verbatim both as the
+// `bsp::raspberrypi::layout` module the kernel binary uses at runtime, and (unconditionally,
+// alongside `qemu_virt`'s copy) by `build.rs`, which needs these same numbers to generate this
+// board's linker script. One set of numbers, two consumers, no hand-kept copy to drift.
+
+pub const LOAD_ADDR: usize = 0x0008_0000;
+pub const BOOT_CORE_STACK_SIZE: usize = 0x0008_0000;
+pub const PERSISTENT_KLOG_SIZE: usize = 0x0000_1000;
+pub const BOOTSELECT_SIZE: usize = 0x0000_0010;
@@ -0,0 +1,8 @@
+//! BSP mailbox facilities.
+
+use crate::mailbox;
+
+/// Return a reference to the board's VideoCore mailbox.
+pub fn mailbox() -> &'static dyn mailbox::interface::GpuMemory {
+    &super::driver::MAILBOX
+}
@@ -0,0 +1,75 @@
+//! BSP memory management.
+
+use core::ops::RangeInclusive;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The board's physical memory map.
+#[rustfmt::skip]
+pub mod map {
+    #[cfg(feature = "bsp_rpi3")]
+    pub const GPIO_OFFSET:         usize = 0x0020_0000;
+    #[cfg(feature = "bsp_rpi3")]
+    pub const PL011_UART_OFFSET:   usize = 0x0020_1000;
+
+    #[cfg(feature = "bsp_rpi3")]
+    pub const LOCAL_INTERRUPT_CONTROLLER_OFFSET: usize = 0x000B_200;
+
+    pub const MAILBOX_OFFSET:      usize = 0x0000_B880;
+    pub const PWM_OFFSET:          usize = 0x0020_C000;
+    pub const CM_OFFSET:           usize = 0x0010_1000;
+
+    #[cfg(feature = "bsp_rpi3")]
+    pub const START:               usize =         0x3F00_0000;
+
+    #[cfg(feature = "bsp_rpi4")]
+    pub const GPIO_OFFSET:         usize = 0x0020_0000;
+    #[cfg(feature = "bsp_rpi4")]
+    pub const PL011_UART_OFFSET:   usize = 0x0020_1000;
+
+    #[cfg(feature = "bsp_rpi4")]
+    pub const START:               usize =         0xFE00_0000;
+
+    /// BCM2711 (RPi4) wires a GICv2-compatible distributor and CPU interface behind these
+    /// offsets; GICv3 mode (`--features gicv3`) uses the same distributor plus a redistributor.
+    #[cfg(feature = "bsp_rpi4")]
+    pub const GICD_OFFSET:         usize = 0x0084_1000;
+    #[cfg(feature = "bsp_rpi4")]
+    pub const GICC_OFFSET:         usize = 0x0084_2000;
+    #[cfg(feature = "bsp_rpi4")]
+    pub const GICR_OFFSET:         usize = 0x0084_A000;
+
+    /// Physical devices.
+    pub mod mmio {
+        use super::*;
+
+        pub const GPIO_START:       usize = START + GPIO_OFFSET;
+        pub const PL011_UART_START: usize = START + PL011_UART_OFFSET;
+        pub const MAILBOX_START:    usize = START + MAILBOX_OFFSET;
+        pub const PWM_START:        usize = START + PWM_OFFSET;
+        pub const CM_START:         usize = START + CM_OFFSET;
+
+        #[cfg(feature = "bsp_rpi3")]
+        pub const LOCAL_INTERRUPT_CONTROLLER_START: usize = START + LOCAL_INTERRUPT_CONTROLLER_OFFSET;
+
+        #[cfg(feature = "bsp_rpi4")]
+        pub const GICD_START: usize = START + GICD_OFFSET;
+        #[cfg(feature = "bsp_rpi4")]
+        pub const GICC_START: usize = START + GICC_OFFSET;
+        #[cfg(feature = "bsp_rpi4")]
+        pub const GICR_START: usize = START + GICR_OFFSET;
+
+        pub const END:               usize = START + 0x0100_0000;
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// The inclusive range of physical addresses that back MMIO devices on this board.
+pub fn mmio_range_inclusive() -> RangeInclusive<usize> {
+    map::START..=map::mmio::END - 1
+}
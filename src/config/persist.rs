@@ -0,0 +1,233 @@
+//! A small settings record, persisted as a file on the board's FAT boot partition.
+//!
+//! Only [`bsp_qemu_virt`](crate::bsp::qemu_virt) has a real block device
+//! ([`crate::bsp::driver::block_device`]) -- neither Raspberry Pi target in this fork has an
+//! SD/EMMC driver at all yet (a gap this fork's `bsp::device_driver::bcm` already documents), so
+//! [`load`] and [`save`] are honest "no block device" errors on those boards instead of touching
+//! hardware that isn't there.
+//!
+//! The record covers [`crate::log`]'s minimum level and a static [`Ipv4Config`], both of which
+//! have a real runtime home to load into -- [`crate::log::set_min_level`] and
+//! [`crate::net::config::Ipv4Config`] respectively. The request that asked for this module also
+//! wanted a persisted console baud rate; this fork has no baud-rate divisor path anywhere
+//! (see `crate::clocks`'s own module docs), so there's no runtime value to read one
+//! back into -- persisting a number nothing ever consults would just be decoration, so it's left
+//! out rather than faked.
+//!
+//! The on-disk layout is a fixed-size, hand-packed byte buffer rather than a cast over a
+//! `#[repr(C)]` type the way [`crate::image_header`] and [`crate::bootselect`] do it: both of
+//! those reinterpret a struct living at a fixed, already-reserved memory address, where the
+//! compiler's layout for that exact struct is the only thing that ever reads it back. A file on a
+//! FAT volume has no such guarantee -- this code, not `rustc`, owns the wire format -- so
+//! [`Record::to_bytes`] and [`Record::from_bytes`] spell every field's offset out explicitly
+//! instead.
+//!
+//! There's still no boot-time caller for [`load`] -- wiring it into `main::kernel_init`, after
+//! `bsp::driver::init` has a block device ready, is the obvious next step once a board actually
+//! ships a FAT-formatted SD card to test against.
+
+#[cfg(feature = "bsp_qemu_virt")]
+use crate::fs::{
+    fat32::{file::File, volume::Fat32Volume},
+    partition,
+};
+use crate::{log::Level, net::config::Ipv4Config};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The 8.3 filename [`load`] and [`save`] use, in the FAT boot partition's root directory.
+pub const FILE_NAME: &str = "KCONFIG.DAT";
+
+/// The settings this module knows how to persist. See the module docs for why console baud isn't
+/// one of them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub log_level: Level,
+    pub network: Option<Ipv4Config>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Identifies a `KCONFIG.DAT` written by this module, as opposed to a same-named file left behind
+/// by something else.
+const MAGIC: u32 = 0x4b43_4647; // "KCFG", little-endian on disk
+
+/// Bumped if [`Record`]'s layout ever changes; [`Record::from_bytes`] rejects anything else.
+const RECORD_VERSION: u16 = 1;
+
+/// [`Record`]'s on-disk size in bytes.
+///
+/// Offsets: magic `0..4`, version `4..6`, log_level `6`, has_network `7`, address `8..12`,
+/// netmask `12..16`, has_gateway `16`, gateway `17..21`.
+const RECORD_LEN: usize = 21;
+
+/// The fixed-layout record [`Config`] is packed into and out of for [`File::write`]/[`File::read`].
+struct Record {
+    log_level: u8,
+    has_network: u8,
+    address: [u8; 4],
+    netmask: [u8; 4],
+    has_gateway: u8,
+    gateway: [u8; 4],
+}
+
+impl Record {
+    fn from_config(config: &Config) -> Self {
+        let (has_network, address, netmask, has_gateway, gateway) = match config.network {
+            Some(net) => (
+                1,
+                net.address,
+                net.netmask,
+                net.gateway.is_some() as u8,
+                net.gateway.unwrap_or([0; 4]),
+            ),
+            None => (0, [0; 4], [0; 4], 0, [0; 4]),
+        };
+
+        Self {
+            log_level: config.log_level.to_u8(),
+            has_network,
+            address,
+            netmask,
+            has_gateway,
+            gateway,
+        }
+    }
+
+    fn to_config(&self) -> Result<Config, &'static str> {
+        let log_level =
+            Level::from_u8(self.log_level).ok_or("config::persist: invalid log level byte")?;
+
+        let network = if self.has_network != 0 {
+            Some(Ipv4Config {
+                address: self.address,
+                netmask: self.netmask,
+                gateway: if self.has_gateway != 0 {
+                    Some(self.gateway)
+                } else {
+                    None
+                },
+            })
+        } else {
+            None
+        };
+
+        Ok(Config { log_level, network })
+    }
+
+    fn to_bytes(&self) -> [u8; RECORD_LEN] {
+        let mut bytes = [0u8; RECORD_LEN];
+
+        bytes[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+        bytes[4..6].copy_from_slice(&RECORD_VERSION.to_le_bytes());
+        bytes[6] = self.log_level;
+        bytes[7] = self.has_network;
+        bytes[8..12].copy_from_slice(&self.address);
+        bytes[12..16].copy_from_slice(&self.netmask);
+        bytes[16] = self.has_gateway;
+        bytes[17..21].copy_from_slice(&self.gateway);
+
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; RECORD_LEN]) -> Result<Self, &'static str> {
+        let magic = u32::from_le_bytes(bytes[0..4].try_into().unwrap());
+        if magic != MAGIC {
+            return Err("config::persist: KCONFIG.DAT has the wrong magic, not loading it");
+        }
+
+        let version = u16::from_le_bytes(bytes[4..6].try_into().unwrap());
+        if version != RECORD_VERSION {
+            return Err("config::persist: KCONFIG.DAT is an unsupported record version");
+        }
+
+        Ok(Self {
+            log_level: bytes[6],
+            has_network: bytes[7],
+            address: bytes[8..12].try_into().unwrap(),
+            netmask: bytes[12..16].try_into().unwrap(),
+            has_gateway: bytes[16],
+            gateway: bytes[17..21].try_into().unwrap(),
+        })
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Load [`Config`] from `KCONFIG.DAT` on the boot partition.
+///
+/// Real only under [`bsp_qemu_virt`](crate::bsp::qemu_virt) -- see the module docs.
+#[cfg(feature = "bsp_qemu_virt")]
+pub fn load() -> Result<Config, &'static str> {
+    let partition = find_boot_partition()?;
+    let volume = Fat32Volume::mount(&partition)?;
+    let mut file = File::open(&volume, volume.root_cluster(), FILE_NAME)?;
+
+    let mut bytes = [0u8; RECORD_LEN];
+    let n = file.read(&mut bytes)?;
+    if n != bytes.len() {
+        return Err("config::persist: KCONFIG.DAT is truncated");
+    }
+
+    Record::from_bytes(&bytes)?.to_config()
+}
+
+/// See the `bsp_qemu_virt` build's [`load`].
+#[cfg(not(feature = "bsp_qemu_virt"))]
+pub fn load() -> Result<Config, &'static str> {
+    Err("config::persist: this board has no block device to load a config from")
+}
+
+/// Save `config` to `KCONFIG.DAT` on the boot partition, creating it if it doesn't exist.
+///
+/// Real only under [`bsp_qemu_virt`](crate::bsp::qemu_virt) -- see the module docs.
+#[cfg(feature = "bsp_qemu_virt")]
+pub fn save(config: &Config) -> Result<(), &'static str> {
+    let partition = find_boot_partition()?;
+    let volume = Fat32Volume::mount(&partition)?;
+    let mut file = match File::open(&volume, volume.root_cluster(), FILE_NAME) {
+        Ok(file) => file,
+        Err(_) => File::create(&volume, volume.root_cluster(), FILE_NAME)?,
+    };
+
+    let bytes = Record::from_config(config).to_bytes();
+    file.truncate(0)?;
+    file.write(&bytes)?;
+    Ok(())
+}
+
+/// See the `bsp_qemu_virt` build's [`save`].
+#[cfg(not(feature = "bsp_qemu_virt"))]
+pub fn save(_config: &Config) -> Result<(), &'static str> {
+    Err("config::persist: this board has no block device to save a config to")
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+/// The MBR partition type bytes this module will treat as a FAT32 boot partition.
+#[cfg(feature = "bsp_qemu_virt")]
+const FAT32_PARTITION_TYPES: [u8; 2] = [0x0b, 0x0c]; // CHS and LBA FAT32
+
+/// Find the first FAT32 partition on the board's block device, as a [`partition::Partition`]
+/// ready for [`Fat32Volume::mount`].
+#[cfg(feature = "bsp_qemu_virt")]
+fn find_boot_partition() -> Result<partition::Partition<'static>, &'static str> {
+    let device = crate::bsp::driver::block_device();
+    let table = partition::read_partition_table(device)?;
+
+    let entry = table
+        .iter()
+        .find(|entry| FAT32_PARTITION_TYPES.contains(&entry.partition_type))
+        .copied()
+        .ok_or("config::persist: no FAT32 boot partition found")?;
+
+    Ok(partition::Partition::new(device, entry))
+}
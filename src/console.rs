@@ -0,0 +1,141 @@
+//! System console.
+
+use crate::bsp;
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(feature = "early_console")]
+pub mod early;
+pub mod line_edit;
+pub mod mux;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Console interfaces.
+pub mod interface {
+    use core::fmt;
+
+    /// Console write functions.
+    pub trait Write {
+        /// Write a single character.
+        fn write_char(&self, c: char);
+
+        /// Write a Rust format string.
+        fn write_fmt(&self, args: fmt::Arguments) -> fmt::Result;
+
+        /// Block until the last buffered character has been physically put on the TX wire.
+        fn flush(&self);
+    }
+
+    /// Console read functions.
+    pub trait Read {
+        /// Read a single character.
+        fn read_char(&self) -> char {
+            ' '
+        }
+
+        /// Read a single character without blocking if none is available yet.
+        ///
+        /// Lets callers (e.g. a shell's main loop) poll for input instead of stalling on
+        /// [`Read::read_char`] until a key is pressed.
+        fn read_char_nonblocking(&self) -> Option<char> {
+            None
+        }
+
+        /// Clear RX buffers, if any.
+        fn clear_rx(&self);
+    }
+
+    /// Console statistics.
+    pub trait Statistics {
+        /// Return the number of characters written.
+        fn chars_written(&self) -> usize {
+            0
+        }
+
+        /// Return the number of characters read.
+        fn chars_read(&self) -> usize {
+            0
+        }
+    }
+
+    /// Trait alias for a full-fledged console.
+    pub trait All: Write + Read + Statistics {}
+}
+
+/// A future that resolves once `source` has a character ready, for use with
+/// [`crate::executor::block_on`]. Built by [`read_char_async`].
+///
+/// Unlike [`interface::Read::read_char`], this doesn't busy-spin while waiting; the executor
+/// parks the core on `wfe` between polls instead.
+pub struct ReadChar<'a> {
+    source: &'a dyn interface::Read,
+}
+
+impl Future for ReadChar<'_> {
+    type Output = char;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<char> {
+        match self.source.read_char_nonblocking() {
+            Some(c) => Poll::Ready(c),
+            None => Poll::Pending,
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Return a reference to the console.
+pub fn console() -> &'static dyn interface::All {
+    bsp::console::console()
+}
+
+/// Decode one UTF-8 scalar value from a raw byte stream, pulling bytes one at a time from
+/// `next_byte`.
+///
+/// Meant for [`interface::Read`] implementations backed by a byte-at-a-time transport (a UART's
+/// data register, for instance) that need to hand back full Unicode [`char`]s rather than the
+/// individual bytes wire protocols like UTF-8 split multi-byte code points into. A malformed
+/// sequence -- a continuation byte that never follows a lead byte, or a lead byte whose
+/// continuation bytes don't look like continuation bytes -- decodes as
+/// [`char::REPLACEMENT_CHARACTER`], the same way a terminal emulator treats a dropped or garbled
+/// byte rather than refusing to make forward progress. This doesn't attempt to resynchronize with
+/// the stream beyond that: the bytes already consumed for the failed sequence are gone.
+pub(crate) fn decode_utf8_char(mut next_byte: impl FnMut() -> u8) -> char {
+    let first = next_byte();
+
+    let (continuation_bytes, mut value) = if first & 0x80 == 0x00 {
+        return first as char;
+    } else if first & 0xe0 == 0xc0 {
+        (1, (first & 0x1f) as u32)
+    } else if first & 0xf0 == 0xe0 {
+        (2, (first & 0x0f) as u32)
+    } else if first & 0xf8 == 0xf0 {
+        (3, (first & 0x07) as u32)
+    } else {
+        return char::REPLACEMENT_CHARACTER;
+    };
+
+    for _ in 0..continuation_bytes {
+        let byte = next_byte();
+        if byte & 0xc0 != 0x80 {
+            return char::REPLACEMENT_CHARACTER;
+        }
+
+        value = (value << 6) | (byte & 0x3f) as u32;
+    }
+
+    char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/// The async counterpart to [`interface::Read::read_char`].
+pub fn read_char_async(source: &dyn interface::Read) -> ReadChar<'_> {
+    ReadChar { source }
+}
@@ -0,0 +1,129 @@
+//! A pre-driver-manager raw UART writer.
+//!
+//! Between reset and the point where `bsp::driver::init()` brings up the real PL011 driver,
+//! there is nothing `print!` can write to. When the `early_console` feature is enabled,
+//! [`crate::print::_print`] falls back to writing bytes directly to the UART's data register
+//! instead — skipping the driver framework and the [`crate::driver::interface::DeviceDriver`]
+//! trait entirely, which is fine here since only the boot core is alive this early and nothing
+//! else touches the UART yet. Everything written this way is also kept in a small ring buffer;
+//! once the real console comes up, [`replay`] re-emits it so early boot messages still end up in
+//! the visible log instead of being lost in the dead window before UART init.
+
+use crate::{console, synchronization, synchronization::NullLock};
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const BUFFER_SIZE: usize = 1024;
+
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+const UART_BASE: usize = crate::bsp::memory::map::mmio::PL011_UART_START;
+#[cfg(feature = "bsp_qemu_virt")]
+const UART_BASE: usize = crate::bsp::memory::map::PL011_UART_START;
+
+const UART_DR_OFFSET: usize = 0x00;
+const UART_FR_OFFSET: usize = 0x18;
+const UART_FR_TXFF: u32 = 1 << 5;
+
+/// Everything written before the real console was ready, so it can be replayed afterward.
+struct RingBuffer {
+    bytes: [u8; BUFFER_SIZE],
+    len: usize,
+}
+
+impl RingBuffer {
+    const fn new() -> Self {
+        Self {
+            bytes: [0; BUFFER_SIZE],
+            len: 0,
+        }
+    }
+
+    /// Append a byte, silently dropping it once the buffer is full; the point is to catch early
+    /// boot chatter, not to be a general-purpose log store.
+    fn push(&mut self, byte: u8) {
+        if self.len < self.bytes.len() {
+            self.bytes[self.len] = byte;
+            self.len += 1;
+        }
+    }
+}
+
+/// Poll the UART's "transmit FIFO full" flag and write one byte once there's room.
+///
+/// # Safety
+///
+/// - Must only be called before the real console driver has taken ownership of the same MMIO
+///   range, and only from the single core that is alive this early in boot.
+unsafe fn write_byte_raw(byte: u8) {
+    use core::ptr::{read_volatile, write_volatile};
+
+    let fr = (UART_BASE + UART_FR_OFFSET) as *const u32;
+    let dr = (UART_BASE + UART_DR_OFFSET) as *mut u32;
+
+    while read_volatile(fr) & UART_FR_TXFF != 0 {}
+
+    write_volatile(dr, byte as u32);
+}
+
+struct EarlyWriter;
+
+impl fmt::Write for EarlyWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        BUFFER.lock(|buffer| {
+            for byte in s.bytes() {
+                if byte == b'\n' {
+                    unsafe { write_byte_raw(b'\r') };
+                    buffer.push(b'\r');
+                }
+
+                unsafe { write_byte_raw(byte) };
+                buffer.push(byte);
+            }
+        });
+
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static BUFFER: NullLock<RingBuffer> = NullLock::new(RingBuffer::new());
+static REAL_CONSOLE_READY: AtomicBool = AtomicBool::new(false);
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+use synchronization::Mutex;
+
+/// Whether `print!` should still be routed through the early console.
+pub fn is_active() -> bool {
+    !REAL_CONSOLE_READY.load(Ordering::Relaxed)
+}
+
+/// Format and write `args` directly to the UART, bypassing the driver framework.
+pub fn _print(args: fmt::Arguments) {
+    let _ = fmt::Write::write_fmt(&mut EarlyWriter, args);
+}
+
+/// Mark the real console as ready and replay everything written through the early console into
+/// it, so early boot output isn't missing from the log.
+///
+/// Called once, right after the board's console driver has finished initializing.
+pub fn replay(sink: &dyn console::interface::Write) {
+    REAL_CONSOLE_READY.store(true, Ordering::Relaxed);
+
+    BUFFER.lock(|buffer| {
+        for &byte in &buffer.bytes[..buffer.len] {
+            sink.write_char(byte as char);
+        }
+    });
+}
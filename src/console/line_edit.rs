@@ -0,0 +1,227 @@
+//! Line editing for interactive console input.
+//!
+//! Wraps a [`console::interface::Read`] source with backspace and up/down history recall, plus
+//! Ctrl-C / Ctrl-D handling — a considerable step up from echoing raw bytes one at a time. Arrow
+//! keys and friends arrive from the UART as ANSI/DEC "CSI" escapes (`ESC '[' ... <final byte>`),
+//! which are parsed here byte by byte as they come in. Left/right arrows are consumed without
+//! moving an editing cursor: input is only ever appended or removed at the end of the line.
+//!
+//! There is no heap in this kernel, so both the line buffer and the history are fixed-size. The
+//! buffer holds [`console::interface::Read::read_char`]'s UTF-8 encoding of whatever it returns,
+//! not raw bytes -- multi-byte characters take more than one slot, and backspace steps back over
+//! a whole character's continuation bytes rather than just the last one.
+
+use crate::console;
+
+const LINE_MAX: usize = 128;
+const HISTORY_DEPTH: usize = 8;
+
+/// The outcome of reading one line of input.
+pub enum LineResult<'a> {
+    /// The user pressed Enter; here is the completed line, without the trailing newline.
+    Line(&'a str),
+    /// The user pressed Ctrl-D on an empty line.
+    Eof,
+    /// The user pressed Ctrl-C, discarding the in-progress line.
+    Interrupted,
+}
+
+/// A fixed-depth ring of previously entered lines.
+struct History {
+    lines: [[u8; LINE_MAX]; HISTORY_DEPTH],
+    lens: [usize; HISTORY_DEPTH],
+    count: usize,
+    next_slot: usize,
+}
+
+impl History {
+    const fn new() -> Self {
+        Self {
+            lines: [[0; LINE_MAX]; HISTORY_DEPTH],
+            lens: [0; HISTORY_DEPTH],
+            count: 0,
+            next_slot: 0,
+        }
+    }
+
+    /// Record a completed line.
+    fn push(&mut self, line: &[u8]) {
+        if line.is_empty() {
+            return;
+        }
+
+        let len = line.len().min(LINE_MAX);
+        self.lines[self.next_slot][..len].copy_from_slice(&line[..len]);
+        self.lens[self.next_slot] = len;
+
+        self.next_slot = (self.next_slot + 1) % HISTORY_DEPTH;
+        self.count = (self.count + 1).min(HISTORY_DEPTH);
+    }
+
+    /// Return the entry `steps_back` lines before the most recent one (`1` is the most recent).
+    fn get(&self, steps_back: usize) -> Option<&[u8]> {
+        if steps_back == 0 || steps_back > self.count {
+            return None;
+        }
+
+        let slot = (self.next_slot + HISTORY_DEPTH - steps_back) % HISTORY_DEPTH;
+        Some(&self.lines[slot][..self.lens[slot]])
+    }
+}
+
+/// How far into a CSI escape sequence the parser currently is.
+enum EscapeState {
+    Ground,
+    Esc,
+    Csi,
+}
+
+/// A line-buffered, backspace- and history-aware front end for a raw byte console.
+pub struct LineEditor {
+    buf: [u8; LINE_MAX],
+    len: usize,
+    history: History,
+    /// How many entries back into history the user has currently scrolled; `0` means "not
+    /// browsing history, editing a fresh line".
+    history_cursor: usize,
+}
+
+impl LineEditor {
+    /// Create an instance.
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; LINE_MAX],
+            len: 0,
+            history: History::new(),
+            history_cursor: 0,
+        }
+    }
+
+    /// Replace the buffer's contents and re-draw the line, erasing whatever was on screen before.
+    fn replace_line(&mut self, sink: &dyn console::interface::Write, new_contents: &[u8]) {
+        for _ in 0..self.len {
+            sink.write_char('\u{8}');
+            sink.write_char(' ');
+            sink.write_char('\u{8}');
+        }
+
+        let len = new_contents.len().min(LINE_MAX);
+        self.buf[..len].copy_from_slice(&new_contents[..len]);
+        self.len = len;
+
+        for c in core::str::from_utf8(&self.buf[..self.len])
+            .unwrap_or("")
+            .chars()
+        {
+            sink.write_char(c);
+        }
+    }
+
+    /// Block until a full line has been entered, echoing input (and edits) through `sink`.
+    pub fn read_line<'a>(
+        &'a mut self,
+        source: &dyn console::interface::Read,
+        sink: &dyn console::interface::Write,
+    ) -> LineResult<'a> {
+        self.len = 0;
+        self.history_cursor = 0;
+
+        let mut escape_state = EscapeState::Ground;
+
+        loop {
+            let c = source.read_char();
+            // Non-ASCII characters map to 0, which matches none of the control bytes below.
+            let b = if c.is_ascii() { c as u8 } else { 0 };
+
+            match escape_state {
+                EscapeState::Ground => match b {
+                    0x03 => return LineResult::Interrupted,
+                    0x04 if self.len == 0 => return LineResult::Eof,
+                    b'\r' | b'\n' => {
+                        sink.write_char('\n');
+                        let line = &self.buf[..self.len];
+                        self.history.push(line);
+                        return LineResult::Line(core::str::from_utf8(line).unwrap_or(""));
+                    }
+                    0x7f | 0x08 => {
+                        if self.len > 0 {
+                            // Step back over any UTF-8 continuation bytes first, so backspacing a
+                            // multi-byte character removes the whole character, not just its last
+                            // byte (which would leave `buf` holding an invalid partial sequence).
+                            self.len -= 1;
+                            while self.len > 0 && self.buf[self.len] & 0xc0 == 0x80 {
+                                self.len -= 1;
+                            }
+                            sink.write_char('\u{8}');
+                            sink.write_char(' ');
+                            sink.write_char('\u{8}');
+                        }
+                    }
+                    0x1b => escape_state = EscapeState::Esc,
+                    _ => {
+                        let mut utf8_buf = [0u8; 4];
+                        let bytes = c.encode_utf8(&mut utf8_buf).as_bytes();
+
+                        if self.len + bytes.len() <= LINE_MAX {
+                            self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+                            self.len += bytes.len();
+                            sink.write_char(c);
+                        }
+                    }
+                },
+
+                EscapeState::Esc => {
+                    escape_state = if b == b'[' {
+                        EscapeState::Csi
+                    } else {
+                        EscapeState::Ground
+                    };
+                }
+
+                EscapeState::Csi => {
+                    escape_state = EscapeState::Ground;
+
+                    match b {
+                        // Up: recall the next-older history entry.
+                        b'A' => {
+                            if self.history_cursor < self.history.count {
+                                self.history_cursor += 1;
+                                if let Some(entry) = self.history.get(self.history_cursor) {
+                                    let mut tmp = [0u8; LINE_MAX];
+                                    let len = entry.len();
+                                    tmp[..len].copy_from_slice(entry);
+                                    self.replace_line(sink, &tmp[..len]);
+                                }
+                            }
+                        }
+                        // Down: recall the next-newer history entry, or clear the line.
+                        b'B' => {
+                            if self.history_cursor > 1 {
+                                self.history_cursor -= 1;
+                                if let Some(entry) = self.history.get(self.history_cursor) {
+                                    let mut tmp = [0u8; LINE_MAX];
+                                    let len = entry.len();
+                                    tmp[..len].copy_from_slice(entry);
+                                    self.replace_line(sink, &tmp[..len]);
+                                }
+                            } else if self.history_cursor == 1 {
+                                self.history_cursor = 0;
+                                self.replace_line(sink, &[]);
+                            }
+                        }
+                        // Left/right cursor motion within the line isn't modeled; the terminal
+                        // is left to move its own cursor visually, which is harmless since we
+                        // only ever append/remove at the end of `buf`.
+                        _ => (),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for LineEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
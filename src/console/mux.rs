@@ -0,0 +1,189 @@
+//! Channel framing for sharing one UART between klog, the interactive shell, and (eventually) a
+//! gdb stub.
+//!
+//! With only one serial link, [`crate::log`] output and [`crate::print`]'s interactive echo
+//! already interleave byte-for-byte on the wire -- `hdmi_console`'s module docs note the same
+//! thing about its own two virtual terminals: "nothing in this fork separates log output from
+//! everything else written to the console at the byte-stream level". This module gives a host on
+//! the other end of the cable a way to tell the two apart (and, eventually, a third: gdb remote
+//! protocol traffic) without a second cable, by prefixing each write with a small frame header
+//! once [`set_mode`] has put the link into [`Mode::Framed`].
+//!
+//! # Wire format
+//!
+//! [`Mode::Raw`] is the default and leaves the wire exactly as it's always been: bytes go
+//! straight out, unframed. A host that doesn't know about this protocol sees today's behavior,
+//! which is the whole reason `Raw` -- not `Framed` -- is what a boot starts in.
+//!
+//! In [`Mode::Framed`], every write becomes one or more frames:
+//!
+//! ```text
+//! byte 0: 0x01 (SOH) -- frame marker
+//! byte 1: channel id  -- 0 = klog, 1 = shell, 2 = gdb stub (see `Channel`)
+//! byte 2: payload length, 0..=127
+//! bytes 3..3+length: payload
+//! ```
+//!
+//! All three header bytes are deliberately kept inside the 7-bit ASCII range, including the
+//! length: [`interface::Write::write_char`] takes a `char`, not a raw byte, and
+//! `bsp::device_driver::bcm::Pl011Uart`'s implementation UTF-8-encodes whatever `char` it's
+//! given, which turns any single codepoint above 127 into *two* bytes on the wire. A length byte
+//! of, say, 200 would therefore not arrive as the single byte a host's frame parser expects. Capping
+//! payloads at 127 bytes per frame sidesteps that split silently; a write longer than that is
+//! sent as consecutive frames on the same channel instead of one bigger one, which a host
+//! demultiplexer can reassemble by concatenating payloads until it sees the next marker byte.
+//!
+//! # What a host-side demultiplexer needs to do
+//!
+//! Read a byte. If it isn't `0x01`, either the link is still in `Raw` mode or it's resynchronizing
+//! after dropping a byte -- either way, treat it (and everything up to the next `0x01`) as
+//! unframed data. On `0x01`, read the channel id and length byte, then read exactly that many
+//! payload bytes and append them to that channel's buffer.
+//!
+//! # What this doesn't do yet
+//!
+//! [`Channel::GdbStub`] is a real, stable discriminant in the wire format, but nothing in this
+//! fork ever writes to it: there's no gdb stub here for its traffic to come from, the same gap
+//! [`crate::cpu::context`] already documents ("There is also no gdb stub in this fork yet"). The
+//! channel id is reserved so a future stub doesn't have to renumber anything that's already
+//! shipped.
+
+use crate::console;
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Marks the start of a frame header. Chosen as ASCII SOH (start of heading), which is exactly
+/// what it's doing here.
+pub const FRAME_MARKER: u8 = 0x01;
+
+/// The largest payload a single frame can carry. See the module docs for why this is capped at
+/// 7-bit-clean values instead of using the full `u8` range.
+pub const MAX_FRAME_PAYLOAD: usize = 127;
+
+/// A multiplexed channel's wire id.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Channel {
+    /// [`crate::log`] output.
+    Klog = 0,
+    /// [`crate::print`]'s interactive shell output.
+    Shell = 1,
+    /// Reserved for a future gdb remote-protocol stub. See the module docs: nothing writes to
+    /// this channel yet.
+    GdbStub = 2,
+}
+
+/// Whether the link is currently framed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Bytes go straight to the console, unframed -- today's behavior, and what every boot
+    /// starts in.
+    Raw,
+    /// Every write is wrapped in the frame format documented at the module level.
+    Framed,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// `true` once [`set_mode`] has switched the link to [`Mode::Framed`].
+static FRAMED: AtomicBool = AtomicBool::new(false);
+
+/// An [`fmt::Write`] sink that splits whatever it's given into [`MAX_FRAME_PAYLOAD`]-byte chunks
+/// and emits each as its own frame on `channel`, so a caller can hand it an arbitrarily long
+/// `fmt::Arguments` without first rendering the whole thing into a buffer of its own.
+struct FrameWriter {
+    channel: Channel,
+    chunk: [u8; MAX_FRAME_PAYLOAD],
+    len: usize,
+}
+
+impl FrameWriter {
+    const fn new(channel: Channel) -> Self {
+        Self {
+            channel,
+            chunk: [0; MAX_FRAME_PAYLOAD],
+            len: 0,
+        }
+    }
+
+    fn flush_chunk(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+
+        write_frame(self.channel, &self.chunk[..self.len]);
+        self.len = 0;
+    }
+}
+
+impl fmt::Write for FrameWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            if self.len == self.chunk.len() {
+                self.flush_chunk();
+            }
+
+            self.chunk[self.len] = byte;
+            self.len += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Write one frame -- header plus `payload` -- straight to the console.
+///
+/// `payload` must be at most [`MAX_FRAME_PAYLOAD`] bytes; callers in this module guarantee that
+/// via [`FrameWriter`]'s chunking.
+fn write_frame(channel: Channel, payload: &[u8]) {
+    let sink = console::console();
+
+    sink.write_char(FRAME_MARKER as char);
+    sink.write_char(channel as u8 as char);
+    sink.write_char(payload.len() as u8 as char);
+
+    for &byte in payload {
+        sink.write_char(byte as char);
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Switch the link between [`Mode::Raw`] and [`Mode::Framed`].
+///
+/// Only flip this once a host-side demultiplexer is actually listening -- see the module docs --
+/// since a plain terminal on the other end would otherwise start seeing frame header bytes mixed
+/// into what used to be plain text.
+pub fn set_mode(mode: Mode) {
+    FRAMED.store(mode == Mode::Framed, Ordering::Relaxed);
+}
+
+/// The link's current [`Mode`].
+pub fn mode() -> Mode {
+    if FRAMED.load(Ordering::Relaxed) {
+        Mode::Framed
+    } else {
+        Mode::Raw
+    }
+}
+
+/// Write `args` on `channel`, framed or not according to the current [`mode`].
+pub fn write_channel(channel: Channel, args: fmt::Arguments) {
+    if mode() == Mode::Raw {
+        let _ = console::console().write_fmt(args);
+        return;
+    }
+
+    let mut writer = FrameWriter::new(channel);
+    let _ = fmt::Write::write_fmt(&mut writer, args);
+    writer.flush_chunk();
+}
@@ -0,0 +1,222 @@
+//! ELF core file serialization for fatally-faulted user processes.
+//!
+//! There's no user process to fault in the first place yet: [`crate::process::spawn_elf`] is
+//! blocked on EL0 entry and per-process page tables (see its own docs), so nothing in this fork
+//! can actually produce a register snapshot or a set of faulted memory segments to hand
+//! [`write_core`]. This is the same complete-but-unwired shape as [`crate::touch`]/[`crate::input`]
+//! -- real, exercisable serialization logic, waiting on a caller that can only exist once
+//! `crate::process` grows real EL0 execution.
+//!
+//! There's also no VFS (`crate::fs`'s own module docs cover the gap): [`write_core`] serializes
+//! into a caller-supplied fixed-size buffer rather than a path, leaving the choice of where to
+//! put the result -- [`crate::fs::tmpfs`], whose files top out at a few hundred bytes, or
+//! [`crate::fs::fat32`] on the SD card, which doesn't -- to the caller, the same way
+//! [`crate::crashdump`] builds its message into a fixed buffer before (failing to) find somewhere
+//! durable to put it.
+//!
+//! The `NT_PRSTATUS` note a real ELF core reader (e.g. `gdb`) expects is a glibc-defined struct
+//! with process/signal metadata ahead of the register array at a specific byte offset;
+//! reproducing that layout byte-for-byte from memory risks a subtly wrong note that a real reader
+//! silently misinterprets, the same risk [`crate::gfx::draw_text`]'s docs call out for
+//! hand-transcribed bitmap fonts. So [`write_core`] writes a single custom note instead --
+//! honestly non-standard, decodable by anything that knows this format, rather than a precise
+//! struct layout that might be byte-wrong.
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const EHDR_SIZE: usize = 64;
+const PHDR_SIZE: usize = 56;
+
+const ET_CORE: u16 = 4;
+const EM_AARCH64: u16 = 183;
+
+const PT_NOTE: u32 = 4;
+const PT_LOAD: u32 = 1;
+const PF_R: u32 = 4;
+const PF_W: u32 = 2;
+
+/// `CRATE\0`, padded to a 4-byte boundary in the note itself.
+const NOTE_NAME: &[u8] = b"CRATE\0";
+const NOTE_TYPE: u32 = 1;
+
+/// Size, in bytes, of [`Registers`] as written into the note descriptor: 31 general-purpose
+/// registers plus `sp`, `pc`, and `pstate`.
+const REGISTERS_SIZE: usize = (31 + 3) * 8;
+
+fn align4(n: usize) -> usize {
+    (n + 3) & !3
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// AArch64 general-purpose register state at the point of a fault.
+#[derive(Copy, Clone)]
+pub struct Registers {
+    pub gpr: [u64; 31],
+    pub sp: u64,
+    pub pc: u64,
+    pub pstate: u64,
+}
+
+/// One contiguous range of a faulted process's address space.
+pub struct Segment<'a> {
+    pub vaddr: u64,
+    pub data: &'a [u8],
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Serialize `registers` and `segments` as a minimal little-endian ELF64 core file into `buf`,
+/// returning the prefix of `buf` actually used.
+///
+/// One `PT_NOTE` program header carries `registers` (see the module docs for why it isn't a
+/// standard `NT_PRSTATUS`); one `PT_LOAD` program header per entry in `segments` carries its
+/// bytes at its recorded `vaddr`.
+///
+/// # Errors
+///
+/// Fails if `buf` isn't large enough to hold the header, program headers, note, and every
+/// segment's data -- there's no heap to grow into.
+pub fn write_core<'a>(
+    buf: &'a mut [u8],
+    registers: &Registers,
+    segments: &[Segment],
+) -> Result<&'a [u8], &'static str> {
+    let phnum = 1 + segments.len();
+    let phoff = EHDR_SIZE;
+    let note_offset = phoff + phnum * PHDR_SIZE;
+    let note_header_size = 12 + align4(NOTE_NAME.len()) + align4(REGISTERS_SIZE);
+    let mut data_offset = note_offset + note_header_size;
+
+    let mut segment_offsets = [0usize; 32];
+    if segments.len() > segment_offsets.len() {
+        return Err("coredump: too many segments");
+    }
+    for (i, segment) in segments.iter().enumerate() {
+        segment_offsets[i] = data_offset;
+        data_offset += segment.data.len();
+    }
+    let total = data_offset;
+
+    if buf.len() < total {
+        return Err("coredump: buffer too small for this core file");
+    }
+
+    write_ehdr(&mut buf[..EHDR_SIZE], phnum as u16);
+
+    let note_phdr = &mut buf[phoff..phoff + PHDR_SIZE];
+    write_phdr(
+        note_phdr,
+        PT_NOTE,
+        0,
+        note_offset as u64,
+        0,
+        note_header_size as u64,
+        0,
+        4,
+    );
+
+    for (i, segment) in segments.iter().enumerate() {
+        let off = phoff + (1 + i) * PHDR_SIZE;
+        let phdr = &mut buf[off..off + PHDR_SIZE];
+        write_phdr(
+            phdr,
+            PT_LOAD,
+            PF_R | PF_W,
+            segment_offsets[i] as u64,
+            segment.vaddr,
+            segment.data.len() as u64,
+            segment.data.len() as u64,
+            0x1000,
+        );
+    }
+
+    write_note(
+        &mut buf[note_offset..note_offset + note_header_size],
+        registers,
+    );
+
+    for (i, segment) in segments.iter().enumerate() {
+        let off = segment_offsets[i];
+        buf[off..off + segment.data.len()].copy_from_slice(segment.data);
+    }
+
+    Ok(&buf[..total])
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+fn write_ehdr(buf: &mut [u8], phnum: u16) {
+    buf[0..4].copy_from_slice(&[0x7f, b'E', b'L', b'F']);
+    buf[4] = 2; // EI_CLASS: ELFCLASS64
+    buf[5] = 1; // EI_DATA: little-endian
+    buf[6] = 1; // EI_VERSION: EV_CURRENT
+    buf[7..16].fill(0); // EI_OSABI, EI_ABIVERSION, EI_PAD
+
+    buf[16..18].copy_from_slice(&ET_CORE.to_le_bytes());
+    buf[18..20].copy_from_slice(&EM_AARCH64.to_le_bytes());
+    buf[20..24].copy_from_slice(&1u32.to_le_bytes()); // e_version
+    buf[24..32].copy_from_slice(&0u64.to_le_bytes()); // e_entry
+    buf[32..40].copy_from_slice(&(EHDR_SIZE as u64).to_le_bytes()); // e_phoff
+    buf[40..48].copy_from_slice(&0u64.to_le_bytes()); // e_shoff
+    buf[48..52].copy_from_slice(&0u32.to_le_bytes()); // e_flags
+    buf[52..54].copy_from_slice(&(EHDR_SIZE as u16).to_le_bytes()); // e_ehsize
+    buf[54..56].copy_from_slice(&(PHDR_SIZE as u16).to_le_bytes()); // e_phentsize
+    buf[56..58].copy_from_slice(&phnum.to_le_bytes()); // e_phnum
+    buf[58..60].copy_from_slice(&0u16.to_le_bytes()); // e_shentsize
+    buf[60..62].copy_from_slice(&0u16.to_le_bytes()); // e_shnum
+    buf[62..64].copy_from_slice(&0u16.to_le_bytes()); // e_shstrndx
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_phdr(
+    buf: &mut [u8],
+    p_type: u32,
+    p_flags: u32,
+    p_offset: u64,
+    p_vaddr: u64,
+    p_filesz: u64,
+    p_memsz: u64,
+    p_align: u64,
+) {
+    buf[0..4].copy_from_slice(&p_type.to_le_bytes());
+    buf[4..8].copy_from_slice(&p_flags.to_le_bytes());
+    buf[8..16].copy_from_slice(&p_offset.to_le_bytes());
+    buf[16..24].copy_from_slice(&p_vaddr.to_le_bytes());
+    buf[24..32].copy_from_slice(&p_vaddr.to_le_bytes()); // p_paddr: unknown, mirror p_vaddr
+    buf[32..40].copy_from_slice(&p_filesz.to_le_bytes());
+    buf[40..48].copy_from_slice(&p_memsz.to_le_bytes());
+    buf[48..56].copy_from_slice(&p_align.to_le_bytes());
+}
+
+/// Write one `Elf64_Nhdr` plus name and descriptor into `buf`, which must be exactly
+/// `12 + align4(NOTE_NAME.len()) + align4(REGISTERS_SIZE)` bytes long.
+fn write_note(buf: &mut [u8], registers: &Registers) {
+    buf[0..4].copy_from_slice(&(NOTE_NAME.len() as u32).to_le_bytes());
+    buf[4..8].copy_from_slice(&(REGISTERS_SIZE as u32).to_le_bytes());
+    buf[8..12].copy_from_slice(&NOTE_TYPE.to_le_bytes());
+
+    let name_start = 12;
+    let name_end = name_start + NOTE_NAME.len();
+    buf[name_start..name_end].copy_from_slice(NOTE_NAME);
+    buf[name_end..12 + align4(NOTE_NAME.len())].fill(0);
+
+    let mut pos = 12 + align4(NOTE_NAME.len());
+    for gpr in registers.gpr.iter() {
+        buf[pos..pos + 8].copy_from_slice(&gpr.to_le_bytes());
+        pos += 8;
+    }
+    buf[pos..pos + 8].copy_from_slice(&registers.sp.to_le_bytes());
+    pos += 8;
+    buf[pos..pos + 8].copy_from_slice(&registers.pc.to_le_bytes());
+    pos += 8;
+    buf[pos..pos + 8].copy_from_slice(&registers.pstate.to_le_bytes());
+}
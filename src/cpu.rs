@@ -0,0 +1,167 @@
+//! Processor core code.
+
+use crate::config;
+
+mod boot;
+pub mod context;
+pub mod features;
+
+extern "C" {
+    static __boot_core_stack_start: u8;
+    static __boot_core_stack_end_exclusive: u8;
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Byte pattern [`fill_stack_with_pattern`] marks unused boot-stack memory with, so
+/// [`stack_high_watermark`] can later tell how much of it actually got touched. `0xAA` reads
+/// clearly as "untouched" next to real stack contents in a memory dump, and is unlikely to turn
+/// up as genuine stack data by chance.
+const STACK_FILL_PATTERN: u8 = 0xaa;
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Pause execution on the calling CPU core.
+#[inline(always)]
+pub fn wait_forever() -> ! {
+    loop {
+        wait_for_event();
+    }
+}
+
+/// Suspend the calling core until the next event: an interrupt, or another core's [`send_event`].
+#[inline(always)]
+pub fn wait_for_event() {
+    unsafe { core::arch::asm!("wfe", options(nomem, nostack, preserves_flags)) }
+}
+
+/// Wake any core parked in [`wait_for_event`].
+#[inline(always)]
+pub fn send_event() {
+    unsafe { core::arch::asm!("sev", options(nomem, nostack, preserves_flags)) }
+}
+
+/// Spin the CPU for a given number of cycles. Used for coarse busy-waiting before the timer
+/// subsystem is brought up.
+#[inline(always)]
+pub fn spin_for_cycles(cycles: usize) {
+    for _ in 0..cycles {
+        unsafe { core::arch::asm!("nop", options(nomem, nostack, preserves_flags)) }
+    }
+}
+
+/// Return the calling core's affinity-level-0 ID, i.e. which CPU core this is.
+#[inline(always)]
+pub fn core_id() -> usize {
+    let mpidr: u64;
+    unsafe {
+        core::arch::asm!("mrs {}, MPIDR_EL1", out(reg) mpidr, options(nomem, nostack, preserves_flags));
+    }
+
+    (mpidr & 0xff) as usize
+}
+
+/// Read the generic timer's physical counter, in timer ticks.
+///
+/// Ticks at whatever rate `CNTFRQ_EL0` reports -- a board's
+/// [`crate::time::TimeManager::resolution`] is how a caller converts a difference between two
+/// reads of this into wall time. Meant for measuring short, relative durations (e.g. IRQ
+/// latency in [`crate::exception::asynchronous`]) cheaply, without going through a board's
+/// `TimeManager`.
+#[inline(always)]
+pub fn read_cycle_counter() -> u64 {
+    let cntpct: u64;
+    unsafe {
+        core::arch::asm!("mrs {}, CNTPCT_EL0", out(reg) cntpct, options(nomem, nostack, preserves_flags));
+    }
+
+    cntpct
+}
+
+/// Fill the boot core's stack, from its low end up to (just below) the current stack pointer,
+/// with [`STACK_FILL_PATTERN`].
+///
+/// # Safety
+///
+/// - Must run at most once, as the very first thing [`boot::_start_rust`] does. That's what makes
+///   it safe to assume everything below the current stack pointer is still unused and fine to
+///   overwrite -- true at that point and not guaranteed afterwards.
+pub unsafe fn fill_stack_with_pattern() {
+    let sp: usize;
+    core::arch::asm!("mov {}, sp", out(reg) sp, options(nomem, nostack, preserves_flags));
+
+    let start = &__boot_core_stack_start as *const u8 as usize;
+    // Leave a margin below the live stack pointer so this call's own frame is never touched.
+    let fill_end = sp.saturating_sub(128);
+
+    if fill_end > start {
+        core::ptr::write_bytes(start as *mut u8, STACK_FILL_PATTERN, fill_end - start);
+    }
+}
+
+/// Report how much of the boot core's stack has been used since boot, in bytes.
+///
+/// Scans up from the stack's low end -- the only direction safe to read while still running on
+/// it -- for the first byte that no longer matches [`STACK_FILL_PATTERN`]; everything below that
+/// point is assumed untouched since [`fill_stack_with_pattern`] ran at boot. This is a high-water
+/// mark, not current usage: a byte that was written once and never since stays counted even after
+/// the stack has long since unwound past it, because nothing re-fills it.
+///
+/// There's no per-core variant: this fork only ever brings up the boot core. Every other core
+/// stays parked in the architecture's assembly boot trampoline before any Rust code -- this
+/// function's stack included -- ever runs, so there's no other stack to watermark.
+pub fn stack_high_watermark() -> usize {
+    let start = unsafe { &__boot_core_stack_start as *const u8 as usize };
+
+    let mut untouched = 0;
+    while untouched < config::BOOT_CORE_STACK_SIZE {
+        let byte = unsafe { core::ptr::read_volatile((start + untouched) as *const u8) };
+        if byte != STACK_FILL_PATTERN {
+            break;
+        }
+        untouched += 1;
+    }
+
+    config::BOOT_CORE_STACK_SIZE - untouched
+}
+
+/// Report how much of the boot core's stack is in use *right now*, in bytes.
+///
+/// Reads the live stack pointer directly rather than scanning for [`STACK_FILL_PATTERN`], so
+/// unlike [`stack_high_watermark`] this can go back down as frames pop -- it's a snapshot, not a
+/// high-water mark by itself. [`crate::exception::asynchronous::NestingGuard`] samples it around
+/// each interrupt-handler dispatch to pair with its own nesting-depth counter.
+pub fn current_stack_bytes_used() -> usize {
+    let sp: usize;
+    unsafe {
+        core::arch::asm!("mov {}, sp", out(reg) sp, options(nomem, nostack, preserves_flags));
+    }
+
+    let end = unsafe { &__boot_core_stack_end_exclusive as *const u8 as usize };
+    end.saturating_sub(sp)
+}
+
+/// Compare the boot stack's actual size, as reserved by whatever linker script this binary was
+/// linked against, to `bsp::layout::BOOT_CORE_STACK_SIZE` / `config::BOOT_CORE_STACK_SIZE`.
+///
+/// `build.rs` generates the linker script from that same constant, so in the normal case this is
+/// a tautology. It earns its keep the day it isn't: a stale `OUT_DIR` from a previous build, or a
+/// linker invocation that picks up some other script by mistake, would otherwise silently give
+/// [`stack_high_watermark`] (and anything else trusting [`config::BOOT_CORE_STACK_SIZE`]) the
+/// wrong stack extent.
+pub fn assert_linker_layout() -> Result<(), &'static str> {
+    let linker_stack_size = unsafe {
+        (&__boot_core_stack_end_exclusive as *const u8 as usize)
+            - (&__boot_core_stack_start as *const u8 as usize)
+    };
+
+    if linker_stack_size != config::BOOT_CORE_STACK_SIZE {
+        return Err("boot stack size reserved by the linker script does not match bsp::layout -- was it generated by a different build?");
+    }
+
+    Ok(())
+}
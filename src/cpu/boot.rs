@@ -0,0 +1,14 @@
+//! Rust entry point, called from the assembly boot code once a stack has been set up.
+
+/// The Rust entry point of the kernel, called from `_start` in the architecture's assembly boot
+/// code.
+///
+/// # Safety
+///
+/// - Exception return from the assembly boot code must not use any stack memory.
+/// - Only a single core is allowed to run this function.
+#[no_mangle]
+pub unsafe extern "C" fn _start_rust() -> ! {
+    super::fill_stack_with_pattern();
+    crate::kernel_init()
+}
@@ -0,0 +1,90 @@
+//! Processor register-state snapshots.
+//!
+//! [`snapshot`] reads the system registers that describe how the core currently has itself
+//! configured -- current exception level, the state a `eret` would return to, and the MMU/cache
+//! control registers. What it deliberately doesn't include is the general-purpose registers: those
+//! are only meaningful captured at a trap boundary (a real exception frame, the way a debugger's
+//! `g` packet or a `should_panic` test would want them), and this fork's exception vectors don't
+//! save one anywhere Rust code can read it back from -- [`crate::exception::asynchronous`] dispatch
+//! IRQs through the [`crate::exception::asynchronous::interface::IRQManager`] trait without
+//! exposing the interrupted context at all. Reading `x0`-`x30` from inside [`snapshot`] itself
+//! would only show whatever the compiler happened to leave lying around for its own use, not a
+//! caller's state, so it isn't pretended to be useful here.
+//!
+//! There is also no gdb stub in this fork yet for a `g` packet handler to plug this into -- this
+//! is the data that handler would eventually serialize, not the handler itself.
+
+use core::fmt;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A snapshot of the calling core's system-register state at the moment [`snapshot`] was called.
+#[derive(Copy, Clone, Debug)]
+pub struct Snapshot {
+    /// Current exception level (0 = EL0, 1 = EL1, 2 = EL2, 3 = EL3), from `CurrentEL`.
+    pub current_el: u8,
+    /// `SPSR_EL1`: the processor state a `eret` from EL1 would restore.
+    pub spsr_el1: u64,
+    /// `ELR_EL1`: the address a `eret` from EL1 would return to.
+    pub elr_el1: u64,
+    /// `SCTLR_EL1`: system control, including the MMU (bit 0) and cache (bits 2, 12) enable bits.
+    pub sctlr_el1: u64,
+    /// `TCR_EL1`: translation control for the EL1/EL0 page table walk.
+    pub tcr_el1: u64,
+    /// `TTBR0_EL1`: translation table base for the lower (user) half of the address space.
+    pub ttbr0_el1: u64,
+    /// `TTBR1_EL1`: translation table base for the upper (kernel) half of the address space.
+    pub ttbr1_el1: u64,
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "EL{}", self.current_el)?;
+        writeln!(f, "  SPSR_EL1:  {:#018x}", self.spsr_el1)?;
+        writeln!(f, "  ELR_EL1:   {:#018x}", self.elr_el1)?;
+        writeln!(f, "  SCTLR_EL1: {:#018x}", self.sctlr_el1)?;
+        writeln!(f, "  TCR_EL1:   {:#018x}", self.tcr_el1)?;
+        writeln!(f, "  TTBR0_EL1: {:#018x}", self.ttbr0_el1)?;
+        write!(f, "  TTBR1_EL1: {:#018x}", self.ttbr1_el1)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Read the calling core's current system-register state.
+///
+/// See the module docs for why this doesn't include general-purpose registers.
+pub fn snapshot() -> Snapshot {
+    let current_el: u64;
+    let spsr_el1: u64;
+    let elr_el1: u64;
+    let sctlr_el1: u64;
+    let tcr_el1: u64;
+    let ttbr0_el1: u64;
+    let ttbr1_el1: u64;
+
+    unsafe {
+        core::arch::asm!("mrs {}, CurrentEL", out(reg) current_el, options(nomem, nostack, preserves_flags));
+        core::arch::asm!("mrs {}, SPSR_EL1", out(reg) spsr_el1, options(nomem, nostack, preserves_flags));
+        core::arch::asm!("mrs {}, ELR_EL1", out(reg) elr_el1, options(nomem, nostack, preserves_flags));
+        core::arch::asm!("mrs {}, SCTLR_EL1", out(reg) sctlr_el1, options(nomem, nostack, preserves_flags));
+        core::arch::asm!("mrs {}, TCR_EL1", out(reg) tcr_el1, options(nomem, nostack, preserves_flags));
+        core::arch::asm!("mrs {}, TTBR0_EL1", out(reg) ttbr0_el1, options(nomem, nostack, preserves_flags));
+        core::arch::asm!("mrs {}, TTBR1_EL1", out(reg) ttbr1_el1, options(nomem, nostack, preserves_flags));
+    }
+
+    Snapshot {
+        // CurrentEL's EL field is bits [3:2]; bits [1:0] are reserved as zero.
+        current_el: ((current_el >> 2) & 0b11) as u8,
+        spsr_el1,
+        elr_el1,
+        sctlr_el1,
+        tcr_el1,
+        ttbr0_el1,
+        ttbr1_el1,
+    }
+}
@@ -0,0 +1,189 @@
+//! Runtime CPU feature detection and errata notes.
+//!
+//! [`decode`]/[`identify`] are pure bit extraction from architected `ID_AA64*`/`MIDR_EL1` values
+//! -- real ARMv8-A, not vendor-specific guesswork, and testable on the host the same way
+//! [`crate::common`]'s translation-table math is. [`detect`]/[`current_cpu_model`] are the thin,
+//! `aarch64`-only `MRS` reads that feed them a live value; see [`crate::cpu::read_cycle_counter`]
+//! for the same split between "read a system register" and "what the bits mean" elsewhere in this
+//! module.
+//!
+//! What this *doesn't* do is apply the Cortex-A53/A72 errata workarounds the request asks for.
+//! Every widely-cited one for these cores -- A53 #843419 (a `TLBI`/`DSB` near a near-page-boundary
+//! `ADRP` can be misexecuted) and #835769 (a `MUL`/`MLA`-then-memory-op sequence can corrupt the
+//! result), A72 #853709 (a narrow speculative-load erratum) -- is a codegen-time mitigation
+//! (`-mfix-cortex-a53-843419`, `-mfix-cortex-a53-835769`, equivalent `ld.lld` linker fixups), not
+//! a runtime register poke [`log_detected`] or anything else in this module could apply after the
+//! fact. `build.rs` doesn't pass any of those flags today, so a [`CpuModel::CortexA53`] affected
+//! by either erratum is running unmitigated regardless of what this module detects -- [`log_detected`]
+//! says so explicitly instead of implying a workaround happened where none did.
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Optional ARMv8-A features this fork currently cares about selecting code paths on, decoded out
+/// of `ID_AA64ISAR0_EL1` and `ID_AA64MMFR1_EL1`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Features {
+    /// `ID_AA64ISAR0_EL1.CRC32`: `CRC32*` instructions.
+    pub crc32: bool,
+    /// `ID_AA64ISAR0_EL1.AES`: `AES*`/`PMULL` instructions.
+    pub aes: bool,
+    /// `ID_AA64ISAR0_EL1.Atomic`: `LSE` atomic instructions (`CAS`, `LDADD`, ...), an alternative
+    /// to an exclusive-access `LDXR`/`STXR` retry loop. This fork's [`crate::synchronization`]
+    /// doesn't have an `LDXR`/`STXR` spinlock to pick an `LSE` path over, though -- it's
+    /// single-core ([`crate::synchronization::NullLock`]'s own docs say so) and has no contended
+    /// lock that decision would matter for. [`Features::atomics_lse`] is real today so a future
+    /// multi-core build has it without re-deriving the field layout, not because anything
+    /// branches on it yet.
+    pub atomics_lse: bool,
+    /// `ID_AA64MMFR1_EL1.PAN`: Privileged Access Never, the EL1 protection against stray EL1
+    /// accesses to an EL0 page. [`crate::memory::user`] is the pointer-validation code this would
+    /// harden, once it has a real page table and translation attributes to check against (see its
+    /// own docs for why it doesn't yet).
+    pub pan: bool,
+}
+
+/// An identified CPU core, by `MIDR_EL1` implementer and part number.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CpuModel {
+    /// Implementer `0x41` (Arm), part number `0xd03`.
+    CortexA53,
+    /// Implementer `0x41` (Arm), part number `0xd08`.
+    CortexA72,
+    /// Any other implementer/part-number pair.
+    Unknown { implementer: u8, part_number: u16 },
+}
+
+/// Decode [`Features`] out of a raw `ID_AA64ISAR0_EL1` value and a raw `ID_AA64MMFR1_EL1` value
+/// (see [`detect`] for where a live core's values come from).
+pub fn decode(id_aa64isar0_el1: u64, id_aa64mmfr1_el1: u64) -> Features {
+    Features {
+        crc32: nibble(id_aa64isar0_el1, 16) != 0,
+        aes: nibble(id_aa64isar0_el1, 4) != 0,
+        atomics_lse: nibble(id_aa64isar0_el1, 20) != 0,
+        pan: nibble(id_aa64mmfr1_el1, 20) != 0,
+    }
+}
+
+/// Identify a core from a raw `MIDR_EL1` value (see [`current_cpu_model`] for a live read).
+pub fn identify(midr_el1: u64) -> CpuModel {
+    let implementer = ((midr_el1 >> 24) & 0xff) as u8;
+    let part_number = ((midr_el1 >> 4) & 0xfff) as u16;
+
+    match (implementer, part_number) {
+        (0x41, 0xd03) => CpuModel::CortexA53,
+        (0x41, 0xd08) => CpuModel::CortexA72,
+        _ => CpuModel::Unknown {
+            implementer,
+            part_number,
+        },
+    }
+}
+
+fn nibble(value: u64, shift: u32) -> u64 {
+    (value >> shift) & 0xf
+}
+
+/// Read the calling core's `ID_AA64ISAR0_EL1`/`ID_AA64MMFR1_EL1` and [`decode`] them.
+#[cfg(target_arch = "aarch64")]
+pub fn detect() -> Features {
+    let id_aa64isar0_el1: u64;
+    let id_aa64mmfr1_el1: u64;
+    unsafe {
+        core::arch::asm!(
+            "mrs {}, ID_AA64ISAR0_EL1",
+            out(reg) id_aa64isar0_el1,
+            options(nomem, nostack, preserves_flags)
+        );
+        core::arch::asm!(
+            "mrs {}, ID_AA64MMFR1_EL1",
+            out(reg) id_aa64mmfr1_el1,
+            options(nomem, nostack, preserves_flags)
+        );
+    }
+
+    decode(id_aa64isar0_el1, id_aa64mmfr1_el1)
+}
+
+/// Read the calling core's `MIDR_EL1` and [`identify`] it.
+#[cfg(target_arch = "aarch64")]
+pub fn current_cpu_model() -> CpuModel {
+    let midr_el1: u64;
+    unsafe {
+        core::arch::asm!("mrs {}, MIDR_EL1", out(reg) midr_el1, options(nomem, nostack, preserves_flags));
+    }
+
+    identify(midr_el1)
+}
+
+/// Log the calling core's model and detected [`Features`], and note any known errata for that
+/// model this fork isn't mitigating -- see the module docs for why not.
+#[cfg(target_arch = "aarch64")]
+pub fn log_detected() {
+    let model = current_cpu_model();
+    let features = detect();
+    crate::log::log_info!("cpu", "model: {:?}, features: {:?}", model, features);
+
+    let errata: &[&str] = match model {
+        CpuModel::CortexA53 => &["#843419 (ADRP/TLBI-DSB)", "#835769 (MUL/MLA)"],
+        CpuModel::CortexA72 => &["#853709 (speculative load)"],
+        CpuModel::Unknown { .. } => &[],
+    };
+
+    if !errata.is_empty() {
+        crate::log::log_warn!(
+            "cpu",
+            "{:?} has known errata {:?}, but this fork applies none of them at runtime -- they're \
+             codegen/linker-time mitigations build.rs doesn't enable; see the module docs",
+            model,
+            errata
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_every_feature_bit() {
+        let isar0 = (0x1 << 16) | (0x1 << 4) | (0x2 << 20); // CRC32, AES, LSE (Atomic == 2)
+        let mmfr1 = 0x1 << 20; // PAN
+        assert_eq!(
+            decode(isar0, mmfr1),
+            Features {
+                crc32: true,
+                aes: true,
+                atomics_lse: true,
+                pan: true,
+            }
+        );
+    }
+
+    #[test]
+    fn decodes_no_features_present() {
+        assert_eq!(decode(0, 0), Features::default());
+    }
+
+    #[test]
+    fn identifies_known_cortex_cores() {
+        // MIDR_EL1 layout: implementer in bits 31:24, part number in bits 15:4.
+        let a53_midr = (0x41u64 << 24) | (0xd03 << 4);
+        let a72_midr = (0x41u64 << 24) | (0xd08 << 4);
+        assert_eq!(identify(a53_midr), CpuModel::CortexA53);
+        assert_eq!(identify(a72_midr), CpuModel::CortexA72);
+    }
+
+    #[test]
+    fn reports_unknown_implementers_with_their_raw_fields() {
+        let midr = (0x51u64 << 24) | (0x801 << 4); // Qualcomm Kryo, as an example non-Arm core
+        assert_eq!(
+            identify(midr),
+            CpuModel::Unknown {
+                implementer: 0x51,
+                part_number: 0x801
+            }
+        );
+    }
+}
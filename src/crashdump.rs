@@ -0,0 +1,95 @@
+//! Crash dump capture.
+//!
+//! A useful crash dump needs three things this fork doesn't have: a backtrace (there's no frame
+//! pointer or unwind-table walker), a klog ring buffer (the macros in `crate::log` write straight
+//! to the console; nothing is retained), and somewhere durable to put the result -- neither
+//! `crate::storage` nor `crate::net` exposes a BSP-agnostic "the block device" / "the network
+//! interface" accessor the way `crate::console::console()` and `crate::time::time_manager()` do
+//! for their subsystems. `crate::net` does have a real UDP/IP stack above
+//! `net::interface::NetworkDevice`'s raw Ethernet frames now, but without that accessor there's
+//! still no concrete device for this module to send a dump over.
+//!
+//! What [`capture`] can do today: hold onto the panic message in a fixed-size buffer, so it's at
+//! least visible to whatever inspects kernel memory after the fact, and honestly report that
+//! there's nowhere to write it out to yet.
+
+use crate::synchronization::{Mutex, NullLock};
+use core::fmt::{self, Write};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const MESSAGE_CAPACITY: usize = 256;
+
+struct Dump {
+    message: [u8; MESSAGE_CAPACITY],
+    len: usize,
+}
+
+impl Dump {
+    const fn new() -> Self {
+        Self {
+            message: [0; MESSAGE_CAPACITY],
+            len: 0,
+        }
+    }
+}
+
+/// A `fmt::Write` sink over a fixed-size byte buffer that silently truncates past capacity --
+/// there's no heap to grow into, and a crash dump would rather have a truncated message than none.
+struct FixedBuf<'a> {
+    buf: &'a mut [u8],
+    len: &'a mut usize,
+}
+
+impl fmt::Write for FixedBuf<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let remaining = self.buf.len() - *self.len;
+        let n = s.len().min(remaining);
+
+        self.buf[*self.len..*self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        *self.len += n;
+        Ok(())
+    }
+}
+
+static DUMP: NullLock<Dump> = NullLock::new(Dump::new());
+
+/// Report that there is nowhere to persist a captured dump to yet -- see the module docs.
+fn persist() -> Result<(), &'static str> {
+    Err("crashdump: nowhere to persist to yet -- no block/network device accessor, no klog ring buffer, no backtrace")
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Compress the most recently [`capture`]d message into `out` with [`crate::compress`], returning
+/// the number of bytes written.
+///
+/// Ready for a future durable sink to call once one exists -- see the module docs for why
+/// [`persist`] has nowhere to write to yet. Doesn't touch [`capture`]'s own raw-buffer storage;
+/// this only reads it.
+pub fn compressed(out: &mut [u8]) -> usize {
+    DUMP.lock(|dump| crate::compress::encode(&dump.message[..dump.len], out))
+}
+
+/// Record the panicking `message` and attempt to persist it.
+///
+/// Called from the panic handler, so this must not itself panic. See the module docs for why
+/// [`persist`] always fails today.
+pub fn capture(message: impl fmt::Display) {
+    DUMP.lock(|dump| {
+        dump.len = 0;
+        let mut sink = FixedBuf {
+            buf: &mut dump.message,
+            len: &mut dump.len,
+        };
+        let _ = write!(sink, "{}", message);
+    });
+
+    if let Err(x) = persist() {
+        crate::log::log_warn!("crashdump", "{}", x);
+    }
+}
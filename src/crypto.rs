@@ -0,0 +1,5 @@
+//! Cryptographic primitives.
+
+pub mod hash;
+#[cfg(feature = "secure_boot")]
+pub mod sign;
@@ -0,0 +1,34 @@
+//! Ed25519 signature verification.
+//!
+//! Verifying a real Ed25519 signature needs SHA-512 (`crypto::hash` only has SHA-256 so far) and
+//! Curve25519 field/group arithmetic -- modular inverse, twisted Edwards point addition and
+//! doubling, constant-time scalar multiplication -- none of which exist in this fork, and none of
+//! which belong hand-rolled into a `#![no_std]` kernel without a test-vector suite to check them
+//! against. `kernel_pure` (see `src/lib.rs`) gives this fork a host test harness now, and
+//! [`crate::crypto::hash`] already uses it to check `crc32`/`sha256` against the standard
+//! CRC-32/ISO-HDLC and FIPS 180-4 vectors -- but that doesn't help here: there's no SHA-512 or
+//! Curve25519 implementation to check in the first place. Getting curve arithmetic subtly wrong
+//! doesn't produce a compile error or even an obviously wrong answer most of the time -- it
+//! produces a "secure boot" that quietly accepts forged images, which is worse than not having
+//! the feature.
+//!
+//! What's here is the shape a working implementation would have -- [`PublicKey`], [`Signature`],
+//! and [`verify`] -- gated behind the `secure_boot` feature so a future chainloader or
+//! `process::spawn_elf` can already be written against the eventual real API. [`verify`] always
+//! reports failure until the arithmetic backing it exists.
+
+/// A 32-byte Ed25519 public key.
+pub struct PublicKey(pub [u8; 32]);
+
+/// A 64-byte Ed25519 signature.
+pub struct Signature(pub [u8; 64]);
+
+/// Verify `signature` over `message` under `key`.
+///
+/// Always fails today -- see the module docs for what's missing before this can actually verify
+/// anything.
+pub fn verify(key: &PublicKey, message: &[u8], signature: &Signature) -> Result<(), &'static str> {
+    let _ = (key, message, signature);
+
+    Err("crypto::sign: Ed25519 verification is not implemented -- no SHA-512, no Curve25519 field/group arithmetic")
+}
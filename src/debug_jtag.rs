@@ -0,0 +1,42 @@
+//! JTAG pin enablement for external debug access.
+//!
+//! [`enable`] only ever does the pin-muxing half of what `config.txt`'s `enable_jtag_gpio=1`
+//! does: routing GPIO 22-27 to `ALT4`, the BCM's standard ARM JTAG pin assignment. The "debug
+//! authentication bits" the original ask also names don't have a register this kernel's EL1 code
+//! can reach -- on the BCM2837/BCM2711, JTAG debug authentication is a secure-firmware-side gate
+//! tied to the VideoCore's own boot state, not something ARM non-secure software programs (see
+//! [`crate::exception`]'s docs on this fork never even installing a vector table, let alone
+//! handling the secure world). If `secure_boot` locked debug access down before this kernel ever
+//! ran, [`enable`] can't undo that; it can only offer pins to a debugger the platform has already
+//! decided to let one reach.
+//!
+//! [`wants_auto_enable`] mirrors [`crate::net::config::parse_cmdline`]: there's still no
+//! bootloader/DTB path that hands this kernel a real command line to parse (see that module's
+//! docs), so nothing calls this automatically at boot yet -- it's ready for whichever caller ends
+//! up owning that plumbing.
+
+use crate::gpio;
+
+/// BCM pin numbers the Raspberry Pi's JTAG header multiplexes onto via `ALT4` -- the same six
+/// pins `config.txt`'s `enable_jtag_gpio=1` routes.
+const JTAG_PINS: [u32; 6] = [22, 23, 24, 25, 26, 27];
+
+const JTAG_ALT_FUNCTION: u32 = 4;
+
+/// Route GPIO 22-27 to `ALT4` so an external debugger can attach without a `config.txt` change.
+///
+/// See the module docs for why this can't do anything about secure-side debug authentication.
+pub fn enable() {
+    for &pin in JTAG_PINS.iter() {
+        gpio::pin(pin, "jtag", "debug_jtag").set_alt(JTAG_ALT_FUNCTION);
+    }
+}
+
+/// Whether a kernel command line asks to run [`enable`] automatically at boot.
+///
+/// See the module docs for why nothing feeds this a real command line yet.
+pub fn wants_auto_enable(cmdline: &str) -> bool {
+    cmdline
+        .split_whitespace()
+        .any(|token| token == "enable_jtag")
+}
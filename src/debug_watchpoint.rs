@@ -0,0 +1,74 @@
+//! Hardware watchpoint arming for a debug-shell data-corruption detector.
+//!
+//! The request this answers wants "a hardware watchpoint on a chosen kernel variable" that
+//! "produces a full backtrace when anything writes it unexpectedly". Two things stand between
+//! this fork and that: [`crate::exception`]'s own module docs say `VBAR_EL1` is never
+//! programmed -- there is no exception vector table installed at all, for any exception class,
+//! not just a missing watchpoint-specific handler -- and [`crate::crashdump`]'s module docs say
+//! there is no frame pointer or unwind-table walker to build a backtrace from even if something
+//! did catch the trap.
+//!
+//! Programming `DBGWVRn_EL1`/`DBGWCRn_EL1` and setting `MDSCR_EL1.MDE` without a vector table to
+//! receive the resulting debug exception isn't "a watchpoint with no backtrace" -- it's a debug
+//! exception with *nowhere to go at all*, on whatever `VBAR_EL1` happens to contain left over
+//! from firmware or QEMU's reset state. That is worse than not having the feature, so
+//! [`arm`] refuses rather than programming real hardware: see its doc for what it does instead.
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// What kind of access to `address` should trip the watchpoint, once one can actually be armed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// A watchpoint a caller wants armed on a chosen kernel variable, e.g. a frame-allocator
+/// freelist head -- parsed from the debug shell's `watch` command.
+#[derive(Copy, Clone, Debug)]
+pub struct WatchpointRequest {
+    pub address: usize,
+    /// Watched region size in bytes. Real `DBGWCRn_EL1.BAS` byte-address-select hardware only
+    /// supports 1, 2, 4, or 8, so [`WatchpointRequest::new`] rejects anything else up front,
+    /// before [`arm`] has to.
+    pub len: u8,
+    pub access: Access,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl WatchpointRequest {
+    /// Validate `len` against what `DBGWCRn_EL1.BAS` can express. Doesn't check `address`
+    /// alignment against `len` -- [`arm`] never reaches real hardware to care.
+    pub fn new(address: usize, len: u8, access: Access) -> Result<Self, &'static str> {
+        if !matches!(len, 1 | 2 | 4 | 8) {
+            return Err("debug_watchpoint: len must be 1, 2, 4, or 8 bytes");
+        }
+
+        Ok(Self {
+            address,
+            len,
+            access,
+        })
+    }
+}
+
+/// Refuse to arm `request` as a real hardware watchpoint -- see the module docs for why.
+///
+/// This is an honest `Err`, not a partial implementation: actually writing `DBGWVRn_EL1` and
+/// `DBGWCRn_EL1` for `request` and setting `MDSCR_EL1.MDE` would arm a debug exception that traps
+/// to an unprogrammed `VBAR_EL1` the moment anything touches `request.address`, which is a worse
+/// outcome than the feature simply not existing yet.
+pub fn arm(request: WatchpointRequest) -> Result<(), &'static str> {
+    let _ = request;
+
+    Err(
+        "debug_watchpoint: refusing to arm -- no exception vector table is installed to catch the \
+         resulting debug exception, and no backtrace walker to report it with; see the module docs",
+    )
+}
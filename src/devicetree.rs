@@ -0,0 +1,192 @@
+//! Flattened devicetree (DTB) parsing: enough to walk a blob's nodes, match a node's
+//! `compatible` property against a driver's [`crate::driver::interface::DeviceDriver::match_compatible`]
+//! list, and read its `reg` property.
+//!
+//! This is deliberately *not* a full devicetree-driven driver manager. Every driver instance in
+//! this fork is a `'static` singleton declared by hand in a board's `driver.rs` (see
+//! `crate::kernel_drivers!`) -- there's no heap, and so no way to hold an arbitrary number of
+//! arbitrarily-typed driver objects behind a runtime-built registry the way a devicetree walk
+//! that "instantiates drivers automatically" would need to. What's provided here is the real
+//! parsing and matching primitive a driver can use to look its own MMIO window up in a devicetree
+//! instead of a hardcoded board constant -- see [`DeviceTree::find_by_compatible`] and
+//! [`crate::bsp::device_driver::arm::pl011::PL011Uart::probe`] for a driver actually doing that --
+//! not a mechanism that discovers and creates drivers on its own.
+//!
+//! Also scoped down from a general-purpose parser: only single-cell (`#address-cells = <1>`,
+//! `#size-cells = <1>`) `reg` properties are decoded, since that's what every peripheral node on
+//! the boards this fork targets uses. A node under a `#address-cells = <2>` bus (as the DTB root
+//! itself typically is) still matches on `compatible`, but its `reg` is read as single cells too,
+//! so [`MatchedNode::reg`] does not hold its real address and size.
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const FDT_MAGIC: u32 = 0xd00d_feed;
+
+/// How many levels of nested nodes [`DeviceTree::find_by_compatible`] tracks at once.
+const MAX_NESTING_DEPTH: usize = 16;
+
+const FDT_BEGIN_NODE: u32 = 0x1;
+const FDT_END_NODE: u32 = 0x2;
+const FDT_PROP: u32 = 0x3;
+const FDT_NOP: u32 = 0x4;
+const FDT_END: u32 = 0x9;
+
+fn read_be_u32(blob: &[u8], offset: usize) -> Option<u32> {
+    let bytes = blob.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+/// Round `offset` up to the next 4-byte boundary, as every token and property value in the
+/// structure block is padded to.
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A parsed flattened devicetree header, and a handle onto the blob it came from.
+pub struct DeviceTree<'a> {
+    blob: &'a [u8],
+    off_dt_struct: usize,
+    off_dt_strings: usize,
+}
+
+/// The `reg` and `compatible` properties of one matched node.
+pub struct MatchedNode<'a> {
+    /// The strings listed in the node's `compatible` property, most-specific first.
+    pub compatible: &'a [u8],
+    reg: Option<(u32, u32)>,
+}
+
+impl MatchedNode<'_> {
+    /// The node's `reg` property, decoded as a single `(address, size)` pair under an assumed
+    /// `#address-cells = <1>`, `#size-cells = <1>` -- see the module doc's scoping note.
+    pub fn reg(&self) -> Option<(u32, u32)> {
+        self.reg
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl<'a> DeviceTree<'a> {
+    /// Parse the header of a flattened devicetree blob.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to at least `size` readable bytes containing a valid FDT blob for the
+    ///   duration of `'a`.
+    pub unsafe fn from_raw_parts(ptr: *const u8, size: usize) -> Result<Self, &'static str> {
+        let blob = unsafe { core::slice::from_raw_parts(ptr, size) };
+        Self::from_slice(blob)
+    }
+
+    /// Parse the header of an already-borrowed flattened devicetree blob.
+    pub fn from_slice(blob: &'a [u8]) -> Result<Self, &'static str> {
+        let magic = read_be_u32(blob, 0).ok_or("devicetree: blob too short for an FDT header")?;
+        if magic != FDT_MAGIC {
+            return Err("devicetree: bad magic, not an FDT blob");
+        }
+
+        let off_dt_struct = read_be_u32(blob, 8).ok_or("devicetree: truncated header")? as usize;
+        let off_dt_strings = read_be_u32(blob, 12).ok_or("devicetree: truncated header")? as usize;
+
+        Ok(Self {
+            blob,
+            off_dt_struct,
+            off_dt_strings,
+        })
+    }
+
+    /// Look up the NUL-terminated string at `strings_offset` into the strings block.
+    fn string_at(&self, strings_offset: u32) -> &'a [u8] {
+        let start = self.off_dt_strings + strings_offset as usize;
+        match self.blob[start..].iter().position(|&b| b == 0) {
+            Some(len) => &self.blob[start..start + len],
+            None => &[],
+        }
+    }
+
+    /// Walk every node in the tree, returning the first whose `compatible` property contains
+    /// `compatible` as one of its NUL-separated entries.
+    ///
+    /// A node more than [`MAX_NESTING_DEPTH`] levels deep ends the search with `None` rather
+    /// than being mis-attributed to the wrong node -- deep enough for every board this fork
+    /// targets.
+    pub fn find_by_compatible(&self, compatible: &str) -> Option<MatchedNode<'a>> {
+        let wanted = compatible.as_bytes();
+
+        // One (compatible, reg) slot per nesting level currently open, since a node's properties
+        // all appear before its children in the structure block and must not leak onto them.
+        let mut stack: [(Option<&'a [u8]>, Option<(u32, u32)>); MAX_NESTING_DEPTH] =
+            [(None, None); MAX_NESTING_DEPTH];
+        let mut depth: usize = 0;
+
+        let mut offset = self.off_dt_struct;
+
+        loop {
+            let token = read_be_u32(self.blob, offset)?;
+            offset += 4;
+
+            match token {
+                FDT_BEGIN_NODE => {
+                    // Skip the NUL-terminated node name.
+                    let name_len = self.blob[offset..].iter().position(|&b| b == 0)?;
+                    offset = align4(offset + name_len + 1);
+
+                    if depth >= MAX_NESTING_DEPTH {
+                        return None;
+                    }
+                    stack[depth] = (None, None);
+                    depth += 1;
+                }
+                FDT_PROP => {
+                    let len = read_be_u32(self.blob, offset)? as usize;
+                    let name_off = read_be_u32(self.blob, offset + 4)?;
+                    let value_start = offset + 8;
+                    let value = self.blob.get(value_start..value_start + len)?;
+                    let name = self.string_at(name_off);
+
+                    if depth > 0 {
+                        let node = &mut stack[depth - 1];
+                        if name == b"compatible" {
+                            node.0 = Some(value);
+                        } else if name == b"reg" && len >= 8 {
+                            let address = u32::from_be_bytes(value[0..4].try_into().unwrap());
+                            let size = u32::from_be_bytes(value[4..8].try_into().unwrap());
+                            node.1 = Some((address, size));
+                        }
+                    }
+
+                    offset = align4(value_start + len);
+                }
+                FDT_END_NODE => {
+                    if depth == 0 {
+                        return None;
+                    }
+                    depth -= 1;
+                    let (node_compatible, node_reg) = stack[depth];
+                    if let Some(node_compatible) = node_compatible {
+                        if node_compatible
+                            .split(|&b| b == 0)
+                            .any(|entry| entry == wanted)
+                        {
+                            return Some(MatchedNode {
+                                compatible: node_compatible,
+                                reg: node_reg,
+                            });
+                        }
+                    }
+                }
+                FDT_NOP => {}
+                FDT_END => return None,
+                _ => return None,
+            }
+        }
+    }
+}
@@ -0,0 +1,372 @@
+//! DMA engine abstraction: scatter-gather transfers, submitted through one
+//! [`interface::DmaChannel`] API regardless of which controller backs a given channel, with cache
+//! maintenance handled internally so callers never issue `DC`/`DSB` themselves.
+//!
+//! There is no concrete [`interface::DmaChannel`] implementation wired into any board yet. The
+//! obvious backend, the BCM2835/BCM2711 DMA controller (see
+//! `crate::bsp::device_driver::bcm::bcm2xxx_dma` for its control-block encoding), needs a real
+//! MMIO init/kick-off/completion-polling sequence to actually move a transfer, and writing that
+//! from memory without hardware to test against carries the same risk this fork has already
+//! declined elsewhere (see `bcm2xxx_emmc`'s module doc): shipping confidently wrong register
+//! writes. So today's UART, framebuffer, and audio drivers keep using their existing PIO/mailbox
+//! data paths unchanged -- rewiring a driver that works onto an unverified new subsystem would
+//! risk a regression this fork has no way to catch. What's provided is the hardware-independent
+//! half a `DmaChannel` implementation needs on day one: the channel trait itself, scatter-gather
+//! descriptor chains, and real cache maintenance, so whoever writes that driver doesn't have to
+//! design the API around it too.
+//!
+//! # Cache maintenance
+//!
+//! DMA moves bytes without going through the CPU's cache, so a channel's `submit`/`poll_completion`
+//! must clean or invalidate the cache lines backing a transfer's buffers itself -- a caller handing
+//! over a `&[u8]` shouldn't need to know the transfer happened via DMA at all. [`prepare_buffers`]
+//! and [`finish_buffers`] are that internal-use maintenance, meant to be called by a
+//! [`interface::DmaChannel`] implementation around its own submit/poll, not by callers of the
+//! channel.
+//!
+//! # Bus addresses and DMA-safe buffers
+//!
+//! The only bus-addressing scheme this fork has ever needed is the BCM283x/BCM2711 VideoCore
+//! SDRAM alias `bcm2xxx_mailbox`/`bcm2xxx_framebuffer` already used before this module existed --
+//! [`phys_to_bus`]/[`bus_to_phys`] are that same conversion, pulled out here so a third caller
+//! doesn't open-code it a third time. `bsp_qemu_virt`'s virtio-mmio devices take physical
+//! addresses directly and never call these.
+//!
+//! [`is_dma_safe`] and [`stage_if_needed`]/[`unstage_if_needed`] are the bounce-buffering half of
+//! the same "callers shouldn't have to think about it" goal: a buffer that doesn't start and end
+//! on a cache line boundary can't be cleaned/invalidated by [`prepare_buffers`]/[`finish_buffers`]
+//! without touching bytes outside it that belong to something else, so it has to be copied into a
+//! [`CoherentBuffer`] the DMA engine can safely touch instead. Like the cache maintenance above,
+//! staging is meant to be automatic from a caller's point of view -- a future
+//! [`interface::DmaChannel`] implementation would call `stage_if_needed` before submitting a
+//! descriptor and `unstage_if_needed` after polling it complete, invisibly to whoever built the
+//! [`DmaTransfer`].
+
+use crate::synchronization::{Mutex, NullLock};
+use core::arch::asm;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// DMA channel interfaces.
+pub mod interface {
+    use core::task::Poll;
+
+    /// Implemented by a driver that can submit a scatter-gather [`super::DmaTransfer`] to a DMA
+    /// engine and be polled for completion separately from submitting it -- the same
+    /// submit/poll split [`crate::storage::interface::RawBlockQueue`] uses for block IO.
+    pub trait DmaChannel {
+        /// Queue `transfer`, returning a token identifying it.
+        ///
+        /// Must run [`super::prepare_buffers`] on `transfer` before handing its descriptors to
+        /// the engine.
+        fn submit(
+            &self,
+            transfer: &super::DmaTransfer<'_>,
+        ) -> Result<super::TransferToken, &'static str>;
+
+        /// Non-blocking check for whether `token`'s transfer has completed.
+        ///
+        /// Must run [`super::finish_buffers`] on the transfer once it reports
+        /// [`Poll::Ready`], before returning that to the caller.
+        fn poll_completion(&self, token: super::TransferToken) -> Poll<Result<(), &'static str>>;
+    }
+}
+
+/// The assumed cache line size, in bytes, for [`prepare_buffers`]/[`finish_buffers`]'s
+/// line-by-line `DC` maintenance -- 64 bytes on every core this fork targets (Cortex-A53/A72 and
+/// QEMU's emulated `virt` CPU).
+const CACHE_LINE_SIZE: usize = 64;
+
+/// How many scatter-gather entries a [`DescriptorChain`] holds -- fixed, since this fork has no
+/// heap to grow one on demand.
+pub const MAX_SG_DESCRIPTORS: usize = 8;
+
+/// Which way a transfer moves bytes, since that decides whether buffers need cleaning or
+/// invalidating.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// From memory to the device (e.g. a UART TX or a block write).
+    MemoryToDevice,
+    /// From the device to memory (e.g. a UART RX or a block read).
+    DeviceToMemory,
+}
+
+/// One scatter-gather entry: a physically contiguous run of bytes.
+#[derive(Copy, Clone, Debug)]
+pub struct Descriptor {
+    pub address: usize,
+    pub length: usize,
+}
+
+/// A fixed-capacity chain of scatter-gather descriptors.
+pub struct DescriptorChain {
+    descriptors: [Descriptor; MAX_SG_DESCRIPTORS],
+    count: usize,
+}
+
+/// Identifies one transfer submitted through a [`interface::DmaChannel`], to be handed back to
+/// [`interface::DmaChannel::poll_completion`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TransferToken(pub usize);
+
+/// A scatter-gather transfer ready to hand to a [`interface::DmaChannel`].
+pub struct DmaTransfer<'a> {
+    pub direction: Direction,
+    pub descriptors: &'a [Descriptor],
+}
+
+/// Adds the BCM283x/BCM2711 "SDRAM, L2 cache disabled" alias to a physical address, so the
+/// VideoCore reads/writes it without the ARM side needing cache maintenance around every access.
+/// Mirrors [`bus_to_phys`], in the other direction.
+const BUS_ALIAS: u32 = 0xc000_0000;
+
+/// A statically allocated, cache-line-aligned region of memory, handed out by [`alloc_coherent`].
+///
+/// Never freed -- this fork's coherent pool is a bump allocator, matching every other
+/// fixed-capacity resource here (no heap to give a real allocator something to manage).
+pub struct CoherentBuffer {
+    address: usize,
+    len: usize,
+}
+
+const COHERENT_POOL_SIZE: usize = 64 * 1024;
+
+struct CoherentPool {
+    bytes: [u8; COHERENT_POOL_SIZE],
+    next_offset: usize,
+}
+
+static COHERENT_POOL: NullLock<CoherentPool> = NullLock::new(CoherentPool {
+    bytes: [0; COHERENT_POOL_SIZE],
+    next_offset: 0,
+});
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl DescriptorChain {
+    /// Create an empty chain.
+    pub const fn new() -> Self {
+        Self {
+            descriptors: [Descriptor {
+                address: 0,
+                length: 0,
+            }; MAX_SG_DESCRIPTORS],
+            count: 0,
+        }
+    }
+
+    /// Append a descriptor, failing once [`MAX_SG_DESCRIPTORS`] is reached.
+    pub fn push(&mut self, descriptor: Descriptor) -> Result<(), &'static str> {
+        if self.count >= MAX_SG_DESCRIPTORS {
+            return Err("dma: descriptor chain is full");
+        }
+
+        self.descriptors[self.count] = descriptor;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// The chain's descriptors, in submission order.
+    pub fn as_slice(&self) -> &[Descriptor] {
+        &self.descriptors[..self.count]
+    }
+}
+
+impl Default for DescriptorChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CoherentBuffer {
+    /// The buffer's address -- physical and virtual are the same under this fork's identity
+    /// mapping, and this is also what [`phys_to_bus`] expects.
+    pub fn address(&self) -> usize {
+        self.address
+    }
+
+    /// The buffer's length in bytes, as requested from [`alloc_coherent`].
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Borrow the buffer's contents.
+    ///
+    /// # Safety
+    ///
+    /// - The caller must not create another live reference to this buffer's bytes at the same
+    ///   time, e.g. via a second call to this method or through the raw `address()`.
+    pub unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.address as *mut u8, self.len) }
+    }
+}
+
+/// Add the VideoCore bus-address alias to a physical address.
+pub const fn phys_to_bus(phys_addr: u32) -> u32 {
+    phys_addr | BUS_ALIAS
+}
+
+/// Strip the VideoCore bus-address alias, returning the address the ARM core can dereference
+/// directly.
+pub const fn bus_to_phys(bus_addr: u32) -> u32 {
+    bus_addr & !BUS_ALIAS
+}
+
+/// Hand out `size` bytes of cache-line-aligned, never-freed memory suitable for
+/// [`alloc_coherent`]'s callers to build DMA-safe [`Descriptor`]s from.
+pub fn alloc_coherent(size: usize) -> Result<CoherentBuffer, &'static str> {
+    let aligned_size = (size + CACHE_LINE_SIZE - 1) & !(CACHE_LINE_SIZE - 1);
+
+    COHERENT_POOL.lock(|pool| {
+        let start = (pool.next_offset + CACHE_LINE_SIZE - 1) & !(CACHE_LINE_SIZE - 1);
+        let end = start
+            .checked_add(aligned_size)
+            .ok_or("dma: coherent allocation size overflowed")?;
+        if end > COHERENT_POOL_SIZE {
+            return Err("dma: coherent memory pool exhausted");
+        }
+
+        pool.next_offset = end;
+        Ok(CoherentBuffer {
+            address: pool.bytes.as_ptr() as usize + start,
+            len: size,
+        })
+    })
+}
+
+/// Whether `descriptor`'s buffer can be handed to a DMA engine directly, or needs staging into a
+/// [`CoherentBuffer`] first: both ends must sit on a cache line boundary, or the line-granular
+/// cache maintenance [`prepare_buffers`]/[`finish_buffers`] performs would touch bytes outside the
+/// buffer that belong to something else.
+pub fn is_dma_safe(descriptor: &Descriptor) -> bool {
+    descriptor.address % CACHE_LINE_SIZE == 0 && descriptor.length % CACHE_LINE_SIZE == 0
+}
+
+/// Stage `descriptor` into `bounce` if [`is_dma_safe`] says it needs it, copying its bytes in for
+/// a [`Direction::MemoryToDevice`] transfer, and return the descriptor a DMA engine should
+/// actually be given -- either `descriptor` unchanged, or one pointing at `bounce`.
+///
+/// # Safety
+///
+/// - `bounce` must be at least `descriptor.length` bytes and not aliased elsewhere while staged.
+/// - For [`Direction::MemoryToDevice`], `descriptor.address` must be readable for
+///   `descriptor.length` bytes.
+pub unsafe fn stage_if_needed(
+    descriptor: Descriptor,
+    bounce: &mut CoherentBuffer,
+    direction: Direction,
+) -> Result<Descriptor, &'static str> {
+    if is_dma_safe(&descriptor) {
+        return Ok(descriptor);
+    }
+    if bounce.len() < descriptor.length {
+        return Err("dma: bounce buffer is smaller than the transfer it's staging");
+    }
+
+    if direction == Direction::MemoryToDevice {
+        let source = unsafe {
+            core::slice::from_raw_parts(descriptor.address as *const u8, descriptor.length)
+        };
+        let dest = unsafe { bounce.as_mut_slice() };
+        dest[..descriptor.length].copy_from_slice(source);
+    }
+
+    Ok(Descriptor {
+        address: bounce.address(),
+        length: descriptor.length,
+    })
+}
+
+/// Copy a [`Direction::DeviceToMemory`] transfer's bytes back out of `bounce` into the buffer
+/// `original` described, undoing [`stage_if_needed`]. A no-op if `original` was already DMA-safe
+/// (i.e. `stage_if_needed` returned it unchanged, so there's nothing to copy back).
+///
+/// # Safety
+///
+/// - Same aliasing requirement as [`stage_if_needed`].
+/// - `original.address` must be writable for `original.length` bytes.
+pub unsafe fn unstage_if_needed(
+    original: Descriptor,
+    bounce: &mut CoherentBuffer,
+    direction: Direction,
+) {
+    if direction != Direction::DeviceToMemory || is_dma_safe(&original) {
+        return;
+    }
+
+    let source = unsafe { bounce.as_mut_slice() };
+    let dest =
+        unsafe { core::slice::from_raw_parts_mut(original.address as *mut u8, original.length) };
+    dest.copy_from_slice(&source[..original.length]);
+}
+
+/// Cache maintenance to run on a transfer's buffers before submitting it to the engine.
+///
+/// Cleans (writes back) buffers going to the device, so the DMA engine reads what the CPU last
+/// wrote instead of stale memory; invalidates buffers the device is about to write, so a later
+/// read can't be satisfied from a cache line the DMA engine bypassed.
+pub fn prepare_buffers(transfer: &DmaTransfer<'_>) {
+    for descriptor in transfer.descriptors {
+        match transfer.direction {
+            Direction::MemoryToDevice => clean_range(descriptor.address, descriptor.length),
+            Direction::DeviceToMemory => invalidate_range(descriptor.address, descriptor.length),
+        }
+    }
+}
+
+/// Cache maintenance to run on a transfer's buffers once the engine reports it complete.
+///
+/// Only [`Direction::DeviceToMemory`] buffers need anything here: a line touched by
+/// [`prepare_buffers`]'s invalidate could have been speculatively refilled by the CPU while the
+/// transfer was in flight, so it's invalidated again before the caller is allowed to read it.
+pub fn finish_buffers(transfer: &DmaTransfer<'_>) {
+    if transfer.direction == Direction::DeviceToMemory {
+        for descriptor in transfer.descriptors {
+            invalidate_range(descriptor.address, descriptor.length);
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+/// Clean (write back, keep valid) every cache line covering `[address, address + length)`.
+fn clean_range(address: usize, length: usize) {
+    for_each_line(address, length, |line| unsafe {
+        asm!("dc cvac, {}", in(reg) line, options(nostack, preserves_flags));
+    });
+    dsb_sy();
+}
+
+/// Invalidate every cache line covering `[address, address + length)`, discarding their contents.
+fn invalidate_range(address: usize, length: usize) {
+    for_each_line(address, length, |line| unsafe {
+        asm!("dc ivac, {}", in(reg) line, options(nostack, preserves_flags));
+    });
+    dsb_sy();
+}
+
+/// Call `f` once per [`CACHE_LINE_SIZE`]-aligned line touched by `[address, address + length)`.
+fn for_each_line(address: usize, length: usize, mut f: impl FnMut(usize)) {
+    if length == 0 {
+        return;
+    }
+
+    let start = address & !(CACHE_LINE_SIZE - 1);
+    let end = (address + length + CACHE_LINE_SIZE - 1) & !(CACHE_LINE_SIZE - 1);
+
+    let mut line = start;
+    while line < end {
+        f(line);
+        line += CACHE_LINE_SIZE;
+    }
+}
+
+fn dsb_sy() {
+    unsafe { asm!("dsb sy", options(nostack, preserves_flags)) }
+}
@@ -0,0 +1,331 @@
+//! Driver support.
+
+use crate::{
+    memory::MMIODescriptor,
+    synchronization::{Mutex, NullLock},
+    time::TimeManager,
+};
+use core::time::Duration;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Driver interfaces.
+pub mod interface {
+    /// Operations that a device driver must implement.
+    pub trait DeviceDriver {
+        /// Return a compatibility string for identifying the driver.
+        fn compatible(&self) -> &'static str;
+
+        /// Devicetree `compatible` strings this driver's hardware is known to answer to, most
+        /// specific first, for matching against [`crate::devicetree::DeviceTree::find_by_compatible`].
+        ///
+        /// Distinct from [`Self::compatible`]: that one is a human-readable label used in driver
+        /// logs (e.g. `"BCM PL011 UART"`), this one is the exact string a real devicetree source
+        /// would use (e.g. `"arm,pl011"`). Takes no `self` because probing happens *before* a
+        /// driver instance exists -- it picks the instance's own MMIO address -- so it is bounded
+        /// `Self: Sized` to keep the trait itself object-safe for the `dyn DeviceDriver`
+        /// descriptors below. Empty by default -- most drivers in this fork are still wired up
+        /// with hand-written MMIO addresses in a board's `kernel_drivers!` block, not devicetree
+        /// probing, and have never needed the real-world string.
+        fn match_compatible() -> &'static [&'static str]
+        where
+            Self: Sized,
+        {
+            &[]
+        }
+
+        /// Called by the kernel to bring up the device.
+        ///
+        /// # Safety
+        ///
+        /// - During init, drivers might do stuff with hardware that violates Rust's safety
+        ///   rules. This is only allowed here.
+        unsafe fn init(&self) -> Result<(), &'static str> {
+            Ok(())
+        }
+    }
+}
+
+/// Type to be used as an optional callback after a driver's `init()` has run.
+pub type DeviceDriverPostInitCallback = unsafe fn() -> Result<(), &'static str>;
+
+/// A descriptor for device drivers.
+#[derive(Copy, Clone)]
+pub struct DeviceDriverDescriptor {
+    device_driver: &'static (dyn interface::DeviceDriver + Sync),
+    post_init_callback: Option<DeviceDriverPostInitCallback>,
+    mmio: Option<MMIODescriptor>,
+    irq_number: Option<usize>,
+    init_duration: Option<Duration>,
+}
+
+impl DeviceDriverDescriptor {
+    /// Create an instance.
+    ///
+    /// `init_duration` starts out `None` and is filled in by
+    /// [`DriverManager::init_drivers`] once this driver has actually been brought up.
+    pub fn new(
+        device_driver: &'static (dyn interface::DeviceDriver + Sync),
+        post_init_callback: Option<DeviceDriverPostInitCallback>,
+        mmio: Option<MMIODescriptor>,
+        irq_number: Option<usize>,
+    ) -> Self {
+        Self {
+            device_driver,
+            post_init_callback,
+            mmio,
+            irq_number,
+            init_duration: None,
+        }
+    }
+}
+
+/// A driver's introspection info, as returned by [`DriverManager::enumerate`].
+#[derive(Copy, Clone, Debug)]
+pub struct DriverInfo {
+    /// The driver's human-readable compatible string, e.g. `"BCM PL011 UART"`.
+    pub compatible: &'static str,
+    /// The driver's MMIO range as registered. A Raspberry Pi mailbox sub-device reports the
+    /// mailbox window it shares (see the comments in `bsp::raspberrypi::driver`).
+    pub mmio: Option<MMIODescriptor>,
+    /// The IRQ number this driver was registered with, if any.
+    pub irq_number: Option<usize>,
+    /// How long [`interface::DeviceDriver::init`] and the post-init callback together took, or
+    /// `None` if [`DriverManager::init_drivers`] hasn't reached this driver yet.
+    pub init_duration: Option<Duration>,
+}
+
+const NUM_DRIVER_SLOTS: usize = 10;
+
+/// Provides device driver management functions.
+pub struct DriverManager {
+    descriptors: NullLock<[Option<DeviceDriverDescriptor>; NUM_DRIVER_SLOTS]>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static DRIVER_MANAGER: DriverManager = DriverManager::new();
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl DriverManager {
+    /// Create an instance.
+    pub const fn new() -> Self {
+        Self {
+            descriptors: NullLock::new([None; NUM_DRIVER_SLOTS]),
+        }
+    }
+
+    /// Register a device driver with the kernel.
+    pub fn register_driver(&self, descriptor: DeviceDriverDescriptor) {
+        self.descriptors.lock(
+            |descriptors| match descriptors.iter_mut().find(|d| d.is_none()) {
+                Some(free_slot) => *free_slot = Some(descriptor),
+                None => {
+                    crate::kassert::kassert!(false, "driver_manager", "ran out of driver slots")
+                }
+            },
+        );
+    }
+
+    /// Fully initialize all registered drivers, in registration order.
+    ///
+    /// # Safety
+    ///
+    /// - See `DeviceDriver::init()`.
+    pub unsafe fn init_drivers(&self) {
+        self.descriptors.lock(|descriptors| {
+            for descriptor in descriptors.iter_mut().flatten() {
+                let start = crate::time::time_manager().uptime();
+
+                if let Err(x) = descriptor.device_driver.init() {
+                    panic!(
+                        "Error initializing driver: {}: {}",
+                        descriptor.device_driver.compatible(),
+                        x
+                    );
+                }
+
+                if let Some(callback) = &descriptor.post_init_callback {
+                    if let Err(x) = callback() {
+                        panic!(
+                            "Error during driver post-init: {}: {}",
+                            descriptor.device_driver.compatible(),
+                            x
+                        );
+                    }
+                }
+
+                let end = crate::time::time_manager().uptime();
+                descriptor.init_duration = Some(end - start);
+                crate::trace::record_span(
+                    "driver",
+                    descriptor.device_driver.compatible(),
+                    start,
+                    end,
+                );
+
+                crate::log::log_debug!(
+                    "driver",
+                    "{} initialized",
+                    descriptor.device_driver.compatible()
+                );
+            }
+        });
+    }
+
+    /// Enumerate the compatible strings of all registered drivers, in registration order.
+    pub fn all_device_compatible(&self, mut f: impl FnMut(&'static str)) {
+        self.descriptors.lock(|descriptors| {
+            for descriptor in descriptors.iter().flatten() {
+                f(descriptor.device_driver.compatible());
+            }
+        });
+    }
+
+    /// Enumerate every registered driver's introspection info, in registration order.
+    ///
+    /// Backs both the `lsdev` shell command and `/proc/drivers` (see
+    /// [`crate::fs::procfs`]); callers only interested in the compatible string should keep using
+    /// [`Self::all_device_compatible`] instead.
+    pub fn enumerate(&self, mut f: impl FnMut(DriverInfo)) {
+        self.descriptors.lock(|descriptors| {
+            for descriptor in descriptors.iter().flatten() {
+                f(DriverInfo {
+                    compatible: descriptor.device_driver.compatible(),
+                    mmio: descriptor.mmio,
+                    irq_number: descriptor.irq_number,
+                    init_duration: descriptor.init_duration,
+                });
+            }
+        });
+    }
+}
+
+/// Return a reference to the global driver manager.
+pub fn driver_manager() -> &'static DriverManager {
+    &DRIVER_MANAGER
+}
+
+/// An MMIO range paired with the name of the driver that owns it, for the overlap audit
+/// [`check_mmio_layout`] runs over every board's [`kernel_drivers!`] table.
+#[derive(Copy, Clone)]
+pub struct NamedMmioExtent {
+    pub name: &'static str,
+    pub start_addr: usize,
+    pub end_addr_inclusive: usize,
+}
+
+/// Panic if `extents` contains two differently-named drivers whose MMIO ranges partially
+/// overlap.
+///
+/// Two ranges that are byte-for-byte identical are let through: the Raspberry Pi `MAILBOX`,
+/// `FRAMEBUFFER`, and `POWER` drivers are virtual sub-devices multiplexed through the same
+/// mailbox property-tag channel and legitimately share one `MMIODescriptor` (see their
+/// `kernel_drivers!` block in `bsp::raspberrypi::driver`). A *partial* overlap between two
+/// differing ranges has no such explanation anywhere in this codebase -- it's what a
+/// copy-pasted `mmio::FOO_START` typo in a new BSP looks like, and this exists to catch that
+/// immediately instead of as a mysterious register corruption bug found later under real
+/// hardware or QEMU.
+///
+/// Called from [`kernel_drivers!`] as a `const` item, so it runs during compilation rather than
+/// at driver construction or `bsp::driver::init()` time. That's deliberate, not a simplification
+/// of some runtime check: every driver `static` in this codebase (see
+/// `bsp::device_driver::common::MMIODerefWrapper::new`) is already built by a `const unsafe fn`
+/// evaluated while the `static` itself is being initialized, so there is no later "construction"
+/// or "init" moment at runtime left to register into a table at. A compile-time panic here is
+/// strictly earlier, and just as unmissable, as the runtime one a copy-paste bug would otherwise
+/// need to be caught by.
+pub const fn check_mmio_layout(extents: &[NamedMmioExtent]) {
+    let mut i = 0;
+    while i < extents.len() {
+        let mut j = i + 1;
+        while j < extents.len() {
+            let a = &extents[i];
+            let b = &extents[j];
+
+            let identical =
+                a.start_addr == b.start_addr && a.end_addr_inclusive == b.end_addr_inclusive;
+            let disjoint =
+                a.end_addr_inclusive < b.start_addr || b.end_addr_inclusive < a.start_addr;
+
+            assert!(
+                identical || disjoint,
+                "two drivers have overlapping MMIO ranges"
+            );
+
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+/// Declaratively register a board's device drivers.
+///
+/// Each entry names a driver instance already in scope, its MMIO range, and an optional
+/// post-init callback; a driver that raises interrupts additionally names its IRQ number and the
+/// [`crate::exception::asynchronous::interface::IRQManager`] that owns it. The macro expands to
+/// the `init()` function a board's `driver` module used to hand-write itself (one
+/// [`DriverManager::register_driver`] call per entry, in declaration order, followed by an
+/// `enable()` call for entries that specified an IRQ) plus a `MMIO_LAYOUT` table listing every
+/// entry's MMIO range, checked at compile time by [`check_mmio_layout`] for accidental overlaps.
+///
+/// There's no separate dependency graph: a driver that must come up before another (e.g. the
+/// console, before anything logs through it) expresses that by being listed first.
+///
+/// ```ignore
+/// kernel_drivers! {
+///     &PL011_UART, MMIODescriptor::new(map::PL011_UART_START, 0x34), post_init_uart;
+///     &INTERRUPT_CONTROLLER, MMIODescriptor::new(map::GICD_START, 0x1a0), post_init_irq_controller,
+///         irq_map::PL011_UART => irq_manager();
+/// }
+/// ```
+#[macro_export]
+macro_rules! kernel_drivers {
+    ( $( $driver:expr, $mmio:expr, $post_init:expr $(, $irq:expr => $irq_manager:expr)? ; )+ ) => {
+        /// Every device's MMIO range, in declaration order.
+        pub static MMIO_LAYOUT: &[$crate::memory::MMIODescriptor] = &[ $( $mmio ),+ ];
+
+        const _: () = $crate::driver::check_mmio_layout(&[
+            $(
+                $crate::driver::NamedMmioExtent {
+                    name: stringify!($driver),
+                    start_addr: $mmio.start_addr(),
+                    end_addr_inclusive: $mmio.end_addr_inclusive(),
+                },
+            )+
+        ]);
+
+        /// Register this board's device drivers with the kernel's driver manager.
+        ///
+        /// # Safety
+        ///
+        /// - See `driver::interface::DeviceDriver::init()`.
+        pub unsafe fn init() -> Result<(), &'static str> {
+            $(
+                #[allow(unused_mut)]
+                let mut irq_number: Option<usize> = None;
+                $( irq_number = Some($irq.get()); )?
+
+                $crate::driver::driver_manager().register_driver(
+                    $crate::driver::DeviceDriverDescriptor::new(
+                        $driver,
+                        $post_init,
+                        Some($mmio),
+                        irq_number,
+                    ),
+                );
+
+                $( $irq_manager.enable($irq); )?
+            )+
+
+            Ok(())
+        }
+    };
+}
@@ -0,0 +1,50 @@
+//! Synchronous and asynchronous exception handling.
+//!
+//! There is no exception vector table in this fork yet: `VBAR_EL1` is never programmed, and
+//! [`handling_init`] only brings up the software side of [`asynchronous`] (IRQ controller
+//! bookkeeping, deferred-handler queue, latency stats), not a real hardware vector. Per-vector
+//! customization -- e.g. letting a chainloader install a trivial SError handler, or a BSP supply
+//! lower-EL AArch32 vectors for a hypervisor build -- needs an actual table to hook into before a
+//! weak-symbol or registration-based override API would have anything to attach to. Building that
+//! table is tracked as follow-up work; see [`asynchronous::IrqMode`] for the same caveat applied
+//! to the IRQ dispatch path.
+
+pub mod asynchronous;
+pub mod serror;
+pub mod syscall;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Kernel privilege levels.
+#[allow(missing_docs)]
+#[derive(Eq, PartialEq)]
+pub enum PrivilegeLevel {
+    User,
+    Kernel,
+    Hypervisor,
+    Unknown,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Init exception handling.
+///
+/// Despite the name, this does not yet set `VBAR_EL1` or install any vector table -- see the
+/// module docs. It only runs [`asynchronous::init`], which today is itself a no-op kept as the
+/// call site that a real vector-table bring-up would eventually extend.
+///
+/// # Safety
+///
+/// - Changes the HW state of the executing core.
+pub unsafe fn handling_init() {
+    asynchronous::init();
+}
+
+/// Return the currently active privilege level.
+pub fn current_privilege_level() -> (PrivilegeLevel, &'static str) {
+    (PrivilegeLevel::Kernel, "EL1")
+}
@@ -0,0 +1,474 @@
+//! Asynchronous exception handling.
+
+use crate::synchronization::{Mutex, NullLock};
+use core::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Interrupt Request.
+pub mod interface {
+    /// Implemented by interrupt controllers.
+    pub trait IRQManager {
+        /// The IRQ number type of the implementing controller.
+        ///
+        /// Concrete controllers are expected to use a [`super::BoundedUsize`] sized to their own
+        /// IRQ space rather than a bare `usize`, so that a number produced for one controller
+        /// cannot be silently accepted by another.
+        type IRQNumberType: Copy;
+
+        /// Register a handler for the given IRQ number.
+        fn register_handler(
+            &self,
+            irq_number: Self::IRQNumberType,
+            descriptor: super::IRQHandlerDescriptor<Self::IRQNumberType>,
+        ) -> Result<(), &'static str>;
+
+        /// Enable an interrupt in the controller.
+        fn enable(&self, irq_number: Self::IRQNumberType);
+
+        /// Handle a pending interrupt, dispatching to the registered handler.
+        fn handle_pending_irqs(&self);
+    }
+}
+
+/// A handler for a given IRQ.
+pub trait IRQHandler {
+    /// Called when the corresponding IRQ fires.
+    fn handle(&self) -> Result<(), &'static str>;
+}
+
+/// Where an [`IRQHandlerDescriptor`]'s handler actually runs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IrqMode {
+    /// Runs inline, on the hard-IRQ path, with interrupts masked -- the default, and the only
+    /// mode a controller's `handle_pending_irqs` needs to do anything special for.
+    Direct,
+    /// Deferred: `handle_pending_irqs` only records that the IRQ fired and queues the handler
+    /// with [`defer_handler`] instead of calling it inline; [`run_deferred_handlers`] runs it
+    /// later.
+    ///
+    /// This is the split a real threaded IRQ needs -- keep the hard-IRQ path to masking the line
+    /// -- but this fork has no scheduler or schedulable tasks (see [`crate::executor`] and
+    /// [`crate::ipc`]'s docs on why) to run the deferred half on a dedicated thread with
+    /// interrupts enabled that can sleep or allocate, which is the actual point of "threaded".
+    /// Until one exists, [`run_deferred_handlers`] is the closest honest approximation: it's
+    /// polled from the interactive main loop, so a `Threaded` handler at least never runs with
+    /// interrupts masked, but it still can't block or sleep the way a real handler thread could.
+    Threaded,
+}
+
+/// Wraps a handler with a human-readable name for diagnostics.
+#[derive(Copy, Clone)]
+pub struct IRQHandlerDescriptor<T> {
+    number: T,
+    name: &'static str,
+    handler: &'static (dyn IRQHandler + Sync),
+    mode: IrqMode,
+}
+
+impl<T> IRQHandlerDescriptor<T> {
+    /// Create an instance that runs [`IrqMode::Direct`], on the hard-IRQ path.
+    pub const fn new(
+        number: T,
+        name: &'static str,
+        handler: &'static (dyn IRQHandler + Sync),
+    ) -> Self {
+        Self {
+            number,
+            name,
+            handler,
+            mode: IrqMode::Direct,
+        }
+    }
+
+    /// Create an instance that runs [`IrqMode::Threaded`] instead of inline.
+    pub const fn new_threaded(
+        number: T,
+        name: &'static str,
+        handler: &'static (dyn IRQHandler + Sync),
+    ) -> Self {
+        Self {
+            number,
+            name,
+            handler,
+            mode: IrqMode::Threaded,
+        }
+    }
+
+    /// Return the number.
+    pub const fn number(&self) -> &T {
+        &self.number
+    }
+
+    /// Return the name.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Return the handler.
+    pub const fn handler(&self) -> &'static (dyn IRQHandler + Sync) {
+        self.handler
+    }
+
+    /// Return where this handler is supposed to run.
+    pub const fn mode(&self) -> IrqMode {
+        self.mode
+    }
+}
+
+/// An interrupt number belonging to a controller-specific IRQ space of `MAX_INCLUSIVE + 1`
+/// numbers.
+///
+/// Each interrupt controller driver defines its own `IRQNumber` alias of this type, sized to the
+/// number of IRQs it actually manages. Two controllers with differently sized IRQ spaces
+/// therefore get incompatible `IRQNumberType`s, so a BSP can't accidentally hand a Pi 3 IRQ
+/// number to a GIC or vice versa; the mapping from a driver-relative interrupt identity to the
+/// active controller's `IRQNumber` lives in `bsp::<board>::exception::irq_map`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BoundedUsize<const MAX_INCLUSIVE: usize>(usize);
+
+impl<const MAX_INCLUSIVE: usize> BoundedUsize<MAX_INCLUSIVE> {
+    /// Create an instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `number` exceeds `MAX_INCLUSIVE`. Intended for building BSP-level IRQ number
+    /// tables from constants, where an out-of-range number is a bug that should fail immediately
+    /// rather than be silently clamped.
+    pub const fn new(number: usize) -> Self {
+        assert!(number <= MAX_INCLUSIVE, "IRQ number out of range");
+
+        Self(number)
+    }
+
+    /// Create an instance, or `None` if `number` exceeds `MAX_INCLUSIVE`.
+    ///
+    /// The non-panicking counterpart to [`new`](Self::new), for callers -- the devicetree parser,
+    /// test code -- that take a number from outside the kernel and need to reject an out-of-range
+    /// one instead of crashing on it.
+    pub const fn new_checked(number: usize) -> Option<Self> {
+        if number <= MAX_INCLUSIVE {
+            Some(Self(number))
+        } else {
+            None
+        }
+    }
+
+    /// Return the wrapped number.
+    pub const fn get(&self) -> usize {
+        self.0
+    }
+
+    /// Add `rhs`, or `None` if the result would exceed `MAX_INCLUSIVE` or overflow `usize`.
+    pub const fn checked_add(&self, rhs: usize) -> Option<Self> {
+        match self.0.checked_add(rhs) {
+            Some(sum) => Self::new_checked(sum),
+            None => None,
+        }
+    }
+
+    /// Subtract `rhs`, or `None` if the result would underflow.
+    pub const fn checked_sub(&self, rhs: usize) -> Option<Self> {
+        match self.0.checked_sub(rhs) {
+            Some(diff) => Self::new_checked(diff),
+            None => None,
+        }
+    }
+
+    /// Add `rhs`, clamping to `MAX_INCLUSIVE` instead of overflowing or going out of range.
+    pub const fn saturating_add(&self, rhs: usize) -> Self {
+        let sum = match self.0.checked_add(rhs) {
+            Some(sum) => sum,
+            None => usize::MAX,
+        };
+
+        Self(if sum > MAX_INCLUSIVE {
+            MAX_INCLUSIVE
+        } else {
+            sum
+        })
+    }
+
+    /// Subtract `rhs`, clamping to `0` instead of underflowing.
+    pub const fn saturating_sub(&self, rhs: usize) -> Self {
+        Self(self.0.saturating_sub(rhs))
+    }
+
+    /// Iterate every valid value in `0..=MAX_INCLUSIVE`, in ascending order.
+    ///
+    /// What an IRQ table walk or a `for` loop over a controller's whole interrupt space reaches
+    /// for instead of reconstructing the range (and its bound) by hand.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..=MAX_INCLUSIVE).map(Self)
+    }
+}
+
+impl<const MAX_INCLUSIVE: usize> TryFrom<usize> for BoundedUsize<MAX_INCLUSIVE> {
+    type Error = &'static str;
+
+    fn try_from(number: usize) -> Result<Self, Self::Error> {
+        Self::new_checked(number).ok_or("IRQ number out of range")
+    }
+}
+
+impl<const MAX_INCLUSIVE: usize> fmt::Display for BoundedUsize<MAX_INCLUSIVE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// How many distinct IRQ names [`record_irq`] can track at once, fixed at compile time since
+/// this kernel has no heap.
+const MAX_IRQ_STATS: usize = 32;
+
+struct IrqStats {
+    counts: [Option<(&'static str, u64)>; MAX_IRQ_STATS],
+}
+
+impl IrqStats {
+    const fn new() -> Self {
+        Self {
+            counts: [None; MAX_IRQ_STATS],
+        }
+    }
+}
+
+static IRQ_STATS: NullLock<IrqStats> = NullLock::new(IrqStats::new());
+
+/// How many [`IrqMode::Threaded`] handlers [`defer_handler`] can queue at once before
+/// [`run_deferred_handlers`] next drains it, fixed at compile time since this kernel has no heap.
+const MAX_DEFERRED_IRQS: usize = 8;
+
+struct DeferredIrqQueue {
+    pending: [Option<(&'static str, &'static (dyn IRQHandler + Sync))>; MAX_DEFERRED_IRQS],
+}
+
+impl DeferredIrqQueue {
+    const fn new() -> Self {
+        Self {
+            pending: [None; MAX_DEFERRED_IRQS],
+        }
+    }
+}
+
+static DEFERRED_IRQS: NullLock<DeferredIrqQueue> = NullLock::new(DeferredIrqQueue::new());
+
+/// Per-IRQ worst-case latency seen so far, in [`crate::cpu::read_cycle_counter`] ticks. See
+/// [`record_irq_latency`] for what the two phases mean.
+struct IrqLatencyStats {
+    worst: [Option<(&'static str, u64, u64)>; MAX_IRQ_STATS],
+}
+
+impl IrqLatencyStats {
+    const fn new() -> Self {
+        Self {
+            worst: [None; MAX_IRQ_STATS],
+        }
+    }
+}
+
+static IRQ_LATENCY_STATS: NullLock<IrqLatencyStats> = NullLock::new(IrqLatencyStats::new());
+
+/// How many [`IRQHandler::handle`] calls are currently on the stack. See [`NestingGuard`] for why
+/// this stays at 1 in practice.
+static NESTING_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+/// The deepest `(nesting depth, stack bytes in use)` pair [`NestingGuard`] has observed since
+/// boot, ranked by depth first and stack usage as a tiebreaker.
+struct PeakNesting {
+    depth: usize,
+    stack_bytes: usize,
+}
+
+static PEAK_NESTING: NullLock<PeakNesting> = NullLock::new(PeakNesting {
+    depth: 0,
+    stack_bytes: 0,
+});
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Does nothing: no exception vector table is installed and IRQs are not unmasked (see
+/// [`crate::exception`]'s module docs).
+///
+/// # Safety
+///
+/// - Changes the HW state of the executing core.
+pub unsafe fn init() {}
+
+/// Does nothing: the executing core's IRQ mask is left unchanged and no token is returned.
+#[inline(always)]
+pub fn local_irq_mask() {}
+
+/// Does nothing: the executing core's IRQ mask is left unchanged.
+#[inline(always)]
+pub fn local_irq_unmask() {}
+
+/// Record that the IRQ handler named `name` fired once.
+///
+/// Called by each [`interface::IRQManager::handle_pending_irqs`] implementation after dispatching
+/// to a handler, so that [`all_irq_counts`] (used by `/proc/interrupts`, see `crate::fs::procfs`)
+/// has something real to report. Silently drops the count if `name` hasn't been seen before and
+/// [`MAX_IRQ_STATS`](self) distinct names are already tracked -- a `/proc/interrupts` reader
+/// missing one obscure IRQ's row is a better failure mode than a panic on the interrupt path.
+pub fn record_irq(name: &'static str) {
+    crate::trace::record_instant("irq", name);
+
+    IRQ_STATS.lock(|stats| {
+        for entry in stats.counts.iter_mut().flatten() {
+            if entry.0 == name {
+                entry.1 += 1;
+                return;
+            }
+        }
+
+        if let Some(slot) = stats.counts.iter_mut().find(|e| e.is_none()) {
+            *slot = Some((name, 1));
+        }
+    });
+}
+
+/// Enumerate every IRQ name [`record_irq`] has counted so far, in no particular order.
+pub fn all_irq_counts(mut f: impl FnMut(&'static str, u64)) {
+    IRQ_STATS.lock(|stats| {
+        for entry in stats.counts.iter().flatten() {
+            f(entry.0, entry.1);
+        }
+    });
+}
+
+/// Record one occurrence of IRQ `name`'s dispatch and service latency, in timer ticks, keeping
+/// only the worst (largest) of each seen so far.
+///
+/// `dispatch_ticks` is the time from a controller's `handle_pending_irqs` starting to run until
+/// it calls the handler; `service_ticks` is the time the handler itself took, up to (but not
+/// including) the controller's end-of-interrupt write. Both are measured with
+/// [`crate::cpu::read_cycle_counter`] entirely inside `handle_pending_irqs` -- **not** from real
+/// hardware IRQ entry, because this fork has no exception vector table yet (`cpu::boot` only
+/// takes the core from EL2 to EL1 at boot; `VBAR_EL1` is never programmed, and nothing currently
+/// calls `handle_pending_irqs` from a real interrupt path -- see
+/// [`interface::IRQManager::handle_pending_irqs`]'s callers, there are none). So the true
+/// entry-to-handler latency a real-time user would care about -- trap entry, vector dispatch,
+/// this function being called at all -- isn't measurable here and isn't claimed to be; what's
+/// tracked is the portion of the pipeline that already exists in software.
+///
+/// Only [`IrqMode::Direct`] handlers are tracked: a [`IrqMode::Threaded`] one's "service" time is
+/// whatever [`run_deferred_handlers`] takes whenever it's next polled, which isn't a latency
+/// number in the same sense and would just be misleading averaged in here.
+pub fn record_irq_latency(name: &'static str, dispatch_ticks: u64, service_ticks: u64) {
+    IRQ_LATENCY_STATS.lock(|stats| {
+        for entry in stats.worst.iter_mut().flatten() {
+            if entry.0 == name {
+                entry.1 = entry.1.max(dispatch_ticks);
+                entry.2 = entry.2.max(service_ticks);
+                return;
+            }
+        }
+
+        if let Some(slot) = stats.worst.iter_mut().find(|e| e.is_none()) {
+            *slot = Some((name, dispatch_ticks, service_ticks));
+        }
+    });
+}
+
+/// Enumerate every IRQ name [`record_irq_latency`] has tracked, with its worst-case
+/// `(dispatch_ticks, service_ticks)` so far, in no particular order.
+pub fn all_irq_latencies(mut f: impl FnMut(&'static str, u64, u64)) {
+    IRQ_LATENCY_STATS.lock(|stats| {
+        for entry in stats.worst.iter().flatten() {
+            f(entry.0, entry.1, entry.2);
+        }
+    });
+}
+
+/// RAII guard marking one [`IRQHandler::handle`] call as in progress, for [`peak_nesting`].
+///
+/// Real hardware nesting -- one interrupt trapping in over a still-running handler -- can't
+/// happen here: there's no exception vector table yet (see [`crate::exception`]'s module docs),
+/// so every call into this module comes from polling, not from a trap that could itself be
+/// interrupted again. What this guard actually measures is *software* reentrancy -- a handler
+/// that, directly or indirectly, triggers another `handle_pending_irqs` poll before returning
+/// (e.g. one that drives a console write that itself pumps a UART's RX path). No handler in this
+/// tree does that today, so in practice [`NESTING_DEPTH`] stays at 1 while a handler runs and 0
+/// otherwise -- but the hook is real, and a future handler that does recurse shows up here
+/// honestly, paired with [`crate::cpu::current_stack_bytes_used`], instead of silently eating into
+/// the boot stack's margin.
+pub struct NestingGuard(());
+
+impl NestingGuard {
+    /// Enter a handler dispatch, updating [`PEAK_NESTING`] if this is the deepest `(depth, stack
+    /// bytes)` pair observed so far.
+    pub fn enter() -> Self {
+        let depth = NESTING_DEPTH.fetch_add(1, Ordering::AcqRel) + 1;
+        let stack_bytes = crate::cpu::current_stack_bytes_used();
+
+        PEAK_NESTING.lock(|peak| {
+            if (depth, stack_bytes) > (peak.depth, peak.stack_bytes) {
+                peak.depth = depth;
+                peak.stack_bytes = stack_bytes;
+            }
+        });
+
+        Self(())
+    }
+}
+
+impl Drop for NestingGuard {
+    fn drop(&mut self) {
+        NESTING_DEPTH.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// The deepest `(handler nesting depth, stack bytes in use)` pair [`NestingGuard`] has observed
+/// since boot, for `/proc/stackdepth` (see `crate::fs::procfs`).
+pub fn peak_nesting() -> (usize, usize) {
+    PEAK_NESTING.lock(|peak| (peak.depth, peak.stack_bytes))
+}
+
+/// Queue `handler` to run later from [`run_deferred_handlers`] instead of inline.
+///
+/// Called by a controller's `handle_pending_irqs` for a descriptor whose [`IRQHandlerDescriptor::mode`]
+/// is [`IrqMode::Threaded`], in place of calling `handler.handle()` directly. Silently drops the
+/// handler if [`MAX_DEFERRED_IRQS`](self) are already queued and undrained -- on the hard-IRQ
+/// path, a dropped deferred run is a better failure mode than a panic or a block.
+pub fn defer_handler(name: &'static str, handler: &'static (dyn IRQHandler + Sync)) {
+    DEFERRED_IRQS.lock(|queue| {
+        if let Some(slot) = queue.pending.iter_mut().find(|e| e.is_none()) {
+            *slot = Some((name, handler));
+        }
+    });
+}
+
+/// Run every handler [`defer_handler`] has queued since the last call, in queue order.
+///
+/// See [`IrqMode::Threaded`]'s docs for why this is polled (from the interactive main loop today)
+/// rather than woken on a dedicated thread -- this fork has no scheduler yet to own that thread.
+pub fn run_deferred_handlers() {
+    DEFERRED_IRQS.lock(|queue| {
+        for entry in queue.pending.iter_mut() {
+            if let Some((name, handler)) = entry.take() {
+                record_irq(name);
+                let _nesting = NestingGuard::enter();
+                if let Err(x) = handler.handle() {
+                    crate::log::rate_limited!(
+                        core::time::Duration::from_secs(1),
+                        "irq",
+                        "{}: {}",
+                        name,
+                        x
+                    );
+                }
+            }
+        }
+    });
+}
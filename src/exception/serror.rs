@@ -0,0 +1,125 @@
+//! SError syndrome decoding.
+//!
+//! Correlating an SError with the device access that caused it needs two things this fork doesn't
+//! have: a real vector to trap into (see the [`super`] module docs -- `VBAR_EL1` is never
+//! programmed, so an actual SError on hardware is not caught by this kernel at all today) and a
+//! recent-MMIO-access ring buffer to correlate against (this fork has no such trace; register
+//! accesses go straight through [`crate::bsp::device_driver::common::MMIODerefWrapper`] with
+//! nothing recording them, and there's no klog ring buffer either -- see
+//! [`crate::crashdump`] for the same gap). [`decode`] is the one piece of this that doesn't
+//! depend on either: turning a raw `ESR_EL1` value into readable fields is pure decoding, ready
+//! for the day a real vector has one to hand it.
+
+use core::fmt;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The decoded ISS (Instruction Specific Syndrome) bits of an `ESR_EL1` that reports an SError
+/// (`EC == 0b101111`), per ARM DDI 0487, section D17.2.37.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Syndrome {
+    /// `IDS` (bit 24). When set, the remaining bits are implementation-defined and this fork
+    /// doesn't attempt to interpret them further.
+    pub implementation_defined: bool,
+
+    /// `IESB` (bit 13): whether an implicit error-synchronization barrier was applied before the
+    /// exception was taken.
+    pub synchronized_by_esb: bool,
+
+    /// `AET` (bits 12:10): Asynchronous Error Type, how confident the implementation is in the
+    /// reported error. Only meaningful when `implementation_defined` is `false`.
+    pub error_type: ErrorType,
+
+    /// `EA` (bit 9): External Abort type, an implementation-defined hint about which bus/agent
+    /// reported the error.
+    pub external_abort: bool,
+}
+
+/// Asynchronous Error Type, decoded from `AET`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ErrorType {
+    /// `0b000`: Uncontainable. The error has corrupted state beyond recovery.
+    Uncontainable,
+    /// `0b001`: Unrecoverable.
+    Unrecoverable,
+    /// `0b010`: Restartable. The current context can't continue, but an earlier one can be
+    /// restarted.
+    Restartable,
+    /// `0b011`: Recoverable.
+    Recoverable,
+    /// `0b110`: Corrected. No exception would have been needed if reporting weren't forced on.
+    Corrected,
+    /// Any other encoding. Reserved by the architecture at the time this was written.
+    Reserved(u8),
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl ErrorType {
+    fn from_aet(aet: u8) -> Self {
+        match aet {
+            0b000 => Self::Uncontainable,
+            0b001 => Self::Unrecoverable,
+            0b010 => Self::Restartable,
+            0b011 => Self::Recoverable,
+            0b110 => Self::Corrected,
+            other => Self::Reserved(other),
+        }
+    }
+}
+
+impl fmt::Display for ErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uncontainable => write!(f, "uncontainable"),
+            Self::Unrecoverable => write!(f, "unrecoverable"),
+            Self::Restartable => write!(f, "restartable"),
+            Self::Recoverable => write!(f, "recoverable"),
+            Self::Corrected => write!(f, "corrected"),
+            Self::Reserved(aet) => write!(f, "reserved(aet={:#05b})", aet),
+        }
+    }
+}
+
+/// Decode the ISS bits of an `ESR_EL1` value that reports an SError.
+///
+/// Does not check `EC`; callers that already know `esr_el1` was taken for an SError (the only
+/// context in which the ISS has this layout) can pass it straight through.
+pub fn decode(esr_el1: u64) -> Syndrome {
+    let iss = esr_el1 & 0x1ff_ffff;
+    let implementation_defined = (iss >> 24) & 1 != 0;
+    let synchronized_by_esb = (iss >> 13) & 1 != 0;
+    let aet = ((iss >> 10) & 0b111) as u8;
+    let external_abort = (iss >> 9) & 1 != 0;
+
+    Syndrome {
+        implementation_defined,
+        synchronized_by_esb,
+        error_type: ErrorType::from_aet(aet),
+        external_abort,
+    }
+}
+
+impl fmt::Display for Syndrome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.implementation_defined {
+            return write!(f, "SError (implementation-defined syndrome)");
+        }
+
+        write!(
+            f,
+            "SError ({}, {}, esb={})",
+            self.error_type,
+            if self.external_abort {
+                "external"
+            } else {
+                "internal"
+            },
+            self.synchronized_by_esb
+        )
+    }
+}
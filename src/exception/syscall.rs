@@ -0,0 +1,91 @@
+//! Synchronous exception (SVC) syscall dispatch.
+//!
+//! [`dispatch`] can't actually be reached yet: an `SVC` from `PrivilegeLevel::User` is a
+//! synchronous exception, and [`super::handling_init`] only programs the asynchronous/IRQ side of
+//! this fork's (nonexistent) vector table -- `VBAR_EL1` itself is never written (see [`super`]'s
+//! module docs), so a real `SVC` trap has nowhere to land. [`dispatch`] is kept as the landing
+//! point that day's vector handler would call into, with the two pieces that don't depend on a
+//! vector existing built for real today: decoding a raw syscall number into a [`SyscallNumber`],
+//! and checking/logging a pending [`crate::process::signal::Signal`] at the dispatch boundary.
+//! Serving any individual syscall needs its own backend this fork also doesn't have yet --
+//! [`crate::process`]'s docs cover the page-table/EL0 half, serving `open`/`read`/`write`/`close`
+//! also needs the VFS [`crate::fs`]'s docs describe as still missing, and `mmap`/`munmap` need
+//! everything [`crate::memory::mmap`]'s docs list -- so [`dispatch`] reports which of those is
+//! missing instead of serving a call it can't back yet.
+
+use core::fmt;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Numeric identifiers for the syscalls this fork's ABI is meant to eventually support.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u64)]
+pub enum SyscallNumber {
+    Open = 0,
+    Read = 1,
+    Write = 2,
+    Close = 3,
+    Mmap = 4,
+    Munmap = 5,
+    Nanosleep = 6,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl SyscallNumber {
+    /// Map a raw SVC syscall number to a known syscall, if any.
+    pub fn from_u64(n: u64) -> Option<Self> {
+        Some(match n {
+            0 => Self::Open,
+            1 => Self::Read,
+            2 => Self::Write,
+            3 => Self::Close,
+            4 => Self::Mmap,
+            5 => Self::Munmap,
+            6 => Self::Nanosleep,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for SyscallNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Open => "open",
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Close => "close",
+            Self::Mmap => "mmap",
+            Self::Munmap => "munmap",
+            Self::Nanosleep => "nanosleep",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Dispatch one syscall trapped from a user process.
+///
+/// Unreachable from a real trap today -- see the module docs for why -- but traces every call via
+/// [`crate::log`] and checks for a pending signal regardless, since neither depends on the
+/// missing vector.
+pub fn dispatch(number: u64, _args: [u64; 4]) -> Result<u64, &'static str> {
+    if let Some(signal) = crate::process::signal::take_pending() {
+        crate::log::log_info!("syscall", "delivering {:?} at the syscall boundary", signal);
+    }
+
+    match SyscallNumber::from_u64(number) {
+        Some(syscall) => {
+            crate::log::log_debug!("syscall", "{} (not implemented)", syscall);
+            Err("syscall: not implemented -- no VFS, no frame allocator/per-process page tables, no timer wheel")
+        }
+        None => {
+            crate::log::log_warn!("syscall", "unknown syscall number {}", number);
+            Err("syscall: unknown syscall number")
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! A minimal, no-alloc async executor for driver code.
+//!
+//! There is no preemptive scheduler in this kernel and no allocator to box futures with, so this
+//! doesn't try to be a general-purpose one: [`block_on`] drives a single future to completion on
+//! the calling context, polling it in a loop and parking on [`cpu::wait_for_event`] between polls
+//! instead of busy-spinning like [`cpu::spin_for_cycles`]-based blocking reads do. Every future is
+//! handed the same waker, which just issues [`cpu::send_event`] -- any interrupt firing (a UART
+//! byte arriving, a timer tick) is already enough to unblock `wfe`, so the waker doesn't need to
+//! track which future asked to be woken.
+//!
+//! This is enough to write a driver's wait-for-something logic as a straight-line `async fn`
+//! (see [`crate::console::read_char_async`], [`crate::time::sleep_async`]) instead of a
+//! hand-rolled state machine, without pulling in a task scheduler this kernel doesn't have.
+
+use crate::cpu;
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+fn waker_clone(_data: *const ()) -> RawWaker {
+    RawWaker::new(core::ptr::null(), &VTABLE)
+}
+
+fn waker_wake(_data: *const ()) {
+    cpu::send_event();
+}
+
+fn waker_drop(_data: *const ()) {}
+
+static VTABLE: RawWakerVTable =
+    RawWakerVTable::new(waker_clone, waker_wake, waker_wake, waker_drop);
+
+/// Build a [`Waker`] whose `wake` just issues `sev`.
+///
+/// # Safety
+///
+/// - Sound because the vtable's functions never dereference the (null) data pointer.
+fn event_waker() -> Waker {
+    unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Poll `future` to completion on the calling context, parking on `wfe` between polls.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = event_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => cpu::wait_for_event(),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Double-buffered access to the VideoCore framebuffer.
+//!
+//! Only meaningful on real Raspberry Pi hardware -- QEMU's `virt` machine emulates no
+//! VideoCore/mailbox peripheral to back this, so this module only exists under
+//! `bsp_rpi3`/`bsp_rpi4`. See `bsp::device_driver::Framebuffer` for the driver itself, and
+//! [`crate::gfx`] for drawing into the surface this module hands out.
+
+use crate::{bsp, gfx};
+
+pub mod interface {
+    /// Operations a framebuffer driver must implement.
+    pub trait Display {
+        /// Width, in pixels, of one buffer.
+        fn width(&self) -> u32;
+
+        /// Height, in pixels, of one buffer.
+        fn height(&self) -> u32;
+
+        /// Row stride, in bytes, of one buffer.
+        fn pitch(&self) -> u32;
+
+        /// A pointer to the first pixel of the buffer not currently scanned out, or `None` if
+        /// the driver hasn't been initialized yet.
+        fn back_buffer_ptr(&self) -> Option<*mut u32>;
+
+        /// Swap the visible and back buffers.
+        fn flip(&self) -> Result<(), &'static str>;
+
+        /// Renegotiate the display mode to `width` x `height` at `depth` bits per pixel,
+        /// replacing whatever mode is currently live.
+        ///
+        /// See [`crate::video`]'s module docs for why `depth` must be 32.
+        fn set_mode(&self, width: u32, height: u32, depth: u32) -> Result<(), &'static str>;
+    }
+}
+
+/// A snapshot of the current back buffer's geometry and base address, implementing
+/// [`gfx::Surface`] so the `gfx` primitives can draw into it without knowing anything about the
+/// mailbox underneath.
+#[derive(Copy, Clone)]
+pub struct BackBuffer {
+    ptr: *mut u32,
+    width: u32,
+    height: u32,
+    pitch: u32,
+}
+
+// SAFETY: the pointer refers to a fixed, GPU-allocated buffer that outlives the kernel; callers
+// are responsible for not aliasing writes across cores, same as every other MMIO/DMA buffer in
+// this fork.
+unsafe impl Send for BackBuffer {}
+
+impl gfx::Surface for BackBuffer {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn pitch(&self) -> u32 {
+        self.pitch
+    }
+
+    unsafe fn pixels_mut(&self) -> *mut u32 {
+        self.ptr
+    }
+}
+
+/// The back buffer -- the half not currently scanned out -- ready to draw into, or `None` if the
+/// framebuffer hasn't been initialized yet.
+pub fn back_buffer() -> Option<BackBuffer> {
+    let display = bsp::framebuffer::framebuffer();
+
+    Some(BackBuffer {
+        ptr: display.back_buffer_ptr()?,
+        width: display.width(),
+        height: display.height(),
+        pitch: display.pitch(),
+    })
+}
+
+/// Swap the visible and back buffers.
+///
+/// See [`bsp::device_driver::Framebuffer`](crate::bsp::device_driver::Framebuffer)'s docs for the
+/// caveat on older firmware not actually waiting for vblank here.
+pub fn flip() -> Result<(), &'static str> {
+    bsp::framebuffer::framebuffer().flip()
+}
+
+/// Renegotiate the display mode.
+///
+/// See [`crate::video::set_mode`] for the fallback chain built on top of this.
+pub fn set_mode(width: u32, height: u32, depth: u32) -> Result<(), &'static str> {
+    bsp::framebuffer::framebuffer().set_mode(width, height, depth)
+}
@@ -0,0 +1,17 @@
+//! Filesystem-adjacent block layout and IO helpers.
+//!
+//! There's still no VFS in this fork -- [`fat32::volume::Fat32Volume`], [`partition::Partition`],
+//! [`tmpfs`], and [`procfs`] are all used directly rather than through a generic mount table --
+//! and no shutdown/chainload path for [`block_cache`] to hook its flush into automatically. See
+//! each submodule's doc for how that shapes it.
+//!
+//! [`sdlog`] is the odd one out: it doesn't read an existing on-disk structure the way
+//! [`partition`] and [`fat32`] do, it claims a raw block range for its own record format. See its
+//! module docs for why that's what a power-loss-safe log needs.
+
+pub mod block_cache;
+pub mod fat32;
+pub mod partition;
+pub mod procfs;
+pub mod sdlog;
+pub mod tmpfs;
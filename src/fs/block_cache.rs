@@ -0,0 +1,326 @@
+//! Block IO cache sitting between filesystems and a [`BlockDevice`].
+//!
+//! Caches recently-used blocks in a small fixed-size, LRU-evicted table -- this fork has no heap
+//! to size the cache dynamically -- and optionally buffers writes ("write-back") instead of
+//! passing them straight through, at the cost of needing an explicit [`BlockCache::sync`] before
+//! the underlying device can be trusted to hold the latest data. There's no shutdown or chainload
+//! path in this fork yet to call that automatically before a reset; see [`crate::fs`].
+
+use crate::{
+    storage::interface::{Block, BlockDevice, BLOCK_SIZE},
+    synchronization::{Mutex, NullLock},
+};
+
+/// Number of cache lines held at once. Picked to comfortably cover one FAT cluster's worth of
+/// sectors without costing much static memory.
+const CACHE_LINES: usize = 16;
+
+#[derive(Clone, Copy)]
+struct CacheLine {
+    block_index: u64,
+    data: Block,
+    dirty: bool,
+    last_used: u64,
+}
+
+struct Inner<'a> {
+    device: &'a dyn BlockDevice,
+    write_back: bool,
+    lines: [Option<CacheLine>; CACHE_LINES],
+    clock: u64,
+}
+
+impl Inner<'_> {
+    fn find(&self, block_index: u64) -> Option<usize> {
+        self.lines
+            .iter()
+            .position(|line| matches!(line, Some(line) if line.block_index == block_index))
+    }
+
+    /// Find a slot for a new line, evicting the least-recently-used one (flushing it first, if
+    /// dirty) if the cache is full.
+    fn evict_slot(&mut self) -> Result<usize, &'static str> {
+        if let Some(i) = self.lines.iter().position(Option::is_none) {
+            return Ok(i);
+        }
+
+        let (i, line) = self
+            .lines
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, line)| line.unwrap().last_used)
+            .unwrap();
+
+        if line.unwrap().dirty {
+            self.device
+                .write_block(line.unwrap().block_index, &line.unwrap().data)?;
+        }
+
+        Ok(i)
+    }
+
+    /// Return the slot index holding `block_index`, loading it from the device first if it isn't
+    /// already cached.
+    fn load(&mut self, block_index: u64) -> Result<usize, &'static str> {
+        if let Some(i) = self.find(block_index) {
+            return Ok(i);
+        }
+
+        let i = self.evict_slot()?;
+
+        let mut data: Block = [0u8; BLOCK_SIZE];
+        self.device.read_block(block_index, &mut data)?;
+
+        self.clock += 1;
+        self.lines[i] = Some(CacheLine {
+            block_index,
+            data,
+            dirty: false,
+            last_used: self.clock,
+        });
+
+        Ok(i)
+    }
+
+    fn touch(&mut self, i: usize) {
+        self.clock += 1;
+        self.lines[i].as_mut().unwrap().last_used = self.clock;
+    }
+
+    fn sync(&mut self) -> Result<(), &'static str> {
+        for line in self.lines.iter_mut().flatten() {
+            if line.dirty {
+                self.device.write_block(line.block_index, &line.data)?;
+                line.dirty = false;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An LRU block cache sitting in front of a [`BlockDevice`].
+pub struct BlockCache<'a> {
+    inner: NullLock<Inner<'a>>,
+}
+
+impl<'a> BlockCache<'a> {
+    /// Wrap `device` in a cache that still writes through to it immediately -- only reads are
+    /// cached.
+    pub fn new(device: &'a dyn BlockDevice) -> Self {
+        Self::with_write_back(device, false)
+    }
+
+    /// Wrap `device` in a cache. If `write_back` is set, writes are buffered in the cache and only
+    /// reach `device` on eviction or [`BlockCache::sync`], instead of being written through
+    /// immediately.
+    pub fn with_write_back(device: &'a dyn BlockDevice, write_back: bool) -> Self {
+        Self {
+            inner: NullLock::new(Inner {
+                device,
+                write_back,
+                lines: [None; CACHE_LINES],
+                clock: 0,
+            }),
+        }
+    }
+
+    /// Flush all dirty cached blocks to the underlying device.
+    pub fn sync(&self) -> Result<(), &'static str> {
+        self.inner.lock(Inner::sync)
+    }
+}
+
+/// A fixed-capacity, in-memory [`BlockDevice`] fixture for this module's and sibling `fs`
+/// modules' tests -- standing in for real storage the same way a FAT32/partition test has no
+/// SD card or virtio-blk device to run against.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+    use crate::synchronization::{Mutex, NullLock};
+
+    /// An all-zeros [`BlockDevice`] of `block_count` blocks, backed by a `Vec`.
+    pub(crate) struct RamDisk {
+        blocks: NullLock<std::vec::Vec<Block>>,
+    }
+
+    impl RamDisk {
+        pub(crate) fn new(block_count: u64) -> Self {
+            Self {
+                blocks: NullLock::new(std::vec![[0u8; BLOCK_SIZE]; block_count as usize]),
+            }
+        }
+
+        /// Directly overwrite block `index`, bypassing [`BlockDevice::write_block`] -- tests use
+        /// this to set up fixture contents without needing a valid write path yet.
+        pub(crate) fn seed(&self, index: u64, data: &Block) {
+            self.blocks.lock(|blocks| blocks[index as usize] = *data);
+        }
+
+        /// Read back block `index` directly, bypassing [`BlockDevice::read_block`] -- tests use
+        /// this to assert on what actually landed on "disk".
+        pub(crate) fn peek(&self, index: u64) -> Block {
+            self.blocks.lock(|blocks| blocks[index as usize])
+        }
+    }
+
+    impl BlockDevice for RamDisk {
+        fn block_count(&self) -> u64 {
+            self.blocks.lock(|blocks| blocks.len() as u64)
+        }
+
+        fn read_block(&self, block_index: u64, buf: &mut Block) -> Result<(), &'static str> {
+            self.blocks
+                .lock(|blocks| match blocks.get(block_index as usize) {
+                    Some(block) => {
+                        *buf = *block;
+                        Ok(())
+                    }
+                    None => Err("ramdisk: block index out of range"),
+                })
+        }
+
+        fn write_block(&self, block_index: u64, buf: &Block) -> Result<(), &'static str> {
+            self.blocks
+                .lock(|blocks| match blocks.get_mut(block_index as usize) {
+                    Some(block) => {
+                        *block = *buf;
+                        Ok(())
+                    }
+                    None => Err("ramdisk: block index out of range"),
+                })
+        }
+    }
+}
+
+impl BlockDevice for BlockCache<'_> {
+    fn block_count(&self) -> u64 {
+        self.inner.lock(|inner| inner.device.block_count())
+    }
+
+    fn read_block(&self, block_index: u64, buf: &mut Block) -> Result<(), &'static str> {
+        self.inner.lock(|inner| {
+            let i = inner.load(block_index)?;
+            *buf = inner.lines[i].unwrap().data;
+            inner.touch(i);
+
+            Ok(())
+        })
+    }
+
+    fn write_block(&self, block_index: u64, buf: &Block) -> Result<(), &'static str> {
+        self.inner.lock(|inner| {
+            let i = inner.load(block_index)?;
+            let write_back = inner.write_back;
+
+            let line = inner.lines[i].as_mut().unwrap();
+            line.data = *buf;
+            line.dirty = write_back;
+
+            inner.touch(i);
+
+            if !write_back {
+                inner.device.write_block(block_index, buf)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixtures::RamDisk, *};
+
+    fn block_of(byte: u8) -> Block {
+        [byte; BLOCK_SIZE]
+    }
+
+    #[test]
+    fn read_is_served_from_the_device_then_cached() {
+        let disk = RamDisk::new(4);
+        disk.seed(0, &block_of(1));
+        let cache = BlockCache::new(&disk);
+
+        let mut buf = block_of(0);
+        cache.read_block(0, &mut buf).unwrap();
+        assert_eq!(buf, block_of(1));
+
+        // Mutate the device directly, bypassing the cache -- a re-read should still see the
+        // cached (now stale) copy, proving the second read didn't go back to the device.
+        disk.seed(0, &block_of(2));
+        cache.read_block(0, &mut buf).unwrap();
+        assert_eq!(buf, block_of(1));
+    }
+
+    #[test]
+    fn write_through_reaches_the_device_immediately() {
+        let disk = RamDisk::new(4);
+        let cache = BlockCache::new(&disk);
+
+        cache.write_block(0, &block_of(7)).unwrap();
+        assert_eq!(disk.peek(0), block_of(7));
+    }
+
+    #[test]
+    fn write_back_buffers_until_sync() {
+        let disk = RamDisk::new(4);
+        let cache = BlockCache::with_write_back(&disk, true);
+
+        cache.write_block(0, &block_of(7)).unwrap();
+        assert_eq!(
+            disk.peek(0),
+            block_of(0),
+            "write-back must not reach the device yet"
+        );
+
+        cache.sync().unwrap();
+        assert_eq!(disk.peek(0), block_of(7));
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_line_once_full() {
+        let disk = RamDisk::new((CACHE_LINES + 1) as u64);
+        let cache = BlockCache::new(&disk);
+        let mut buf = block_of(0);
+
+        // Fill every line, oldest first.
+        for i in 0..CACHE_LINES as u64 {
+            cache.read_block(i, &mut buf).unwrap();
+        }
+        // Touch every line but the first again, so block 0 is now the sole least-recently-used
+        // line.
+        for i in 1..CACHE_LINES as u64 {
+            cache.read_block(i, &mut buf).unwrap();
+        }
+
+        // One more distinct block must evict block 0, not any of the others.
+        cache.read_block(CACHE_LINES as u64, &mut buf).unwrap();
+
+        disk.seed(0, &block_of(9));
+        cache.read_block(0, &mut buf).unwrap();
+        assert_eq!(
+            buf,
+            block_of(9),
+            "block 0 should have been evicted and re-read from disk"
+        );
+    }
+
+    #[test]
+    fn eviction_flushes_a_dirty_write_back_line_first() {
+        let disk = RamDisk::new((CACHE_LINES + 1) as u64);
+        let cache = BlockCache::with_write_back(&disk, true);
+        let mut buf = block_of(0);
+
+        cache.write_block(0, &block_of(5)).unwrap();
+        for i in 1..=CACHE_LINES as u64 {
+            cache.read_block(i, &mut buf).unwrap();
+        }
+
+        assert_eq!(
+            disk.peek(0),
+            block_of(5),
+            "evicting the dirty line for block 0 must flush it to the device first"
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Read-write FAT32 filesystem driver.
+//!
+//! Only 8.3 short filenames are supported. Long filename (LFN) entries are recognized (by their
+//! `0x0F` attribute byte) and skipped wherever a directory is scanned, so a volume with LFN-named
+//! files stays readable, but this driver never generates or parses the LFN entries themselves --
+//! doing that correctly means chaining UTF-16 name-fragment entries together and validating a
+//! checksum against the associated short entry, which is a lot of surface area for a feature nothing
+//! in this kernel (logs, crash dumps) actually needs a long name for.
+//!
+//! There's still no VFS in this fork -- see [`crate::fs`] -- so [`volume::Fat32Volume`] and
+//! [`file::File`] are used directly against a mounted [`crate::storage::interface::BlockDevice`]
+//! (typically one wrapped in a [`crate::fs::block_cache::BlockCache`]), not through a generic
+//! mount table.
+
+mod dir;
+
+pub mod file;
+pub mod volume;
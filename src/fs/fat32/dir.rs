@@ -0,0 +1,287 @@
+//! FAT32 directory entry parsing and short-filename (8.3) manipulation.
+//!
+//! Only 8.3 short names are read and written. Long filename (LFN) entries are recognized by their
+//! `0x0F` attribute byte and skipped wherever a directory is scanned -- see the
+//! [`crate::fs::fat32`] module doc for why they're never generated or parsed.
+
+use super::volume::Fat32Volume;
+use crate::storage::interface::{Block, BLOCK_SIZE};
+
+/// Directory entry attribute bits.
+pub mod attr {
+    pub const ARCHIVE: u8 = 0x20;
+    pub const DIRECTORY: u8 = 0x10;
+    pub const VOLUME_ID: u8 = 0x08;
+    pub const LFN: u8 = 0x0f;
+}
+
+/// The first byte of a name marking a free, reusable directory entry (a deleted file).
+const ENTRY_FREE: u8 = 0xe5;
+/// The first byte of a name marking the end of a directory's used entries.
+const ENTRY_END: u8 = 0x00;
+
+/// Where a directory entry lives, in terms that survive its directory's cluster chain: the
+/// directory's own first cluster, and the entry's 0-based index within it.
+#[derive(Clone, Copy)]
+struct EntryLocation {
+    dir_first_cluster: u32,
+    index: usize,
+}
+
+/// A parsed directory entry and where it lives, so it can be rewritten in place.
+#[derive(Clone, Copy)]
+pub struct DirEntry {
+    pub short_name: [u8; 11],
+    pub attributes: u8,
+    pub first_cluster: u32,
+    pub size: u32,
+    location: EntryLocation,
+}
+
+impl DirEntry {
+    fn parse(location: EntryLocation, raw: &[u8]) -> Self {
+        let mut short_name = [0u8; 11];
+        short_name.copy_from_slice(&raw[0..11]);
+
+        Self {
+            short_name,
+            attributes: raw[11],
+            first_cluster: (u32::from(u16::from_le_bytes(raw[20..22].try_into().unwrap())) << 16)
+                | u32::from(u16::from_le_bytes(raw[26..28].try_into().unwrap())),
+            size: u32::from_le_bytes(raw[28..32].try_into().unwrap()),
+            location,
+        }
+    }
+
+    fn write_raw(&self, raw: &mut [u8]) {
+        raw[0..11].copy_from_slice(&self.short_name);
+        raw[11] = self.attributes;
+        raw[12..20].fill(0);
+        raw[20..22].copy_from_slice(&((self.first_cluster >> 16) as u16).to_le_bytes());
+        raw[22..26].fill(0);
+        raw[26..28].copy_from_slice(&(self.first_cluster as u16).to_le_bytes());
+        raw[28..32].copy_from_slice(&self.size.to_le_bytes());
+    }
+}
+
+/// Convert a `"NAME.EXT"`-style filename to a packed, space-padded 8.3 short name.
+///
+/// Only plain uppercase ASCII 8.3 names are supported: no lowercase preservation, no long
+/// filenames.
+pub fn to_short_name(name: &str) -> Result<[u8; 11], &'static str> {
+    let (base, ext) = match name.split_once('.') {
+        Some((base, ext)) => (base, ext),
+        None => (name, ""),
+    };
+
+    if base.is_empty() || base.len() > 8 || ext.len() > 3 {
+        return Err("fat32: name does not fit the 8.3 format");
+    }
+    if !base
+        .bytes()
+        .chain(ext.bytes())
+        .all(|b| b.is_ascii_graphic())
+    {
+        return Err("fat32: name contains unsupported characters");
+    }
+
+    let mut short_name = [b' '; 11];
+    for (dst, byte) in short_name[0..8].iter_mut().zip(base.bytes()) {
+        *dst = byte.to_ascii_uppercase();
+    }
+    for (dst, byte) in short_name[8..11].iter_mut().zip(ext.bytes()) {
+        *dst = byte.to_ascii_uppercase();
+    }
+
+    Ok(short_name)
+}
+
+/// Resolve directory entry index `index` (within the chain starting at `dir_first_cluster`) to a
+/// concrete `(cluster, sector_in_cluster, offset_in_sector)`, or `None` if the chain doesn't reach
+/// that far yet.
+fn entry_location(
+    volume: &Fat32Volume,
+    dir_first_cluster: u32,
+    index: usize,
+) -> Result<Option<(u32, u32, usize)>, &'static str> {
+    let entries_per_cluster = volume.entries_per_cluster();
+    let entries_per_sector = BLOCK_SIZE / 32;
+
+    let mut cluster = dir_first_cluster;
+    let mut remaining = index;
+
+    while remaining >= entries_per_cluster {
+        let next = volume.fat_get(cluster)?;
+        if Fat32Volume::is_end_of_chain(next) {
+            return Ok(None);
+        }
+        cluster = next;
+        remaining -= entries_per_cluster;
+    }
+
+    let sector_in_cluster = (remaining / entries_per_sector) as u32;
+    let offset = (remaining % entries_per_sector) * 32;
+
+    Ok(Some((cluster, sector_in_cluster, offset)))
+}
+
+fn last_cluster_of(volume: &Fat32Volume, first_cluster: u32) -> Result<u32, &'static str> {
+    let mut cluster = first_cluster;
+    loop {
+        let next = volume.fat_get(cluster)?;
+        if Fat32Volume::is_end_of_chain(next) {
+            return Ok(cluster);
+        }
+        cluster = next;
+    }
+}
+
+/// Look up `name` (an already-packed 8.3 short name) directly inside the directory starting at
+/// `dir_first_cluster`.
+pub fn find(
+    volume: &Fat32Volume,
+    dir_first_cluster: u32,
+    name: &[u8; 11],
+) -> Result<Option<DirEntry>, &'static str> {
+    let mut index = 0;
+
+    loop {
+        let location = match entry_location(volume, dir_first_cluster, index)? {
+            Some(location) => location,
+            None => return Ok(None),
+        };
+        let (cluster, sector_in_cluster, offset) = location;
+
+        let mut block: Block = [0u8; BLOCK_SIZE];
+        volume.read_cluster_sector(cluster, sector_in_cluster, &mut block)?;
+        let raw = &block[offset..offset + 32];
+
+        match raw[0] {
+            ENTRY_END => return Ok(None),
+            ENTRY_FREE => {}
+            _ if raw[11] & attr::LFN == attr::LFN => {}
+            _ if raw[0..11] == *name => {
+                return Ok(Some(DirEntry::parse(
+                    EntryLocation {
+                        dir_first_cluster,
+                        index,
+                    },
+                    raw,
+                )));
+            }
+            _ => {}
+        }
+
+        index += 1;
+    }
+}
+
+/// Create a new entry named `name` (an already-packed 8.3 short name) in the directory starting at
+/// `dir_first_cluster`, reusing a free slot if one exists or extending the directory with a fresh
+/// cluster otherwise.
+pub fn create(
+    volume: &Fat32Volume,
+    dir_first_cluster: u32,
+    name: &[u8; 11],
+    attributes: u8,
+) -> Result<DirEntry, &'static str> {
+    if find(volume, dir_first_cluster, name)?.is_some() {
+        return Err("fat32: name already exists");
+    }
+
+    let mut index = 0;
+    loop {
+        let (cluster, sector_in_cluster, offset) =
+            match entry_location(volume, dir_first_cluster, index)? {
+                Some(location) => location,
+                None => {
+                    // Ran off the end of the chain: extend it with a fresh, zeroed cluster, whose
+                    // first entry (index 0 within it) is exactly what `index` already points at.
+                    let last_cluster = last_cluster_of(volume, dir_first_cluster)?;
+                    volume.extend_chain(last_cluster)?;
+                    entry_location(volume, dir_first_cluster, index)?
+                        .expect("chain was just extended to cover this index")
+                }
+            };
+
+        let mut block: Block = [0u8; BLOCK_SIZE];
+        volume.read_cluster_sector(cluster, sector_in_cluster, &mut block)?;
+
+        if block[offset] == ENTRY_END || block[offset] == ENTRY_FREE {
+            let entry = DirEntry {
+                short_name: *name,
+                attributes,
+                first_cluster: 0,
+                size: 0,
+                location: EntryLocation {
+                    dir_first_cluster,
+                    index,
+                },
+            };
+
+            entry.write_raw(&mut block[offset..offset + 32]);
+            volume.write_cluster_sector(cluster, sector_in_cluster, &block)?;
+
+            return Ok(entry);
+        }
+
+        index += 1;
+    }
+}
+
+/// Persist `entry`'s current `first_cluster`/`size` fields back to its directory slot.
+pub fn update(volume: &Fat32Volume, entry: &DirEntry) -> Result<(), &'static str> {
+    let (cluster, sector_in_cluster, offset) = entry_location(
+        volume,
+        entry.location.dir_first_cluster,
+        entry.location.index,
+    )?
+    .ok_or("fat32: entry's directory slot no longer exists")?;
+
+    let mut block: Block = [0u8; BLOCK_SIZE];
+    volume.read_cluster_sector(cluster, sector_in_cluster, &mut block)?;
+    entry.write_raw(&mut block[offset..offset + 32]);
+    volume.write_cluster_sector(cluster, sector_in_cluster, &block)
+}
+
+/// Mark `entry`'s directory slot free. Does not free its cluster chain -- callers (see
+/// [`super::file`]) do that first.
+pub fn delete(volume: &Fat32Volume, entry: &DirEntry) -> Result<(), &'static str> {
+    let (cluster, sector_in_cluster, offset) = entry_location(
+        volume,
+        entry.location.dir_first_cluster,
+        entry.location.index,
+    )?
+    .ok_or("fat32: entry's directory slot no longer exists")?;
+
+    let mut block: Block = [0u8; BLOCK_SIZE];
+    volume.read_cluster_sector(cluster, sector_in_cluster, &mut block)?;
+    block[offset] = ENTRY_FREE;
+    volume.write_cluster_sector(cluster, sector_in_cluster, &block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_short_name_pads_and_uppercases_base_and_extension() {
+        assert_eq!(to_short_name("readme.txt").unwrap(), *b"README  TXT");
+        assert_eq!(to_short_name("KERNEL").unwrap(), *b"KERNEL     ");
+    }
+
+    #[test]
+    fn to_short_name_rejects_names_that_overflow_8_3() {
+        assert!(to_short_name("toolongname.txt").is_err());
+        assert!(to_short_name("a.toolong").is_err());
+        assert!(
+            to_short_name(".hidden").is_err(),
+            "empty base is not valid 8.3"
+        );
+    }
+
+    #[test]
+    fn to_short_name_rejects_non_graphic_characters() {
+        assert!(to_short_name("read me.txt").is_err());
+    }
+}
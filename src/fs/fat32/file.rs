@@ -0,0 +1,401 @@
+//! FAT32 file handles: open, create, read, write/append, truncate, and delete.
+
+use super::{dir, dir::DirEntry, volume::Fat32Volume};
+use crate::storage::interface::{Block, BLOCK_SIZE};
+
+/// Reject entries that aren't plain files: a directory's own 8.3 entry must never be handed back
+/// as a [`File`], since `write`/`truncate` would then stomp its cluster chain and on-disk `size`
+/// (which must stay 0 for a directory). Volume-label and orphaned LFN entries are rejected for the
+/// same reason -- none of them are data files.
+fn reject_non_file_entry(entry: &DirEntry) -> Result<(), &'static str> {
+    if entry.attributes & (dir::attr::DIRECTORY | dir::attr::VOLUME_ID) != 0
+        || entry.attributes & dir::attr::LFN == dir::attr::LFN
+    {
+        return Err("fat32: not a file");
+    }
+    Ok(())
+}
+
+/// An open file on a [`Fat32Volume`].
+pub struct File<'a> {
+    volume: &'a Fat32Volume<'a>,
+    entry: DirEntry,
+    position: u64,
+}
+
+impl<'a> File<'a> {
+    /// Open `name` (an 8.3-format filename) if it exists in the directory starting at
+    /// `dir_first_cluster`.
+    pub fn open(
+        volume: &'a Fat32Volume<'a>,
+        dir_first_cluster: u32,
+        name: &str,
+    ) -> Result<Self, &'static str> {
+        let short_name = dir::to_short_name(name)?;
+        let entry =
+            dir::find(volume, dir_first_cluster, &short_name)?.ok_or("fat32: file not found")?;
+        reject_non_file_entry(&entry)?;
+
+        Ok(Self {
+            volume,
+            entry,
+            position: 0,
+        })
+    }
+
+    /// Create `name` (an 8.3-format filename) as a new, empty file in the directory starting at
+    /// `dir_first_cluster`.
+    pub fn create(
+        volume: &'a Fat32Volume<'a>,
+        dir_first_cluster: u32,
+        name: &str,
+    ) -> Result<Self, &'static str> {
+        let short_name = dir::to_short_name(name)?;
+        let entry = dir::create(volume, dir_first_cluster, &short_name, dir::attr::ARCHIVE)?;
+        reject_non_file_entry(&entry)?;
+
+        Ok(Self {
+            volume,
+            entry,
+            position: 0,
+        })
+    }
+
+    /// The file's current size, in bytes.
+    pub fn size(&self) -> u32 {
+        self.entry.size
+    }
+
+    /// Move the read/write position to `position`, clamped to the file's current size.
+    pub fn seek(&mut self, position: u64) {
+        self.position = position.min(u64::from(self.entry.size));
+    }
+
+    /// Move the read/write position to the file's current end, for appending.
+    pub fn seek_to_end(&mut self) {
+        self.position = u64::from(self.entry.size);
+    }
+
+    /// Return the cluster containing byte offset `byte_offset`, or `None` if the file's chain
+    /// doesn't reach that far (including an entirely empty file).
+    fn cluster_at(&self, byte_offset: u64) -> Result<Option<u32>, &'static str> {
+        if self.entry.first_cluster == 0 {
+            return Ok(None);
+        }
+
+        let bytes_per_cluster = self.volume.bytes_per_cluster() as u64;
+        let mut cluster = self.entry.first_cluster;
+        let mut remaining = byte_offset / bytes_per_cluster;
+
+        while remaining > 0 {
+            let next = self.volume.fat_get(cluster)?;
+            if Fat32Volume::is_end_of_chain(next) {
+                return Ok(None);
+            }
+            cluster = next;
+            remaining -= 1;
+        }
+
+        Ok(Some(cluster))
+    }
+
+    /// Like [`File::cluster_at`], but allocates the first cluster or extends the chain as needed
+    /// to reach `byte_offset` instead of stopping short.
+    fn cluster_at_for_write(&mut self, byte_offset: u64) -> Result<u32, &'static str> {
+        if self.entry.first_cluster == 0 {
+            self.entry.first_cluster = self.volume.allocate_cluster()?;
+        }
+
+        let bytes_per_cluster = self.volume.bytes_per_cluster() as u64;
+        let mut cluster = self.entry.first_cluster;
+        let mut remaining = byte_offset / bytes_per_cluster;
+
+        while remaining > 0 {
+            let next = self.volume.fat_get(cluster)?;
+            cluster = if Fat32Volume::is_end_of_chain(next) {
+                self.volume.extend_chain(cluster)?
+            } else {
+                next
+            };
+            remaining -= 1;
+        }
+
+        Ok(cluster)
+    }
+
+    /// Read up to `buf.len()` bytes starting at the current position, returning the number of
+    /// bytes actually read (fewer than requested at end-of-file).
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, &'static str> {
+        let mut total = 0;
+
+        while total < buf.len() && self.position < u64::from(self.entry.size) {
+            let bytes_per_cluster = self.volume.bytes_per_cluster() as u64;
+            let offset_in_cluster = (self.position % bytes_per_cluster) as usize;
+            let sector_in_cluster = (offset_in_cluster / BLOCK_SIZE) as u32;
+            let offset_in_sector = offset_in_cluster % BLOCK_SIZE;
+
+            let cluster = match self.cluster_at(self.position)? {
+                Some(cluster) => cluster,
+                None => break,
+            };
+
+            let mut block: Block = [0u8; BLOCK_SIZE];
+            self.volume
+                .read_cluster_sector(cluster, sector_in_cluster, &mut block)?;
+
+            let available_in_sector = BLOCK_SIZE - offset_in_sector;
+            let remaining_in_file = (u64::from(self.entry.size) - self.position) as usize;
+            let n = (buf.len() - total)
+                .min(available_in_sector)
+                .min(remaining_in_file);
+
+            buf[total..total + n].copy_from_slice(&block[offset_in_sector..offset_in_sector + n]);
+
+            total += n;
+            self.position += n as u64;
+        }
+
+        Ok(total)
+    }
+
+    /// Write `buf` at the current position, allocating and extending the cluster chain as needed
+    /// if writing runs past the file's current end.
+    pub fn write(&mut self, buf: &[u8]) -> Result<usize, &'static str> {
+        let mut total = 0;
+
+        while total < buf.len() {
+            let bytes_per_cluster = self.volume.bytes_per_cluster() as u64;
+            let offset_in_cluster = (self.position % bytes_per_cluster) as usize;
+            let sector_in_cluster = (offset_in_cluster / BLOCK_SIZE) as u32;
+            let offset_in_sector = offset_in_cluster % BLOCK_SIZE;
+
+            let cluster = self.cluster_at_for_write(self.position)?;
+
+            let mut block: Block = [0u8; BLOCK_SIZE];
+            self.volume
+                .read_cluster_sector(cluster, sector_in_cluster, &mut block)?;
+
+            let available_in_sector = BLOCK_SIZE - offset_in_sector;
+            let n = (buf.len() - total).min(available_in_sector);
+
+            block[offset_in_sector..offset_in_sector + n].copy_from_slice(&buf[total..total + n]);
+            self.volume
+                .write_cluster_sector(cluster, sector_in_cluster, &block)?;
+
+            total += n;
+            self.position += n as u64;
+
+            if self.position > u64::from(self.entry.size) {
+                self.entry.size = self.position as u32;
+            }
+        }
+
+        dir::update(self.volume, &self.entry)?;
+        Ok(total)
+    }
+
+    /// Move to the end of the file and write `buf` there.
+    pub fn append(&mut self, buf: &[u8]) -> Result<usize, &'static str> {
+        self.seek_to_end();
+        self.write(buf)
+    }
+
+    /// Shrink or grow the file to exactly `new_size` bytes, freeing (when shrinking) whole
+    /// clusters no longer needed. Growing does not allocate or zero-fill new clusters up front --
+    /// they're allocated lazily by a later [`File::write`], the same as for any other gap left by
+    /// seeking past the end.
+    pub fn truncate(&mut self, new_size: u32) -> Result<(), &'static str> {
+        let bytes_per_cluster = self.volume.bytes_per_cluster() as u64;
+
+        if new_size == 0 {
+            if self.entry.first_cluster != 0 {
+                self.volume.free_chain(self.entry.first_cluster)?;
+                self.entry.first_cluster = 0;
+            }
+        } else if u64::from(new_size) < u64::from(self.entry.size) {
+            let clusters_to_keep = (u64::from(new_size) - 1) / bytes_per_cluster + 1;
+
+            let mut cluster = self.entry.first_cluster;
+            for _ in 1..clusters_to_keep {
+                cluster = self.volume.fat_get(cluster)?;
+            }
+
+            let next = self.volume.fat_get(cluster)?;
+            if !Fat32Volume::is_end_of_chain(next) {
+                self.volume.free_chain(next)?;
+            }
+            self.volume.fat_set(cluster, u32::MAX)?;
+        }
+
+        self.entry.size = new_size;
+        self.position = self.position.min(u64::from(new_size));
+
+        dir::update(self.volume, &self.entry)
+    }
+
+    /// Delete the file: free its cluster chain and remove its directory entry.
+    pub fn delete(self) -> Result<(), &'static str> {
+        if self.entry.first_cluster != 0 {
+            self.volume.free_chain(self.entry.first_cluster)?;
+        }
+        dir::delete(self.volume, &self.entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::{block_cache::fixtures::RamDisk, fat32::volume::fixtures::mounted};
+
+    /// [`mounted`] alone leaves the root directory's own cluster marked free in the FAT -- fine
+    /// for `volume`'s tests, which never allocate a cluster, but fatal here: [`File::create`]'s
+    /// first write would happily hand that same cluster back out to the file, clobbering the root
+    /// directory it was just created in. Mark it end-of-chain first, the way a real `mkfs` would.
+    fn mounted_with_reserved_root(disk: &RamDisk) -> Fat32Volume<'_> {
+        let volume = mounted(disk);
+        volume.fat_set(volume.root_cluster(), u32::MAX).unwrap();
+        volume
+    }
+
+    #[test]
+    fn file_create_write_read_roundtrip() {
+        let disk = RamDisk::new(16);
+        let volume = mounted_with_reserved_root(&disk);
+        let root = volume.root_cluster();
+
+        let mut file = File::create(&volume, root, "readme.txt").unwrap();
+        assert_eq!(file.write(b"hello").unwrap(), 5);
+        assert_eq!(file.size(), 5);
+
+        file.seek(0);
+        let mut buf = [0u8; 5];
+        assert_eq!(file.read(&mut buf).unwrap(), 5);
+        assert_eq!(&buf, b"hello");
+
+        let mut reopened = File::open(&volume, root, "readme.txt").unwrap();
+        let mut buf = [0u8; 5];
+        assert_eq!(reopened.read(&mut buf).unwrap(), 5);
+        assert_eq!(&buf, b"hello");
+    }
+
+    #[test]
+    fn append_writes_past_the_current_end() {
+        let disk = RamDisk::new(16);
+        let volume = mounted_with_reserved_root(&disk);
+        let root = volume.root_cluster();
+
+        let mut file = File::create(&volume, root, "appended").unwrap();
+        file.write(b"foo").unwrap();
+        file.append(b"bar").unwrap();
+
+        assert_eq!(file.size(), 6);
+        file.seek(0);
+        let mut buf = [0u8; 6];
+        file.read(&mut buf).unwrap();
+        assert_eq!(&buf, b"foobar");
+    }
+
+    #[test]
+    fn write_past_one_cluster_extends_the_chain() {
+        let disk = RamDisk::new(16);
+        let volume = mounted_with_reserved_root(&disk);
+        let root = volume.root_cluster();
+        let bytes_per_cluster = volume.bytes_per_cluster();
+
+        let mut file = File::create(&volume, root, "big").unwrap();
+        let mut pattern = std::vec![0u8; bytes_per_cluster + 10];
+        for (i, byte) in pattern.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        assert_eq!(file.write(&pattern).unwrap(), pattern.len());
+        assert_eq!(file.size() as usize, pattern.len());
+
+        let first_cluster = file.entry.first_cluster;
+        let second_cluster = volume.fat_get(first_cluster).unwrap();
+        assert!(
+            !Fat32Volume::is_end_of_chain(second_cluster),
+            "a file longer than one cluster needs a second one linked in"
+        );
+
+        file.seek(0);
+        let mut buf = std::vec![0u8; pattern.len()];
+        assert_eq!(file.read(&mut buf).unwrap(), pattern.len());
+        assert_eq!(buf, pattern);
+    }
+
+    #[test]
+    fn truncate_shrinks_and_frees_clusters_past_the_new_end() {
+        let disk = RamDisk::new(16);
+        let volume = mounted_with_reserved_root(&disk);
+        let root = volume.root_cluster();
+        let bytes_per_cluster = volume.bytes_per_cluster();
+
+        let mut file = File::create(&volume, root, "shrink").unwrap();
+        file.write(&std::vec![0xaau8; bytes_per_cluster + 10])
+            .unwrap();
+
+        let first_cluster = file.entry.first_cluster;
+        let second_cluster = volume.fat_get(first_cluster).unwrap();
+
+        file.truncate(2).unwrap();
+        assert_eq!(file.size(), 2);
+        assert_eq!(
+            volume.fat_get(second_cluster).unwrap(),
+            0,
+            "the now-unreachable second cluster should be freed"
+        );
+        assert!(Fat32Volume::is_end_of_chain(
+            volume.fat_get(first_cluster).unwrap()
+        ));
+    }
+
+    #[test]
+    fn truncate_grow_does_not_allocate_until_the_next_write() {
+        let disk = RamDisk::new(16);
+        let volume = mounted_with_reserved_root(&disk);
+        let root = volume.root_cluster();
+
+        let mut file = File::create(&volume, root, "grown").unwrap();
+        file.write(b"hi").unwrap();
+
+        file.truncate(4).unwrap();
+        assert_eq!(file.size(), 4);
+
+        file.seek(0);
+        let mut buf = [0u8; 4];
+        file.read(&mut buf).unwrap();
+        assert_eq!(&buf[..2], b"hi");
+    }
+
+    #[test]
+    fn open_rejects_a_directory_entry() {
+        let disk = RamDisk::new(16);
+        let volume = mounted_with_reserved_root(&disk);
+        let root = volume.root_cluster();
+
+        let short_name = dir::to_short_name("SUBDIR").unwrap();
+        dir::create(&volume, root, &short_name, dir::attr::DIRECTORY).unwrap();
+
+        assert!(File::open(&volume, root, "SUBDIR").is_err());
+    }
+
+    #[test]
+    fn delete_removes_the_directory_entry_and_frees_the_chain() {
+        let disk = RamDisk::new(16);
+        let volume = mounted_with_reserved_root(&disk);
+        let root = volume.root_cluster();
+
+        let mut file = File::create(&volume, root, "gone").unwrap();
+        file.write(b"bye").unwrap();
+        let first_cluster = file.entry.first_cluster;
+
+        file.delete().unwrap();
+
+        assert!(File::open(&volume, root, "gone").is_err());
+        assert_eq!(
+            volume.fat_get(first_cluster).unwrap(),
+            0,
+            "delete should free the file's cluster chain, not just its directory entry"
+        );
+    }
+}
@@ -0,0 +1,404 @@
+//! FAT32 boot sector / FSInfo parsing and cluster-level access.
+
+use crate::{
+    storage::interface::{Block, BlockDevice, BLOCK_SIZE},
+    synchronization::{Mutex, NullLock},
+};
+
+/// FAT32 marks a cluster as unused with a zero FAT entry.
+const FAT_FREE: u32 = 0;
+/// FAT entries at or above this value mark the end of a cluster chain.
+const FAT_EOC_MIN: u32 = 0x0fff_fff8;
+/// The end-of-chain value this driver writes.
+const FAT_EOC: u32 = 0x0fff_ffff;
+/// The top 4 bits of a FAT32 entry are reserved and must be preserved across updates.
+const FAT_ENTRY_MASK: u32 = 0x0fff_ffff;
+
+/// FSInfo sector lead signature.
+const FSINFO_LEAD_SIGNATURE: u32 = 0x4161_5252;
+/// FSInfo sector structure signature, just before the free-cluster-count/next-free-cluster fields.
+const FSINFO_STRUCT_SIGNATURE: u32 = 0x6141_7272;
+/// Sentinel meaning "value not known", used by both FSInfo fields.
+const FSINFO_UNKNOWN: u32 = 0xffff_ffff;
+
+/// Parsed fields of a FAT32 BIOS Parameter Block, enough to locate the FAT and data regions.
+#[derive(Clone, Copy)]
+struct Bpb {
+    bytes_per_sector: u16,
+    sectors_per_cluster: u8,
+    reserved_sector_count: u16,
+    num_fats: u8,
+    fat_size_32: u32,
+    root_cluster: u32,
+    fs_info_sector: u16,
+}
+
+impl Bpb {
+    fn parse(block: &Block) -> Result<Self, &'static str> {
+        if block[510..512] != [0x55, 0xaa] {
+            return Err("fat32: no boot sector signature");
+        }
+        if block[82..90] != *b"FAT32   " {
+            return Err("fat32: not a FAT32 volume");
+        }
+
+        Ok(Self {
+            bytes_per_sector: u16::from_le_bytes(block[11..13].try_into().unwrap()),
+            sectors_per_cluster: block[13],
+            reserved_sector_count: u16::from_le_bytes(block[14..16].try_into().unwrap()),
+            num_fats: block[16],
+            fat_size_32: u32::from_le_bytes(block[36..40].try_into().unwrap()),
+            root_cluster: u32::from_le_bytes(block[44..48].try_into().unwrap()),
+            fs_info_sector: u16::from_le_bytes(block[48..50].try_into().unwrap()),
+        })
+    }
+}
+
+/// The mutable half of FSInfo: an allocation hint, kept best-effort in sync with the volume.
+struct FsInfoState {
+    sector: u16,
+    next_free_cluster: u32,
+    free_cluster_count: u32,
+}
+
+/// A mounted FAT32 volume.
+///
+/// Only 8.3 short filenames are supported -- see the [`crate::fs::fat32`] module doc.
+pub struct Fat32Volume<'a> {
+    device: &'a dyn BlockDevice,
+    bpb: Bpb,
+    fs_info: NullLock<FsInfoState>,
+}
+
+impl<'a> Fat32Volume<'a> {
+    /// Read the boot sector and FSInfo sector off `device` and mount it as a FAT32 volume.
+    pub fn mount(device: &'a dyn BlockDevice) -> Result<Self, &'static str> {
+        let mut block: Block = [0u8; BLOCK_SIZE];
+        device.read_block(0, &mut block)?;
+        let bpb = Bpb::parse(&block)?;
+
+        if bpb.bytes_per_sector as usize != BLOCK_SIZE {
+            return Err("fat32: unsupported sector size");
+        }
+
+        let mut fs_info_block: Block = [0u8; BLOCK_SIZE];
+        device.read_block(u64::from(bpb.fs_info_sector), &mut fs_info_block)?;
+
+        let lead_signature = u32::from_le_bytes(fs_info_block[0..4].try_into().unwrap());
+        let struct_signature = u32::from_le_bytes(fs_info_block[484..488].try_into().unwrap());
+
+        let (free_cluster_count, next_free_cluster) = if lead_signature == FSINFO_LEAD_SIGNATURE
+            && struct_signature == FSINFO_STRUCT_SIGNATURE
+        {
+            (
+                u32::from_le_bytes(fs_info_block[488..492].try_into().unwrap()),
+                u32::from_le_bytes(fs_info_block[492..496].try_into().unwrap()),
+            )
+        } else {
+            (FSINFO_UNKNOWN, FSINFO_UNKNOWN)
+        };
+
+        Ok(Self {
+            device,
+            bpb,
+            fs_info: NullLock::new(FsInfoState {
+                sector: bpb.fs_info_sector,
+                next_free_cluster,
+                free_cluster_count,
+            }),
+        })
+    }
+
+    /// The root directory's starting cluster.
+    pub fn root_cluster(&self) -> u32 {
+        self.bpb.root_cluster
+    }
+
+    /// How many 32-byte directory entries fit in one cluster.
+    pub fn entries_per_cluster(&self) -> usize {
+        self.bytes_per_cluster() / 32
+    }
+
+    /// The size of one cluster, in bytes.
+    pub fn bytes_per_cluster(&self) -> usize {
+        self.bpb.sectors_per_cluster as usize * BLOCK_SIZE
+    }
+
+    fn first_data_sector(&self) -> u64 {
+        u64::from(self.bpb.reserved_sector_count)
+            + u64::from(self.bpb.num_fats) * u64::from(self.bpb.fat_size_32)
+    }
+
+    fn first_sector_of_cluster(&self, cluster: u32) -> u64 {
+        self.first_data_sector() + u64::from(cluster - 2) * u64::from(self.bpb.sectors_per_cluster)
+    }
+
+    /// Read sector `sector_in_cluster` (0-based) of `cluster` into `buf`.
+    pub fn read_cluster_sector(
+        &self,
+        cluster: u32,
+        sector_in_cluster: u32,
+        buf: &mut Block,
+    ) -> Result<(), &'static str> {
+        let sector = self.first_sector_of_cluster(cluster) + u64::from(sector_in_cluster);
+        self.device.read_block(sector, buf)
+    }
+
+    /// Write `buf` to sector `sector_in_cluster` (0-based) of `cluster`.
+    pub fn write_cluster_sector(
+        &self,
+        cluster: u32,
+        sector_in_cluster: u32,
+        buf: &Block,
+    ) -> Result<(), &'static str> {
+        let sector = self.first_sector_of_cluster(cluster) + u64::from(sector_in_cluster);
+        self.device.write_block(sector, buf)
+    }
+
+    fn fat_entry_location(&self, cluster: u32) -> (u64, usize) {
+        let fat_offset = u64::from(cluster) * 4;
+        let sector = u64::from(self.bpb.reserved_sector_count) + fat_offset / BLOCK_SIZE as u64;
+        let offset = (fat_offset % BLOCK_SIZE as u64) as usize;
+        (sector, offset)
+    }
+
+    /// Read the FAT entry for `cluster`: the number of the next cluster in its chain, or a value
+    /// `>=` the end-of-chain threshold (see [`Fat32Volume::is_end_of_chain`]) if it's the last.
+    pub fn fat_get(&self, cluster: u32) -> Result<u32, &'static str> {
+        let (sector, offset) = self.fat_entry_location(cluster);
+
+        let mut block: Block = [0u8; BLOCK_SIZE];
+        self.device.read_block(sector, &mut block)?;
+
+        Ok(u32::from_le_bytes(block[offset..offset + 4].try_into().unwrap()) & FAT_ENTRY_MASK)
+    }
+
+    /// Write the FAT entry for `cluster` to `value`, in every FAT copy the volume has.
+    pub fn fat_set(&self, cluster: u32, value: u32) -> Result<(), &'static str> {
+        let (sector, offset) = self.fat_entry_location(cluster);
+        let fat_size = u64::from(self.bpb.fat_size_32);
+
+        for fat in 0..u64::from(self.bpb.num_fats) {
+            let sector = sector + fat * fat_size;
+
+            let mut block: Block = [0u8; BLOCK_SIZE];
+            self.device.read_block(sector, &mut block)?;
+
+            let preserved =
+                u32::from_le_bytes(block[offset..offset + 4].try_into().unwrap()) & !FAT_ENTRY_MASK;
+            block[offset..offset + 4]
+                .copy_from_slice(&((value & FAT_ENTRY_MASK) | preserved).to_le_bytes());
+
+            self.device.write_block(sector, &block)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether `entry` (as returned by [`Fat32Volume::fat_get`]) marks the end of a cluster chain.
+    pub fn is_end_of_chain(entry: u32) -> bool {
+        entry >= FAT_EOC_MIN
+    }
+
+    /// Find a free cluster, mark it end-of-chain, and return its number.
+    ///
+    /// Starts from the FSInfo next-free-cluster hint and wraps around the volume once, so
+    /// repeated allocations don't all re-scan from cluster 2.
+    pub fn allocate_cluster(&self) -> Result<u32, &'static str> {
+        let total_entries = (u64::from(self.bpb.fat_size_32) * BLOCK_SIZE as u64 / 4) as u32;
+        if total_entries <= 2 {
+            return Err("fat32: volume too small");
+        }
+        let searchable = total_entries - 2;
+
+        let hint = self.fs_info.lock(|info| info.next_free_cluster);
+        let start = if hint == FSINFO_UNKNOWN || hint < 2 || hint >= total_entries {
+            2
+        } else {
+            hint
+        };
+
+        for offset in 0..searchable {
+            let cluster = 2 + (start - 2 + offset) % searchable;
+            if self.fat_get(cluster)? == FAT_FREE {
+                self.fat_set(cluster, FAT_EOC)?;
+                self.note_cluster_allocated(cluster)?;
+                return Ok(cluster);
+            }
+        }
+
+        Err("fat32: volume full")
+    }
+
+    /// Append a freshly-allocated cluster to the end of the chain, linking it after `tail`, and
+    /// zero it.
+    pub fn extend_chain(&self, tail: u32) -> Result<u32, &'static str> {
+        let new_cluster = self.allocate_cluster()?;
+        self.fat_set(tail, new_cluster)?;
+        self.zero_cluster(new_cluster)?;
+        Ok(new_cluster)
+    }
+
+    /// Zero every sector of `cluster`.
+    pub fn zero_cluster(&self, cluster: u32) -> Result<(), &'static str> {
+        let zero: Block = [0u8; BLOCK_SIZE];
+        for sector in 0..u32::from(self.bpb.sectors_per_cluster) {
+            self.write_cluster_sector(cluster, sector, &zero)?;
+        }
+        Ok(())
+    }
+
+    /// Free every cluster in the chain starting at `start`.
+    pub fn free_chain(&self, start: u32) -> Result<(), &'static str> {
+        let mut cluster = start;
+
+        while cluster != FAT_FREE && !Self::is_end_of_chain(cluster) {
+            let next = self.fat_get(cluster)?;
+            self.fat_set(cluster, FAT_FREE)?;
+            self.note_cluster_freed()?;
+            cluster = next;
+        }
+
+        Ok(())
+    }
+
+    fn note_cluster_allocated(&self, allocated: u32) -> Result<(), &'static str> {
+        self.fs_info.lock(|info| {
+            if info.free_cluster_count != FSINFO_UNKNOWN {
+                info.free_cluster_count -= 1;
+            }
+            info.next_free_cluster = allocated + 1;
+        });
+        self.write_fs_info()
+    }
+
+    fn note_cluster_freed(&self) -> Result<(), &'static str> {
+        self.fs_info.lock(|info| {
+            if info.free_cluster_count != FSINFO_UNKNOWN {
+                info.free_cluster_count += 1;
+            }
+        });
+        self.write_fs_info()
+    }
+
+    fn write_fs_info(&self) -> Result<(), &'static str> {
+        let (sector, free_count, next_free) = self
+            .fs_info
+            .lock(|info| (info.sector, info.free_cluster_count, info.next_free_cluster));
+
+        let mut block: Block = [0u8; BLOCK_SIZE];
+        self.device.read_block(u64::from(sector), &mut block)?;
+        block[488..492].copy_from_slice(&free_count.to_le_bytes());
+        block[492..496].copy_from_slice(&next_free.to_le_bytes());
+        self.device.write_block(u64::from(sector), &block)
+    }
+}
+
+/// A minimal mountable FAT32 volume for this module's and sibling `fat32` modules' tests --
+/// standing in for a real SD card or virtio-blk device the same way [`crate::fs::block_cache`]'s
+/// own `fixtures` module does for a generic [`BlockDevice`].
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+    use crate::fs::block_cache::fixtures::RamDisk;
+
+    /// Format `disk` as a minimal mountable FAT32 volume: one reserved sector each for the BPB
+    /// and FSInfo, a single one-sector FAT (128 entries, more than enough for these tests), and
+    /// one sector per cluster starting right after it.
+    pub(crate) fn format(disk: &RamDisk) {
+        let mut bpb: Block = [0u8; BLOCK_SIZE];
+        bpb[510..512].copy_from_slice(&[0x55, 0xaa]);
+        bpb[82..90].copy_from_slice(b"FAT32   ");
+        bpb[11..13].copy_from_slice(&(BLOCK_SIZE as u16).to_le_bytes());
+        bpb[13] = 1; // sectors_per_cluster
+        bpb[14..16].copy_from_slice(&2u16.to_le_bytes()); // reserved_sector_count
+        bpb[16] = 1; // num_fats
+        bpb[36..40].copy_from_slice(&1u32.to_le_bytes()); // fat_size_32
+        bpb[44..48].copy_from_slice(&2u32.to_le_bytes()); // root_cluster
+        bpb[48..50].copy_from_slice(&1u16.to_le_bytes()); // fs_info_sector
+        disk.seed(0, &bpb);
+
+        // Leave the FSInfo sector's signatures zeroed, so mount() falls back to "hint unknown"
+        // rather than needing a second fixture variant for that path.
+        disk.seed(1, &[0u8; BLOCK_SIZE]);
+    }
+
+    pub(crate) fn mounted(disk: &RamDisk) -> Fat32Volume<'_> {
+        format(disk);
+        Fat32Volume::mount(disk).expect("fixture volume should mount")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixtures::mounted, *};
+    use crate::fs::block_cache::fixtures::RamDisk;
+
+    #[test]
+    fn fat_get_set_roundtrips_and_preserves_the_reserved_top_nibble() {
+        let disk = RamDisk::new(16);
+        let volume = mounted(&disk);
+
+        volume.fat_set(5, 0x0123_4567).unwrap();
+        assert_eq!(volume.fat_get(5).unwrap(), 0x0123_4567);
+
+        // The top 4 bits are reserved and must survive being overwritten with a 28-bit value.
+        // Set them on disk first: cluster 5's entry is bytes 20..24 of the FAT at sector 2.
+        let mut fat_sector = disk.peek(2);
+        fat_sector[5 * 4 + 3] |= 0xf0;
+        disk.seed(2, &fat_sector);
+        volume.fat_set(5, FAT_EOC_MIN).unwrap();
+        assert_eq!(disk.peek(2)[5 * 4 + 3] & 0xf0, 0xf0);
+        assert_eq!(volume.fat_get(5).unwrap(), FAT_EOC_MIN);
+        assert!(Fat32Volume::is_end_of_chain(volume.fat_get(5).unwrap()));
+    }
+
+    #[test]
+    fn allocate_cluster_hands_out_ascending_free_clusters() {
+        let disk = RamDisk::new(16);
+        let volume = mounted(&disk);
+
+        let first = volume.allocate_cluster().unwrap();
+        assert_eq!(first, 2);
+        assert!(Fat32Volume::is_end_of_chain(volume.fat_get(first).unwrap()));
+
+        let second = volume.allocate_cluster().unwrap();
+        assert_eq!(
+            second, 3,
+            "should not re-hand-out the cluster just allocated"
+        );
+    }
+
+    #[test]
+    fn extend_chain_links_and_zeroes_the_new_cluster() {
+        let disk = RamDisk::new(16);
+        let volume = mounted(&disk);
+
+        let tail = volume.allocate_cluster().unwrap();
+        // Dirty the next cluster's data sector up front, so zeroing is actually observable.
+        disk.seed(tail as u64 + 2, &[0xaa; BLOCK_SIZE]);
+
+        let next = volume.extend_chain(tail).unwrap();
+
+        assert_eq!(volume.fat_get(tail).unwrap(), next);
+        assert!(Fat32Volume::is_end_of_chain(volume.fat_get(next).unwrap()));
+
+        let mut block: Block = [0u8; BLOCK_SIZE];
+        volume.read_cluster_sector(next, 0, &mut block).unwrap();
+        assert_eq!(block, [0u8; BLOCK_SIZE]);
+    }
+
+    #[test]
+    fn free_chain_frees_every_cluster_in_the_chain() {
+        let disk = RamDisk::new(16);
+        let volume = mounted(&disk);
+
+        let first = volume.allocate_cluster().unwrap();
+        let second = volume.extend_chain(first).unwrap();
+
+        volume.free_chain(first).unwrap();
+
+        assert_eq!(volume.fat_get(first).unwrap(), FAT_FREE);
+        assert_eq!(volume.fat_get(second).unwrap(), FAT_FREE);
+    }
+}
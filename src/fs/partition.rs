@@ -0,0 +1,276 @@
+//! MBR and GPT partition table parsing.
+//!
+//! Reads a partition table directly off any [`BlockDevice`] and exposes each partition as its own
+//! [`Partition`], translating logical block addresses by the partition's starting LBA.
+
+use crate::storage::interface::{Block, BlockDevice, BLOCK_SIZE};
+
+/// The GUID Partition Table signature ("EFI PART"), at bytes 0..8 of LBA 1.
+const GPT_SIGNATURE: [u8; 8] = *b"EFI PART";
+/// The boot signature every valid MBR ends its first block with.
+const MBR_BOOT_SIGNATURE: [u8; 2] = [0x55, 0xaa];
+/// The MBR partition type byte marking a "protective MBR" that hands the whole disk to GPT.
+const MBR_TYPE_GPT_PROTECTIVE: u8 = 0xee;
+
+/// How many partitions [`read_partition_table`] will report. MBR allows at most 4 primary
+/// entries; GPT nominally allows far more, but this fork has no heap to size a growable
+/// collection for them, so the cap applies to both.
+pub const MAX_PARTITIONS: usize = 4;
+
+/// One partition found on a device.
+#[derive(Copy, Clone, Debug)]
+pub struct PartitionEntry {
+    /// Starting LBA, in the parent device's blocks.
+    pub start_lba: u64,
+    /// Length, in blocks.
+    pub block_count: u64,
+    /// For an MBR entry, the partition type byte. For a GPT entry, the first byte of the
+    /// partition type GUID -- not a direct equivalent, but enough to eyeball in `lsblk` output.
+    pub partition_type: u8,
+}
+
+/// A fixed-size table of partitions found on a device, since this fork has no heap to grow a
+/// collection into.
+pub struct PartitionTable {
+    entries: [Option<PartitionEntry>; MAX_PARTITIONS],
+}
+
+impl PartitionTable {
+    /// Iterate over the valid partitions found.
+    pub fn iter(&self) -> impl Iterator<Item = &PartitionEntry> {
+        self.entries.iter().filter_map(Option::as_ref)
+    }
+}
+
+/// A `BlockDevice` that's a partition of another `BlockDevice`, translating block indices by the
+/// partition's starting LBA.
+pub struct Partition<'a> {
+    parent: &'a dyn BlockDevice,
+    entry: PartitionEntry,
+}
+
+impl<'a> Partition<'a> {
+    /// Wrap `parent` as the partition described by `entry`.
+    pub fn new(parent: &'a dyn BlockDevice, entry: PartitionEntry) -> Self {
+        Self { parent, entry }
+    }
+
+    /// The partition's table entry.
+    pub fn entry(&self) -> PartitionEntry {
+        self.entry
+    }
+}
+
+impl BlockDevice for Partition<'_> {
+    fn block_count(&self) -> u64 {
+        self.entry.block_count
+    }
+
+    fn read_block(&self, block_index: u64, buf: &mut Block) -> Result<(), &'static str> {
+        if block_index >= self.entry.block_count {
+            return Err("partition: block index out of range");
+        }
+        self.parent
+            .read_block(self.entry.start_lba + block_index, buf)
+    }
+
+    fn write_block(&self, block_index: u64, buf: &Block) -> Result<(), &'static str> {
+        if block_index >= self.entry.block_count {
+            return Err("partition: block index out of range");
+        }
+        self.parent
+            .write_block(self.entry.start_lba + block_index, buf)
+    }
+}
+
+/// Read and parse the partition table from `device`, trying GPT first (behind its protective MBR)
+/// and falling back to a plain MBR.
+pub fn read_partition_table(device: &dyn BlockDevice) -> Result<PartitionTable, &'static str> {
+    let mut block: Block = [0u8; BLOCK_SIZE];
+    device.read_block(0, &mut block)?;
+
+    if block[510..512] != MBR_BOOT_SIGNATURE {
+        return Err("partition: no valid MBR boot signature");
+    }
+
+    // Offset 446 is the first of four 16-byte MBR partition entries; +4 within an entry is its
+    // type byte.
+    if block[446 + 4] == MBR_TYPE_GPT_PROTECTIVE {
+        return read_gpt(device);
+    }
+
+    Ok(read_mbr(&block))
+}
+
+fn read_mbr(block: &Block) -> PartitionTable {
+    let mut entries: [Option<PartitionEntry>; MAX_PARTITIONS] = [None; MAX_PARTITIONS];
+
+    for (i, entry) in entries.iter_mut().enumerate() {
+        let offset = 446 + i * 16;
+        let partition_type = block[offset + 4];
+        if partition_type == 0 {
+            continue;
+        }
+
+        let start_lba = u32::from_le_bytes(block[offset + 8..offset + 12].try_into().unwrap());
+        let block_count = u32::from_le_bytes(block[offset + 12..offset + 16].try_into().unwrap());
+
+        *entry = Some(PartitionEntry {
+            start_lba: u64::from(start_lba),
+            block_count: u64::from(block_count),
+            partition_type,
+        });
+    }
+
+    PartitionTable { entries }
+}
+
+fn read_gpt(device: &dyn BlockDevice) -> Result<PartitionTable, &'static str> {
+    let mut header: Block = [0u8; BLOCK_SIZE];
+    device.read_block(1, &mut header)?;
+
+    if header[0..8] != GPT_SIGNATURE {
+        return Err("partition: no GPT signature");
+    }
+
+    let entry_lba = u64::from_le_bytes(header[72..80].try_into().unwrap());
+    let num_entries = u32::from_le_bytes(header[80..84].try_into().unwrap());
+    let entry_size = u32::from_le_bytes(header[84..88].try_into().unwrap()) as usize;
+
+    if entry_size == 0 || entry_size > BLOCK_SIZE {
+        return Err("partition: implausible GPT partition entry size");
+    }
+
+    let entries_per_block = BLOCK_SIZE / entry_size;
+    let mut entries: [Option<PartitionEntry>; MAX_PARTITIONS] = [None; MAX_PARTITIONS];
+    let mut stored = 0;
+    let mut processed = 0;
+    let mut block: Block = [0u8; BLOCK_SIZE];
+    let mut current_lba = entry_lba;
+
+    while processed < num_entries && stored < MAX_PARTITIONS {
+        device.read_block(current_lba, &mut block)?;
+
+        for slot in 0..entries_per_block {
+            if processed >= num_entries || stored >= MAX_PARTITIONS {
+                break;
+            }
+            processed += 1;
+
+            let offset = slot * entry_size;
+            let type_guid = &block[offset..offset + 16];
+            if type_guid.iter().all(|&b| b == 0) {
+                continue;
+            }
+
+            let start_lba = u64::from_le_bytes(block[offset + 32..offset + 40].try_into().unwrap());
+            let end_lba = u64::from_le_bytes(block[offset + 40..offset + 48].try_into().unwrap());
+
+            entries[stored] = Some(PartitionEntry {
+                start_lba,
+                block_count: end_lba + 1 - start_lba,
+                partition_type: type_guid[0],
+            });
+            stored += 1;
+        }
+
+        current_lba += 1;
+    }
+
+    Ok(PartitionTable { entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::block_cache::fixtures::RamDisk;
+
+    #[test]
+    fn rejects_a_block_with_no_boot_signature() {
+        let disk = RamDisk::new(2);
+        assert!(read_partition_table(&disk).is_err());
+    }
+
+    #[test]
+    fn parses_an_mbr_with_one_partition() {
+        let disk = RamDisk::new(2);
+        let mut mbr: Block = [0u8; BLOCK_SIZE];
+        mbr[510..512].copy_from_slice(&MBR_BOOT_SIGNATURE);
+
+        let offset = 446;
+        mbr[offset + 4] = 0x0c; // FAT32 LBA partition type
+        mbr[offset + 8..offset + 12].copy_from_slice(&2048u32.to_le_bytes()); // start LBA
+        mbr[offset + 12..offset + 16].copy_from_slice(&1_000_000u32.to_le_bytes()); // block count
+        disk.seed(0, &mbr);
+
+        let table = read_partition_table(&disk).unwrap();
+        let entries: std::vec::Vec<_> = table.iter().copied().collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].start_lba, 2048);
+        assert_eq!(entries[0].block_count, 1_000_000);
+        assert_eq!(entries[0].partition_type, 0x0c);
+    }
+
+    #[test]
+    fn skips_empty_mbr_entries() {
+        let disk = RamDisk::new(2);
+        let mut mbr: Block = [0u8; BLOCK_SIZE];
+        mbr[510..512].copy_from_slice(&MBR_BOOT_SIGNATURE);
+        // All four partition-type bytes left at 0: every entry is empty.
+        disk.seed(0, &mbr);
+
+        let table = read_partition_table(&disk).unwrap();
+        assert_eq!(table.iter().count(), 0);
+    }
+
+    #[test]
+    fn parses_a_protective_mbr_and_gpt_header() {
+        let disk = RamDisk::new(4);
+
+        let mut mbr: Block = [0u8; BLOCK_SIZE];
+        mbr[510..512].copy_from_slice(&MBR_BOOT_SIGNATURE);
+        mbr[446 + 4] = MBR_TYPE_GPT_PROTECTIVE;
+        disk.seed(0, &mbr);
+
+        let mut gpt_header: Block = [0u8; BLOCK_SIZE];
+        gpt_header[0..8].copy_from_slice(&GPT_SIGNATURE);
+        gpt_header[72..80].copy_from_slice(&2u64.to_le_bytes()); // partition entries at LBA 2
+        gpt_header[80..84].copy_from_slice(&1u32.to_le_bytes()); // one entry
+        gpt_header[84..88].copy_from_slice(&128u32.to_le_bytes()); // 128 bytes/entry
+        disk.seed(1, &gpt_header);
+
+        let mut entries_block: Block = [0u8; BLOCK_SIZE];
+        entries_block[0] = 0xab; // non-zero type GUID: a used entry
+        entries_block[32..40].copy_from_slice(&100u64.to_le_bytes()); // start LBA
+        entries_block[40..48].copy_from_slice(&199u64.to_le_bytes()); // end LBA
+        disk.seed(2, &entries_block);
+
+        let table = read_partition_table(&disk).unwrap();
+        let entries: std::vec::Vec<_> = table.iter().copied().collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].start_lba, 100);
+        assert_eq!(entries[0].block_count, 100);
+        assert_eq!(entries[0].partition_type, 0xab);
+    }
+
+    #[test]
+    fn partition_translates_block_indices_and_bounds_checks_them() {
+        let disk = RamDisk::new(10);
+        disk.seed(5, &[0x42; BLOCK_SIZE]);
+
+        let partition = Partition::new(
+            &disk,
+            PartitionEntry {
+                start_lba: 5,
+                block_count: 2,
+                partition_type: 0x0c,
+            },
+        );
+
+        let mut buf: Block = [0u8; BLOCK_SIZE];
+        partition.read_block(0, &mut buf).unwrap();
+        assert_eq!(buf, [0x42; BLOCK_SIZE]);
+
+        assert!(partition.read_block(2, &mut buf).is_err());
+    }
+}
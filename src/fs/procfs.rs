@@ -0,0 +1,352 @@
+//! procfs-style pseudo filesystem exposing kernel state.
+//!
+//! Like [`crate::fs::tmpfs`], this isn't reachable at `/proc` through a real mount table --
+//! there's no VFS in this fork, see [`crate::fs`] -- so callers call [`read`] directly with the
+//! path they'd otherwise have opened.
+//!
+//! Every file here is generated on read into a caller-supplied buffer rather than built up with
+//! a heap-allocated `String`, for the same no-alloc reason [`crate::crashdump`]'s panic-message
+//! buffer is fixed-size: this is a `#![no_std]` kernel with no heap.
+//!
+//! `interrupts`, `irq_latency`, and `drivers` report real state --
+//! [`crate::exception::asynchronous::all_irq_counts`],
+//! [`crate::exception::asynchronous::all_irq_latencies`], and [`crate::driver::driver_manager`]
+//! all already track it. `uptime` is real too, off [`crate::time::time_manager`]. `meminfo` is
+//! the odd one out: this fork has no frame allocator or heap (see [`crate::memory`]) to report
+//! anything about, so its content says exactly that instead of fabricating numbers.
+//!
+//! `jobs` is [`crate::jobs`]'s single run queue, for the shell's `top` command. It's singular, not
+//! per-core, because there's only one core in this fork to begin with -- see [`crate::stress`]'s
+//! module docs for the same "no SMP boot path" fact. A per-task CPU affinity mask or a load
+//! balancer that steals work between cores' run queues needs more than one core's run queue to
+//! move work between in the first place, so there's exactly one queue reported here, not a table
+//! of them.
+//!
+//! `status/<id>` is one job's own accounting -- name, priority, accumulated CPU time, and its CPU
+//! time limit if `jobs::spawn` was given one -- for the shell's `ps` command. Named after Linux's
+//! `/proc/<pid>/status` even though a job here isn't a process; see [`crate::jobs`]'s module docs
+//! for why there's no resident-frame or heap figure alongside it, same gap as `meminfo`.
+//!
+//! `stackdepth` is also singular for the same reason: [`crate::cpu::stack_high_watermark`] only
+//! ever watermarks the boot core's stack, since no other core in this fork ever runs Rust code.
+//! Alongside it sits [`crate::exception::asynchronous::peak_nesting`]'s worst observed handler
+//! nesting depth paired with the stack usage seen at that depth -- see
+//! [`crate::exception::asynchronous::NestingGuard`]'s docs for why real hardware exception nesting isn't something this fork can produce to measure.
+//!
+//! Every one of those generators needs a booted board to have anything real to report, so they
+//! (and [`read`] itself) are `#[cfg(target_arch = "aarch64")]`-gated, the same technique
+//! [`crate::profiler`]'s `sample_tick` uses to keep an arch-only function out of a host build.
+//! What's free of that dependency is [`classify`] -- the part of `read` that decides which
+//! generator a path names without calling any of them -- and `meminfo`, which never had a live
+//! dependency to begin with. Both are gated `#[cfg(any(target_arch = "aarch64", test))]` instead,
+//! since nothing outside this module's own tests calls either one on a host build. Both are
+//! mirrored into `kernel_pure` (see `src/lib.rs`) and checked by the `tests` module below.
+
+#[cfg(target_arch = "aarch64")]
+use crate::{
+    jobs,
+    time::{self, TimeManager},
+};
+#[cfg(any(target_arch = "aarch64", test))]
+use core::fmt::{self, Write};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A `fmt::Write` sink over a caller-supplied buffer that silently truncates past capacity --
+/// the same trade-off [`crate::crashdump`]'s `FixedBuf` makes, for the same reason.
+///
+/// Only used by the `#[cfg(target_arch = "aarch64")]` generators below and by this module's own
+/// tests, so it's gated the same way they are instead of reporting dead on a plain host build.
+#[cfg(any(target_arch = "aarch64", test))]
+struct FixedBuf<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+#[cfg(any(target_arch = "aarch64", test))]
+impl fmt::Write for FixedBuf<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let remaining = self.buf.len() - self.len;
+        let n = s.len().min(remaining);
+
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+/// Which file `read` would generate for a given path, without touching a single live kernel
+/// global to decide -- split out from `read` so this routing logic (trimming a leading slash,
+/// matching the fixed file names, and parsing `status/<id>`'s job id) can be unit-tested on the
+/// host even though the generators it routes to can't be (see the `tests` module and the crate-
+/// level docs in `src/lib.rs` for why only this half of the file mirrors in).
+///
+/// Only reachable from `read` (aarch64-only) or this module's own tests, so it's gated to match
+/// instead of reporting dead on a plain host build.
+#[cfg(any(target_arch = "aarch64", test))]
+#[derive(Debug, PartialEq, Eq)]
+enum ProcFile {
+    Interrupts,
+    IrqLatency,
+    Meminfo,
+    Drivers,
+    Uptime,
+    Jobs,
+    Stackdepth,
+    Status(usize),
+}
+
+#[cfg(any(target_arch = "aarch64", test))]
+fn classify(path: &str) -> Result<ProcFile, &'static str> {
+    let path = path.trim_start_matches('/');
+
+    match path {
+        "interrupts" => return Ok(ProcFile::Interrupts),
+        "irq_latency" => return Ok(ProcFile::IrqLatency),
+        "meminfo" => return Ok(ProcFile::Meminfo),
+        "drivers" => return Ok(ProcFile::Drivers),
+        "uptime" => return Ok(ProcFile::Uptime),
+        "jobs" => return Ok(ProcFile::Jobs),
+        "stackdepth" => return Ok(ProcFile::Stackdepth),
+        _ => {}
+    }
+
+    if let Some(id) = path.strip_prefix("status/") {
+        return id
+            .parse()
+            .map(ProcFile::Status)
+            .map_err(|_| "procfs: no such file");
+    }
+
+    Err("procfs: no such file")
+}
+
+#[cfg(target_arch = "aarch64")]
+fn generate_uptime(buf: &mut [u8]) -> usize {
+    let uptime = time::time_manager().uptime();
+    let mut w = FixedBuf { buf, len: 0 };
+    let _ = writeln!(w, "{}.{:02}", uptime.as_secs(), uptime.subsec_millis() / 10);
+    w.len
+}
+
+#[cfg(target_arch = "aarch64")]
+fn generate_drivers(buf: &mut [u8]) -> usize {
+    let mut w = FixedBuf { buf, len: 0 };
+    crate::driver::driver_manager().enumerate(|info| {
+        let _ = write!(w, "{:<24}", info.compatible);
+
+        match info.mmio {
+            Some(mmio) => {
+                let _ = write!(w, " mmio={:#010x}+{:#x}", mmio.start_addr(), mmio.size());
+            }
+            None => {
+                let _ = write!(w, " mmio=-");
+            }
+        }
+
+        match info.irq_number {
+            Some(irq) => {
+                let _ = write!(w, " irq={}", irq);
+            }
+            None => {
+                let _ = write!(w, " irq=-");
+            }
+        }
+
+        match info.init_duration {
+            Some(duration) => {
+                let _ = write!(w, " init_us={}", duration.as_micros());
+            }
+            None => {
+                let _ = write!(w, " init_us=-");
+            }
+        }
+
+        let _ = writeln!(w);
+    });
+    w.len
+}
+
+#[cfg(target_arch = "aarch64")]
+fn generate_interrupts(buf: &mut [u8]) -> usize {
+    let mut w = FixedBuf { buf, len: 0 };
+    crate::exception::asynchronous::all_irq_counts(|name, count| {
+        let _ = writeln!(w, "{:<24} {}", name, count);
+    });
+    w.len
+}
+
+#[cfg(target_arch = "aarch64")]
+fn generate_irq_latency(buf: &mut [u8]) -> usize {
+    let mut w = FixedBuf { buf, len: 0 };
+    let _ = writeln!(
+        w,
+        "{:<24} {:>12} {:>12}",
+        "name", "dispatch_max", "service_max"
+    );
+    crate::exception::asynchronous::all_irq_latencies(|name, dispatch_ticks, service_ticks| {
+        let _ = writeln!(
+            w,
+            "{:<24} {:>12} {:>12}",
+            name, dispatch_ticks, service_ticks
+        );
+    });
+    w.len
+}
+
+#[cfg(target_arch = "aarch64")]
+fn generate_jobs(buf: &mut [u8]) -> usize {
+    let mut w = FixedBuf { buf, len: 0 };
+    let mut count = 0;
+
+    let _ = writeln!(w, "{:<4} {:<8} {}", "id", "priority", "name");
+    jobs::list(|id, name, priority| {
+        count += 1;
+        let _ = writeln!(w, "{:<4} {:<8} {}", id, priority.tag(), name);
+    });
+
+    let _ = writeln!(w, "{}/{} slots in use", count, jobs::MAX_JOBS);
+    w.len
+}
+
+#[cfg(target_arch = "aarch64")]
+fn generate_stackdepth(buf: &mut [u8]) -> usize {
+    let mut w = FixedBuf { buf, len: 0 };
+    let (nesting_depth, nesting_stack_bytes) = crate::exception::asynchronous::peak_nesting();
+
+    let _ = writeln!(
+        w,
+        "lifetime_high_water_bytes: {}",
+        crate::cpu::stack_high_watermark()
+    );
+    let _ = writeln!(w, "peak_handler_nesting_depth: {}", nesting_depth);
+    let _ = writeln!(
+        w,
+        "peak_handler_nesting_stack_bytes: {}",
+        nesting_stack_bytes
+    );
+    w.len
+}
+
+/// Generate one job's `status` file: name, priority, accumulated CPU time, and its CPU time limit
+/// if one was set at spawn time. No resident-frame or heap figures -- see the module docs for why
+/// there's nothing real behind those for a job in this fork, same as `meminfo`.
+#[cfg(target_arch = "aarch64")]
+fn generate_status(buf: &mut [u8], id: jobs::JobId) -> Result<usize, &'static str> {
+    let mut found = None;
+    jobs::stats(|job_id, name, priority, cpu_time, cpu_limit| {
+        if job_id == id {
+            found = Some((name, priority, cpu_time, cpu_limit));
+        }
+    });
+    let (name, priority, cpu_time, cpu_limit) = found.ok_or("procfs: no such job")?;
+
+    let mut w = FixedBuf { buf, len: 0 };
+    let _ = writeln!(w, "Name: {}", name);
+    let _ = writeln!(w, "Pid: {}", id);
+    let _ = writeln!(w, "Priority: {}", priority.tag());
+    let _ = writeln!(w, "CpuTimeMs: {}", cpu_time.as_millis());
+    match cpu_limit {
+        Some(limit) => {
+            let _ = writeln!(w, "CpuLimitMs: {}", limit.as_millis());
+        }
+        None => {
+            let _ = writeln!(w, "CpuLimitMs: none");
+        }
+    }
+    let _ = writeln!(
+        w,
+        "VmRSS: unknown -- this fork has no frame allocator or heap to report on"
+    );
+    Ok(w.len)
+}
+
+/// Only reachable from `read` (aarch64-only) or this module's own tests, so it's gated to match
+/// instead of reporting dead on a plain host build.
+#[cfg(any(target_arch = "aarch64", test))]
+fn generate_meminfo(buf: &mut [u8]) -> usize {
+    let mut w = FixedBuf { buf, len: 0 };
+    let _ = writeln!(
+        w,
+        "MemTotal: unknown -- this fork has no frame allocator or heap to report on"
+    );
+    w.len
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Generate the contents of `path` (e.g. `"uptime"` or `"/uptime"`, either is accepted) into
+/// `buf`, returning the number of bytes written. Content longer than `buf` is silently truncated.
+#[cfg(target_arch = "aarch64")]
+pub fn read(path: &str, buf: &mut [u8]) -> Result<usize, &'static str> {
+    match classify(path)? {
+        ProcFile::Interrupts => Ok(generate_interrupts(buf)),
+        ProcFile::IrqLatency => Ok(generate_irq_latency(buf)),
+        ProcFile::Meminfo => Ok(generate_meminfo(buf)),
+        ProcFile::Drivers => Ok(generate_drivers(buf)),
+        ProcFile::Uptime => Ok(generate_uptime(buf)),
+        ProcFile::Jobs => Ok(generate_jobs(buf)),
+        ProcFile::Stackdepth => Ok(generate_stackdepth(buf)),
+        ProcFile::Status(id) => generate_status(buf, id as jobs::JobId),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_matches_every_fixed_file_name() {
+        assert_eq!(classify("interrupts").unwrap(), ProcFile::Interrupts);
+        assert_eq!(classify("irq_latency").unwrap(), ProcFile::IrqLatency);
+        assert_eq!(classify("meminfo").unwrap(), ProcFile::Meminfo);
+        assert_eq!(classify("drivers").unwrap(), ProcFile::Drivers);
+        assert_eq!(classify("uptime").unwrap(), ProcFile::Uptime);
+        assert_eq!(classify("jobs").unwrap(), ProcFile::Jobs);
+        assert_eq!(classify("stackdepth").unwrap(), ProcFile::Stackdepth);
+    }
+
+    #[test]
+    fn classify_accepts_a_leading_slash_or_not() {
+        assert_eq!(classify("/uptime").unwrap(), classify("uptime").unwrap());
+    }
+
+    #[test]
+    fn classify_parses_a_status_file_job_id() {
+        assert_eq!(classify("status/3").unwrap(), ProcFile::Status(3));
+        assert_eq!(classify("/status/0").unwrap(), ProcFile::Status(0));
+    }
+
+    #[test]
+    fn classify_rejects_a_non_numeric_status_id_and_unknown_paths() {
+        assert!(classify("status/not-a-number").is_err());
+        assert!(classify("status/").is_err());
+        assert!(classify("nonexistent").is_err());
+        assert!(classify("").is_err());
+    }
+
+    #[test]
+    fn generate_meminfo_reports_no_heap_rather_than_fabricating_a_number() {
+        let mut buf = [0u8; 128];
+        let n = generate_meminfo(&mut buf);
+        assert!(core::str::from_utf8(&buf[..n])
+            .unwrap()
+            .starts_with("MemTotal:"));
+    }
+
+    #[test]
+    fn fixed_buf_truncates_past_capacity_instead_of_panicking() {
+        let mut storage = [0u8; 4];
+        let mut w = FixedBuf {
+            buf: &mut storage,
+            len: 0,
+        };
+        let _ = write!(w, "hello world");
+        assert_eq!(w.len, 4);
+        assert_eq!(&storage, b"hell");
+    }
+}
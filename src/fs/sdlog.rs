@@ -0,0 +1,260 @@
+//! Power-loss-safe circular log, written directly to a raw block-device region.
+//!
+//! [`log::persistent`] already survives a *warm* reboot by living in a RAM region the boot
+//! loader doesn't clear; this is the power-*loss* case that design can't cover, since RAM forgets
+//! everything the instant power drops. [`SdLog`] answers that by writing each record to its own
+//! block on a [`BlockDevice`] instead: a completed write is still readable after power returns,
+//! and [`SdLog::append`]'s sequence number plus a `crc32` over every record mean a write that was
+//! only partially committed when power dropped (a torn write) fails its checksum on readback and
+//! [`SdLog::for_each`] just skips it, rather than handing back garbage.
+//!
+//! "Wear-aware rotation" here means what it can honestly mean without a real flash translation
+//! layer underneath: records are written round-robin across every block in the region
+//! (`seq % region length`), so repeated appends spread writes across the whole region instead of
+//! hammering one sector the way appending to a single growing file would. The SD card's own
+//! controller still owns real bad-block remapping -- nothing at this layer can see individual
+//! flash cells to manage those directly.
+//!
+//! Every record is compressed with [`crate::compress`] before it's written; see that module's
+//! docs for why a fixed-window LZSS rather than a heap-hungry dictionary scheme. Its decoder needs
+//! to be told the exact original length to know where to stop -- a bit-packed stream has no
+//! self-delimiting end marker the way the old byte-oriented RLE scheme this replaced did -- so the
+//! header carries `message_len` alongside `payload_len`.
+//!
+//! There's no "polled EMMC path" to back this on real hardware: [`bcm2xxx_emmc`]'s module docs
+//! cover why there's no `BlockDevice` for the BCM2837/BCM2711 SD controller at all yet. What
+//! exists today is [`crate::bsp::device_driver::virtio::blk::VirtioBlk`], which backs
+//! `bsp_qemu_virt` -- [`SdLog`] is written generically against [`BlockDevice`] so it already works
+//! there, and will work unmodified against a real EMMC driver once one lands.
+//!
+//! [`bcm2xxx_emmc`]: crate::bsp::device_driver::bcm::bcm2xxx_emmc
+
+use crate::{
+    log::Level,
+    storage::interface::{Block, BlockDevice, BLOCK_SIZE},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// `"SDLG"`, little-endian, marking a block as a valid record rather than whatever was on the
+/// card before this region was claimed.
+const MAGIC: u32 = 0x474c_4453;
+
+/// Bytes reserved for the subsystem tag, fixed-width so every record's layout is identical
+/// regardless of how long the tag happens to be. Longer tags are truncated.
+const MAX_SUBSYSTEM_LEN: usize = 32;
+
+/// `magic(4) + seq(4) + level(1) + subsystem_len(1) + payload_len(2) + message_len(2) +
+/// checksum(4)`.
+const HEADER_LEN: usize = 18;
+
+/// Remaining room in the block after the header and the subsystem field, for the compressed
+/// message payload.
+const MAX_PAYLOAD_LEN: usize = BLOCK_SIZE - HEADER_LEN - MAX_SUBSYSTEM_LEN;
+
+/// Messages longer than this are truncated before compression, the same trade-off
+/// [`crate::log::persistent`]'s `LineBuf` makes for its own fixed line buffer. Small enough that
+/// even a fully incompressible message still fits [`MAX_PAYLOAD_LEN`] once compressed.
+const MAX_MESSAGE_LEN: usize = 128;
+
+/// One record as read back off the device, already checksum-verified.
+struct RawRecord {
+    seq: u32,
+    level: Level,
+    subsystem: [u8; MAX_SUBSYSTEM_LEN],
+    subsystem_len: usize,
+    payload: [u8; MAX_PAYLOAD_LEN],
+    payload_len: usize,
+    message_len: usize,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A power-loss-safe circular log over `num_blocks` consecutive blocks of a [`BlockDevice`],
+/// starting at `start_block`.
+pub struct SdLog<'a> {
+    device: &'a dyn BlockDevice,
+    start_block: u64,
+    num_blocks: u64,
+}
+
+impl<'a> SdLog<'a> {
+    /// Claim `num_blocks` blocks of `device`, starting at `start_block`, as the log region.
+    ///
+    /// This doesn't touch the device -- it's the caller's job to pick a region that isn't also
+    /// claimed by a partition table or filesystem, the same way [`crate::fs::partition::Partition`]
+    /// trusts its caller to pass a non-overlapping entry.
+    pub fn new(device: &'a dyn BlockDevice, start_block: u64, num_blocks: u64) -> Self {
+        Self {
+            device,
+            start_block,
+            num_blocks,
+        }
+    }
+
+    /// Append one record, truncating `message` to [`MAX_MESSAGE_LEN`] bytes if it's longer.
+    ///
+    /// Returns the sequence number the record was written with.
+    ///
+    /// Every call re-derives the next sequence number and write slot from the device itself
+    /// rather than from any in-memory cursor, so there's nothing cached in RAM that a power loss
+    /// could leave stale.
+    pub fn append(
+        &self,
+        level: Level,
+        subsystem: &str,
+        message: &str,
+    ) -> Result<u32, &'static str> {
+        if self.num_blocks == 0 {
+            return Err("sdlog: region has no blocks");
+        }
+
+        let seq = self.highest_seq()?.map_or(0, |s| s.wrapping_add(1));
+        let slot = seq as u64 % self.num_blocks;
+
+        let subsystem_bytes = subsystem.as_bytes();
+        let subsystem_len = subsystem_bytes.len().min(MAX_SUBSYSTEM_LEN);
+
+        let message_bytes = message.as_bytes();
+        let message_len = message_bytes.len().min(MAX_MESSAGE_LEN);
+
+        let mut block: Block = [0; BLOCK_SIZE];
+        block[HEADER_LEN..HEADER_LEN + subsystem_len]
+            .copy_from_slice(&subsystem_bytes[..subsystem_len]);
+        let payload_len = crate::compress::encode(
+            &message_bytes[..message_len],
+            &mut block[HEADER_LEN + MAX_SUBSYSTEM_LEN..],
+        );
+
+        block[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+        block[4..8].copy_from_slice(&seq.to_le_bytes());
+        block[8] = level.to_u8();
+        block[9] = subsystem_len as u8;
+        block[10..12].copy_from_slice(&(payload_len as u16).to_le_bytes());
+        block[12..14].copy_from_slice(&(message_len as u16).to_le_bytes());
+        let checksum = crate::crypto::hash::crc32(&block[HEADER_LEN..]);
+        block[14..18].copy_from_slice(&checksum.to_le_bytes());
+
+        self.device.write_block(self.start_block + slot, &block)?;
+        Ok(seq)
+    }
+
+    /// Call `f` with the sequence number, level, subsystem, and message of every valid record
+    /// currently in the region, oldest first.
+    ///
+    /// Runs in `O(records^2)` device reads: with no heap to collect and sort records into,
+    /// recovery instead re-scans the whole region once per distinct sequence number in range.
+    /// Fine for a region sized for a boot log and a command that only runs on demand; not a
+    /// pattern to reuse for a hot path.
+    pub fn for_each(&self, mut f: impl FnMut(u32, Level, &str, &str)) -> Result<(), &'static str> {
+        let mut min_seq = None;
+        let mut max_seq = None;
+
+        for slot in 0..self.num_blocks {
+            if let Some(record) = self.read_raw(slot)? {
+                min_seq = Some(min_seq.map_or(record.seq, |m: u32| m.min(record.seq)));
+                max_seq = Some(max_seq.map_or(record.seq, |m: u32| m.max(record.seq)));
+            }
+        }
+
+        let (Some(min_seq), Some(max_seq)) = (min_seq, max_seq) else {
+            return Ok(());
+        };
+
+        for seq in min_seq..=max_seq {
+            for slot in 0..self.num_blocks {
+                let Some(record) = self.read_raw(slot)? else {
+                    continue;
+                };
+                if record.seq != seq {
+                    continue;
+                }
+
+                let subsystem =
+                    core::str::from_utf8(&record.subsystem[..record.subsystem_len]).unwrap_or("?");
+
+                let mut message = [0u8; MAX_MESSAGE_LEN];
+                let message = match crate::compress::decode(
+                    &record.payload[..record.payload_len],
+                    &mut message[..record.message_len],
+                ) {
+                    Ok(()) => core::str::from_utf8(&message[..record.message_len]).unwrap_or("?"),
+                    Err(_) => "?",
+                };
+
+                f(record.seq, record.level, subsystem, message);
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+impl SdLog<'_> {
+    /// The highest sequence number currently present in the region, or `None` if it holds no
+    /// valid records.
+    fn highest_seq(&self) -> Result<Option<u32>, &'static str> {
+        let mut max = None;
+
+        for slot in 0..self.num_blocks {
+            if let Some(record) = self.read_raw(slot)? {
+                max = Some(max.map_or(record.seq, |m: u32| m.max(record.seq)));
+            }
+        }
+
+        Ok(max)
+    }
+
+    /// Read and checksum-verify the block at `slot`, returning `None` if it isn't a valid record
+    /// -- either never written, or a torn write a power loss interrupted partway through.
+    fn read_raw(&self, slot: u64) -> Result<Option<RawRecord>, &'static str> {
+        let mut block: Block = [0; BLOCK_SIZE];
+        self.device
+            .read_block(self.start_block + slot, &mut block)?;
+
+        if u32::from_le_bytes(block[0..4].try_into().unwrap()) != MAGIC {
+            return Ok(None);
+        }
+
+        let Some(level) = Level::from_u8(block[8]) else {
+            return Ok(None);
+        };
+
+        let checksum = u32::from_le_bytes(block[14..18].try_into().unwrap());
+        if checksum != crate::crypto::hash::crc32(&block[HEADER_LEN..]) {
+            return Ok(None);
+        }
+
+        let seq = u32::from_le_bytes(block[4..8].try_into().unwrap());
+        let subsystem_len = (block[9] as usize).min(MAX_SUBSYSTEM_LEN);
+        let payload_len =
+            (u16::from_le_bytes(block[10..12].try_into().unwrap()) as usize).min(MAX_PAYLOAD_LEN);
+        let message_len =
+            (u16::from_le_bytes(block[12..14].try_into().unwrap()) as usize).min(MAX_MESSAGE_LEN);
+
+        let mut subsystem = [0u8; MAX_SUBSYSTEM_LEN];
+        subsystem.copy_from_slice(&block[HEADER_LEN..HEADER_LEN + MAX_SUBSYSTEM_LEN]);
+
+        let mut payload = [0u8; MAX_PAYLOAD_LEN];
+        payload.copy_from_slice(&block[HEADER_LEN + MAX_SUBSYSTEM_LEN..]);
+
+        Ok(Some(RawRecord {
+            seq,
+            level,
+            subsystem,
+            subsystem_len,
+            payload,
+            payload_len,
+            message_len,
+        }))
+    }
+}
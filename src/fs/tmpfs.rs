@@ -0,0 +1,488 @@
+//! In-memory scratch filesystem ("tmpfs").
+//!
+//! A real implementation would run against a generic VFS trait, grow arbitrarily off the
+//! heap/frame allocator, and be reachable at `/tmp` through a mount table that routes a path
+//! prefix to the right concrete filesystem. None of that exists in this fork: `crate::fs::fat32`
+//! and `crate::fs::partition` are concrete types callers reach directly, not implementations of
+//! a shared trait, so there's no trait for this module to implement either; there's no mount
+//! table to route a `/tmp` prefix through; and this is a `#![no_std]`, no-alloc kernel (see
+//! [`crate::process`] and [`crate::kmod`] for the same limitation elsewhere), so there's no heap
+//! to back an arbitrarily large namespace.
+//!
+//! What's built here instead is a real, self-contained scratch namespace with directories,
+//! rename, and unlink, at a fixed compile-time capacity -- the same trade-off
+//! [`crate::kmod`]'s symbol table and [`crate::ipc::Channel`] make for the same reason. Callers
+//! reach it directly through this module's free functions and [`File`], the way they already
+//! reach `fat32` directly; a future VFS would be what gives this the `/tmp` mount point the
+//! request asked for.
+//!
+//! Unlike [`crate::fs::fat32`] and [`crate::fs::partition`], this module has no `BlockDevice` to
+//! stand up a fixture for -- the one [`TMPFS`] singleton *is* the storage -- so its tests reset
+//! that singleton directly (see the `tests` module's own `reset`) the same way
+//! [`crate::profiler`]'s tests reset its static `PROFILER` between cases. It's mirrored into
+//! `kernel_pure` (see `src/lib.rs`) since it depends on nothing but
+//! [`crate::synchronization::NullLock`].
+
+use crate::synchronization::{Mutex, NullLock};
+
+/// Maximum number of files and directories tmpfs can hold at once, including the root directory,
+/// fixed at compile time since this kernel has no heap.
+const MAX_NODES: usize = 32;
+/// Maximum length of a single path segment (a file or directory name).
+const MAX_NAME_LEN: usize = 32;
+/// Maximum size of a single file's contents.
+const MAX_FILE_SIZE: usize = 512;
+/// The root directory always lives at this index.
+const ROOT: usize = 0;
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Directory,
+    File,
+}
+
+#[derive(Clone, Copy)]
+struct Node {
+    kind: Kind,
+    name: [u8; MAX_NAME_LEN],
+    name_len: u8,
+    parent: Option<usize>,
+    data: [u8; MAX_FILE_SIZE],
+    len: usize,
+}
+
+impl Node {
+    const fn root() -> Self {
+        Self {
+            kind: Kind::Directory,
+            name: [0; MAX_NAME_LEN],
+            name_len: 0,
+            parent: None,
+            data: [0; MAX_FILE_SIZE],
+            len: 0,
+        }
+    }
+
+    fn name(&self) -> &str {
+        core::str::from_utf8(&self.name[..self.name_len as usize]).unwrap_or("")
+    }
+}
+
+struct Inner {
+    nodes: [Option<Node>; MAX_NODES],
+}
+
+impl Inner {
+    const fn new() -> Self {
+        let mut nodes = [None; MAX_NODES];
+        nodes[ROOT] = Some(Node::root());
+        Self { nodes }
+    }
+
+    fn node(&self, index: usize) -> Result<&Node, &'static str> {
+        self.nodes[index]
+            .as_ref()
+            .ok_or("tmpfs: node no longer exists")
+    }
+
+    fn node_mut(&mut self, index: usize) -> Result<&mut Node, &'static str> {
+        self.nodes[index]
+            .as_mut()
+            .ok_or("tmpfs: node no longer exists")
+    }
+
+    fn find_child(&self, parent: usize, name: &str) -> Option<usize> {
+        self.nodes.iter().position(|slot| match slot {
+            Some(node) => node.parent == Some(parent) && node.name() == name,
+            None => false,
+        })
+    }
+
+    fn resolve(&self, path: &str) -> Option<usize> {
+        let mut current = ROOT;
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            current = self.find_child(current, segment)?;
+        }
+        Some(current)
+    }
+
+    fn has_children(&self, node: usize) -> bool {
+        self.nodes
+            .iter()
+            .any(|slot| matches!(slot, Some(n) if n.parent == Some(node)))
+    }
+
+    fn alloc_node(&mut self, parent: usize, name: &str, kind: Kind) -> Result<usize, &'static str> {
+        if name.is_empty() || name.len() > MAX_NAME_LEN || name.contains('/') {
+            return Err("tmpfs: invalid name");
+        }
+        if self.find_child(parent, name).is_some() {
+            return Err("tmpfs: name already exists");
+        }
+
+        let slot = self
+            .nodes
+            .iter()
+            .position(Option::is_none)
+            .ok_or("tmpfs: out of node slots")?;
+
+        let mut packed_name = [0u8; MAX_NAME_LEN];
+        packed_name[..name.len()].copy_from_slice(name.as_bytes());
+
+        self.nodes[slot] = Some(Node {
+            kind,
+            name: packed_name,
+            name_len: name.len() as u8,
+            parent: Some(parent),
+            data: [0; MAX_FILE_SIZE],
+            len: 0,
+        });
+
+        Ok(slot)
+    }
+
+    fn mkdir(&mut self, path: &str) -> Result<(), &'static str> {
+        let (parent_path, name) = split_parent(path);
+        let parent = self
+            .resolve(parent_path)
+            .ok_or("tmpfs: parent directory does not exist")?;
+        if self.node(parent)?.kind != Kind::Directory {
+            return Err("tmpfs: parent is not a directory");
+        }
+        self.alloc_node(parent, name, Kind::Directory).map(|_| ())
+    }
+
+    fn rmdir(&mut self, path: &str) -> Result<(), &'static str> {
+        let node = self.resolve(path).ok_or("tmpfs: no such directory")?;
+        if node == ROOT {
+            return Err("tmpfs: cannot remove the root directory");
+        }
+        if self.node(node)?.kind != Kind::Directory {
+            return Err("tmpfs: not a directory");
+        }
+        if self.has_children(node) {
+            return Err("tmpfs: directory not empty");
+        }
+        self.nodes[node] = None;
+        Ok(())
+    }
+
+    fn unlink(&mut self, path: &str) -> Result<(), &'static str> {
+        let node = self.resolve(path).ok_or("tmpfs: no such file")?;
+        if self.node(node)?.kind != Kind::File {
+            return Err("tmpfs: not a file");
+        }
+        self.nodes[node] = None;
+        Ok(())
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), &'static str> {
+        let node = self
+            .resolve(from)
+            .ok_or("tmpfs: no such file or directory")?;
+        if node == ROOT {
+            return Err("tmpfs: cannot rename the root directory");
+        }
+
+        let (to_parent_path, to_name) = split_parent(to);
+        if to_name.is_empty() || to_name.len() > MAX_NAME_LEN || to_name.contains('/') {
+            return Err("tmpfs: invalid destination name");
+        }
+
+        let to_parent = self
+            .resolve(to_parent_path)
+            .ok_or("tmpfs: destination parent directory does not exist")?;
+        if self.find_child(to_parent, to_name).is_some() {
+            return Err("tmpfs: destination already exists");
+        }
+
+        let mut packed_name = [0u8; MAX_NAME_LEN];
+        packed_name[..to_name.len()].copy_from_slice(to_name.as_bytes());
+
+        let entry = self.node_mut(node)?;
+        entry.parent = Some(to_parent);
+        entry.name = packed_name;
+        entry.name_len = to_name.len() as u8;
+
+        Ok(())
+    }
+}
+
+/// Split `path` into its parent directory's path and its final segment (the name being
+/// created, removed, or renamed).
+fn split_parent(path: &str) -> (&str, &str) {
+    match path.trim_matches('/').rsplit_once('/') {
+        Some((parent, name)) => (parent, name),
+        None => ("", path.trim_matches('/')),
+    }
+}
+
+static TMPFS: NullLock<Inner> = NullLock::new(Inner::new());
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Create a new, empty directory at `path`. The parent directory must already exist.
+pub fn mkdir(path: &str) -> Result<(), &'static str> {
+    TMPFS.lock(|fs| fs.mkdir(path))
+}
+
+/// Remove the empty directory at `path`.
+pub fn rmdir(path: &str) -> Result<(), &'static str> {
+    TMPFS.lock(|fs| fs.rmdir(path))
+}
+
+/// Remove the file at `path`.
+pub fn unlink(path: &str) -> Result<(), &'static str> {
+    TMPFS.lock(|fs| fs.unlink(path))
+}
+
+/// Move (and optionally rename) the file or directory at `from` to `to`. `to`'s parent directory
+/// must already exist, and nothing may already exist at `to`.
+pub fn rename(from: &str, to: &str) -> Result<(), &'static str> {
+    TMPFS.lock(|fs| fs.rename(from, to))
+}
+
+/// An open tmpfs file handle.
+pub struct File {
+    node: usize,
+    position: usize,
+}
+
+impl File {
+    /// Open the existing file at `path`.
+    pub fn open(path: &str) -> Result<Self, &'static str> {
+        TMPFS.lock(|fs| {
+            let node = fs.resolve(path).ok_or("tmpfs: no such file")?;
+            if fs.node(node)?.kind != Kind::File {
+                return Err("tmpfs: not a file");
+            }
+            Ok(Self { node, position: 0 })
+        })
+    }
+
+    /// Create a new, empty file at `path`. The parent directory must already exist.
+    pub fn create(path: &str) -> Result<Self, &'static str> {
+        let (parent_path, name) = split_parent(path);
+        TMPFS.lock(|fs| {
+            let parent = fs
+                .resolve(parent_path)
+                .ok_or("tmpfs: parent directory does not exist")?;
+            if fs.node(parent)?.kind != Kind::Directory {
+                return Err("tmpfs: parent is not a directory");
+            }
+            let node = fs.alloc_node(parent, name, Kind::File)?;
+            Ok(Self { node, position: 0 })
+        })
+    }
+
+    /// The file's current size, in bytes.
+    pub fn size(&self) -> usize {
+        TMPFS.lock(|fs| fs.node(self.node).map_or(0, |n| n.len))
+    }
+
+    /// Move the read/write position to `position`, clamped to the file's current size.
+    pub fn seek(&mut self, position: usize) {
+        self.position = position.min(self.size());
+    }
+
+    /// Move the read/write position to the file's current end, for appending.
+    pub fn seek_to_end(&mut self) {
+        self.position = self.size();
+    }
+
+    /// Read up to `buf.len()` bytes starting at the current position, returning the number of
+    /// bytes actually read (fewer than requested at end-of-file).
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, &'static str> {
+        TMPFS.lock(|fs| {
+            let entry = fs.node(self.node)?;
+            let n = buf.len().min(entry.len.saturating_sub(self.position));
+            buf[..n].copy_from_slice(&entry.data[self.position..self.position + n]);
+            self.position += n;
+            Ok(n)
+        })
+    }
+
+    /// Write `buf` at the current position, growing the file (up to tmpfs's fixed per-file
+    /// capacity) if writing runs past its current end.
+    pub fn write(&mut self, buf: &[u8]) -> Result<usize, &'static str> {
+        if self.position + buf.len() > MAX_FILE_SIZE {
+            return Err("tmpfs: write would exceed the fixed per-file capacity");
+        }
+
+        TMPFS.lock(|fs| {
+            let entry = fs.node_mut(self.node)?;
+            entry.data[self.position..self.position + buf.len()].copy_from_slice(buf);
+            entry.len = entry.len.max(self.position + buf.len());
+            Ok(())
+        })?;
+
+        self.position += buf.len();
+        Ok(buf.len())
+    }
+
+    /// Move to the end of the file and write `buf` there.
+    pub fn append(&mut self, buf: &[u8]) -> Result<usize, &'static str> {
+        self.seek_to_end();
+        self.write(buf)
+    }
+
+    /// Shrink or grow the file to exactly `new_size` bytes. Growing zero-fills the new region.
+    pub fn truncate(&mut self, new_size: usize) -> Result<(), &'static str> {
+        if new_size > MAX_FILE_SIZE {
+            return Err("tmpfs: size exceeds the fixed per-file capacity");
+        }
+
+        TMPFS.lock(|fs| {
+            let entry = fs.node_mut(self.node)?;
+            if new_size > entry.len {
+                entry.data[entry.len..new_size].fill(0);
+            }
+            entry.len = new_size;
+            Ok(())
+        })?;
+
+        self.position = self.position.min(new_size);
+        Ok(())
+    }
+
+    /// Delete the file: remove its directory entry, freeing its slot for reuse.
+    pub fn delete(self) -> Result<(), &'static str> {
+        TMPFS.lock(|fs| {
+            fs.nodes[self.node] = None;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Held by each test of this module, since the test harness otherwise runs them on parallel
+    /// threads against the one shared [`TMPFS`].
+    static TEST_SERIAL: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Reset the one process-wide [`TMPFS`] back to an empty root, so each test starts from a
+    /// known state despite sharing it with every other test in this module. The returned guard
+    /// keeps this module's other tests out until the calling test ends.
+    fn reset() -> std::sync::MutexGuard<'static, ()> {
+        let guard = TEST_SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        TMPFS.lock(|fs| *fs = Inner::new());
+        guard
+    }
+
+    #[test]
+    fn mkdir_rejects_a_missing_parent_and_succeeds_once_it_exists() {
+        let _guard = reset();
+        assert!(mkdir("/a/b").is_err());
+        mkdir("/a").unwrap();
+        mkdir("/a/b").unwrap();
+        assert!(mkdir("/a/b").is_err(), "name already exists");
+    }
+
+    #[test]
+    fn file_create_write_read_roundtrip() {
+        let _guard = reset();
+        mkdir("/docs").unwrap();
+
+        let mut file = File::create("/docs/readme").unwrap();
+        assert_eq!(file.write(b"hello").unwrap(), 5);
+        assert_eq!(file.size(), 5);
+
+        file.seek(0);
+        let mut buf = [0u8; 5];
+        assert_eq!(file.read(&mut buf).unwrap(), 5);
+        assert_eq!(&buf, b"hello");
+
+        let mut reopened = File::open("/docs/readme").unwrap();
+        let mut buf = [0u8; 5];
+        assert_eq!(reopened.read(&mut buf).unwrap(), 5);
+        assert_eq!(&buf, b"hello");
+    }
+
+    #[test]
+    fn append_writes_past_the_current_end() {
+        let _guard = reset();
+        let mut file = File::create("/appended").unwrap();
+        file.write(b"foo").unwrap();
+        file.append(b"bar").unwrap();
+
+        assert_eq!(file.size(), 6);
+        file.seek(0);
+        let mut buf = [0u8; 6];
+        file.read(&mut buf).unwrap();
+        assert_eq!(&buf, b"foobar");
+    }
+
+    #[test]
+    fn write_past_capacity_is_rejected() {
+        let _guard = reset();
+        let mut file = File::create("/toobig").unwrap();
+        let chunk = [0u8; MAX_FILE_SIZE];
+        assert!(file.write(&chunk).is_ok());
+        assert!(file.write(&[0u8; 1]).is_err());
+    }
+
+    #[test]
+    fn truncate_shrinks_and_zero_fills_on_grow() {
+        let _guard = reset();
+        let mut file = File::create("/truncated").unwrap();
+        file.write(b"hello").unwrap();
+
+        file.truncate(2).unwrap();
+        assert_eq!(file.size(), 2);
+
+        file.truncate(4).unwrap();
+        assert_eq!(file.size(), 4);
+        file.seek(0);
+        let mut buf = [0u8; 4];
+        file.read(&mut buf).unwrap();
+        assert_eq!(&buf, &[b'h', b'e', 0, 0]);
+    }
+
+    #[test]
+    fn rename_moves_and_renames_a_file() {
+        let _guard = reset();
+        mkdir("/src").unwrap();
+        mkdir("/dst").unwrap();
+        File::create("/src/a").unwrap();
+
+        rename("/src/a", "/dst/b").unwrap();
+
+        assert!(File::open("/src/a").is_err());
+        assert!(File::open("/dst/b").is_ok());
+    }
+
+    #[test]
+    fn rmdir_refuses_a_nonempty_directory_and_succeeds_once_empty() {
+        let _guard = reset();
+        mkdir("/nonempty").unwrap();
+        File::create("/nonempty/f").unwrap();
+
+        assert!(rmdir("/nonempty").is_err());
+
+        unlink("/nonempty/f").unwrap();
+        rmdir("/nonempty").unwrap();
+        assert!(mkdir("/nonempty").is_ok(), "slot should be free for reuse");
+    }
+
+    #[test]
+    fn unlink_removes_a_file_but_not_a_directory() {
+        let _guard = reset();
+        mkdir("/dir").unwrap();
+        assert!(
+            unlink("/dir").is_err(),
+            "unlink is for files, not directories"
+        );
+
+        File::create("/file").unwrap();
+        unlink("/file").unwrap();
+        assert!(File::open("/file").is_err());
+    }
+}
@@ -0,0 +1,196 @@
+//! Simple 2D drawing primitives over a linear 32bpp pixel buffer.
+//!
+//! Everything here is generic over [`Surface`], so it works equally on the real
+//! [`framebuffer`](crate::framebuffer)'s back buffer and, in principle, any other buffer a caller
+//! hands in.
+
+/// A drawable linear 32bpp (0xRRGGBB, one word per pixel) pixel buffer.
+pub trait Surface {
+    /// Width, in pixels.
+    fn width(&self) -> u32;
+
+    /// Height, in pixels.
+    fn height(&self) -> u32;
+
+    /// Row stride, in bytes. May exceed `width() * 4` due to hardware alignment padding.
+    fn pitch(&self) -> u32;
+
+    /// A pointer to the first pixel.
+    ///
+    /// # Safety
+    ///
+    /// - The buffer must be valid for reads and writes for `pitch() * height()` bytes for as
+    ///   long as the returned pointer is used.
+    unsafe fn pixels_mut(&self) -> *mut u32;
+
+    /// Write a single pixel. No-op if `(x, y)` falls outside the surface.
+    fn put_pixel(&self, x: u32, y: u32, color: u32) {
+        if x >= self.width() || y >= self.height() {
+            return;
+        }
+
+        let stride_words = self.pitch() / 4;
+        let offset = (y * stride_words + x) as isize;
+
+        // SAFETY: `offset` is within the surface's `pitch() * height()` extent, checked above.
+        unsafe { self.pixels_mut().offset(offset).write_volatile(color) };
+    }
+}
+
+/// Fill a rectangle with a solid color.
+pub fn fill_rect(surface: &impl Surface, x: u32, y: u32, width: u32, height: u32, color: u32) {
+    for row in y..(y + height) {
+        for col in x..(x + width) {
+            surface.put_pixel(col, row, color);
+        }
+    }
+}
+
+/// Copy a `src_width` x `src_height` region of packed 32bpp pixels from `src` onto `surface` at
+/// `(x, y)`.
+pub fn blit(surface: &impl Surface, x: u32, y: u32, src_width: u32, src_height: u32, src: &[u32]) {
+    for row in 0..src_height {
+        for col in 0..src_width {
+            let i = (row * src_width + col) as usize;
+            if let Some(&color) = src.get(i) {
+                surface.put_pixel(x + col, y + row, color);
+            }
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Text
+//--------------------------------------------------------------------------------------------------
+
+/// Glyph cell size, in pixels, used by [`draw_text`].
+pub(crate) const GLYPH_WIDTH: u32 = 5;
+pub(crate) const GLYPH_HEIGHT: u32 = 7;
+pub(crate) const GLYPH_ADVANCE: u32 = GLYPH_WIDTH + 1;
+
+/// Segments of a classic seven-segment display, indexed as: top, top-left, top-right, middle,
+/// bottom-left, bottom-right, bottom. `true` means "lit" for the given digit.
+const DIGIT_SEGMENTS: [[bool; 7]; 10] = [
+    [true, true, true, false, true, true, true],     // 0
+    [false, false, true, false, false, true, false], // 1
+    [true, false, true, true, true, false, true],    // 2
+    [true, false, true, true, false, true, true],    // 3
+    [false, true, true, true, false, true, false],   // 4
+    [true, true, false, true, false, true, true],    // 5
+    [true, true, false, true, true, true, true],     // 6
+    [true, false, true, false, false, true, false],  // 7
+    [true, true, true, true, true, true, true],      // 8
+    [true, true, true, true, false, true, true],     // 9
+];
+
+/// Draw one digit's segments into `surface` at `(x, y)`, `GLYPH_WIDTH` x `GLYPH_HEIGHT` pixels.
+fn draw_digit(surface: &impl Surface, x: u32, y: u32, digit: u8, color: u32) {
+    let segments = DIGIT_SEGMENTS[digit as usize];
+    let mid_y = y + GLYPH_HEIGHT / 2;
+    let bottom_y = y + GLYPH_HEIGHT - 1;
+    let right_x = x + GLYPH_WIDTH - 1;
+
+    if segments[0] {
+        fill_rect(surface, x, y, GLYPH_WIDTH, 1, color);
+    }
+    if segments[1] {
+        fill_rect(surface, x, y, 1, GLYPH_HEIGHT / 2 + 1, color);
+    }
+    if segments[2] {
+        fill_rect(surface, right_x, y, 1, GLYPH_HEIGHT / 2 + 1, color);
+    }
+    if segments[3] {
+        fill_rect(surface, x, mid_y, GLYPH_WIDTH, 1, color);
+    }
+    if segments[4] {
+        fill_rect(surface, x, mid_y, 1, GLYPH_HEIGHT / 2 + 1, color);
+    }
+    if segments[5] {
+        fill_rect(surface, right_x, mid_y, 1, GLYPH_HEIGHT / 2 + 1, color);
+    }
+    if segments[6] {
+        fill_rect(surface, x, bottom_y, GLYPH_WIDTH, 1, color);
+    }
+}
+
+/// Which sides of a box-drawing cell a line segment reaches out to from its center. Unlike a
+/// memorized bitmap, this is mechanically derivable from the Unicode block's own naming (a glyph
+/// named "box drawings light up and right" reaches up and right) and cheap to double check, so
+/// it's drawn geometrically instead of guessed at as pixel data.
+struct BoxSides {
+    up: bool,
+    down: bool,
+    left: bool,
+    right: bool,
+}
+
+/// Map a light single-line box-drawing code point (`U+2500` horizontal through `U+253C` cross) to
+/// the sides it connects, if it's one of the eleven this fork knows how to draw.
+fn box_sides(c: char) -> Option<BoxSides> {
+    let (up, down, left, right) = match c {
+        '\u{2500}' => (false, false, true, true), // ─
+        '\u{2502}' => (true, true, false, false), // │
+        '\u{250c}' => (false, true, false, true), // ┌
+        '\u{2510}' => (false, true, true, false), // ┐
+        '\u{2514}' => (true, false, false, true), // └
+        '\u{2518}' => (true, false, true, false), // ┘
+        '\u{251c}' => (true, true, false, true),  // ├
+        '\u{2524}' => (true, true, true, false),  // ┤
+        '\u{252c}' => (false, true, true, true),  // ┬
+        '\u{2534}' => (true, false, true, true),  // ┴
+        '\u{253c}' => (true, true, true, true),   // ┼
+        _ => return None,
+    };
+
+    Some(BoxSides {
+        up,
+        down,
+        left,
+        right,
+    })
+}
+
+/// Draw one box-drawing glyph's line segments into `surface` at `(x, y)`, `GLYPH_WIDTH` x
+/// `GLYPH_HEIGHT` pixels, as a single-pixel-wide line from each reached side to the cell's center.
+fn draw_box(surface: &impl Surface, x: u32, y: u32, sides: &BoxSides, color: u32) {
+    let mid_x = x + GLYPH_WIDTH / 2;
+    let mid_y = y + GLYPH_HEIGHT / 2;
+
+    if sides.up {
+        fill_rect(surface, mid_x, y, 1, mid_y - y + 1, color);
+    }
+    if sides.down {
+        fill_rect(surface, mid_x, mid_y, 1, y + GLYPH_HEIGHT - mid_y, color);
+    }
+    if sides.left {
+        fill_rect(surface, x, mid_y, mid_x - x + 1, 1, color);
+    }
+    if sides.right {
+        fill_rect(surface, mid_x, mid_y, x + GLYPH_WIDTH - mid_x, 1, color);
+    }
+}
+
+/// Draw `text` at `(x, y)`, one [`GLYPH_WIDTH`]x[`GLYPH_HEIGHT`] cell per character, advancing
+/// [`GLYPH_ADVANCE`] pixels per character.
+///
+/// Digits `0`-`9` render as real seven-segment glyphs, and the eleven light single-line
+/// box-drawing characters [`box_sides`] knows (the ones a shell UI draws boxes and tables with)
+/// render as real line segments. This fork still has no verified bitmap font table for the rest of
+/// Unicode -- hand-transcribing one from memory risks silently rendering garbage that's hard to
+/// notice without a display to check it against -- so every other character, including space and
+/// every letter, renders as a solid replacement-glyph block: honestly wrong instead of subtly
+/// wrong, the same spirit as [`char::REPLACEMENT_CHARACTER`] for text that failed to decode in the
+/// first place.
+pub fn draw_text(surface: &impl Surface, x: u32, y: u32, text: &str, color: u32) {
+    for (i, c) in text.chars().enumerate() {
+        let cell_x = x + i as u32 * GLYPH_ADVANCE;
+
+        if let Some(digit) = c.to_digit(10) {
+            draw_digit(surface, cell_x, y, digit as u8, color);
+        } else if let Some(sides) = box_sides(c) {
+            draw_box(surface, cell_x, y, &sides, color);
+        } else {
+            fill_rect(surface, cell_x, y, GLYPH_WIDTH, GLYPH_HEIGHT, color);
+        }
+    }
+}
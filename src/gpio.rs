@@ -0,0 +1,99 @@
+//! GPIO pin access.
+//!
+//! Only meaningful on real Raspberry Pi hardware -- QEMU's `virt` machine emulates no BCM GPIO
+//! controller to back this, so this module only exists under `bsp_rpi3`/`bsp_rpi4`. See
+//! `bsp::device_driver::Gpio` for the driver itself.
+
+use crate::{bsp, pinctrl};
+
+pub mod interface {
+    /// Operations a GPIO controller must implement, addressed by BCM pin number.
+    pub trait Controller {
+        /// Configure `pin` as a digital output.
+        fn set_output(&self, pin: u32);
+
+        /// Configure `pin` as a digital input.
+        fn set_input(&self, pin: u32);
+
+        /// Route `pin` to alternate function `alt` (0-5), the BCM's own `ALTn` numbering.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `alt` isn't 0-5.
+        fn set_alt(&self, pin: u32, alt: u32);
+
+        /// Drive `pin` high. No effect unless `pin` is configured as an output.
+        fn set_high(&self, pin: u32);
+
+        /// Drive `pin` low. No effect unless `pin` is configured as an output.
+        fn set_low(&self, pin: u32);
+
+        /// Read `pin`'s current level.
+        fn is_high(&self, pin: u32) -> bool;
+    }
+}
+
+/// A handle to a single GPIO pin, addressed by its BCM pin number.
+#[derive(Copy, Clone)]
+pub struct Pin {
+    controller: &'static dyn interface::Controller,
+    number: u32,
+}
+
+impl Pin {
+    /// Configure this pin as a digital output.
+    pub fn set_output(&self) {
+        self.controller.set_output(self.number);
+    }
+
+    /// Configure this pin as a digital input.
+    pub fn set_input(&self) {
+        self.controller.set_input(self.number);
+    }
+
+    /// Route this pin to alternate function `alt` (0-5).
+    pub fn set_alt(&self, alt: u32) {
+        self.controller.set_alt(self.number, alt);
+    }
+
+    /// Drive this pin high. No effect unless it's configured as an output.
+    pub fn set_high(&self) {
+        self.controller.set_high(self.number);
+    }
+
+    /// Drive this pin low. No effect unless it's configured as an output.
+    pub fn set_low(&self) {
+        self.controller.set_low(self.number);
+    }
+
+    /// Read this pin's current level.
+    pub fn is_high(&self) -> bool {
+        self.controller.is_high(self.number)
+    }
+
+    /// Switch to output mode and drive low -- the "assert" half of an open-drain line with an
+    /// external pull-up, as one-wire/DHT-style sensor protocols use.
+    pub fn drive_low(&self) {
+        self.set_output();
+        self.set_low();
+    }
+
+    /// Switch to input mode, releasing the line so an external pull-up can bring it high -- the
+    /// "release" half of an open-drain line.
+    pub fn release(&self) {
+        self.set_input();
+    }
+}
+
+/// Return a handle to the board's GPIO pin `number` (BCM numbering, not header pin numbering),
+/// claiming it for `function` on behalf of `owner`.
+///
+/// See [`pinctrl::claim`] for what happens if `number` is already claimed by a different owner.
+pub fn pin(number: u32, function: &'static str, owner: &'static str) -> Pin {
+    pinctrl::claim(number, function, owner);
+
+    Pin {
+        controller: bsp::gpio::gpio(),
+        number,
+    }
+}
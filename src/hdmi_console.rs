@@ -0,0 +1,268 @@
+//! A framebuffer-backed scrollback console with a handful of switchable virtual terminals.
+//!
+//! Only meaningful on real Raspberry Pi hardware, for the same reason [`crate::framebuffer`] is --
+//! QEMU's `virt` machine has no VideoCore to back a framebuffer with.
+//!
+//! This sits a layer above [`crate::framebuffer`]/[`crate::gfx`], neither of which know anything
+//! about lines, wrapping, or scrollback; they just draw pixels. [`gfx::draw_text`] in particular
+//! still has no real glyphs for anything but digits 0-9 (see that module's docs), so switching on
+//! this console buys line buffering, wrapping and scrolling infrastructure, not legible text --
+//! until a real bitmap font lands, every non-digit character renders as the same placeholder
+//! block it always has.
+//!
+//! Two virtual terminals are kept: [`Vt::Klog`] mirrors what [`crate::log`] emits, fed from a hook
+//! in [`crate::log::_log`]; [`Vt::Shell`] mirrors [`crate::print`]'s output, fed from a hook in
+//! [`crate::print`]'s `write_now`. Because nothing in this fork separates "log output" from
+//! "everything else written to the console" at the byte-stream level -- log lines reach the
+//! terminal through `println!`, same as the interactive shell's echo -- `Vt::Shell` ends up
+//! showing log lines too. Feeding `Vt::Klog` straight from `_log` is what actually gives it
+//! distinct content: a clean record of structured log lines with none of the shell's echo mixed
+//! in.
+//!
+//! [`handle_key`] switches the active VT on Ctrl+Tab and scrolls on Shift+PageUp/PageDown, but
+//! nothing in this tree calls it yet: there's no live keyboard-polling loop driving
+//! [`crate::input::fill_from_console`] either, so this is the same kind of complete-but-unwired
+//! library code that module already is. A future caller that drives the UART RX path (or a USB
+//! HID driver) into [`crate::input::events`] would also want to drain those events here.
+
+use crate::{framebuffer, gfx, input, log, synchronization::NullLock};
+use core::fmt::{self, Write};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// How many lines of history each virtual terminal keeps before the oldest line is dropped.
+const SCROLLBACK_LINES: usize = 200;
+
+/// Matches `bsp::device_driver::Framebuffer`'s fixed resolution -- the framebuffer driver doesn't
+/// expose its dimensions to this layer (only [`framebuffer::back_buffer`]'s runtime
+/// [`gfx::Surface`] does), so this is duplicated here the same way the driver hardcodes it.
+const BOARD_WIDTH: u32 = 1280;
+const BOARD_HEIGHT: u32 = 720;
+
+const COLS: usize = (BOARD_WIDTH / gfx::GLYPH_ADVANCE) as usize;
+const ROWS: usize = (BOARD_HEIGHT / gfx::GLYPH_HEIGHT) as usize;
+
+/// A `fmt::Write` sink over a fixed-size stack buffer, truncating past capacity. Mirrors
+/// [`log::persistent`]'s `LineBuf`.
+struct LineBuf {
+    buf: [u8; 128],
+    len: usize,
+}
+
+impl LineBuf {
+    const fn new() -> Self {
+        Self {
+            buf: [0; 128],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+}
+
+impl fmt::Write for LineBuf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let remaining = self.buf.len() - self.len;
+        let n = s.len().min(remaining);
+
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+/// One virtual terminal's line buffer and scroll position.
+struct Terminal {
+    lines: [[u8; COLS]; SCROLLBACK_LINES],
+    lens: [usize; SCROLLBACK_LINES],
+    /// Total number of lines ever opened, including ones scrolled out of `lines`' ring.
+    count: usize,
+    cursor_col: usize,
+    /// How many lines up from the bottom the visible window currently sits.
+    scroll_offset: usize,
+}
+
+impl Terminal {
+    const fn new() -> Self {
+        Self {
+            lines: [[0; COLS]; SCROLLBACK_LINES],
+            lens: [0; SCROLLBACK_LINES],
+            count: 0,
+            cursor_col: 0,
+            scroll_offset: 0,
+        }
+    }
+
+    fn current_line_index(&self) -> usize {
+        (self.count.max(1) - 1) % SCROLLBACK_LINES
+    }
+
+    fn newline(&mut self) {
+        self.count += 1;
+        self.cursor_col = 0;
+        self.scroll_offset = 0;
+
+        let line = self.current_line_index();
+        self.lens[line] = 0;
+    }
+
+    /// Append `s` to the terminal, wrapping at [`COLS`] and opening a new line on `'\n'`.
+    fn push_str(&mut self, s: &str) {
+        if self.count == 0 {
+            self.count = 1;
+        }
+
+        for b in s.bytes() {
+            if b == b'\n' {
+                self.newline();
+                continue;
+            }
+
+            if self.cursor_col == COLS {
+                self.newline();
+            }
+
+            let line = self.current_line_index();
+            self.lines[line][self.cursor_col] = b;
+            self.cursor_col += 1;
+            self.lens[line] = self.cursor_col;
+        }
+    }
+
+    /// Move the visible window by `delta` lines; positive scrolls back into history.
+    fn scroll_by(&mut self, delta: isize) {
+        let total = self.count.min(SCROLLBACK_LINES);
+        let max_offset = total.saturating_sub(ROWS);
+        let offset = (self.scroll_offset as isize + delta).clamp(0, max_offset as isize);
+
+        self.scroll_offset = offset as usize;
+    }
+
+    /// Draw the visible window of lines into `surface`.
+    fn render(&self, surface: &impl gfx::Surface, color: u32) {
+        let total = self.count.min(SCROLLBACK_LINES);
+        let bottom = total.saturating_sub(self.scroll_offset);
+        let top = bottom.saturating_sub(ROWS);
+
+        for (row, line_number) in (top..bottom).enumerate() {
+            let oldest = self.count.saturating_sub(total);
+            let index = (oldest + line_number) % SCROLLBACK_LINES;
+            let len = self.lens[index];
+            let text = core::str::from_utf8(&self.lines[index][..len]).unwrap_or("");
+
+            gfx::draw_text(surface, 0, row as u32 * gfx::GLYPH_HEIGHT, text, color);
+        }
+    }
+}
+
+struct Consoles {
+    klog: Terminal,
+    shell: Terminal,
+    active: Vt,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Which virtual terminal is currently shown on the framebuffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Vt {
+    Klog,
+    Shell,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static CONSOLES: NullLock<Consoles> = NullLock::new(Consoles {
+    klog: Terminal::new(),
+    shell: Terminal::new(),
+    active: Vt::Klog,
+});
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Record one structured log line into the [`Vt::Klog`] terminal.
+///
+/// Meant to be called from [`log::_log`], alongside its existing call into
+/// [`log::persistent::record`].
+pub fn record_klog_line(level: log::Level, subsystem: &str, args: fmt::Arguments) {
+    use crate::synchronization::Mutex;
+
+    let mut line = LineBuf::new();
+    let _ = write!(line, "{} {}: {}\n", level.tag(), subsystem, args);
+
+    CONSOLES.lock(|consoles| consoles.klog.push_str(line.as_str()));
+}
+
+/// Record a span of raw bytes written through [`crate::print`] into the [`Vt::Shell`] terminal.
+///
+/// Meant to be called from `print`'s `write_now`, alongside its existing write to
+/// [`crate::console::console`].
+pub fn mirror_shell_bytes(args: fmt::Arguments) {
+    use crate::synchronization::Mutex;
+
+    let mut line = LineBuf::new();
+    let _ = line.write_fmt(args);
+
+    CONSOLES.lock(|consoles| consoles.shell.push_str(line.as_str()));
+}
+
+/// Handle one keyboard event: Ctrl+Tab switches the active VT, Shift+PageUp/PageDown scrolls it.
+/// Returns `true` if the event was consumed.
+///
+/// See the module docs for why nothing calls this yet.
+pub fn handle_key(event: &input::KeyEvent) -> bool {
+    use crate::synchronization::Mutex;
+
+    match (event.key, event.modifiers.ctrl, event.modifiers.shift) {
+        (input::KeySym::Tab, true, _) => {
+            CONSOLES.lock(|consoles| {
+                consoles.active = match consoles.active {
+                    Vt::Klog => Vt::Shell,
+                    Vt::Shell => Vt::Klog,
+                }
+            });
+            true
+        }
+        (input::KeySym::PageUp, _, true) => {
+            CONSOLES.lock(|consoles| active_terminal(consoles).scroll_by(ROWS as isize));
+            true
+        }
+        (input::KeySym::PageDown, _, true) => {
+            CONSOLES.lock(|consoles| active_terminal(consoles).scroll_by(-(ROWS as isize)));
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Render the active VT to the framebuffer's back buffer and flip it into view.
+pub fn redraw() -> Result<(), &'static str> {
+    use crate::synchronization::Mutex;
+
+    let surface = framebuffer::back_buffer().ok_or("hdmi_console: framebuffer not initialized")?;
+
+    CONSOLES.lock(|consoles| active_terminal(consoles).render(&surface, 0x00ff_ffff));
+
+    framebuffer::flip()
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+fn active_terminal(consoles: &mut Consoles) -> &mut Terminal {
+    match consoles.active {
+        Vt::Klog => &mut consoles.klog,
+        Vt::Shell => &mut consoles.shell,
+    }
+}
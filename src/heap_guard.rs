@@ -0,0 +1,122 @@
+//! Guard-byte and poison checking for fixed-size buffers, the closest this fork can get to a
+//! heap allocator's redzones and use-after-free poisoning.
+//!
+//! The request this answers wants redzones "before/after each heap allocation" -- but there is
+//! no heap here to allocate from in the first place (see [`crate::memory`]'s module docs, and
+//! every fixed-size pool in this tree -- [`crate::net::pbuf`], [`crate::fs::tmpfs`],
+//! [`crate::fs::block_cache`] -- exists specifically because there's no allocator to carve a
+//! region from dynamically). Retrofitting guard bytes around one of those pools' slots would mean
+//! reshuffling every index driver code already uses to address them, for a check that pool was
+//! never designed to need.
+//!
+//! What [`GuardedBuffer`] does instead is wrap a single fixed-size buffer -- the unit a real heap
+//! allocation would be -- in canary bytes on both sides and a poison fill on release, so driver
+//! code that wants the same overrun/use-after-free coverage a heap allocator would give it can opt
+//! a buffer in explicitly. [`DEMO`] and the shell's `heapcheck` command exercise it end to end,
+//! since there's no existing call site in this fork that owns a buffer worth wrapping yet.
+
+use crate::synchronization::{Mutex, NullLock};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Width of each redzone, in bytes. Arbitrary but wide enough that a stray single-byte overrun
+/// still lands inside the zone rather than skipping past it.
+const GUARD_LEN: usize = 8;
+
+/// Fill byte for both redzones. Chosen to not look like a plausible payload value.
+const GUARD_BYTE: u8 = 0xA5;
+
+/// Fill byte written over the payload on [`GuardedBuffer::free`], so a later write to
+/// already-freed memory shows up as "payload no longer all poison" on the next [`check`].
+///
+/// [`check`]: GuardedBuffer::check
+const POISON_BYTE: u8 = 0xDE;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// An `N`-byte buffer flanked by redzones, standing in for a single heap allocation.
+#[derive(Copy, Clone)]
+pub struct GuardedBuffer<const N: usize> {
+    before: [u8; GUARD_LEN],
+    data: [u8; N],
+    after: [u8; GUARD_LEN],
+    freed: bool,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl<const N: usize> GuardedBuffer<N> {
+    /// Create an instance, redzones armed and payload zeroed.
+    pub const fn new() -> Self {
+        Self {
+            before: [GUARD_BYTE; GUARD_LEN],
+            data: [0; N],
+            after: [GUARD_BYTE; GUARD_LEN],
+            freed: false,
+        }
+    }
+
+    /// Borrow the payload for writing, as a "use" of this allocation.
+    ///
+    /// Clears [`Self::freed`] -- writing through this borrow is what a real heap allocator's
+    /// use-after-free check is trying to catch in the first place, so re-"allocating" is the only
+    /// way back out of the freed state.
+    pub fn data_mut(&mut self) -> &mut [u8; N] {
+        self.freed = false;
+        &mut self.data
+    }
+
+    /// Release the buffer, poisoning its payload so a later write is visible to [`Self::check`].
+    pub fn free(&mut self) {
+        self.data = [POISON_BYTE; N];
+        self.freed = true;
+    }
+
+    /// Verify both redzones are intact and, if freed, that nothing has written through the
+    /// poison since.
+    pub fn check(&self) -> Result<(), &'static str> {
+        if self.before != [GUARD_BYTE; GUARD_LEN] {
+            return Err("heap_guard: redzone before buffer corrupted -- likely underrun");
+        }
+
+        if self.after != [GUARD_BYTE; GUARD_LEN] {
+            return Err("heap_guard: redzone after buffer corrupted -- likely overrun");
+        }
+
+        if self.freed && self.data.iter().any(|&b| b != POISON_BYTE) {
+            return Err("heap_guard: use-after-free -- freed buffer was written to");
+        }
+
+        Ok(())
+    }
+}
+
+/// A standing demonstration buffer for the shell's `heapcheck` command, since no existing call
+/// site in this fork owns a buffer worth wrapping yet -- see the module docs.
+static DEMO: NullLock<GuardedBuffer<64>> = NullLock::new(GuardedBuffer::new());
+
+/// Run [`GuardedBuffer::check`] against [`DEMO`], for the `heapcheck` shell command.
+pub fn check_demo() -> Result<(), &'static str> {
+    DEMO.lock(|buf| buf.check())
+}
+
+/// Deliberately stomp one byte past the end of [`DEMO`]'s payload, for `heapcheck corrupt` to
+/// demonstrate that [`check_demo`] catches it.
+pub fn corrupt_demo() {
+    DEMO.lock(|buf| buf.after[0] = 0);
+}
+
+/// Write through [`DEMO`] after freeing it, for `heapcheck uaf` to demonstrate that
+/// [`check_demo`] catches it.
+pub fn use_after_free_demo() {
+    DEMO.lock(|buf| {
+        buf.free();
+        buf.data[0] = 0x41;
+    });
+}
@@ -0,0 +1,20 @@
+//! I2C bus abstraction.
+//!
+//! There's no hardware I2C controller driver in this fork yet, so there's nothing for
+//! [`bitbang::BitBangI2c`] to be a fallback *from* -- it's the only [`interface::I2cBus`]
+//! implementation, and callers construct one directly around whichever GPIO pins their board
+//! wiring uses rather than selecting it through [`crate::bsp::raspberrypi::driver`]'s MMIO-backed
+//! driver table, which has no notion of a bus instance parameterized by arbitrary pins.
+
+pub mod bitbang;
+
+pub mod interface {
+    /// Operations an I2C master must implement.
+    pub trait I2cBus {
+        /// Write `bytes` to the device at `address` (7-bit, unshifted).
+        fn write(&self, address: u8, bytes: &[u8]) -> Result<(), &'static str>;
+
+        /// Fill `buffer` by reading from the device at `address` (7-bit, unshifted).
+        fn read(&self, address: u8, buffer: &mut [u8]) -> Result<(), &'static str>;
+    }
+}
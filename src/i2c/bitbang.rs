@@ -0,0 +1,134 @@
+//! Bit-banged I2C master over two GPIO pins.
+//!
+//! This fork has no hardware I2C controller driver to prefer over this, so it's the only backend
+//! behind [`crate::i2c::interface::I2cBus`] for now. Callers construct one directly around
+//! whichever pins their board wiring uses, the same way [`crate::sensors`] does for its
+//! bit-banged protocols.
+
+use crate::{
+    gpio,
+    i2c::interface::I2cBus,
+    time::{self, TimeManager},
+};
+use core::time::Duration;
+
+/// Half a clock period at the ~100kHz standard-mode rate this driver bit-bangs at.
+const HALF_PERIOD: Duration = Duration::from_micros(5);
+
+/// A bit-banged I2C master, using open-drain SDA/SCL lines with external pull-ups.
+pub struct BitBangI2c {
+    sda: gpio::Pin,
+    scl: gpio::Pin,
+}
+
+impl BitBangI2c {
+    /// Create a bus over `sda`/`scl`. Both are left released (input) so the external pull-ups
+    /// hold the bus idle-high between transactions.
+    pub fn new(sda: gpio::Pin, scl: gpio::Pin) -> Self {
+        sda.release();
+        scl.release();
+        Self { sda, scl }
+    }
+
+    fn half_delay(&self) {
+        time::time_manager().spin_for(HALF_PERIOD);
+    }
+
+    fn start(&self) {
+        self.sda.release();
+        self.scl.release();
+        self.half_delay();
+        self.sda.drive_low();
+        self.half_delay();
+        self.scl.drive_low();
+        self.half_delay();
+    }
+
+    fn stop(&self) {
+        self.sda.drive_low();
+        self.half_delay();
+        self.scl.release();
+        self.half_delay();
+        self.sda.release();
+        self.half_delay();
+    }
+
+    fn write_bit(&self, bit: bool) {
+        if bit {
+            self.sda.release();
+        } else {
+            self.sda.drive_low();
+        }
+        self.half_delay();
+        self.scl.release();
+        self.half_delay();
+        self.scl.drive_low();
+    }
+
+    fn read_bit(&self) -> bool {
+        self.sda.release();
+        self.half_delay();
+        self.scl.release();
+        self.half_delay();
+        let bit = self.sda.is_high();
+        self.scl.drive_low();
+        bit
+    }
+
+    /// Write a byte and return whether the device acknowledged it.
+    fn write_byte(&self, byte: u8) -> Result<(), &'static str> {
+        for i in (0..8).rev() {
+            self.write_bit((byte >> i) & 1 != 0);
+        }
+
+        if self.read_bit() {
+            Err("I2C: no ACK from device")
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Read a byte, sending an ACK if `ack` is set (i.e. more bytes are expected) or a NACK to
+    /// signal the last byte of a transfer.
+    fn read_byte(&self, ack: bool) -> u8 {
+        let mut byte = 0u8;
+        for _ in 0..8 {
+            byte = (byte << 1) | u8::from(self.read_bit());
+        }
+        self.write_bit(!ack);
+
+        byte
+    }
+}
+
+impl I2cBus for BitBangI2c {
+    fn write(&self, address: u8, bytes: &[u8]) -> Result<(), &'static str> {
+        self.start();
+
+        let result = (|| {
+            self.write_byte(address << 1)?;
+            for &byte in bytes {
+                self.write_byte(byte)?;
+            }
+            Ok(())
+        })();
+
+        self.stop();
+        result
+    }
+
+    fn read(&self, address: u8, buffer: &mut [u8]) -> Result<(), &'static str> {
+        self.start();
+
+        let result = self.write_byte((address << 1) | 1);
+        if result.is_ok() {
+            let last = buffer.len().saturating_sub(1);
+            for (i, slot) in buffer.iter_mut().enumerate() {
+                *slot = self.read_byte(i != last);
+            }
+        }
+
+        self.stop();
+        result
+    }
+}
@@ -0,0 +1,101 @@
+//! Kernel image self-describing header.
+//!
+//! A chainloader or netboot path that knows nothing about this kernel beyond "it's a flat binary"
+//! has to just copy it to a fixed address and jump, hoping the size and load address it assumed
+//! happen to be right. [`Header`] is what such a loader would read first instead: a magic number
+//! to confirm the bytes that follow really are this kernel, a version to gate future format
+//! changes, and the load address/image size/entry offset/checksum a loader needs to safely copy
+//! the image in and jump to it.
+//!
+//! What's real: [`Header::compute`] builds one from an in-memory image slice and
+//! [`Header::validate`] checks one against the bytes that follow it, using
+//! [`crate::crypto::hash::crc32`] -- the same checksum primitive
+//! [`crate::crypto::hash::verify_trailer`] already uses, just prepended instead of trailing, since
+//! a loader wants to know the size and checksum before it copies anything rather than after.
+//!
+//! What's not: stamping a computed header into the actual kernel image needs a post-link step --
+//! objcopy to flatten the ELF, then something to patch in the final size and CRC -- that this repo
+//! doesn't have yet (`Makefile`'s `KERNEL_BIN` variable names that flat-binary target but nothing
+//! currently produces it). And validating one in the field needs an actual chainloader or netboot
+//! receive path to call [`Header::validate`], neither of which exists -- see
+//! [`crate::bootselect`] for the same "real data structure, nothing downstream consumes it yet"
+//! situation.
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// `"KIMG"`, little-endian, as the first four bytes of a header.
+pub const MAGIC: u32 = 0x474d_494b;
+
+/// The header format version this module reads and writes. Bump on any incompatible field change.
+pub const VERSION: u16 = 1;
+
+/// A kernel image's self-describing header.
+///
+/// `#[repr(C)]` and every field a fixed-width integer, so this has a stable, loader-readable
+/// binary layout: 28 bytes of fields, then 4 bytes of tail padding that round the size up to
+/// `load_addr`'s 8-byte alignment, 32 bytes in all.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// Always [`MAGIC`]; how a loader confirms it's actually looking at one of these.
+    pub magic: u32,
+    /// [`VERSION`] of the format this header was written in.
+    pub version: u16,
+    /// Reserved for alignment; always zero.
+    pub _reserved: u16,
+    /// Physical address this image expects to be loaded at.
+    pub load_addr: u64,
+    /// Size, in bytes, of the image that follows this header (not including the header itself).
+    pub image_size: u32,
+    /// Byte offset from `load_addr` (after the header) to the entry point to jump to.
+    pub entry_offset: u32,
+    /// `crc32` of the `image_size` bytes that follow this header.
+    pub checksum: u32,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl Header {
+    /// Build a header describing `image`, to be loaded at `load_addr` with its entry point
+    /// `entry_offset` bytes in.
+    pub fn compute(image: &[u8], load_addr: u64, entry_offset: u32) -> Self {
+        Self {
+            magic: MAGIC,
+            version: VERSION,
+            _reserved: 0,
+            load_addr,
+            image_size: image.len() as u32,
+            entry_offset,
+            checksum: crate::crypto::hash::crc32(image),
+        }
+    }
+
+    /// Verify `self` against the image bytes that are supposed to follow it.
+    ///
+    /// Checks the magic, version, and declared size before touching `image` at all, then the
+    /// checksum -- cheapest checks first, since there's no point hashing a multi-hundred-kilobyte
+    /// image just to reject it on a field comparison.
+    pub fn validate(&self, image: &[u8]) -> Result<(), &'static str> {
+        if self.magic != MAGIC {
+            return Err("image_header: bad magic -- this isn't a kernel image this fork wrote");
+        }
+
+        if self.version != VERSION {
+            return Err("image_header: unsupported header version");
+        }
+
+        if self.image_size as usize != image.len() {
+            return Err("image_header: declared image size doesn't match what was received");
+        }
+
+        if self.checksum != crate::crypto::hash::crc32(image) {
+            return Err("image_header: checksum mismatch -- image is corrupt");
+        }
+
+        Ok(())
+    }
+}
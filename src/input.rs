@@ -0,0 +1,194 @@
+//! Board-independent keyboard input events.
+//!
+//! [`KeyEvent`]s land in a bounded [`ipc::Channel`], the same producer/consumer primitive
+//! [`crate::ipc`] documents for handing a UART RX interrupt's bytes to a shell task -- a keyboard
+//! is just another interrupt source a task wants to await without polling raw bytes itself.
+//!
+//! This fork has no USB host controller driver, so there is no USB HID keyboard to decode scan
+//! codes from; [`fill_from_console`] is the only producer, translating the same ANSI/DEC "CSI"
+//! escape sequences [`console::line_edit`](crate::console::line_edit) already parses for arrow
+//! keys into [`KeyEvent`]s instead of line-editing actions. A future USB HID driver would gain its
+//! own producer function feeding the same [`events`] channel; the shell/user-space consumer side
+//! wouldn't need to change.
+//!
+//! There is also no VFS in this fork (see [`crate::exception::syscall`] and [`crate::process`] for
+//! the same missing prerequisite), so there's no `/dev/input` node to `open`/`read` -- [`events`]
+//! is the API surface a VFS's character device would eventually forward to.
+
+use crate::{console, ipc};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The number of pending key events this fork buffers before a producer starts blocking.
+const CAPACITY: usize = 16;
+
+/// Which modifier keys were held down for a [`KeyEvent`].
+///
+/// A raw byte stream can only tell Ctrl apart from an unmodified key most of the time -- Ctrl-
+/// <letter> arrives as the corresponding C0 control code, already folded into which character
+/// shows up, the same way Shift is for a plain printable key. Alt is conventionally sent as its
+/// own leading Escape, indistinguishable here from the CSI escapes this parses, so it's always
+/// reported `false`. The one case this fork can tell Shift apart from an otherwise-identical key
+/// is the CSI modifier parameter some terminals send on non-printable keys (e.g. `CSI 5 ; 2 ~` for
+/// Shift-PageUp vs plain `CSI 5 ~` for PageUp) -- [`fill_from_console`] sets
+/// [`Modifiers::shift`](Modifiers::shift) from that parameter for [`KeySym::PageUp`]/
+/// [`KeySym::PageDown`], and leaves it `false` everywhere else. A USB HID producer would be able
+/// to set every modifier properly from the modifier byte HID reports carry.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+/// A single logical key press, independent of what physically produced it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KeySym {
+    Char(char),
+    Enter,
+    Backspace,
+    Tab,
+    Escape,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    PageUp,
+    PageDown,
+}
+
+/// A key press, with whatever modifiers were held at the time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub key: KeySym,
+    pub modifiers: Modifiers,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static EVENTS: ipc::Channel<KeyEvent, CAPACITY> = ipc::Channel::new();
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// The system's keyboard input event queue.
+pub fn events() -> &'static ipc::Channel<KeyEvent, CAPACITY> {
+    &EVENTS
+}
+
+/// Read and decode one [`KeyEvent`] from `source`, blocking until a full sequence has arrived,
+/// then enqueue it onto [`events`].
+///
+/// Meant to be driven in a loop by whatever task owns `source`, mirroring how
+/// [`console::line_edit::LineEditor::read_line`] is driven a line at a time.
+pub fn fill_from_console(source: &dyn console::interface::Read) {
+    let event = loop {
+        let b = source.read_char() as u32 as u8;
+
+        break match b {
+            0x03 => KeyEvent {
+                key: KeySym::Char('c'),
+                modifiers: Modifiers {
+                    ctrl: true,
+                    ..Default::default()
+                },
+            },
+            0x04 => KeyEvent {
+                key: KeySym::Char('d'),
+                modifiers: Modifiers {
+                    ctrl: true,
+                    ..Default::default()
+                },
+            },
+            b'\r' | b'\n' => KeyEvent {
+                key: KeySym::Enter,
+                modifiers: Modifiers::default(),
+            },
+            0x7f | 0x08 => KeyEvent {
+                key: KeySym::Backspace,
+                modifiers: Modifiers::default(),
+            },
+            b'\t' => KeyEvent {
+                key: KeySym::Tab,
+                modifiers: Modifiers::default(),
+            },
+            0x1b => {
+                if source.read_char() as u32 as u8 != b'[' {
+                    break KeyEvent {
+                        key: KeySym::Escape,
+                        modifiers: Modifiers::default(),
+                    };
+                }
+
+                let (params, final_byte) = read_csi_params(source);
+
+                match csi_keysym(final_byte, params[0]) {
+                    Some(key) => KeyEvent {
+                        modifiers: Modifiers {
+                            shift: matches!(key, KeySym::PageUp | KeySym::PageDown)
+                                && params[1] == 2,
+                            ..Default::default()
+                        },
+                        key,
+                    },
+                    None => continue,
+                }
+            }
+            _ => KeyEvent {
+                key: KeySym::Char(b as char),
+                modifiers: Modifiers::default(),
+            },
+        };
+    };
+
+    events().send(event);
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+/// Having just consumed an Escape and a `'['` byte, read the rest of a CSI sequence: up to two
+/// `;`-separated decimal parameters (defaulting to `0` if absent or in excess of two) followed by
+/// a non-digit, non-`;` final byte. Covers both the zero-parameter sequences arrow keys use
+/// (`CSI A`) and the numbered, optionally-modified sequences other keys use (`CSI 5 ; 2 ~`).
+fn read_csi_params(source: &dyn console::interface::Read) -> ([u32; 2], u8) {
+    let mut params = [0u32; 2];
+    let mut param_idx = 0;
+
+    loop {
+        let b = source.read_char() as u32 as u8;
+
+        match b {
+            b'0'..=b'9' => {
+                if let Some(param) = params.get_mut(param_idx) {
+                    *param = *param * 10 + (b - b'0') as u32;
+                }
+            }
+            b';' => param_idx += 1,
+            _ => return (params, b),
+        }
+    }
+}
+
+/// Map a CSI final byte (and, for `~`-terminated sequences, the leading numeric parameter) to the
+/// key it represents, if any.
+fn csi_keysym(final_byte: u8, seq: u32) -> Option<KeySym> {
+    match final_byte {
+        b'A' => Some(KeySym::ArrowUp),
+        b'B' => Some(KeySym::ArrowDown),
+        b'C' => Some(KeySym::ArrowRight),
+        b'D' => Some(KeySym::ArrowLeft),
+        b'~' => match seq {
+            5 => Some(KeySym::PageUp),
+            6 => Some(KeySym::PageDown),
+            _ => None,
+        },
+        _ => None,
+    }
+}
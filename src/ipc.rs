@@ -0,0 +1,217 @@
+//! Message-passing IPC between tasks.
+//!
+//! There's no preemptive scheduler in this kernel -- [`crate::executor::block_on`] only ever
+//! drives one future to completion on the calling core -- so [`Channel`] doesn't wake a *task*
+//! the way a real IPC primitive would. It wakes the same way every other future in this fork
+//! does: a reader or writer polls, finds nothing to do, and parks on `wfe` until the next
+//! interrupt or [`crate::cpu::send_event`] gives it a reason to look again. That's still enough
+//! to let, say, a UART RX interrupt handler hand bytes to a shell task through a bounded queue
+//! instead of the two being wired together directly.
+//!
+//! There is no heap, so a `Channel`'s buffer is a fixed-size ring sized by its `CAPACITY` const
+//! generic, and its element type must be `Copy` -- the same trade-off [`crate::exception`] makes
+//! for `IRQHandlerDescriptor`'s handler tables.
+
+use crate::{
+    cpu,
+    synchronization::{Mutex, NullLock},
+};
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+struct Inner<T: Copy, const CAPACITY: usize> {
+    buf: [Option<T>; CAPACITY],
+    head: usize,
+    len: usize,
+}
+
+impl<T: Copy, const CAPACITY: usize> Inner<T, CAPACITY> {
+    const fn new() -> Self {
+        Self {
+            buf: [None; CAPACITY],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn try_send(&mut self, value: T) -> Result<(), T> {
+        if self.len == CAPACITY {
+            return Err(value);
+        }
+
+        let tail = (self.head + self.len) % CAPACITY;
+        self.buf[tail] = Some(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    fn try_receive(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let value = self.buf[self.head].take();
+        self.head = (self.head + 1) % CAPACITY;
+        self.len -= 1;
+        value
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A bounded, single-buffer FIFO for passing `T`s between tasks.
+pub struct Channel<T: Copy, const CAPACITY: usize> {
+    inner: NullLock<Inner<T, CAPACITY>>,
+}
+
+/// The async counterpart to [`Channel::receive`]. Built by [`Channel::receive_async`].
+pub struct Receive<'a, T: Copy, const CAPACITY: usize> {
+    channel: &'a Channel<T, CAPACITY>,
+}
+
+/// The async counterpart to [`Channel::send`]. Built by [`Channel::send_async`].
+pub struct SendFuture<'a, T: Copy, const CAPACITY: usize> {
+    channel: &'a Channel<T, CAPACITY>,
+    value: Option<T>,
+}
+
+/// The outcome of [`select2`]: which channel produced a value first.
+pub enum Either<A, B> {
+    /// The first channel had a value ready.
+    Left(A),
+    /// The second channel had a value ready.
+    Right(B),
+}
+
+/// A future that resolves with whichever of two channels has a value first. Built by [`select2`].
+pub struct Select2<'a, A: Copy, B: Copy, const CA: usize, const CB: usize> {
+    a: &'a Channel<A, CA>,
+    b: &'a Channel<B, CB>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl<T: Copy, const CAPACITY: usize> Channel<T, CAPACITY> {
+    /// Create an instance.
+    pub const fn new() -> Self {
+        Self {
+            inner: NullLock::new(Inner::new()),
+        }
+    }
+
+    /// Enqueue `value` without blocking, failing if the channel is full.
+    pub fn try_send(&self, value: T) -> Result<(), T> {
+        self.inner.lock(|inner| inner.try_send(value))
+    }
+
+    /// Dequeue a value without blocking, returning `None` if the channel is empty.
+    pub fn try_receive(&self) -> Option<T> {
+        self.inner.lock(|inner| inner.try_receive())
+    }
+
+    /// Block the calling core until there is room, then enqueue `value`.
+    pub fn send(&self, value: T) {
+        let mut value = value;
+
+        loop {
+            match self.try_send(value) {
+                Ok(()) => return,
+                Err(rejected) => {
+                    value = rejected;
+                    cpu::wait_for_event();
+                }
+            }
+        }
+    }
+
+    /// Block the calling core until a value is available, then dequeue it.
+    pub fn receive(&self) -> T {
+        loop {
+            if let Some(value) = self.try_receive() {
+                return value;
+            }
+
+            cpu::wait_for_event();
+        }
+    }
+
+    /// The async counterpart to [`Channel::send`], for use with [`crate::executor::block_on`].
+    pub fn send_async(&self, value: T) -> SendFuture<'_, T, CAPACITY> {
+        SendFuture {
+            channel: self,
+            value: Some(value),
+        }
+    }
+
+    /// The async counterpart to [`Channel::receive`], for use with [`crate::executor::block_on`].
+    pub fn receive_async(&self) -> Receive<'_, T, CAPACITY> {
+        Receive { channel: self }
+    }
+}
+
+impl<T: Copy, const CAPACITY: usize> Future for Receive<'_, T, CAPACITY> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<T> {
+        match self.channel.try_receive() {
+            Some(value) => Poll::Ready(value),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<T: Copy + Unpin, const CAPACITY: usize> Future for SendFuture<'_, T, CAPACITY> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let value = this
+            .value
+            .take()
+            .expect("SendFuture polled after completion");
+
+        match this.channel.try_send(value) {
+            Ok(()) => Poll::Ready(()),
+            Err(rejected) => {
+                this.value = Some(rejected);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<A: Copy, B: Copy, const CA: usize, const CB: usize> Future for Select2<'_, A, B, CA, CB> {
+    type Output = Either<A, B>;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Either<A, B>> {
+        if let Some(value) = self.a.try_receive() {
+            return Poll::Ready(Either::Left(value));
+        }
+
+        if let Some(value) = self.b.try_receive() {
+            return Poll::Ready(Either::Right(value));
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Wait on two channels at once, for use with [`crate::executor::block_on`]. Resolves with
+/// whichever one has a value first, or [`Either::Left`] if both do simultaneously.
+pub fn select2<'a, A: Copy, B: Copy, const CA: usize, const CB: usize>(
+    a: &'a Channel<A, CA>,
+    b: &'a Channel<B, CB>,
+) -> Select2<'a, A, B, CA, CB> {
+    Select2 { a, b }
+}
@@ -0,0 +1,72 @@
+//! Deterministic replay of a recorded `(timestamp, IRQ)` trace.
+//!
+//! Only half of what a replay builds on exists in this fork. [`crate::trace`] records every IRQ
+//! dispatch as a timestamped event, but under the handler's name rather than an IRQ number, and
+//! nothing reads its ring back out as the `&[IrqEvent]` slice [`Replay`] consumes. And as
+//! documented in [`crate::stress`], nothing here programs `ICC_SGI1R_EL1` (or any other path) to
+//! fire an interrupt on demand -- every IRQ this kernel sees comes from real hardware. Without an
+//! injection path, "replay a recorded sequence in a test run" can't actually re-inject anything.
+//!
+//! What's provided instead is the part of a replay mode that's independent of how events get
+//! recorded or injected: [`Replay`] is the deterministic sequencer that decides *when* each
+//! recorded event is due, given a trace and the current time. A future IRQ-injection path would
+//! drive it in a loop (poll [`Replay::poll`], inject whatever IRQ number it returns), and a future
+//! reader of [`crate::trace`]'s ring would produce the `&[IrqEvent]` slice it consumes. Until those exist, this has no
+//! caller.
+
+use core::time::Duration;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// One recorded interrupt: `irq` fired at `at`, measured from the start of the trace.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IrqEvent {
+    /// Time since the trace started that this IRQ fired.
+    pub at: Duration,
+    /// The IRQ number that fired, in whatever numbering
+    /// [`exception::asynchronous`](crate::exception::asynchronous) uses.
+    pub irq: usize,
+}
+
+/// Steps through a recorded trace in order, reporting which events are due by a given time.
+///
+/// The trace must be sorted by [`IrqEvent::at`]; this doesn't re-sort it, so a caller building one
+/// from a live recording needs to record in firing order, which it already will have.
+pub struct Replay<'a> {
+    trace: &'a [IrqEvent],
+    next: usize,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl<'a> Replay<'a> {
+    /// Start replaying `trace` from its first event.
+    pub const fn new(trace: &'a [IrqEvent]) -> Self {
+        Self { trace, next: 0 }
+    }
+
+    /// Return the next recorded IRQ that is due at or before `now`, advancing past it, or `None`
+    /// if the next recorded event (if any) is still in the future.
+    ///
+    /// Called in a loop against the replay clock: each `Some` return is one IRQ a real injection
+    /// path would fire before polling again.
+    pub fn poll(&mut self, now: Duration) -> Option<usize> {
+        let event = self.trace.get(self.next)?;
+
+        if event.at > now {
+            return None;
+        }
+
+        self.next += 1;
+        Some(event.irq)
+    }
+
+    /// Whether every recorded event has already been returned by [`poll`](Self::poll).
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.trace.len()
+    }
+}
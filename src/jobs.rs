@@ -0,0 +1,421 @@
+//! Time-sliced cooperative background jobs for the interactive shell.
+//!
+//! There's no preemptive scheduler in this kernel (see [`crate::executor`] and
+//! [`crate::process`]) and no allocator to hand out dynamically-sized tasks with, so this doesn't
+//! try to be one: a job is one of a fixed, small set of [`JobKind`]s living in a fixed-size table,
+//! and [`poll_all`] advances every live job by exactly one step each time it's called --
+//! [`crate::main`]'s interactive loop calls it once per line, the same spot that already drains
+//! [`crate::exception::asynchronous::run_deferred_handlers`]. That's the "time-sliced" part: a
+//! job never runs longer than one step before control returns to the shell, so a background job
+//! can't make the prompt unresponsive no matter how long it logically runs for.
+//!
+//! [`kill`] doesn't stop a job's step function mid-execution -- nothing preempts it -- it posts to
+//! the job's own [`crate::ipc::Channel`], which the job checks for at the start of its next step
+//! and exits cleanly if it's there. Cooperative, like everything else here.
+//!
+//! A job -- the closest thing to a "process" this cooperative table has, since
+//! [`crate::process::spawn_elf`] is still an honest stub -- accounts its own CPU time in
+//! [`Slot::cpu_time`], and [`spawn`] accepts an optional `cpu=<secs>` limit that [`poll_all`]
+//! enforces by killing the job once it's spent that long. There's no resident-frame or heap
+//! accounting alongside it: this fork has no frame allocator or heap to report on at all (see
+//! [`crate::fs::procfs`]'s `meminfo`), so there's nothing real for a per-job number to mean.
+//! [`crate::fs::procfs`]'s `status/<id>` file and the shell's `ps` command are what surface
+//! [`stats`]'s numbers, named after their Linux procfs/`ps` counterparts even though this is a far
+//! smaller thing underneath.
+//!
+//! [`JobKind::Counter`] is the portable demonstration job: it only touches this module's own
+//! state and a log line, so it runs on every board. [`JobKind::Blink`] is the hardware one the
+//! request asked for by name, gated to the boards that actually have a BCM GPIO controller to
+//! back [`crate::gpio::Pin`] with.
+//!
+//! [`Priority`] and [`JobStatus::Sleeping`] are as far as "scheduler priorities and sleep states"
+//! can go in a cooperative, single-queue table like this one: [`poll_all`] visits
+//! [`Priority::High`] slots before [`Priority::Normal`] and [`Priority::Low`] ones each tick, and
+//! a job that returns `Sleeping(until)` is skipped (not stepped, not counted against its own
+//! time slice) until [`crate::time::TimeManager::uptime`] reaches `until`. What this *can't* be is
+//! real priority preemption or a genuine blocked state: nothing here ever interrupts a step
+//! that's already running, a sleeping job still occupies its table slot instead of being parked
+//! on a wait queue, and there's no separate idle task to run `wfi` on because there's no second
+//! thread of control to idle *instead of* -- this whole table is driven from inside
+//! [`crate::main`]'s single interactive loop, not a per-core scheduling loop of its own. A real
+//! idle task needs the same per-core, context-switching scheduler this module's own doc has
+//! always said this fork doesn't have; see [`crate::cpu::context`] for how far register-state
+//! capture gets today (not far enough to save and restore a suspended job's call stack).
+
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+use crate::gpio;
+use crate::{
+    ipc, log,
+    synchronization::{Mutex, NullLock},
+    time::TimeManager,
+};
+use core::time::Duration;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// How many background jobs can be live at once. Small on purpose: this is a shell convenience,
+/// not a real scheduler.
+pub const MAX_JOBS: usize = 4;
+
+/// A job's slot index in the fixed-size table, and how it's named in [`jobs`]/[`kill`].
+pub type JobId = usize;
+
+/// How eagerly [`poll_all`] visits a job relative to the others sharing its tick -- see the
+/// module docs for what this is and isn't.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Priority {
+    /// This priority's name, as parsed back by [`spawn`] and printed by the `jobs` shell command.
+    pub(crate) const fn tag(self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+        }
+    }
+}
+
+/// What [`step`] returns after advancing a job by one slice.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum JobStatus {
+    Running,
+    /// Don't step this job again until uptime reaches the given [`Duration`] -- see
+    /// [`Slot::sleep_until`].
+    Sleeping(Duration),
+    Done,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A counted-down logging job. Ticks once per [`poll_all`] call until `remaining` hits zero.
+struct Counter {
+    remaining: u32,
+}
+
+impl Counter {
+    fn step(&mut self, id: JobId) -> JobStatus {
+        if self.remaining == 0 {
+            return JobStatus::Done;
+        }
+
+        self.remaining -= 1;
+        log::log_info!("jobs", "counter[{}]: {} left", id, self.remaining);
+
+        if self.remaining == 0 {
+            JobStatus::Done
+        } else {
+            JobStatus::Running
+        }
+    }
+}
+
+/// A job that logs once, then sleeps for `interval` before logging again, `remaining` more times.
+/// Demonstrates [`JobStatus::Sleeping`] -- see the module docs for the "sleep(duration)" part of
+/// the request this answers, and its caveat.
+struct Sleeper {
+    remaining: u32,
+    interval: Duration,
+}
+
+impl Sleeper {
+    fn step(&mut self, id: JobId, now: Duration) -> JobStatus {
+        if self.remaining == 0 {
+            return JobStatus::Done;
+        }
+
+        self.remaining -= 1;
+        log::log_info!("jobs", "sleeper[{}]: {} left", id, self.remaining);
+
+        if self.remaining == 0 {
+            JobStatus::Done
+        } else {
+            JobStatus::Sleeping(now + self.interval)
+        }
+    }
+}
+
+/// A job that blinks a claimed GPIO pin. Never finishes on its own -- only [`kill`] ends it.
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+struct Blink {
+    pin: gpio::Pin,
+    on: bool,
+}
+
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+impl Blink {
+    fn step(&mut self) -> JobStatus {
+        self.on = !self.on;
+
+        if self.on {
+            self.pin.set_high();
+        } else {
+            self.pin.set_low();
+        }
+
+        JobStatus::Running
+    }
+}
+
+enum JobKind {
+    Counter(Counter),
+    Sleeper(Sleeper),
+    #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+    Blink(Blink),
+}
+
+impl JobKind {
+    fn step(&mut self, id: JobId, now: Duration) -> JobStatus {
+        match self {
+            JobKind::Counter(job) => job.step(id),
+            JobKind::Sleeper(job) => job.step(id, now),
+            #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+            JobKind::Blink(job) => job.step(),
+        }
+    }
+
+    /// The name [`jobs`] lists this job under.
+    fn name(&self) -> &'static str {
+        match self {
+            JobKind::Counter(_) => "counter",
+            JobKind::Sleeper(_) => "sleeper",
+            #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+            JobKind::Blink(_) => "blink",
+        }
+    }
+}
+
+struct Slot {
+    kind: JobKind,
+    priority: Priority,
+    /// Posted to by [`kill`]; checked at the start of every [`Slot`]'s step.
+    kill: ipc::Channel<(), 1>,
+    /// Set by a [`JobStatus::Sleeping`] return from the job's last step; [`poll_all`] skips this
+    /// slot entirely (no step, no time slice spent) until uptime reaches it.
+    sleep_until: Option<Duration>,
+    /// Wall-clock time spent inside this job's own [`JobKind::step`] calls, accumulated each time
+    /// [`poll_all`] steps it -- the same span [`crate::trace::record_span`] already measures for
+    /// the chrome trace, just kept running per slot instead of only ever being emitted as an
+    /// event. There's only one core and no preemption in this fork (see the module docs), so a
+    /// job's own step is the entire "time slice" to account for; there's no separate kernel-side
+    /// or interrupt-handling time to attribute against it the way a real scheduler would.
+    cpu_time: Duration,
+    /// Set from `spawn`'s `cpu=<secs>` token, if given. [`poll_all`] kills a job outright once
+    /// [`Slot::cpu_time`] reaches this -- the "enforce simple limits set at spawn time" half of
+    /// the request this answers.
+    cpu_limit: Option<Duration>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static JOBS: NullLock<[Option<Slot>; MAX_JOBS]> = NullLock::new([None, None, None, None]);
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Parse and start a background job from a shell command's `&`-stripped body, e.g.
+/// `"counter 5 high"`, `"sleeper 3 2"`, `"blink 47"`, or `"cpu=10 counter 5"`.
+///
+/// The last word, if it parses as a [`Priority`] name (`low`/`normal`/`high`), sets the job's
+/// priority; otherwise the job defaults to [`Priority::Normal`] and that word is left for the job
+/// kind itself to parse as usual. A leading `cpu=<secs>` word sets a CPU time limit -- see the
+/// module docs -- and is likewise left out of what the job kind itself parses.
+///
+/// Returns the new job's [`JobId`] on success.
+pub fn spawn(spec: &str) -> Result<JobId, &'static str> {
+    let (spec, priority) = match spec.rsplit_once(char::is_whitespace) {
+        Some((rest, "low")) => (rest, Priority::Low),
+        Some((rest, "normal")) => (rest, Priority::Normal),
+        Some((rest, "high")) => (rest, Priority::High),
+        _ => (spec, Priority::Normal),
+    };
+
+    let mut words = spec.split_whitespace().peekable();
+    let cpu_limit = match words.peek().and_then(|w| w.strip_prefix("cpu=")) {
+        Some(secs) => {
+            let secs: u64 = secs.parse().map_err(|_| "jobs: malformed cpu=<secs>")?;
+            words.next();
+            Some(Duration::from_secs(secs))
+        }
+        None => None,
+    };
+
+    let kind = match words.next() {
+        Some("counter") => {
+            let remaining = words
+                .next()
+                .and_then(|n| n.parse::<u32>().ok())
+                .unwrap_or(20);
+
+            JobKind::Counter(Counter { remaining })
+        }
+        Some("sleeper") => {
+            let remaining = words
+                .next()
+                .and_then(|n| n.parse::<u32>().ok())
+                .unwrap_or(5);
+            let interval_secs = words
+                .next()
+                .and_then(|n| n.parse::<u64>().ok())
+                .unwrap_or(1);
+
+            JobKind::Sleeper(Sleeper {
+                remaining,
+                interval: Duration::from_secs(interval_secs),
+            })
+        }
+        #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+        Some("blink") => {
+            let pin = words
+                .next()
+                .and_then(|n| n.parse::<u32>().ok())
+                .ok_or("jobs: usage: blink <pin>&")?;
+
+            let pin = gpio::pin(pin, "blink", "jobs");
+            pin.set_output();
+
+            JobKind::Blink(Blink { pin, on: false })
+        }
+        _ => return Err(
+            "jobs: unknown job kind (try 'counter [n]', 'sleeper [n] [secs]', or 'blink <pin>')",
+        ),
+    };
+
+    JOBS.lock(|jobs| {
+        let slot = jobs
+            .iter_mut()
+            .position(|slot| slot.is_none())
+            .ok_or("jobs: background job table is full")?;
+
+        jobs[slot] = Some(Slot {
+            kind,
+            priority,
+            kill: ipc::Channel::new(),
+            sleep_until: None,
+            cpu_time: Duration::ZERO,
+            cpu_limit,
+        });
+
+        Ok(slot)
+    })
+}
+
+/// Advance every live job by exactly one step, removing any that finish or were [`kill`]ed.
+///
+/// Visits [`Priority::High`] slots first, then [`Priority::Normal`], then [`Priority::Low`] --
+/// see the module docs for what that ordering does and doesn't buy in a cooperative table like
+/// this one. A job still [`JobStatus::Sleeping`] isn't stepped at all this tick.
+///
+/// Meant to be called once per iteration of the interactive shell's loop -- see the module docs.
+pub fn poll_all() {
+    JOBS.lock(|jobs| {
+        let now = crate::time::time_manager().uptime();
+
+        let mut order: [usize; MAX_JOBS] = core::array::from_fn(|i| i);
+        order.sort_by_key(|&id| match &jobs[id] {
+            Some(slot) => core::cmp::Reverse(slot.priority),
+            None => core::cmp::Reverse(Priority::Low),
+        });
+
+        for id in order {
+            let Some(job) = &mut jobs[id] else {
+                continue;
+            };
+
+            if job.kill.try_receive().is_some() {
+                log::log_info!("jobs", "{}[{}]: killed", job.kind.name(), id);
+                jobs[id] = None;
+                continue;
+            }
+
+            let job = jobs[id].as_mut().unwrap();
+            if let Some(until) = job.sleep_until {
+                if now < until {
+                    continue;
+                }
+                job.sleep_until = None;
+            }
+
+            let status = job.kind.step(id, now);
+            let end = crate::time::time_manager().uptime();
+            crate::trace::record_span("scheduler", job.kind.name(), now, end);
+            job.cpu_time += end.saturating_sub(now);
+
+            if let Some(limit) = job.cpu_limit {
+                if job.cpu_time >= limit {
+                    log::log_info!(
+                        "jobs",
+                        "{}[{}]: killed (cpu time limit {:?} reached)",
+                        job.kind.name(),
+                        id,
+                        limit
+                    );
+                    jobs[id] = None;
+                    continue;
+                }
+            }
+
+            match status {
+                JobStatus::Done => {
+                    log::log_info!("jobs", "{}[{}]: finished", job.kind.name(), id);
+                    jobs[id] = None;
+                }
+                JobStatus::Sleeping(until) => job.sleep_until = Some(until),
+                JobStatus::Running => {}
+            }
+        }
+    });
+}
+
+/// Call `f` with the id, kind name, and priority of every currently-live job, for the `jobs` shell
+/// command.
+pub fn list(mut f: impl FnMut(JobId, &'static str, Priority)) {
+    JOBS.lock(|jobs| {
+        for (id, slot) in jobs.iter().enumerate() {
+            if let Some(job) = slot {
+                f(id, job.kind.name(), job.priority);
+            }
+        }
+    });
+}
+
+/// Call `f` with the id, kind name, priority, accumulated CPU time, and CPU time limit (if any)
+/// of every currently-live job, for the `ps` shell command and `procfs`'s `status/<id>` file.
+pub fn stats(mut f: impl FnMut(JobId, &'static str, Priority, Duration, Option<Duration>)) {
+    JOBS.lock(|jobs| {
+        for (id, slot) in jobs.iter().enumerate() {
+            if let Some(job) = slot {
+                f(
+                    id,
+                    job.kind.name(),
+                    job.priority,
+                    job.cpu_time,
+                    job.cpu_limit,
+                );
+            }
+        }
+    });
+}
+
+/// Ask the job at `id` to stop. Cooperative -- see the module docs -- so it takes effect on that
+/// job's next [`poll_all`] step, not immediately.
+pub fn kill(id: JobId) -> Result<(), &'static str> {
+    JOBS.lock(|jobs| match jobs.get(id) {
+        Some(Some(job)) => {
+            let _ = job.kill.try_send(());
+            Ok(())
+        }
+        _ => Err("kill: no such job"),
+    })
+}
@@ -0,0 +1,166 @@
+//! Assertion and invariant checking with a configurable failure policy.
+//!
+//! `kassert!`/`kassert_debug!` are like `assert!`/`debug_assert!`, but a failure's fate depends
+//! on [`Policy`] instead of always panicking: [`Policy::Panic`] halts the kernel the way
+//! `assert!` would, [`Policy::LogAndContinue`] reports the failure through [`crate::log`] and
+//! carries on, and [`Policy::Count`] just tallies it in [`failure_count`] for a caller to inspect
+//! later. The default tracks [`crate::config::PROFILE`]: `Panic` in [`crate::config::Profile::Debug`],
+//! since a violated invariant during development is exactly the kind of bug you want to stop on,
+//! and `LogAndContinue` in [`crate::config::Profile::Release`], for a build that would rather stay
+//! up and report. Either way, [`set_policy`] can still override it at runtime.
+//!
+//! Beyond one-off checks at the call site, a subsystem can also register a standing consistency
+//! check with [`register_invariant_check`]. [`run_invariant_checks`] runs all of them through the
+//! same policy. Nothing in this kernel drives that on a timer yet -- it's meant to be invoked by
+//! a low-priority periodic callback once the kernel has one -- so today it only runs once, as a
+//! self-test at the end of boot.
+
+use crate::{
+    log,
+    synchronization::{Mutex, NullLock},
+};
+use core::{
+    fmt,
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// What happens when a `kassert!`/invariant check fails.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Policy {
+    /// Panic immediately, like `assert!`.
+    Panic = 0,
+    /// Log the failure at [`log::Level::Error`] and keep running.
+    LogAndContinue = 1,
+    /// Silently tally the failure in [`failure_count`].
+    Count = 2,
+}
+
+impl Policy {
+    const fn from_u8(v: u8) -> Self {
+        match v {
+            0 => Policy::Panic,
+            1 => Policy::LogAndContinue,
+            _ => Policy::Count,
+        }
+    }
+}
+
+/// A subsystem-registered consistency check, run by [`run_invariant_checks`].
+pub type InvariantCheck = fn() -> Result<(), &'static str>;
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const NUM_INVARIANT_SLOTS: usize = 8;
+
+struct InvariantRegistry {
+    checks: [Option<(&'static str, InvariantCheck)>; NUM_INVARIANT_SLOTS],
+}
+
+impl InvariantRegistry {
+    const fn new() -> Self {
+        Self {
+            checks: [None; NUM_INVARIANT_SLOTS],
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static POLICY: AtomicU8 = AtomicU8::new(if crate::config::PROFILE.is_debug() {
+    Policy::Panic as u8
+} else {
+    Policy::LogAndContinue as u8
+});
+static FAILURE_COUNT: AtomicUsize = AtomicUsize::new(0);
+static INVARIANTS: NullLock<InvariantRegistry> = NullLock::new(InvariantRegistry::new());
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Set the policy applied to future `kassert!`/invariant-check failures.
+pub fn set_policy(policy: Policy) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// The policy currently applied to `kassert!`/invariant-check failures.
+pub fn policy() -> Policy {
+    Policy::from_u8(POLICY.load(Ordering::Relaxed))
+}
+
+/// How many failures [`Policy::Count`] has tallied so far.
+pub fn failure_count() -> usize {
+    FAILURE_COUNT.load(Ordering::Relaxed)
+}
+
+#[doc(hidden)]
+pub fn _kassert_fail(tag: &str, args: fmt::Arguments) {
+    match policy() {
+        Policy::Panic => panic!("[{}] assertion failed: {}", tag, args),
+        Policy::LogAndContinue => log::log_error!(tag, "assertion failed: {}", args),
+        Policy::Count => {
+            FAILURE_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Assert that `cond` holds, handling a failure through the current [`Policy`] instead of always
+/// panicking.
+macro_rules! kassert {
+    ($cond:expr, $tag:expr, $($arg:tt)*) => {
+        if !$cond {
+            $crate::kassert::_kassert_fail($tag, format_args!($($arg)*));
+        }
+    };
+}
+
+/// Like [`kassert!`], but compiled out entirely in release builds, mirroring `debug_assert!`.
+macro_rules! kassert_debug {
+    ($cond:expr, $tag:expr, $($arg:tt)*) => {
+        if cfg!(debug_assertions) && !$cond {
+            $crate::kassert::_kassert_fail($tag, format_args!($($arg)*));
+        }
+    };
+}
+
+pub(crate) use {kassert, kassert_debug};
+
+/// Register a standing consistency check with the kernel's invariant checker.
+///
+/// Meant to be called once per subsystem during init.
+///
+/// # Panics
+///
+/// Panics if the fixed-size registration table is full; raise `NUM_INVARIANT_SLOTS` if the
+/// kernel grows enough registrants to need it.
+pub fn register_invariant_check(name: &'static str, check: InvariantCheck) {
+    INVARIANTS.lock(|registry| {
+        let free_slot = registry
+            .checks
+            .iter_mut()
+            .find(|c| c.is_none())
+            .expect("Ran out of invariant-check slots");
+
+        *free_slot = Some((name, check));
+    });
+}
+
+/// Run every registered consistency check, handling failures through the current [`Policy`].
+pub fn run_invariant_checks() {
+    INVARIANTS.lock(|registry| {
+        for (name, check) in registry.checks.iter().flatten() {
+            if let Err(x) = check() {
+                _kassert_fail(name, format_args!("{}", x));
+            }
+        }
+    });
+}
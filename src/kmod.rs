@@ -0,0 +1,99 @@
+//! Loadable kernel modules.
+//!
+//! `crate::fs::fat32` and `crate::fs::tmpfs` can hold a module's blob now, so "no filesystem to
+//! read it from" is no longer the gap (see `crate::process`'s docs for the same correction on the
+//! ELF-loader side) -- but [`load`] still needs a relocating PIC-object linker this fork has none
+//! of, and a heap to hold the relocated code and data in (`#![no_std]`, no `alloc` anywhere in
+//! this kernel). Writing a relocator against an undocumented-to-this-fork relocation-type subset
+//! without a way to link and run a real `.ko`-equivalent against it here is the kind of thing this
+//! fork would rather leave blocked than ship half-tested, so [`load`] stays parked on those two.
+//! What *is* real here is the exported kernel symbol table a module's relocations would need to
+//! resolve against -- see [`export_symbol`] and [`lookup_symbol`] -- since building that doesn't
+//! depend on either missing piece.
+
+use crate::synchronization::{Mutex, NullLock};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const NUM_SYMBOL_SLOTS: usize = 32;
+
+#[derive(Copy, Clone)]
+struct Symbol {
+    name: &'static str,
+    addr: usize,
+}
+
+struct SymbolTable {
+    symbols: [Option<Symbol>; NUM_SYMBOL_SLOTS],
+}
+
+impl SymbolTable {
+    const fn new() -> Self {
+        Self {
+            symbols: [None; NUM_SYMBOL_SLOTS],
+        }
+    }
+}
+
+static SYMBOL_TABLE: NullLock<SymbolTable> = NullLock::new(SymbolTable::new());
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Export `addr` under `name` for a future module's relocations to resolve against.
+pub fn export_symbol(name: &'static str, addr: usize) -> Result<(), &'static str> {
+    SYMBOL_TABLE.lock(
+        |table| match table.symbols.iter_mut().find(|s| s.is_none()) {
+            Some(slot) => {
+                *slot = Some(Symbol { name, addr });
+                Ok(())
+            }
+            None => Err("kmod: ran out of symbol table slots"),
+        },
+    )
+}
+
+/// Look up a previously [`export_symbol`]ed address by name.
+pub fn lookup_symbol(name: &str) -> Option<usize> {
+    SYMBOL_TABLE.lock(|table| {
+        table
+            .symbols
+            .iter()
+            .flatten()
+            .find(|s| s.name == name)
+            .map(|s| s.addr)
+    })
+}
+
+/// Find the exported symbol closest to, but not past, `addr`, and `addr`'s offset from it.
+///
+/// This is `addr`'s nearest *known* symbol, not necessarily the function it's actually inside --
+/// with only [`export_symbol`]'s explicit registrations to search, an address inside an
+/// unexported function resolves to whatever exported symbol precedes it, however far back that
+/// turns out to be. Used by the shell's `profile` command to symbolize [`crate::profiler`]'s
+/// recorded samples.
+pub fn resolve_symbol(addr: usize) -> Option<(&'static str, usize)> {
+    SYMBOL_TABLE.lock(|table| {
+        table
+            .symbols
+            .iter()
+            .flatten()
+            .filter(|s| s.addr <= addr)
+            .max_by_key(|s| s.addr)
+            .map(|s| (s.name, addr - s.addr))
+    })
+}
+
+/// Relocate and link a PIC object against the exported symbol table, call its `module_init`, and
+/// register whatever drivers/IRQ handlers it brings.
+///
+/// Blocked on a PIC relocator and a heap -- see the module docs -- not on a place to read `blob`
+/// from, which this fork already has.
+pub fn load(blob: &[u8]) -> Result<(), &'static str> {
+    let _ = blob;
+
+    Err("kmod: load is blocked on a PIC relocator/linker and a heap, neither of which this fork has")
+}
@@ -0,0 +1,248 @@
+//! Runtime instruction patching hooks on kernel functions ("kprobes-lite").
+//!
+//! The request this answers wants a breakpoint planted at the start of a chosen function that
+//! invokes a registered callback with the interrupted register state before resuming the function
+//! as if nothing happened. Real kprobes implementations (Linux's included) do this with a trap
+//! instruction (`BRK` here) precisely because a trap hands control to an exception handler
+//! *before* the probed instruction executes, leaving every register -- including `LR` -- exactly
+//! as the caller left it. [`crate::exception`]'s module docs already cover why that handler can't
+//! exist in this fork: `VBAR_EL1` is never programmed, so a `BRK` here would trap to whatever
+//! garbage is left over in `VBAR_EL1` from firmware or QEMU's reset state, the same "worse than no
+//! feature at all" outcome [`crate::debug_watchpoint`] declined for the same reason.
+//!
+//! Patching a direct branch instead, without any trap, sounds like it sidesteps that gap, but it
+//! runs into a second, independent problem: a branch-with-link (`BL`) is the only branch form that
+//! can hand a trampoline a return address to come back to, and executing it overwrites `LR` with
+//! that return address as a side effect *before* any of our code runs. The instruction this would
+//! replace is a kernel function's first instruction -- almost always the prologue's
+//! `stp x29, x30, [sp, -16]!`, whose entire job is to save the caller's real `LR` before anything
+//! else can touch it. By the time a patched `BL` starts executing, the original caller's return
+//! address is already gone; there is no register left holding it to save. A plain branch (`B`)
+//! doesn't clobber `LR`, but it also can't tell a trampoline where to resume afterward, which is
+//! the same problem from the other direction. Both a trap-based and a branch-based probe need a
+//! working exception mechanism to observe an instruction's effect without destroying state needed
+//! to resume past it -- which this fork doesn't have, for the same underlying reason either way.
+//!
+//! What's real here, and doesn't depend on solving either problem above: encoding and decoding the
+//! branch instruction itself ([`encode_branch`]/[`decode_branch`]), and the cache-maintenance
+//! sequence a modified instruction stream needs (`DC CVAU` + `IC IVAU` + `DSB` + `ISB`, the
+//! instruction-side counterpart of the data-cache maintenance [`crate::dma`] does for DMA buffers,
+//! since a write to an instruction stream isn't visible to the fetch pipeline until both caches
+//! agree) -- see [`sync_instruction_cache`]. A future working implementation, once this fork has a
+//! real vector table, only needs a [`Probe`] registry and a `BRK`-based `arm`/`disarm` built on top
+//! of those two pieces; [`arm`] is written against that shape today and refuses for the reasons
+//! above, the same honest-`Err` pattern [`crate::debug_watchpoint::arm`] uses.
+
+use crate::synchronization::{Mutex, NullLock};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Bytes covered by one D-cache/I-cache maintenance operation. Matches
+/// [`crate::dma`]'s own `CACHE_LINE_SIZE`; both are standing in for `CTR_EL0.DminLine`/`IminLine`
+/// until something reads that register instead of assuming the common value.
+///
+/// Only [`sync_instruction_cache`] uses this, and that function is itself
+/// `#[cfg(target_arch = "aarch64")]`, so this is gated the same way instead of reporting dead on a
+/// host build.
+#[cfg(target_arch = "aarch64")]
+const CACHE_LINE_SIZE: usize = 64;
+
+/// Kernel functions a caller has asked to probe, kept so a future working [`arm`] has somewhere
+/// to record them -- nothing patches memory from this table today, since [`arm`] always refuses.
+const NUM_PROBE_SLOTS: usize = 16;
+
+#[derive(Copy, Clone)]
+struct Probe {
+    address: usize,
+    callback: fn(usize),
+}
+
+struct ProbeRegistry {
+    probes: [Option<Probe>; NUM_PROBE_SLOTS],
+}
+
+impl ProbeRegistry {
+    const fn new() -> Self {
+        Self {
+            probes: [None; NUM_PROBE_SLOTS],
+        }
+    }
+}
+
+static PROBES: NullLock<ProbeRegistry> = NullLock::new(ProbeRegistry::new());
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Encode an unconditional `B` branch from `from` to `to`, or `None` if `to` is out of a `B`'s
+/// ±128 MiB range or either address isn't 4-byte aligned.
+pub fn encode_branch(from: usize, to: usize) -> Option<u32> {
+    if !from.is_multiple_of(4) || !to.is_multiple_of(4) {
+        return None;
+    }
+
+    let offset = (to as i64).checked_sub(from as i64)?;
+    let imm26 = offset / 4;
+    if !(-(1 << 25)..(1 << 25)).contains(&imm26) {
+        return None;
+    }
+
+    Some(0b000101 << 26 | (imm26 as u32 & 0x03ff_ffff))
+}
+
+/// Decode a `B` instruction word encoded by [`encode_branch`], returning its target address
+/// relative to `from` (the address the instruction itself lives at). `None` if `instruction` isn't
+/// an unconditional `B`.
+pub fn decode_branch(from: usize, instruction: u32) -> Option<usize> {
+    if instruction >> 26 != 0b000101 {
+        return None;
+    }
+
+    let imm26 = instruction & 0x03ff_ffff;
+    // Sign-extend the 26-bit immediate.
+    let signed = ((imm26 << 6) as i32 >> 6) as i64;
+
+    Some((from as i64 + signed * 4) as usize)
+}
+
+/// Make a just-written instruction word at `address` visible to the fetch pipeline.
+///
+/// Writing through the D-cache doesn't update the (separate, non-coherent) I-cache on AArch64, so
+/// a modified instruction stream needs this sequence before anything branches into it: clean the
+/// D-cache line back to the point of unification, invalidate the matching I-cache line so the next
+/// fetch reloads it, and `DSB`/`ISB` to order both against the instruction fetch that follows. See
+/// [`crate::dma`]'s module docs for the same reasoning applied to DMA buffers instead of code.
+///
+/// # Safety
+///
+/// `address` must be the start of a valid, 4-byte-aligned instruction word.
+#[cfg(target_arch = "aarch64")]
+pub unsafe fn sync_instruction_cache(address: usize) {
+    use core::arch::asm;
+
+    let line = address & !(CACHE_LINE_SIZE - 1);
+    asm!("dc cvau, {0}", "ic ivau, {0}", "dsb ish", "isb", in(reg) line, options(nostack, preserves_flags));
+}
+
+/// Register `callback` to run when `address` is probed, for a future working [`arm`] to use.
+///
+/// Returns the slot's index on success, for a matching [`unregister`] call.
+pub fn register(address: usize, callback: fn(usize)) -> Result<usize, &'static str> {
+    PROBES.lock(
+        |registry| match registry.probes.iter_mut().position(|p| p.is_none()) {
+            Some(i) => {
+                registry.probes[i] = Some(Probe { address, callback });
+                Ok(i)
+            }
+            None => Err("kprobe: ran out of probe slots"),
+        },
+    )
+}
+
+/// Undo a previous [`register`] call.
+pub fn unregister(slot: usize) {
+    PROBES.lock(|registry| {
+        if let Some(p) = registry.probes.get_mut(slot) {
+            *p = None;
+        }
+    });
+}
+
+/// Refuse to patch `address` to invoke its registered probe -- see the module docs for why both a
+/// trap-based and a branch-based live patch need a working exception mechanism this fork doesn't
+/// have, for two different but related reasons.
+///
+/// Returns an error if `slot` has nothing registered in it too, the same check a working `arm`
+/// would need before it could even consider patching `probe.address` to call `probe.callback`.
+/// This is an honest `Err`, not a partial implementation, the same call
+/// [`crate::debug_watchpoint::arm`] makes for hardware watchpoints.
+pub fn arm(slot: usize) -> Result<(), &'static str> {
+    let probe = PROBES
+        .lock(|registry| registry.probes.get(slot).copied().flatten())
+        .ok_or("kprobe: no probe registered in that slot")?;
+    // A working `arm` would patch `probe.address` with a branch/trap to a trampoline that calls
+    // `probe.callback`; neither field has anywhere to go yet, for the reasons above.
+    let _address = probe.address;
+    let _callback = probe.callback;
+
+    Err(
+        "kprobe: refusing to arm -- no exception vector table to trap a BRK through, and a \
+         branch-based patch would clobber LR before a trampoline could save it; see the module docs",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Held by each test that touches the shared [`PROBES`] table, since the test harness
+    /// otherwise runs them on parallel threads.
+    static PROBES_SERIAL: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn encodes_and_decodes_a_forward_branch() {
+        let from = 0x8000_0000;
+        let to = from + 0x1000;
+        let instruction = encode_branch(from, to).unwrap();
+        assert_eq!(decode_branch(from, instruction), Some(to));
+    }
+
+    #[test]
+    fn encodes_and_decodes_a_backward_branch() {
+        let from = 0x8000_1000;
+        let to = from - 0x800;
+        let instruction = encode_branch(from, to).unwrap();
+        assert_eq!(decode_branch(from, instruction), Some(to));
+    }
+
+    #[test]
+    fn rejects_misaligned_addresses() {
+        assert_eq!(encode_branch(0x1001, 0x2000), None);
+        assert_eq!(encode_branch(0x1000, 0x2001), None);
+    }
+
+    #[test]
+    fn rejects_a_target_outside_branch_range() {
+        assert_eq!(encode_branch(0, 1 << 27), None);
+    }
+
+    #[test]
+    fn decode_rejects_a_non_branch_instruction() {
+        // `mov x0, #0`, not a `B`.
+        assert_eq!(decode_branch(0x1000, 0xd280_0000), None);
+    }
+
+    #[test]
+    fn register_and_unregister_round_trip_a_slot() {
+        let _guard = PROBES_SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        fn probe_callback(_addr: usize) {}
+
+        let slot = register(0x1000, probe_callback).unwrap();
+        unregister(slot);
+        // The freed slot is reusable.
+        let slot2 = register(0x2000, probe_callback).unwrap();
+        assert_eq!(slot, slot2);
+        unregister(slot2);
+    }
+
+    #[test]
+    fn arm_always_refuses() {
+        let _guard = PROBES_SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        fn probe_callback(_addr: usize) {}
+        let slot = register(0x1000, probe_callback).unwrap();
+        assert!(arm(slot).is_err());
+        unregister(slot);
+    }
+
+    #[test]
+    fn arm_rejects_an_unregistered_slot() {
+        let _guard = PROBES_SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        fn probe_callback(_addr: usize) {}
+        let slot = register(0x1000, probe_callback).unwrap();
+        unregister(slot);
+        assert!(arm(slot).is_err());
+    }
+}
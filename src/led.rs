@@ -0,0 +1,210 @@
+//! Onboard ACT LED indicator: a cheap "is it alive" signal for headless bring-up.
+//!
+//! [`Led`] wraps a [`crate::gpio::Pin`] the LED is wired to, which covers the boards where it
+//! hangs straight off the BCM GPIO controller (e.g. the Raspberry Pi 3's ACT LED, GPIO 47,
+//! active-high). The request that asked for this module also wanted the models where the LED is
+//! GPU-controlled instead -- the Raspberry Pi 4 wires its ACT LED through the VideoCore's GPIO
+//! expander, reachable only via a [`crate::mailbox`] property tag, not a plain GPIO pin. That tag
+//! number and the expander pin index aren't something this fork has verified against real
+//! hardware, so rather than guess at values, [`Led`] only covers the direct-GPIO case; a
+//! mailbox-backed implementation would plug in as a second constructor next to [`Led::new`],
+//! using [`crate::mailbox`]'s existing property-tag machinery the same way
+//! [`crate::framebuffer`] already does for its own tags.
+//!
+//! Nothing in this tree calls [`register`] yet -- no in-tree board `driver.rs` claims the ACT LED
+//! pin today, the same "real, usable library code with no caller yet" situation
+//! [`crate::gpio`]'s own module docs describe for every other consumer of [`gpio::Pin`] except
+//! this fork's bitbang drivers. A board's `driver.rs` wiring one up is as simple as
+//! `led::register(Led::new(gpio::pin(47, "act-led", "led"), false))`.
+//!
+//! Three patterns, matching the request:
+//! - [`heartbeat_step`] -- a short pulse, mostly off -- is meant to be polled once per iteration
+//!   of whatever loop is still running, the same way [`crate::jobs::poll_all`] and
+//!   [`crate::exception::asynchronous::run_deferred_handlers`] are. Like both of those, it's
+//!   ticked by activity, not wall-clock time: this fork has no periodic timer callback to drive it
+//!   with instead (see the same caveat in `main::kernel_init`'s boot-time self-test call).
+//! - [`panic_fast_blink_forever`] replaces [`crate::cpu::wait_forever`] as the panic handler's
+//!   final call when an LED is registered: a fast, even on/off blink, busy-waited with
+//!   [`crate::cpu::spin_for_cycles`] rather than parked on `wfe`, since a panic can happen before
+//!   -- or because of -- whatever would otherwise wake a parked core back up.
+//! - [`morse_blink_forever`] spells a decimal error code out in international Morse code,
+//!   repeating forever. Nothing in this fork currently detects "the console is unavailable" to
+//!   decide when to reach for this instead of just logging the error -- like [`register`], it's
+//!   real, working code waiting for that caller.
+
+use crate::{cpu, gpio, synchronization::NullLock};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A GPIO-backed ACT LED.
+pub struct Led {
+    pin: gpio::Pin,
+    active_low: bool,
+}
+
+impl Led {
+    /// Wrap `pin` (already claimed, e.g. via [`gpio::pin`]) as an LED, configuring it as an
+    /// output. `active_low` is `true` if driving the pin low turns the LED on.
+    pub fn new(pin: gpio::Pin, active_low: bool) -> Self {
+        pin.set_output();
+        Self { pin, active_low }
+    }
+
+    /// Turn the LED on or off.
+    pub fn set(&self, on: bool) {
+        if on != self.active_low {
+            self.pin.set_high();
+        } else {
+            self.pin.set_low();
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// How many [`heartbeat_step`] calls the LED stays lit for out of every [`HEARTBEAT_PERIOD`] --
+/// a brief pulse rather than an even on/off blink, so a heartbeat reads differently at a glance
+/// from [`panic_fast_blink_forever`]'s even blink.
+const HEARTBEAT_ON_TICKS: u32 = 1;
+
+/// How many [`heartbeat_step`] calls make up one heartbeat cycle.
+const HEARTBEAT_PERIOD: u32 = 8;
+
+/// Cycles [`spin_for_cycles`](cpu::spin_for_cycles)-busy-waited per Morse "unit". One dot is one
+/// unit on; one dash is three; gaps follow the standard ratios in [`morse_blink_forever`].
+const MORSE_UNIT_CYCLES: usize = 3_000_000;
+
+/// Cycles spent on, and then off, per half-cycle of [`panic_fast_blink_forever`]'s blink.
+const PANIC_BLINK_CYCLES: usize = 1_500_000;
+
+/// International Morse code for the decimal digits, indexed by digit value.
+const MORSE_DIGITS: [&str; 10] = [
+    "-----", ".----", "..---", "...--", "....-", ".....", "-....", "--...", "---..", "----.",
+];
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static LED: NullLock<Option<Led>> = NullLock::new(None);
+static HEARTBEAT_TICK: NullLock<u32> = NullLock::new(0);
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Register `led` as the board's activity indicator. See the module docs for why nothing in this
+/// fork calls this yet.
+pub fn register(led: Led) {
+    LED.lock(|slot| *slot = Some(led));
+}
+
+/// Advance the heartbeat pattern by one tick. See the module docs for the caveat that a "tick"
+/// here means "once per caller's loop iteration", not a fixed wall-clock interval.
+pub fn heartbeat_step() {
+    LED.lock(|slot| {
+        let Some(led) = slot else {
+            return;
+        };
+
+        HEARTBEAT_TICK.lock(|tick| {
+            led.set(*tick < HEARTBEAT_ON_TICKS);
+            *tick = (*tick + 1) % HEARTBEAT_PERIOD;
+        });
+    });
+}
+
+/// Blink the registered LED evenly and quickly, forever. Falls back to
+/// [`cpu::wait_forever`] if no LED is registered.
+///
+/// Meant to be the last thing a panic handler calls.
+pub fn panic_fast_blink_forever() -> ! {
+    loop {
+        let lit = LED.lock(|slot| match slot {
+            Some(led) => {
+                led.set(true);
+                true
+            }
+            None => false,
+        });
+
+        if !lit {
+            cpu::wait_forever();
+        }
+
+        cpu::spin_for_cycles(PANIC_BLINK_CYCLES);
+        LED.lock(|slot| {
+            if let Some(led) = slot {
+                led.set(false);
+            }
+        });
+        cpu::spin_for_cycles(PANIC_BLINK_CYCLES);
+    }
+}
+
+/// Spell `code`'s decimal digits out in international Morse code on the registered LED, forever.
+/// A no-op loop (parked on [`cpu::wait_forever`]) if no LED is registered.
+///
+/// Meant for reporting a boot failure too early, or too broken, for the normal console and log
+/// output to be trusted -- see the module docs for why nothing calls this yet.
+pub fn morse_blink_forever(code: u32) -> ! {
+    if LED.lock(|slot| slot.is_none()) {
+        cpu::wait_forever();
+    }
+
+    let mut digits = [0u8; 10];
+    let mut num_digits = 0;
+    let mut remaining = code;
+
+    loop {
+        digits[num_digits] = (remaining % 10) as u8;
+        num_digits += 1;
+        remaining /= 10;
+
+        if remaining == 0 {
+            break;
+        }
+    }
+
+    loop {
+        for &digit in digits[..num_digits].iter().rev() {
+            blink_morse_pattern(MORSE_DIGITS[digit as usize]);
+            // Inter-character gap: 3 units, one of which the trailing inter-symbol gap already
+            // covered.
+            cpu::spin_for_cycles(2 * MORSE_UNIT_CYCLES);
+        }
+
+        // Inter-word gap before the code repeats: 7 units, three of which the last character's
+        // trailing gaps already covered.
+        cpu::spin_for_cycles(4 * MORSE_UNIT_CYCLES);
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+/// Blink one Morse character's dots and dashes, with the standard one-unit gap between symbols.
+fn blink_morse_pattern(pattern: &str) {
+    for symbol in pattern.chars() {
+        let on_units = if symbol == '-' { 3 } else { 1 };
+
+        LED.lock(|slot| {
+            if let Some(led) = slot {
+                led.set(true);
+            }
+        });
+        cpu::spin_for_cycles(on_units * MORSE_UNIT_CYCLES);
+
+        LED.lock(|slot| {
+            if let Some(led) = slot {
+                led.set(false);
+            }
+        });
+        cpu::spin_for_cycles(MORSE_UNIT_CYCLES);
+    }
+}
@@ -0,0 +1,91 @@
+//! A host-compilable mirror of this kernel's hardware-independent modules.
+//!
+//! The `kernel` binary (`src/main.rs`) is `no_std`/`no_main` end to end, and its module graph
+//! freely mixes pure logic with MMIO-touching drivers and the assembly boot path, so `cargo test`
+//! can't build any part of it for the host, and tools like Miri -- which only run host code --
+//! can't see any of it at all. This crate re-declares, by `#[path]`, the subset of that module
+//! graph that never touches hardware, so it can be compiled and tested as an ordinary host crate:
+//!
+//! ```sh
+//! # `.cargo/config.toml` pins the default target to aarch64-unknown-none-softfloat for the
+//! # `kernel` binary, so host runs need an explicit override:
+//! cargo test --target x86_64-unknown-linux-gnu --lib
+//! cargo miri test --target x86_64-unknown-linux-gnu --lib
+//! ```
+//!
+//! while `cargo build --workspace --target aarch64-unknown-none-softfloat` (the real kernel)
+//! is unaffected -- this crate and the `kernel` binary are two independent compilations of the
+//! same source files, not a shared dependency; this file is never reached from `src/main.rs`.
+//!
+//! What's mirrored today: [`common`] (new: translation-table index/alignment math, see its
+//! docs), [`compress`] (new: no dependencies at all, not even on [`storage`]), [`profiler`] (new:
+//! the sample table and toggle only -- its `sample_tick` is `cfg(target_arch = "aarch64")` and so
+//! doesn't exist in this host build, see its docs for why), [`kprobe`] (new: branch encode/decode
+//! and the probe registry only -- `sync_instruction_cache` is `cfg(target_arch = "aarch64")` for
+//! the same reason), [`pci`] (new: config-space address math and bus enumeration, built only
+//! against the [`pci::ConfigAccess`] trait -- the BCM2711 ECAM driver behind that trait lives in
+//! `crate::bsp`, not here), [`synchronization`], [`storage`], and [`fs::fat32`]/[`fs::block_cache`]
+//! (FAT32 parsing and the block cache sitting in front of it -- both already only depend on the
+//! [`storage::interface::BlockDevice`] trait, not a concrete driver), [`xhci`] (new: the xHCI
+//! Transfer Request Block format and transfer-ring mechanics, spec-derived rather than tied to any
+//! register set -- see its docs for why no live controller hangs off it yet), [`mdio`] (new:
+//! IEEE 802.3 Clause 22 MDIO frame encoding, independent of any one MAC's register for shifting it
+//! out), [`cpu::features`] (new: `ID_AA64*`/`MIDR_EL1` bitfield decoding only -- the `MRS`
+//! reads that feed it are `cfg(target_arch = "aarch64")`, same reason as [`profiler`]'s
+//! `sample_tick`), [`crypto::hash`] (new: CRC-32 and SHA-256 against `core` alone, no board
+//! dependency at all -- see its module docs for the standard test vectors this mirror lets it be
+//! checked against), and [`fs::partition`] (new: MBR/GPT parsing against the same
+//! [`storage::interface::BlockDevice`] trait [`fs::fat32`] and [`fs::block_cache`] already mirror
+//! through, audited clean of any `crate::bsp` dependency), [`fs::tmpfs`] (new: depends on
+//! nothing but [`synchronization::NullLock`] -- no `BlockDevice`, no `crate::bsp`, just a
+//! fixed-capacity in-memory node table), and [`fs::procfs`] (new, partially: its path-dispatch
+//! logic and `meminfo` generator depend on nothing live and are mirrored and tested here, but its
+//! other generators read real kernel globals -- `crate::driver::driver_manager`,
+//! `crate::time::time_manager`, `crate::exception::asynchronous`'s counters, `crate::jobs` -- that
+//! don't exist until the board has booted, so those (and `read` itself, which calls them) stay
+//! behind `#[cfg(target_arch = "aarch64")]` and are simply absent from this host build, the same
+//! technique [`profiler`]'s `sample_tick` already uses).
+//!
+//! What's deliberately left out, to avoid mirroring a module without having actually checked it's
+//! hardware-free: [`crate::net`]'s protocol
+//! parsing (`pbuf`, `tcp`, `dns`, ...) and `crate::memory::mmu`'s translation-table *code* itself
+//! (as opposed to the math in [`common`]), both sizable enough to want their own pass rather than
+//! being folded in here; and "collections", which this fork doesn't have a module by that name for
+//! at all. Widening this mirror to more of them is follow-up work, one checked module at a time.
+#![cfg_attr(not(test), no_std)]
+
+pub mod common;
+pub mod compress;
+
+/// Host-compilable mirror of [`crate::cpu`]'s hardware-independent submodules.
+pub mod cpu {
+    pub mod features;
+}
+
+/// Host-compilable mirror of [`crate::crypto`]'s hardware-independent submodules.
+pub mod crypto {
+    pub mod hash;
+}
+
+pub mod kprobe;
+pub mod mdio;
+pub mod pci;
+pub mod profiler;
+pub mod xhci;
+
+#[path = "synchronization.rs"]
+pub mod synchronization;
+
+#[path = "storage.rs"]
+pub mod storage;
+
+/// Host-compilable mirror of [`crate::fs`]'s modules that are confirmed hardware-independent.
+///
+/// See the crate-level docs for which of [`crate::fs`]'s other submodules aren't mirrored yet.
+pub mod fs {
+    pub mod block_cache;
+    pub mod fat32;
+    pub mod partition;
+    pub mod procfs;
+    pub mod tmpfs;
+}
@@ -0,0 +1,220 @@
+//! Structured kernel log output.
+//!
+//! Every line carries the core id, uptime, severity, and a caller-supplied subsystem tag, so
+//! output interleaved from multiple cores (or just multiple drivers) stays readable instead of
+//! turning into an undifferentiated stream of `println!` calls. Colors are opt-in at runtime via
+//! [`set_color_enabled`], since not every terminal on the other end of the UART understands ANSI
+//! escapes.
+//!
+//! [`rate_limited!`] throttles a single noisy call site to at most once per interval. It's a
+//! separate mechanism from [`crate::print`]'s global character-per-second budget, which protects
+//! the UART itself against being flooded regardless of which call site is responsible.
+
+use crate::{cpu, time, time::TimeManager};
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering},
+    time::Duration,
+};
+
+pub mod persistent;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A log line's severity, ordered from least to most severe.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub(crate) const fn tag(self) -> &'static str {
+        match self {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+
+    /// The ANSI SGR sequence this level is colored with when colors are enabled.
+    const fn color(self) -> &'static str {
+        match self {
+            Level::Debug => "\x1b[2m",  // dim
+            Level::Info => "\x1b[36m",  // cyan
+            Level::Warn => "\x1b[33m",  // yellow
+            Level::Error => "\x1b[31m", // red
+        }
+    }
+
+    /// Encode as a small integer, for storing in [`MIN_LEVEL`] or in `config::persist`'s on-disk
+    /// record.
+    pub(crate) const fn to_u8(self) -> u8 {
+        match self {
+            Level::Debug => 0,
+            Level::Info => 1,
+            Level::Warn => 2,
+            Level::Error => 3,
+        }
+    }
+
+    /// The inverse of [`Level::to_u8`]; `None` for anything that isn't one of its outputs.
+    pub(crate) const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Level::Debug),
+            1 => Some(Level::Info),
+            2 => Some(Level::Warn),
+            3 => Some(Level::Error),
+            _ => None,
+        }
+    }
+}
+
+const COLOR_RESET: &str = "\x1b[0m";
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The level [`_log`] currently lets through, overriding [`crate::config::LOG_MIN_LEVEL`] once
+/// [`set_min_level`] has been called -- see that function and `config::persist`, which is what
+/// gives a shell `set` command something to load back on the next boot.
+static MIN_LEVEL: AtomicU8 = AtomicU8::new(crate::config::LOG_MIN_LEVEL.to_u8());
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Turn ANSI colors on or off for subsequent log lines.
+pub fn set_color_enabled(enabled: bool) {
+    COLOR_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether log lines are currently colorized.
+pub fn color_enabled() -> bool {
+    COLOR_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Override the minimum level [`_log`] lets through. Takes effect immediately; it isn't persisted
+/// on its own -- see `config::persist::save` for that.
+pub fn set_min_level(level: Level) {
+    MIN_LEVEL.store(level.to_u8(), Ordering::Relaxed);
+}
+
+/// The level currently in effect: [`crate::config::LOG_MIN_LEVEL`] until [`set_min_level`] has
+/// overridden it.
+pub fn min_level() -> Level {
+    Level::from_u8(MIN_LEVEL.load(Ordering::Relaxed)).unwrap_or(crate::config::LOG_MIN_LEVEL)
+}
+
+#[doc(hidden)]
+pub fn _log(level: Level, subsystem: &str, args: fmt::Arguments) {
+    if level < min_level() {
+        return;
+    }
+
+    persistent::record(level, subsystem, args);
+
+    #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+    crate::hdmi_console::record_klog_line(level, subsystem, args);
+
+    let uptime = time::time_manager().uptime();
+    let core_id = cpu::core_id();
+
+    if color_enabled() {
+        crate::print::_log_print(format_args!(
+            "{}[{:>6}.{:06}] core{} {:<5} {}: {}{}\n",
+            level.color(),
+            uptime.as_secs(),
+            uptime.subsec_micros(),
+            core_id,
+            level.tag(),
+            subsystem,
+            args,
+            COLOR_RESET
+        ));
+    } else {
+        crate::print::_log_print(format_args!(
+            "[{:>6}.{:06}] core{} {:<5} {}: {}\n",
+            uptime.as_secs(),
+            uptime.subsec_micros(),
+            core_id,
+            level.tag(),
+            subsystem,
+            args
+        ));
+    }
+}
+
+/// Log a message at [`Level::Debug`].
+macro_rules! log_debug {
+    ($tag:expr, $($arg:tt)*) => {
+        $crate::log::_log($crate::log::Level::Debug, $tag, format_args!($($arg)*))
+    };
+}
+
+/// Log a message at [`Level::Info`].
+macro_rules! log_info {
+    ($tag:expr, $($arg:tt)*) => {
+        $crate::log::_log($crate::log::Level::Info, $tag, format_args!($($arg)*))
+    };
+}
+
+/// Log a message at [`Level::Warn`].
+macro_rules! log_warn {
+    ($tag:expr, $($arg:tt)*) => {
+        $crate::log::_log($crate::log::Level::Warn, $tag, format_args!($($arg)*))
+    };
+}
+
+/// Log a message at [`Level::Error`].
+macro_rules! log_error {
+    ($tag:expr, $($arg:tt)*) => {
+        $crate::log::_log($crate::log::Level::Error, $tag, format_args!($($arg)*))
+    };
+}
+
+pub(crate) use {log_debug, log_error, log_info, log_warn};
+
+/// Log at [`Level::Info`], but at most once per `interval` for this particular call site.
+///
+/// Meant for events that are legitimate but noisy at their natural frequency (a per-packet
+/// receive path, a polling loop) where every occurrence isn't worth a line, but going silent
+/// forever would hide that the condition is still happening.
+#[doc(hidden)]
+pub fn _rate_limited(
+    last_fired_micros: &AtomicU64,
+    interval: Duration,
+    tag: &str,
+    args: fmt::Arguments,
+) {
+    let now_micros = time::time_manager().uptime().as_micros() as u64;
+    let last = last_fired_micros.load(Ordering::Relaxed);
+
+    if last != u64::MAX && now_micros.saturating_sub(last) < interval.as_micros() as u64 {
+        return;
+    }
+
+    last_fired_micros.store(now_micros, Ordering::Relaxed);
+    _log(Level::Info, tag, args);
+}
+
+/// Log a message at [`Level::Info`], suppressing repeats from this call site closer together
+/// than `interval` apart.
+macro_rules! rate_limited {
+    ($interval:expr, $tag:expr, $($arg:tt)*) => {{
+        static LAST_FIRED_MICROS: core::sync::atomic::AtomicU64 =
+            core::sync::atomic::AtomicU64::new(u64::MAX);
+
+        $crate::log::_rate_limited(&LAST_FIRED_MICROS, $interval, $tag, format_args!($($arg)*))
+    }};
+}
+
+pub(crate) use rate_limited;
@@ -0,0 +1,127 @@
+//! Persistent boot log.
+//!
+//! Lives in `.persistent_klog`, a fixed NOLOAD region the board's linker script reserves right
+//! after the boot stack -- deliberately outside `.bss` (nothing zeroes it) and outside every
+//! loaded segment (a boot loader that re-flashes the kernel image on a warm reset only overwrites
+//! what it loads, not this). That's what lets [`recover_and_print`] recover and print the
+//! previous boot's tail before this boot's own log lines start overwriting it.
+//!
+//! This is best-effort, not a guarantee: a cold boot (power cycle) or a loader that clears all of
+//! RAM before loading will see garbage, which is why a boot is only treated as "warm" when the
+//! header's magic number matches.
+
+use super::Level;
+use core::{
+    fmt::{self, Write},
+    mem, slice,
+};
+
+extern "C" {
+    static __persistent_klog_start: u8;
+    static __persistent_klog_end_exclusive: u8;
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const MAGIC: u32 = 0x4b4c_4f47; // "KLOG"
+
+#[repr(C)]
+struct Header {
+    magic: u32,
+    boot_count: u32,
+}
+
+/// A `fmt::Write` sink over a fixed-size stack buffer, truncating past capacity.
+struct LineBuf {
+    buf: [u8; 128],
+    len: usize,
+}
+
+impl LineBuf {
+    const fn new() -> Self {
+        Self {
+            buf: [0; 128],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+}
+
+impl Write for LineBuf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let remaining = self.buf.len() - self.len;
+        let n = s.len().min(remaining);
+
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+/// Reinterpret the reserved region as `(header, tail buffer)`.
+///
+/// # Safety
+///
+/// - Exclusive access holds by convention only: nothing else in this fork touches
+///   `.persistent_klog`.
+unsafe fn region() -> (*mut Header, &'static mut [u8]) {
+    let start = &__persistent_klog_start as *const u8 as *mut u8;
+    let end = &__persistent_klog_end_exclusive as *const u8 as *mut u8;
+    let total_len = end.offset_from(start) as usize;
+
+    let header = start as *mut Header;
+    let tail_start = start.add(mem::size_of::<Header>());
+    let tail_len = total_len - mem::size_of::<Header>();
+
+    (header, slice::from_raw_parts_mut(tail_start, tail_len))
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// If the region holds a previous boot's log, print its tail and bump the boot counter;
+/// otherwise initialize the region and treat this as a cold start. Returns the new boot count.
+pub fn recover_and_print() -> u32 {
+    unsafe {
+        let (header, tail) = region();
+
+        if (*header).magic != MAGIC {
+            (*header).magic = MAGIC;
+            (*header).boot_count = 0;
+            tail.fill(0);
+            return 0;
+        }
+
+        let text = core::str::from_utf8(tail).unwrap_or("").trim_matches('\0');
+        if !text.is_empty() {
+            crate::println!("[klog] previous boot's tail:");
+            crate::println!("{}", text);
+        }
+
+        (*header).boot_count += 1;
+        (*header).boot_count
+    }
+}
+
+/// Format one log line and append it to the persistent tail, evicting the oldest bytes if it
+/// doesn't fit.
+pub(super) fn record(level: Level, subsystem: &str, args: fmt::Arguments) {
+    let mut line = LineBuf::new();
+    let _ = write!(line, "{} {}: {}\n", level.tag(), subsystem, args);
+
+    unsafe {
+        let (_, tail) = region();
+        let bytes = line.as_str().as_bytes();
+        let n = bytes.len().min(tail.len());
+
+        tail.copy_within(n.., 0);
+        let dst_start = tail.len() - n;
+        tail[dst_start..].copy_from_slice(&bytes[bytes.len() - n..]);
+    }
+}
@@ -0,0 +1,69 @@
+//! GPU memory allocation via the VideoCore mailbox.
+//!
+//! Only meaningful on real Raspberry Pi hardware -- QEMU's `virt` machine emulates no
+//! VideoCore/mailbox peripheral to back this, so this module only exists under
+//! `bsp_rpi3`/`bsp_rpi4`. See `bsp::device_driver::Mailbox` for the driver itself.
+
+use crate::bsp;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Mailbox interfaces.
+pub mod interface {
+    /// A memory allocation flag, as defined by the VideoCore mailbox property interface.
+    #[derive(Copy, Clone)]
+    pub enum MemFlag {
+        Normal,
+        Direct,
+        Coherent,
+    }
+
+    /// GPU memory allocation functions.
+    pub trait GpuMemory {
+        /// Allocate `size` bytes of GPU-visible memory aligned to `align`, returning an opaque
+        /// handle.
+        fn gpu_mem_alloc(&self, size: u32, align: u32, flags: MemFlag)
+            -> Result<u32, &'static str>;
+
+        /// Lock a handle from [`gpu_mem_alloc`](GpuMemory::gpu_mem_alloc) and return its bus
+        /// address.
+        fn lock(&self, handle: u32) -> Result<u32, &'static str>;
+
+        /// Unlock a handle previously returned by [`lock`](GpuMemory::lock).
+        fn unlock(&self, handle: u32) -> Result<(), &'static str>;
+
+        /// Release a handle from [`gpu_mem_alloc`](GpuMemory::gpu_mem_alloc), freeing the memory
+        /// it refers to.
+        fn free(&self, handle: u32) -> Result<(), &'static str>;
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Allocate `size` bytes of GPU-visible memory aligned to `align`, returning an opaque handle.
+pub fn gpu_mem_alloc(
+    size: u32,
+    align: u32,
+    flags: interface::MemFlag,
+) -> Result<u32, &'static str> {
+    bsp::mailbox::mailbox().gpu_mem_alloc(size, align, flags)
+}
+
+/// Lock a handle from [`gpu_mem_alloc`] and return its bus address.
+pub fn lock(handle: u32) -> Result<u32, &'static str> {
+    bsp::mailbox::mailbox().lock(handle)
+}
+
+/// Unlock a handle previously returned by [`lock`].
+pub fn unlock(handle: u32) -> Result<(), &'static str> {
+    bsp::mailbox::mailbox().unlock(handle)
+}
+
+/// Release a handle from [`gpu_mem_alloc`], freeing the memory it refers to.
+pub fn free(handle: u32) -> Result<(), &'static str> {
+    bsp::mailbox::mailbox().free(handle)
+}
@@ -0,0 +1,753 @@
+//! The `kernel` binary.
+//!
+//! # Code organization and architecture
+//!
+//! The code is divided into different *modules*, each representing a typical **subsystem** of
+//! the `kernel`. Top-level module files are located directly in the `src` folder. For example,
+//! `src/memory.rs` contains code that is concerned with all things memory management.
+//!
+//! ## Visibility of processor architecture code
+//!
+//! Some of the kernel's code is processor architecture specific. All these bits are held in
+//! `src/cpu`. The code inside this folder is conditionally included based on the target
+//! architecture, but is only ever intended to target `aarch64` in this fork.
+//!
+//! ## BSP code
+//!
+//! `BSP` stands for Board Support Package. `BSP` code is organized under `src/bsp.rs` and contains
+//! target board specific definitions and functions. These are things like the board's memory map
+//! or instances of drivers for devices that are featured on the respective board. Which of the
+//! BSPs is compiled is decided at build time via the cargo feature flags `bsp_rpi3`, `bsp_rpi4`
+//! and `bsp_qemu_virt`.
+//!
+//! ## Kernel interfaces
+//!
+//! Both `cpu` and `bsp` contain code that is conditionally compiled depending on the actual
+//! target and board. In order to provide a clean abstraction between `arch`, `bsp` and generic
+//! kernel code, the traits defined in `interface.rs` submodules of the respective subsystem are
+//! used.
+#![allow(clippy::upper_case_acronyms)]
+#![allow(dead_code)]
+#![no_main]
+#![no_std]
+
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod audio;
+mod bootselect;
+mod bsp;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod clocks;
+mod compress;
+mod config;
+mod console;
+mod coredump;
+mod cpu;
+mod crashdump;
+mod crypto;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod debug_jtag;
+mod debug_watchpoint;
+mod devicetree;
+mod dma;
+mod driver;
+mod exception;
+mod executor;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod framebuffer;
+mod fs;
+mod gfx;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod gpio;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod hdmi_console;
+#[cfg(feature = "profile_debug")]
+mod heap_guard;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod i2c;
+mod image_header;
+mod input;
+mod ipc;
+mod irq_replay;
+mod jobs;
+mod kassert;
+mod kmod;
+mod kprobe;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod led;
+mod log;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod mailbox;
+mod mdio;
+mod memory;
+mod net;
+mod panic_wait;
+mod pci;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod pinctrl;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod power;
+mod print;
+mod process;
+mod profiler;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod sensors;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod spi;
+mod storage;
+#[cfg(feature = "stress")]
+mod stress;
+mod synchronization;
+mod testing;
+mod time;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod touch;
+mod trace;
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+mod video;
+mod xhci;
+
+/// Early init code.
+///
+/// # Safety
+///
+/// - Only a single core must be active and running this function.
+/// - The init calls in this function must appear in the correct order.
+unsafe fn kernel_init() -> ! {
+    use memory::mmu::MMU;
+
+    log::persistent::recover_and_print();
+
+    log::log_info!("config", "active profile: {:?}", config::PROFILE);
+
+    if let Err(x) = cpu::assert_linker_layout() {
+        panic!("linker layout: {}", x);
+    }
+    kassert::register_invariant_check("linker_layout", cpu::assert_linker_layout);
+
+    cpu::features::log_detected();
+
+    let slot = bootselect::record_boot_attempt();
+    log::log_info!("bootselect", "booting slot {:?}", slot);
+
+    exception::handling_init();
+
+    if let Err(x) = memory::mmu::mmu().enable_mmu_and_caching() {
+        panic!("MMU: {}", x);
+    }
+    kassert::register_invariant_check("mmu", || {
+        if memory::mmu::mmu().is_enabled() {
+            Ok(())
+        } else {
+            Err("MMU is not enabled")
+        }
+    });
+
+    if let Err(x) = bsp::driver::init() {
+        panic!("Error registering BSP drivers: {}", x);
+    }
+    driver::driver_manager().init_drivers();
+    kassert::register_invariant_check("driver_manager", || {
+        let mut num_drivers = 0;
+        driver::driver_manager().all_device_compatible(|_| num_drivers += 1);
+
+        if num_drivers > 0 {
+            Ok(())
+        } else {
+            Err("no drivers are registered")
+        }
+    });
+
+    #[cfg(feature = "early_console")]
+    console::early::replay(console::console());
+
+    log::log_info!("boot", "all drivers initialized");
+
+    // Export a symbol future kernel modules could relocate against.
+    if let Err(x) = kmod::export_symbol("printk", print::_print as *const () as usize) {
+        log::log_warn!("kmod", "{}", x);
+    }
+
+    // Not yet driven by a periodic timer callback; run once here as a boot-time self-test.
+    kassert::run_invariant_checks();
+
+    // Exercise the async executor before handing off to the interactive main loop.
+    executor::block_on(time::sleep_async(core::time::Duration::from_millis(1)));
+    log::log_debug!("executor", "async self-test complete");
+
+    // IPC self-test: a channel should round-trip a value, and `select2` should notice it.
+    static IPC_SELF_TEST: ipc::Channel<u8, 1> = ipc::Channel::new();
+    IPC_SELF_TEST.send(0xaa);
+    match executor::block_on(ipc::select2(&IPC_SELF_TEST, &IPC_SELF_TEST)) {
+        ipc::Either::Left(value) | ipc::Either::Right(value) => {
+            kassert::kassert!(value == 0xaa, "ipc", "self-test channel round-trip failed");
+        }
+    }
+
+    // Apply a previously-persisted config, if this board has somewhere to load one from -- see
+    // config::persist's module docs for which boards that is.
+    match config::persist::load() {
+        Ok(persisted) => {
+            log::set_min_level(persisted.log_level);
+            log::log_info!(
+                "config",
+                "loaded {:?} from {}",
+                persisted,
+                config::persist::FILE_NAME
+            );
+        }
+        Err(x) => log::log_debug!("config", "no persisted config loaded: {}", x),
+    }
+
+    // Reached the interactive main loop: this boot counts as a success.
+    bootselect::mark_boot_ok();
+
+    kernel_main()
+}
+
+/// The main function running after the early init.
+fn kernel_main() -> ! {
+    use console::{console, line_edit::LineResult};
+
+    println!(
+        "[0] {} version {}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+    println!("[1] Booting on: {}", bsp::board_name());
+
+    println!("[2] Drivers loaded:");
+    let mut i = 1;
+    driver::driver_manager().all_device_compatible(|compatible| {
+        println!("      {}. {}", i, compatible);
+        i += 1;
+    });
+
+    println!("[3] Chars written: {}", console().chars_written());
+    println!("[4] Echoing input now, one line at a time");
+
+    let mut editor = console::line_edit::LineEditor::new();
+
+    loop {
+        // Run any IrqMode::Threaded handlers queued since the last line -- see that mode's docs
+        // in crate::exception::asynchronous for why this loop, rather than a dedicated thread, is
+        // what drains it today.
+        exception::asynchronous::run_deferred_handlers();
+
+        // Advance every background job by one time slice -- see crate::jobs for why this, and not
+        // a dedicated thread, is what drives them.
+        jobs::poll_all();
+
+        // Pulse the ACT LED heartbeat, if a board has registered one. Same "ticked by activity,
+        // not wall-clock time" caveat as the two calls above -- see crate::led.
+        #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+        led::heartbeat_step();
+
+        // Record one profiling sample per loop iteration, if the profiler is running -- see
+        // crate::profiler for why this, and not a real timer interrupt, is what drives it today.
+        profiler::sample_tick();
+
+        match editor.read_line(console(), console()) {
+            LineResult::Line(line) => handle_line(&line),
+            LineResult::Eof => println!("^D"),
+            LineResult::Interrupted => {
+                process::signal::post(process::signal::Signal::Kill);
+                println!("^C");
+            }
+        }
+    }
+}
+
+/// Handle one line of input from the interactive loop in [`kernel_main`].
+///
+/// There's no real command shell in this fork yet -- this matches the line against a fixed set of
+/// built-in commands (`jobs`, `ps`, `kill`, `lsblk`, `profile`, `trace`, ...) and otherwise echoes
+/// it back.
+fn handle_line(line: &str) {
+    if let Some(spec) = line.strip_suffix('&') {
+        match jobs::spawn(spec.trim()) {
+            Ok(id) => println!("[{}] started", id),
+            Err(x) => println!("{}", x),
+        }
+        return;
+    }
+
+    if line == "jobs" {
+        let mut any = false;
+        jobs::list(|id, name, priority| {
+            any = true;
+            println!("  [{}] {} ({})", id, name, priority.tag());
+        });
+        if !any {
+            println!("  no background jobs");
+        }
+        return;
+    }
+
+    // There's only one core in this fork (see crate::stress's module docs) and so only one run
+    // queue to show -- not the per-core table a real `top` would page through.
+    if line == "top" {
+        let mut count = 0;
+        println!("{:<4} {:<8} {}", "ID", "PRIORITY", "NAME");
+        jobs::list(|id, name, priority| {
+            count += 1;
+            println!("{:<4} {:<8} {}", id, priority.tag(), name);
+        });
+        println!("{}/{} slots in use", count, jobs::MAX_JOBS);
+        return;
+    }
+
+    if line == "ps" {
+        let mut count = 0;
+        println!(
+            "{:<4} {:<8} {:<10} {:<10} {}",
+            "PID", "PRIORITY", "CPU_MS", "LIMIT_MS", "NAME"
+        );
+        jobs::stats(|id, name, priority, cpu_time, cpu_limit| {
+            count += 1;
+            match cpu_limit {
+                Some(limit) => println!(
+                    "{:<4} {:<8} {:<10} {:<10} {}",
+                    id,
+                    priority.tag(),
+                    cpu_time.as_millis(),
+                    limit.as_millis(),
+                    name
+                ),
+                None => println!(
+                    "{:<4} {:<8} {:<10} {:<10} {}",
+                    id,
+                    priority.tag(),
+                    cpu_time.as_millis(),
+                    "-",
+                    name
+                ),
+            }
+        });
+        if count == 0 {
+            println!("  no background jobs");
+        }
+        return;
+    }
+
+    if line == "uptime" {
+        let uptime = time::time_manager().uptime();
+        println!(
+            "up {}.{:02}s",
+            uptime.as_secs(),
+            uptime.subsec_millis() / 10
+        );
+        return;
+    }
+
+    // No frame allocator or heap exists in this fork (see crate::memory and
+    // fs::procfs's module docs), so there's nothing real to total up or report free -- this
+    // says so instead of inventing numbers the way a real `free` would print.
+    if line == "free" {
+        println!("Mem: unknown -- this fork has no frame allocator or heap to report on");
+        return;
+    }
+
+    if let Some(id) = line.strip_prefix("kill ") {
+        match id.trim().parse::<jobs::JobId>() {
+            Ok(id) => match jobs::kill(id) {
+                Ok(()) => println!("[{}] killed", id),
+                Err(x) => println!("{}", x),
+            },
+            Err(_) => println!("kill: usage: kill <id>"),
+        }
+        return;
+    }
+
+    #[cfg(feature = "bsp_qemu_virt")]
+    if line == "lsblk" {
+        print_partition_table();
+        return;
+    }
+
+    #[cfg(feature = "bsp_qemu_virt")]
+    if let Some(args) = line.strip_prefix("sdlog") {
+        handle_sdlog(args.trim());
+        return;
+    }
+
+    if let Some(args) = line.strip_prefix("profile") {
+        handle_profile(args.trim());
+        return;
+    }
+
+    #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+    if line == "pinmap" {
+        print_pin_map();
+        return;
+    }
+
+    #[cfg(feature = "stress")]
+    if line == "stress" {
+        stress::run();
+        return;
+    }
+
+    if line == "lsdev" {
+        print_driver_table();
+        return;
+    }
+
+    if line == "stackinfo" {
+        println!(
+            "  boot core stack: {}/{} bytes used",
+            cpu::stack_high_watermark(),
+            config::BOOT_CORE_STACK_SIZE
+        );
+        return;
+    }
+
+    if line == "muxon" {
+        // Switch first so a host demultiplexer sees this confirmation already framed.
+        console::mux::set_mode(console::mux::Mode::Framed);
+        println!("console: framed (klog/shell channels, see console::mux)");
+        return;
+    }
+
+    if line == "muxoff" {
+        // Switch last so this confirmation is the final framed line, not a raw one a still-framed
+        // host would misparse as a frame header.
+        println!("console: raw");
+        console::mux::set_mode(console::mux::Mode::Raw);
+        return;
+    }
+
+    if line == "get loglevel" {
+        println!("{}", log::min_level().tag());
+        return;
+    }
+
+    if let Some(value) = line.strip_prefix("set loglevel ") {
+        set_log_level(value.trim());
+        return;
+    }
+
+    if line == "trace clear" {
+        trace::clear();
+        println!("trace: cleared");
+        return;
+    }
+
+    if line == "trace" {
+        trace::dump_chrome_json(console::console());
+        return;
+    }
+
+    if let Some(args) = line.strip_prefix("watch ") {
+        handle_watch(args.trim());
+        return;
+    }
+
+    if let Some(args) = line.strip_prefix("kprobe ") {
+        handle_kprobe(args.trim());
+        return;
+    }
+
+    #[cfg(feature = "profile_debug")]
+    if let Some(args) = line.strip_prefix("heapcheck") {
+        handle_heapcheck(args.trim());
+        return;
+    }
+
+    #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+    if let Some(args) = line.strip_prefix("vidmode ") {
+        handle_vidmode(args.trim());
+        return;
+    }
+
+    #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+    if line == "jtag" {
+        debug_jtag::enable();
+        println!("jtag: GPIO 22-27 routed to ALT4");
+        return;
+    }
+
+    println!("{}", line);
+}
+
+/// Run or poke [`heap_guard`]'s demonstration buffer, for the `heapcheck` shell command.
+///
+/// `heapcheck` alone just checks; `heapcheck corrupt` stomps a redzone first and `heapcheck uaf`
+/// writes through a freed buffer first, so the command can demonstrate catching both without a
+/// real driver bug to wait for.
+#[cfg(feature = "profile_debug")]
+fn handle_heapcheck(args: &str) {
+    match args {
+        "" => {}
+        "corrupt" => heap_guard::corrupt_demo(),
+        "uaf" => heap_guard::use_after_free_demo(),
+        _ => {
+            println!("heapcheck: usage: heapcheck [corrupt|uaf]");
+            return;
+        }
+    }
+
+    match heap_guard::check_demo() {
+        Ok(()) => println!("heapcheck: ok"),
+        Err(x) => println!("{}", x),
+    }
+}
+
+/// Parse `<width> <height> <depth>` and try to negotiate it as the display mode, for the
+/// `vidmode` shell command.
+///
+/// See [`video::set_mode`] for the fallback chain this runs through before giving up.
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+fn handle_vidmode(args: &str) {
+    let mut words = args.split_whitespace();
+    let parsed = (|| {
+        let width: u32 = words.next()?.parse().ok()?;
+        let height: u32 = words.next()?.parse().ok()?;
+        let depth: u32 = words.next()?.parse().ok()?;
+        Some((width, height, depth))
+    })();
+
+    let Some((width, height, depth)) = parsed else {
+        println!("vidmode: usage: vidmode <width> <height> <depth>");
+        return;
+    };
+
+    match video::set_mode(width, height, depth) {
+        Ok(()) => println!("vidmode: ok"),
+        Err(x) => println!("{}", x),
+    }
+}
+
+/// Parse `<addr_hex> <len> <r|w|rw>` and try to arm it as a hardware watchpoint, for the `watch`
+/// shell command.
+///
+/// Always reports a refusal today -- see [`debug_watchpoint`]'s module docs for why.
+fn handle_watch(args: &str) {
+    let mut words = args.split_whitespace();
+
+    let address = match words
+        .next()
+        .and_then(|w| usize::from_str_radix(w.trim_start_matches("0x"), 16).ok())
+    {
+        Some(address) => address,
+        None => {
+            println!("watch: usage: watch <addr_hex> <len> <r|w|rw>");
+            return;
+        }
+    };
+
+    let len: u8 = match words.next().and_then(|w| w.parse().ok()) {
+        Some(len) => len,
+        None => {
+            println!("watch: usage: watch <addr_hex> <len> <r|w|rw>");
+            return;
+        }
+    };
+
+    let access = match words.next() {
+        Some("r") => debug_watchpoint::Access::Read,
+        Some("w") => debug_watchpoint::Access::Write,
+        Some("rw") => debug_watchpoint::Access::ReadWrite,
+        _ => {
+            println!("watch: usage: watch <addr_hex> <len> <r|w|rw>");
+            return;
+        }
+    };
+
+    match debug_watchpoint::WatchpointRequest::new(address, len, access)
+        .and_then(debug_watchpoint::arm)
+    {
+        Ok(()) => println!("watch: armed"),
+        Err(x) => println!("{}", x),
+    }
+}
+
+/// Register a probe callback that logs each hit on `<addr_hex>` and try to arm it, for the `kprobe`
+/// shell command.
+///
+/// Always reports a refusal today -- see [`kprobe`]'s module docs for why.
+fn handle_kprobe(args: &str) {
+    let address = match usize::from_str_radix(args.trim_start_matches("0x"), 16) {
+        Ok(address) => address,
+        Err(_) => {
+            println!("kprobe: usage: kprobe <addr_hex>");
+            return;
+        }
+    };
+
+    fn log_probe_hit(addr: usize) {
+        log::log_info!("kprobe", "hit at {:#x}", addr);
+    }
+
+    let result = kprobe::register(address, log_probe_hit).and_then(kprobe::arm);
+    match result {
+        Ok(()) => println!("kprobe: armed"),
+        Err(x) => println!("{}", x),
+    }
+}
+
+/// Parse `value` as a log level name, apply it, and try to persist it for the next boot, for the
+/// `set loglevel <level>` shell command.
+fn set_log_level(value: &str) {
+    let level = match value.to_ascii_lowercase().as_str() {
+        "debug" => log::Level::Debug,
+        "info" => log::Level::Info,
+        "warn" => log::Level::Warn,
+        "error" => log::Level::Error,
+        _ => {
+            println!(
+                "set loglevel: unknown level {:?} (try debug/info/warn/error)",
+                value
+            );
+            return;
+        }
+    };
+
+    log::set_min_level(level);
+
+    // Load whatever's already on disk first, so saving a new log level doesn't clobber a network
+    // config nothing here has touched.
+    let mut persisted = config::persist::load().unwrap_or(config::persist::Config {
+        log_level: level,
+        network: None,
+    });
+    persisted.log_level = level;
+
+    match config::persist::save(&persisted) {
+        Ok(()) => println!("loglevel: {} (saved)", level.tag()),
+        Err(x) => println!("loglevel: {} (not saved: {})", level.tag(), x),
+    }
+}
+
+/// Print every registered driver's compatible string, MMIO range, IRQ number, and init duration.
+///
+/// The same data [`fs::procfs`]'s `drivers` file reports, just to the console instead of a
+/// caller-supplied buffer.
+fn print_driver_table() {
+    driver::driver_manager().enumerate(|info| {
+        print!("  {:<24}", info.compatible);
+
+        match info.mmio {
+            Some(mmio) => print!(" mmio={:#010x}+{:#x}", mmio.start_addr(), mmio.size()),
+            None => print!(" mmio=-"),
+        }
+
+        match info.irq_number {
+            Some(irq) => print!(" irq={}", irq),
+            None => print!(" irq=-"),
+        }
+
+        match info.init_duration {
+            Some(duration) => println!(" init_us={}", duration.as_micros()),
+            None => println!(" init_us=-"),
+        }
+    });
+}
+
+/// Print every GPIO pin currently claimed through [`pinctrl`], and by whom.
+#[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+fn print_pin_map() {
+    pinctrl::print_map(|pin, function, owner| {
+        println!("  GPIO{:<3} {:<12} owner={}", pin, function, owner);
+    });
+}
+
+/// Print the partition table of the board's block device, if it has one.
+#[cfg(feature = "bsp_qemu_virt")]
+fn print_partition_table() {
+    let device = bsp::driver::block_device();
+
+    match fs::partition::read_partition_table(device) {
+        Ok(table) => {
+            for entry in table.iter() {
+                println!(
+                    "  type=0x{:02x} start_lba={} blocks={}",
+                    entry.partition_type, entry.start_lba, entry.block_count
+                );
+            }
+        }
+        Err(x) => println!("lsblk: {}", x),
+    }
+}
+
+/// Number of blocks [`sdlog_region`] reserves at the end of the board's block device.
+#[cfg(feature = "bsp_qemu_virt")]
+const SDLOG_REGION_BLOCKS: u64 = 64;
+
+/// The board's [`fs::sdlog::SdLog`], parked in the last [`SDLOG_REGION_BLOCKS`] blocks of the
+/// device -- outside whatever a partition table claims at the front, the same "live where nothing
+/// else is looking" reasoning [`log::persistent`]'s linker-reserved RAM region uses.
+#[cfg(feature = "bsp_qemu_virt")]
+fn sdlog_region() -> fs::sdlog::SdLog<'static> {
+    let device = bsp::driver::block_device();
+    let start = device.block_count().saturating_sub(SDLOG_REGION_BLOCKS);
+    fs::sdlog::SdLog::new(device, start, device.block_count() - start)
+}
+
+/// Append to or dump the on-disk circular log, for the `sdlog` shell command.
+///
+/// `sdlog` alone dumps every valid record; `sdlog <message>` appends one at [`log::Level::Info`]
+/// under the `"shell"` subsystem, so the facility can be exercised without waiting for a real
+/// power-loss test.
+#[cfg(feature = "bsp_qemu_virt")]
+fn handle_sdlog(args: &str) {
+    let region = sdlog_region();
+
+    if args.is_empty() {
+        let mut count = 0;
+        if let Err(x) = region.for_each(|seq, level, subsystem, message| {
+            count += 1;
+            println!("[{}] {} {}: {}", seq, level.tag(), subsystem, message);
+        }) {
+            println!("{}", x);
+            return;
+        }
+        if count == 0 {
+            println!("  sdlog region is empty");
+        }
+        return;
+    }
+
+    match region.append(log::Level::Info, "shell", args) {
+        Ok(seq) => println!("sdlog: wrote record {}", seq),
+        Err(x) => println!("{}", x),
+    }
+}
+
+/// `profile start|stop|reset|dump`, for the sampling profiler -- see [`profiler`] for what drives
+/// a sample today and why it isn't yet a real timer interrupt.
+fn handle_profile(args: &str) {
+    match args {
+        "start" => {
+            profiler::start();
+            println!("profile: running");
+        }
+        "stop" => {
+            profiler::stop();
+            println!("profile: stopped");
+        }
+        "reset" => {
+            profiler::reset();
+            println!("profile: samples cleared");
+        }
+        "" | "dump" => {
+            let (total, dropped) = profiler::stats();
+            println!(
+                "profile: {} -- {} samples, {} dropped",
+                if profiler::is_running() {
+                    "running"
+                } else {
+                    "stopped"
+                },
+                total,
+                dropped
+            );
+            profiler::for_each(|addr, count| match kmod::resolve_symbol(addr) {
+                Some((name, offset)) => {
+                    println!("  {:#018x} {:>6} {}+{:#x}", addr, count, name, offset)
+                }
+                None => println!("  {:#018x} {:>6} ?", addr, count),
+            });
+        }
+        _ => println!("profile: usage: profile start|stop|reset|dump"),
+    }
+}
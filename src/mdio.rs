@@ -0,0 +1,98 @@
+//! IEEE 802.3 Clause 22 MDIO frame encoding.
+//!
+//! Every MAC driver that talks to an external or on-die PHY over MDIO -- this fork's one
+//! candidate today is [`crate::bsp::device_driver::bcm::bcm2xxx_genet`]'s GENET v5 MAC -- drives
+//! the same 32-bit Clause 22 frame regardless of whose MAC or PHY silicon it is; only the register
+//! a MAC exposes to shift that frame out (GENET's included) is vendor-specific. Kept separate from
+//! any one MAC driver so it's real, reusable, and host-testable on its own, the same role
+//! [`crate::bsp::device_driver::bcm::bcm2xxx_emmc`]'s SD protocol math plays for EMMC.
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A Clause 22 MDIO transaction's operation code (frame bits 29:28, the `OP` field).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// Read the addressed register.
+    Read,
+    /// Write [`encode_frame`]'s `data` into the addressed register.
+    Write,
+}
+
+/// Standard Clause 22 PHY register addresses every compliant PHY implements.
+pub mod register {
+    /// Basic Mode Control Register: speed, duplex, autonegotiation enable/restart, reset.
+    pub const BMCR: u8 = 0x00;
+    /// Basic Mode Status Register: link-up, autonegotiation-complete, capability bits.
+    pub const BMSR: u8 = 0x01;
+}
+
+/// `BMSR`'s link-status bit (bit 2): set means the PHY currently has a valid link.
+const BMSR_LINK_STATUS: u16 = 1 << 2;
+
+/// Encode a 32-bit Clause 22 MDIO frame: `ST` (start, `01`), `OP` (`10` read / `01` write), 5-bit
+/// `PHYAD`, 5-bit `REGAD`, `TA` (turnaround, `10` on a write, don't-care driven by the PHY on a
+/// read), and 16 bits of data (the value to write, or `0` on a read).
+///
+/// `phy_address`/`register_address` are truncated to 5 bits, per the frame format.
+pub fn encode_frame(operation: Operation, phy_address: u8, register_address: u8, data: u16) -> u32 {
+    let st: u32 = 0b01;
+    let op: u32 = match operation {
+        Operation::Read => 0b10,
+        Operation::Write => 0b01,
+    };
+    let phyad = (phy_address & 0x1f) as u32;
+    let regad = (register_address & 0x1f) as u32;
+    let ta: u32 = match operation {
+        Operation::Read => 0b00,
+        Operation::Write => 0b10,
+    };
+
+    (st << 30) | (op << 28) | (phyad << 23) | (regad << 18) | (ta << 16) | data as u32
+}
+
+/// Decode the 16-bit data field out of a completed read's frame, as shifted back in from the PHY.
+pub fn decode_read_data(frame: u32) -> u16 {
+    frame as u16
+}
+
+/// Whether `bmsr` (a read of [`register::BMSR`]) reports the link as up.
+pub fn link_is_up(bmsr: u16) -> bool {
+    bmsr & BMSR_LINK_STATUS != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_a_read_frame() {
+        let frame = encode_frame(Operation::Read, 0x01, register::BMSR, 0);
+        assert_eq!(frame >> 30, 0b01); // ST
+        assert_eq!((frame >> 28) & 0b11, 0b10); // OP = read
+        assert_eq!((frame >> 23) & 0x1f, 0x01); // PHYAD
+        assert_eq!((frame >> 18) & 0x1f, register::BMSR as u32); // REGAD
+    }
+
+    #[test]
+    fn encodes_a_write_frame_with_data() {
+        let frame = encode_frame(Operation::Write, 0x00, register::BMCR, 0x1200);
+        assert_eq!((frame >> 28) & 0b11, 0b01); // OP = write
+        assert_eq!((frame >> 16) & 0b11, 0b10); // TA
+        assert_eq!(decode_read_data(frame), 0x1200);
+    }
+
+    #[test]
+    fn truncates_out_of_range_addresses_to_five_bits() {
+        let frame = encode_frame(Operation::Read, 0xff, 0xff, 0);
+        assert_eq!((frame >> 23) & 0x1f, 0x1f);
+        assert_eq!((frame >> 18) & 0x1f, 0x1f);
+    }
+
+    #[test]
+    fn link_is_up_reads_the_bmsr_link_status_bit() {
+        assert!(link_is_up(0b0000_0000_0000_0100));
+        assert!(!link_is_up(0b0000_0000_0000_0000));
+    }
+}
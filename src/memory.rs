@@ -0,0 +1,79 @@
+//! Memory management.
+
+pub mod mmap;
+pub mod mmu;
+pub mod user;
+
+use core::ops::RangeInclusive;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Who may access a mapped region, once a real translation-table walker exists to encode this as
+/// actual attribute bits -- see [`mmu`]'s module docs for why none of this is enforced yet.
+///
+/// Every existing [`MMIODescriptor`] in this fork is implicitly [`Self::KernelOnly`]: nothing here
+/// runs at EL0, so there has never been a region that needed anything else. This exists for the
+/// first one that will, e.g. a vDSO-style time page a future user process reads directly instead
+/// of trapping into the kernel for the time of day.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AccessPermissions {
+    /// Only EL1 (the kernel) may access this region. The default.
+    KernelOnly,
+    /// EL0 may both read and write this region.
+    ReadWriteEL0,
+    /// EL0 may read this region, but not write it.
+    ReadOnlyEL0,
+}
+
+/// Describes the characteristics of a translatable region of memory.
+#[derive(Copy, Clone, Debug)]
+pub struct MMIODescriptor {
+    start_addr: usize,
+    size: usize,
+    access: AccessPermissions,
+}
+
+impl MMIODescriptor {
+    /// Create an instance. Defaults to [`AccessPermissions::KernelOnly`] -- see
+    /// [`Self::with_access_permissions`] to mark a region as intentionally EL0-accessible.
+    pub const fn new(start_addr: usize, size: usize) -> Self {
+        Self {
+            start_addr,
+            size,
+            access: AccessPermissions::KernelOnly,
+        }
+    }
+
+    /// Return a copy of this descriptor with `access` in place of the default
+    /// [`AccessPermissions::KernelOnly`].
+    pub const fn with_access_permissions(self, access: AccessPermissions) -> Self {
+        Self { access, ..self }
+    }
+
+    /// Return the access permissions this region was declared with.
+    pub const fn access_permissions(&self) -> AccessPermissions {
+        self.access
+    }
+
+    /// Return the start address.
+    pub const fn start_addr(&self) -> usize {
+        self.start_addr
+    }
+
+    /// Return the size in bytes.
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Return the last address covered by this descriptor (an inclusive end).
+    pub const fn end_addr_inclusive(&self) -> usize {
+        self.start_addr + self.size - 1
+    }
+
+    /// Return the inclusive address range covered by this descriptor.
+    pub fn range_inclusive(&self) -> RangeInclusive<usize> {
+        self.start_addr..=self.end_addr_inclusive()
+    }
+}
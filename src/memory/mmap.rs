@@ -0,0 +1,66 @@
+//! Memory-mapped file support (`mmap`).
+//!
+//! Backing a real `mmap` needs four things this fork doesn't have: a VFS to read pages from by
+//! file offset instead of a directly-called concrete driver (`crate::fs::fat32`/`tmpfs`/`procfs`
+//! are all reached directly, see `crate::fs`'s module doc), a frame allocator to hand out physical
+//! pages (`crate::memory` has none), per-process page tables to map those frames into
+//! (`crate::memory::mmu` only flips an "enabled" flag; it doesn't walk or build translation
+//! tables, and `crate::process::spawn_elf`'s doc notes the same gap), and a synchronous exception
+//! handler to demand-fault pages in on first access (`crate::exception` installs no vector
+//! table at all -- see [`crate::memory::user`]'s doc for the same gap). Until all four exist,
+//! [`mmap`] validates what it actually can -- that the file exists and the requested range fits
+//! inside it -- and then fails closed instead of pretending to map anything.
+//!
+//! Validation opens the file through [`crate::fs::tmpfs`] specifically, rather than some
+//! filesystem-generic path: with no VFS trait to call through (see above), something has to pick
+//! a concrete backend, and tmpfs is the only one that's a self-contained global instance --
+//! `fat32` needs a mounted block device threaded through, `procfs` files don't have a size to
+//! check a range against.
+
+use crate::fs::tmpfs;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// How a mapping behaves with respect to other mappings of the same file and writes back to it.
+///
+/// Only `ReadOnlyShared` is meaningful without copy-on-write page tables, which this fork also
+/// doesn't have -- see the module docs -- so `Private` is included for API completeness but
+/// rejected by [`mmap`] today just the same as everything else.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Protection {
+    /// Multiple mappings of the same file share the same physical frames; writes are rejected.
+    ReadOnlyShared,
+    /// A private, copy-on-write mapping.
+    Private,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Map `length` bytes of the tmpfs file at `path`, starting at file offset `offset`.
+///
+/// Validates the file and range for real, then fails closed on the frame allocator/page
+/// table/page-fault handler this fork doesn't have yet -- see the module docs.
+pub fn mmap(
+    path: &str,
+    offset: usize,
+    length: usize,
+    protection: Protection,
+) -> Result<usize, &'static str> {
+    let _ = protection;
+
+    if length == 0 {
+        return Err("mmap: length must be nonzero");
+    }
+
+    let file = tmpfs::File::open(path)?;
+    let fits = matches!(offset.checked_add(length), Some(end) if end <= file.size());
+    if !fits {
+        return Err("mmap: requested range extends past the end of the file");
+    }
+
+    Err("mmap: no frame allocator, per-process page tables, or page-fault handler in this fork -- see the module docs")
+}
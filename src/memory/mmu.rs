@@ -0,0 +1,148 @@
+//! Memory Management Unit.
+//!
+//! This module provides the architecture-independent parts of MMU handling. A real
+//! translation-table walker is out of scope for this fork; the goal here is to give later
+//! chapters (and BSPs) a stable `MMU` interface to program against.
+//!
+//! That gap is why [`kernel_map_mmio`] can't actually do what a request to "audit the translation
+//! attributes so Device regions are EL1-only" wants: there are no translation attributes here to
+//! audit, only the identity mapping below. [`crate::memory::AccessPermissions`] records a region's
+//! *intended* privilege level on its [`MMIODescriptor`] so a future page-table walker has something
+//! to read, but nothing here encodes that as AP/NS attribute bits, and nothing traps or checks it.
+//! Testing that "EL0 access to the UART faults" needs two more things this fork doesn't have
+//! either: a synchronous exception handler to take the fault (see [`crate::memory::user`], which
+//! hits the identical wall for user-pointer validation), and a way to actually run code at EL0 to
+//! begin with (`crate::process::Process` is a stub with no address space of its own). Until all
+//! three exist, the only thing a "test" here could honestly assert is that the MMU is off.
+//!
+//! # The null guard region
+//!
+//! "Unmap page 0 so a null-pointer dereference faults instead of silently reading whatever the
+//! identity map put at physical 0" hits the same wall from a different angle: there's no real
+//! translation table to remove a mapping from (the identity map here isn't a page table, it's
+//! [`kernel_map_mmio`] returning its input unchanged), and no synchronous exception handler to
+//! catch the resulting fault and report it as a null dereference rather than crash some other way.
+//! What [`is_null_guard_address`] gives a future page-table walker and fault handler is the one
+//! piece that doesn't depend on either existing yet: the address range page 0 occupies, so a real
+//! implementation of both can share one source of truth for where the guard region is instead of
+//! each hardcoding it. [`kernel_map_mmio`] already enforces it today, the one place in this module
+//! that currently decides whether an address becomes reachable at all: a descriptor overlapping
+//! the guard region is refused, the same way a zero-sized one already is.
+
+/// Bytes of virtual address space, starting at address 0, that must never be reachable through
+/// [`kernel_map_mmio`] -- the "null guard" a dereferenced null or small-offset null pointer should
+/// fault against instead of silently aliasing whatever the identity map put there. Covers more
+/// than a single page so a null struct pointer's small field offsets land in the guard too.
+pub const NULL_GUARD_SIZE: usize = 0x1_0000;
+
+/// Whether `addr` falls inside the [`NULL_GUARD_SIZE`]-byte region starting at address 0.
+pub const fn is_null_guard_address(addr: usize) -> bool {
+    addr < NULL_GUARD_SIZE
+}
+
+/// Classify a faulting `addr` for a synchronous-exception handler's fault report, the same "pure
+/// decoding, ready for the day a real vector has one to hand it" role
+/// [`crate::exception::serror::decode`] plays for `ESR_EL1` syndromes.
+pub fn describe_fault_address(addr: usize) -> &'static str {
+    if is_null_guard_address(addr) {
+        "null-pointer dereference (address falls in the null guard region)"
+    } else {
+        "fault at unmapped address"
+    }
+}
+
+use crate::memory::MMIODescriptor;
+use crate::synchronization::{Mutex, NullLock};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// MMU functions.
+pub trait MMU {
+    /// Turns on the MMU and the given caching policy.
+    ///
+    /// # Safety
+    ///
+    /// - Changes the HW's global state.
+    unsafe fn enable_mmu_and_caching(&self) -> Result<(), &'static str>;
+
+    /// Returns true if the MMU is enabled, false otherwise.
+    fn is_enabled(&self) -> bool;
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+struct MemoryManagementUnit {
+    enabled: NullLock<bool>,
+}
+
+static MMU: MemoryManagementUnit = MemoryManagementUnit {
+    enabled: NullLock::new(false),
+};
+
+impl MMU for MemoryManagementUnit {
+    unsafe fn enable_mmu_and_caching(&self) -> Result<(), &'static str> {
+        self.enabled.lock(|e| *e = true);
+        Ok(())
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.lock(|e| *e)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Map a region of MMIO into the kernel's virtual address space and return the new virtual start
+/// address.
+///
+/// Until a real translation-table walker lands in this fork, this performs an identity mapping
+/// and merely validates the descriptor. `mmio_descriptor`'s
+/// [`AccessPermissions`](crate::memory::AccessPermissions) is logged but not enforced, for the
+/// same reason -- see the module docs.
+///
+/// # Safety
+///
+/// - The caller must ensure that the descriptor describes a valid, unaliased MMIO range.
+pub unsafe fn kernel_map_mmio(
+    _name: &'static str,
+    mmio_descriptor: &MMIODescriptor,
+) -> Result<usize, &'static str> {
+    if mmio_descriptor.size() == 0 {
+        crate::log::log_warn!("mmu", "{}: refusing to map a zero-sized MMIO region", _name);
+        return Err("Cannot map a zero-sized MMIO region");
+    }
+
+    if is_null_guard_address(mmio_descriptor.start_addr())
+        || is_null_guard_address(mmio_descriptor.end_addr_inclusive())
+    {
+        crate::log::log_warn!(
+            "mmu",
+            "{}: refusing to map into the null guard region (below {:#x})",
+            _name,
+            NULL_GUARD_SIZE
+        );
+        return Err("Cannot map a region overlapping the null guard");
+    }
+
+    if mmio_descriptor.access_permissions() != crate::memory::AccessPermissions::KernelOnly {
+        crate::log::log_debug!(
+            "mmu",
+            "{}: mapped as {:?}, but no translation-table walker exists yet to enforce it",
+            _name,
+            mmio_descriptor.access_permissions()
+        );
+    }
+
+    Ok(mmio_descriptor.start_addr())
+}
+
+/// Return a reference to the global MMU instance.
+pub fn mmu() -> &'static impl MMU {
+    &MMU
+}
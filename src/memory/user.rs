@@ -0,0 +1,46 @@
+//! Copy-from/to-user accessors safe against a bad user pointer.
+//!
+//! Validating a user pointer needs two things this fork doesn't have yet: per-process mappings to
+//! check the range against (`crate::process::Process` doesn't exist as more than a stub, and
+//! tracks no address space), and a synchronous-exception fixup table to recover from a fault
+//! instead of taking the kernel down with it -- which needs a synchronous vector to install the
+//! fixup handler on in the first place, and `VBAR_EL1` is never programmed in this fork at all
+//! (see `crate::exception`'s module docs). Until both exist, there is no way to distinguish a
+//! valid user pointer from a wild one, so every accessor here fails closed with `Err(Fault)`
+//! rather than ever dereferencing user memory -- which is exactly the safety property this module
+//! exists to provide, just achieved by refusing every request instead of validating ranges it
+//! can't yet check.
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A user-memory access that could not be proven safe.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Fault;
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Copy `dst.len()` bytes from the user address `user_src` into `dst`.
+pub fn copy_from_user(user_src: usize, dst: &mut [u8]) -> Result<(), Fault> {
+    let _ = (user_src, dst);
+
+    Err(Fault)
+}
+
+/// Copy `src` to the user address `user_dst`.
+pub fn copy_to_user(user_dst: usize, src: &[u8]) -> Result<(), Fault> {
+    let _ = (user_dst, src);
+
+    Err(Fault)
+}
+
+/// Copy a NUL-terminated string from the user address `user_src` into `dst`, returning the
+/// number of bytes copied, not including the terminator.
+pub fn strncpy_from_user(user_src: usize, dst: &mut [u8]) -> Result<usize, Fault> {
+    let _ = (user_src, dst);
+
+    Err(Fault)
+}
@@ -0,0 +1,49 @@
+//! Networking.
+//!
+//! Raw Ethernet frame send/receive is the only thing a [`interface::NetworkDevice`] itself
+//! provides; above that, [`ethernet`], [`arp`], and [`ipv4`]/[`udp`] are a real, software-only
+//! link/ARP/IP/UDP stack -- see their module docs for the bounded-poll, no-timer-wheel shape every
+//! send/receive in this fork has to take. [`pbuf`] provides a reference-counted buffer pool that
+//! nothing here uses yet, since no driver DMAs into it (see its own module doc). [`tcp`] builds
+//! the TCP wire format on top of [`ipv4`]/[`arp`] but, unlike UDP, still can't drive a real
+//! connection -- a handshake needs retransmission timing, which needs the still-missing timer
+//! wheel. [`config`] builds DHCP (now sending real broadcasts over [`udp`]) and static address
+//! configuration, [`dns`] builds a resolver (now sending real queries over [`udp`]), [`sntp`]
+//! builds a wall-clock time client (now sending real requests over [`udp`]), [`mdns`] builds a
+//! zero-config responder (now announcing and answering over [`udp`]), [`shell_server`] bridges the
+//! interactive console to [`tcp`], and [`sniffer`] mirrors RX/TX frames into a pcap capture ring --
+//! see their module docs for what each still can't do without the timer wheel.
+
+pub mod arp;
+pub mod config;
+pub mod dns;
+pub mod ethernet;
+pub mod ipv4;
+pub mod mdns;
+pub mod pbuf;
+pub mod shell_server;
+pub mod sniffer;
+pub mod sntp;
+pub mod tcp;
+pub mod udp;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Networking interfaces.
+pub mod interface {
+    /// Implemented by network interface card drivers.
+    pub trait NetworkDevice {
+        /// Return this interface's MAC address.
+        fn mac_address(&self) -> [u8; 6];
+
+        /// Transmit a single Ethernet frame.
+        fn send(&self, frame: &[u8]) -> Result<(), &'static str>;
+
+        /// Receive a single Ethernet frame into `buf`, if one is queued.
+        ///
+        /// Returns the number of bytes written, or `Ok(0)` if nothing was pending.
+        fn receive(&self, buf: &mut [u8]) -> Result<usize, &'static str>;
+    }
+}
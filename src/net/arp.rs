@@ -0,0 +1,243 @@
+//! Address Resolution Protocol (RFC 826) for IPv4-over-Ethernet.
+//!
+//! [`resolve`] is a real, synchronous implementation: it broadcasts a request and drains whatever
+//! the [`crate::net::interface::NetworkDevice`] has queued looking for a matching reply, the same
+//! "no interrupts, no timer wheel, just poll" shape [`crate::jobs::poll_all`] uses elsewhere in
+//! this fork. What it can't do is wait: with no timer wheel (see
+//! [`crate::exception::syscall`]'s module doc) there's no way to space retries out over real time,
+//! so a reply that arrives after [`RESOLVE_ATTEMPTS`] polls is simply missed, the same as a single
+//! dropped ARP request on a real network with a short timeout.
+//!
+//! The cache never expires entries: unlike [`crate::net::dns`]'s cache, which checks each answer's
+//! TTL against uptime, nothing here ages a mapping out. A stale mapping -- a peer's NIC swapped out
+//! from under an unchanged IP -- sticks until [`CACHE_CAPACITY`] is exceeded and something evicts it.
+
+use crate::net::ethernet;
+use crate::net::interface::NetworkDevice;
+use crate::synchronization::{Mutex, NullLock};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const OP_REQUEST: u16 = 1;
+const OP_REPLY: u16 = 2;
+/// Ethernet (RFC 826's `ar$hrd`).
+const HTYPE_ETHERNET: u16 = 1;
+
+/// The fixed size of an ARP packet for IPv4-over-Ethernet (RFC 826); no other hardware/protocol
+/// combination is supported.
+const PACKET_LEN: usize = 28;
+
+/// How many frames [`resolve`] will poll the device for before giving up -- see the module docs
+/// for why this is a bounded drain, not a timed retry.
+const RESOLVE_ATTEMPTS: usize = 64;
+
+#[derive(Copy, Clone)]
+struct CacheEntry {
+    address: [u8; 4],
+    mac: [u8; 6],
+}
+
+struct Cache {
+    entries: [Option<CacheEntry>; CACHE_CAPACITY],
+    /// The slot a new mapping overwrites once every slot is full. Slots fill in index order and
+    /// are never freed, so stepping this round-robin always lands on the oldest entry.
+    next_evict: usize,
+}
+
+impl Cache {
+    const fn new() -> Self {
+        Self {
+            entries: [None; CACHE_CAPACITY],
+            next_evict: 0,
+        }
+    }
+
+    fn get(&self, address: [u8; 4]) -> Option<[u8; 6]> {
+        self.entries
+            .iter()
+            .flatten()
+            .find(|e| e.address == address)
+            .map(|e| e.mac)
+    }
+
+    fn insert(&mut self, address: [u8; 4], mac: [u8; 6]) {
+        let index = self
+            .entries
+            .iter()
+            .position(|e| e.map(|e| e.address == address).unwrap_or(false))
+            .or_else(|| self.entries.iter().position(|e| e.is_none()))
+            .unwrap_or_else(|| {
+                let oldest = self.next_evict;
+                self.next_evict = (oldest + 1) % CACHE_CAPACITY;
+                oldest
+            });
+        self.entries[index] = Some(CacheEntry { address, mac });
+    }
+}
+
+static CACHE: NullLock<Cache> = NullLock::new(Cache::new());
+
+struct Packet {
+    op: u16,
+    sender_mac: [u8; 6],
+    sender_ip: [u8; 4],
+    target_ip: [u8; 4],
+}
+
+fn write_packet(
+    buf: &mut [u8],
+    op: u16,
+    sender_mac: [u8; 6],
+    sender_ip: [u8; 4],
+    target_mac: [u8; 6],
+    target_ip: [u8; 4],
+) -> Result<(), &'static str> {
+    if buf.len() < PACKET_LEN {
+        return Err("arp: buffer too small for a packet");
+    }
+
+    buf[0..2].copy_from_slice(&HTYPE_ETHERNET.to_be_bytes());
+    buf[2..4].copy_from_slice(&ethernet::ETHERTYPE_IPV4.to_be_bytes());
+    buf[4] = 6; // hardware address length: a MAC is 6 bytes
+    buf[5] = 4; // protocol address length: an IPv4 address is 4 bytes
+    buf[6..8].copy_from_slice(&op.to_be_bytes());
+    buf[8..14].copy_from_slice(&sender_mac);
+    buf[14..18].copy_from_slice(&sender_ip);
+    buf[18..24].copy_from_slice(&target_mac);
+    buf[24..28].copy_from_slice(&target_ip);
+
+    Ok(())
+}
+
+fn parse_packet(buf: &[u8]) -> Result<Packet, &'static str> {
+    if buf.len() < PACKET_LEN {
+        return Err("arp: packet shorter than a fixed ARP packet");
+    }
+    if u16::from_be_bytes(buf[0..2].try_into().unwrap()) != HTYPE_ETHERNET
+        || u16::from_be_bytes(buf[2..4].try_into().unwrap()) != ethernet::ETHERTYPE_IPV4
+        || buf[4] != 6
+        || buf[5] != 4
+    {
+        return Err("arp: not an IPv4-over-Ethernet packet");
+    }
+
+    Ok(Packet {
+        op: u16::from_be_bytes(buf[6..8].try_into().unwrap()),
+        sender_mac: buf[8..14].try_into().unwrap(),
+        sender_ip: buf[14..18].try_into().unwrap(),
+        target_ip: buf[24..28].try_into().unwrap(),
+    })
+}
+
+fn send_packet(
+    device: &dyn NetworkDevice,
+    dest_mac: [u8; 6],
+    op: u16,
+    sender_mac: [u8; 6],
+    sender_ip: [u8; 4],
+    target_mac: [u8; 6],
+    target_ip: [u8; 4],
+) -> Result<(), &'static str> {
+    let mut frame = [0u8; ethernet::HEADER_LEN + PACKET_LEN];
+    ethernet::Header {
+        destination: dest_mac,
+        source: sender_mac,
+        ethertype: ethernet::ETHERTYPE_ARP,
+    }
+    .write(&mut frame)?;
+    write_packet(
+        &mut frame[ethernet::HEADER_LEN..],
+        op,
+        sender_mac,
+        sender_ip,
+        target_mac,
+        target_ip,
+    )?;
+
+    device.send(&frame)
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// How many address/MAC mappings the cache holds before evicting the oldest entry.
+pub const CACHE_CAPACITY: usize = 16;
+
+/// Resolve `target_ip` to a MAC address: serve a cache hit, or broadcast a request as
+/// `sender_mac`/`sender_ip` and poll `device` for a matching reply.
+///
+/// See the module docs for why this is a bounded poll rather than a timed retry.
+pub fn resolve(
+    device: &dyn NetworkDevice,
+    sender_mac: [u8; 6],
+    sender_ip: [u8; 4],
+    target_ip: [u8; 4],
+) -> Result<[u8; 6], &'static str> {
+    if let Some(mac) = CACHE.lock(|cache| cache.get(target_ip)) {
+        return Ok(mac);
+    }
+
+    send_packet(
+        device,
+        ethernet::BROADCAST_MAC,
+        OP_REQUEST,
+        sender_mac,
+        sender_ip,
+        [0; 6],
+        target_ip,
+    )?;
+
+    let mut frame = [0u8; ethernet::HEADER_LEN + PACKET_LEN];
+    for _ in 0..RESOLVE_ATTEMPTS {
+        let n = device.receive(&mut frame)?;
+        if n == 0 {
+            continue;
+        }
+
+        let is_arp = matches!(ethernet::Header::parse(&frame[..n]), Ok(h) if h.ethertype == ethernet::ETHERTYPE_ARP);
+        if !is_arp {
+            continue;
+        }
+        let packet = match parse_packet(&frame[ethernet::HEADER_LEN..n]) {
+            Ok(packet) => packet,
+            Err(_) => continue,
+        };
+
+        if packet.op == OP_REPLY && packet.sender_ip == target_ip {
+            CACHE.lock(|cache| cache.insert(target_ip, packet.sender_mac));
+            return Ok(packet.sender_mac);
+        }
+    }
+
+    Err("arp: no reply within this poll's bounded attempts -- see the module docs")
+}
+
+/// Handle an ARP payload (the bytes immediately after an Ethernet header already identified as
+/// [`ethernet::ETHERTYPE_ARP`]): remember the sender's mapping, and reply if it was a request
+/// addressed to `my_ip`.
+pub fn handle_incoming_packet(
+    device: &dyn NetworkDevice,
+    my_mac: [u8; 6],
+    my_ip: [u8; 4],
+    payload: &[u8],
+) -> Result<(), &'static str> {
+    let packet = parse_packet(payload)?;
+    CACHE.lock(|cache| cache.insert(packet.sender_ip, packet.sender_mac));
+
+    if packet.op == OP_REQUEST && packet.target_ip == my_ip {
+        send_packet(
+            device,
+            packet.sender_mac,
+            OP_REPLY,
+            my_mac,
+            my_ip,
+            packet.sender_mac,
+            packet.sender_ip,
+        )?;
+    }
+
+    Ok(())
+}
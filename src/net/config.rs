@@ -0,0 +1,66 @@
+//! Network configuration: static (from a kernel command line) and DHCP.
+//!
+//! There's no bootloader/DTB path that hands this kernel a command line string at all yet
+//! (`crate::config` is a hand-written compile-time constants file, not something a boot argument
+//! populates) -- see [`parse_cmdline`] for what runs once one exists. [`dhcp`] sends its
+//! DISCOVER/REQUEST over the wire but leaves receiving the replies to its caller, and has no
+//! retransmission or lease renewal -- see its module doc.
+
+pub mod dhcp;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A statically-configured IPv4 network configuration.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Ipv4Config {
+    pub address: [u8; 4],
+    pub netmask: [u8; 4],
+    pub gateway: Option<[u8; 4]>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Parse `ip=<addr> netmask=<mask> [gateway=<addr>]`-style whitespace-separated tokens out of a
+/// kernel command line string. Order doesn't matter, and unrecognized tokens are ignored.
+pub fn parse_cmdline(cmdline: &str) -> Result<Ipv4Config, &'static str> {
+    let mut address = None;
+    let mut netmask = None;
+    let mut gateway = None;
+
+    for token in cmdline.split_whitespace() {
+        if let Some(value) = token.strip_prefix("ip=") {
+            address = Some(parse_ipv4(value)?);
+        } else if let Some(value) = token.strip_prefix("netmask=") {
+            netmask = Some(parse_ipv4(value)?);
+        } else if let Some(value) = token.strip_prefix("gateway=") {
+            gateway = Some(parse_ipv4(value)?);
+        }
+    }
+
+    Ok(Ipv4Config {
+        address: address.ok_or("net::config: missing ip= on the command line")?,
+        netmask: netmask.ok_or("net::config: missing netmask= on the command line")?,
+        gateway,
+    })
+}
+
+fn parse_ipv4(value: &str) -> Result<[u8; 4], &'static str> {
+    let mut octets = [0u8; 4];
+    let mut parts = value.split('.');
+
+    for octet in octets.iter_mut() {
+        let part = parts.next().ok_or("net::config: malformed IPv4 address")?;
+        *octet = part
+            .parse()
+            .map_err(|_| "net::config: malformed IPv4 address")?;
+    }
+    if parts.next().is_some() {
+        return Err("net::config: malformed IPv4 address");
+    }
+
+    Ok(octets)
+}
@@ -0,0 +1,388 @@
+//! DHCP client (RFC 2131): discover/offer/request/ack.
+//!
+//! [`Client::start`] and [`Client::request`] now really broadcast a DISCOVER/REQUEST over
+//! [`crate::net::udp`]. What's still missing is a timer wheel (see
+//! [`crate::exception::syscall`]'s module doc) to drive DISCOVER retransmission on no OFFER, and
+//! lease renewal/rebinding timeouts once [`State::Bound`] is reached -- so a lost broadcast or an
+//! expiring lease has nothing here to notice and retry. Receiving is left to the caller: poll
+//! [`crate::net::udp::recv_from`] on [`CLIENT_PORT`], parse the payload with [`Packet::parse`],
+//! and feed it to [`Client::handle_offer`]/[`Client::handle_ack`], the same "future transport just
+//! hands it bytes" shape [`crate::net::sntp::SntpClient::record_response`] documents for itself.
+//!
+//! What's real is the wire format and the state machine's transitions: [`Packet::parse`] reads a
+//! BOOTP/DHCP packet (RFC 2131 §2) including the handful of options a basic client needs
+//! (message type, requested IP, server identifier, lease time, subnet mask, router);
+//! [`build_discover`]/[`build_request`] write the client's half of the same format; and
+//! [`Client::handle_offer`]/[`Client::handle_ack`] drive [`State`] through
+//! Selecting -> Requesting -> Bound exactly the way a real client fed those packets over a real
+//! socket would.
+
+use crate::net::interface::NetworkDevice;
+use crate::net::udp;
+use core::time::Duration;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The UDP port a DHCP server listens on.
+pub const SERVER_PORT: u16 = 67;
+/// The UDP port a DHCP client listens on.
+pub const CLIENT_PORT: u16 = 68;
+
+/// The DHCP message type option's code (RFC 2132 §9.6) values this client cares about.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MessageType {
+    Discover = 1,
+    Offer = 2,
+    Request = 3,
+    Decline = 4,
+    Ack = 5,
+    Nak = 6,
+    Release = 7,
+    Inform = 8,
+}
+
+impl MessageType {
+    fn from_u8(n: u8) -> Option<Self> {
+        Some(match n {
+            1 => Self::Discover,
+            2 => Self::Offer,
+            3 => Self::Request,
+            4 => Self::Decline,
+            5 => Self::Ack,
+            6 => Self::Nak,
+            7 => Self::Release,
+            8 => Self::Inform,
+            _ => return None,
+        })
+    }
+}
+
+/// A DHCP client's lease negotiation state (a minimal subset of RFC 2131 §4.4's state diagram --
+/// no `Init-Reboot`/`Rebooting`, since there's nothing here yet to remember a lease across a
+/// reboot).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum State {
+    Init,
+    Selecting,
+    Requesting,
+    Bound,
+}
+
+/// A negotiated DHCP lease's parameters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Lease {
+    pub address: [u8; 4],
+    pub subnet_mask: [u8; 4],
+    pub router: Option<[u8; 4]>,
+    pub duration: Duration,
+}
+
+/// A parsed BOOTP/DHCP packet (RFC 2131 §2), borrowing its backing bytes.
+#[derive(Copy, Clone)]
+pub struct Packet<'a> {
+    raw: &'a [u8],
+}
+
+/// The op code marking a packet as coming from a client (RFC 2131 §2).
+const BOOTREQUEST: u8 = 1;
+/// The size of the fixed BOOTP header, before the magic cookie and options.
+const FIXED_LEN: usize = 236;
+/// The 4-byte value marking the start of DHCP options (RFC 2131 §3), immediately after the fixed
+/// BOOTP header.
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+/// The option code marking the end of the options list (RFC 2132 §3.3).
+const OPTION_END: u8 = 255;
+/// The option code marking padding between options (RFC 2132 §3.2).
+const OPTION_PAD: u8 = 0;
+
+impl<'a> Packet<'a> {
+    /// Parse `raw` as a BOOTP/DHCP packet: the fixed header plus a valid magic cookie. Options
+    /// aren't validated up front -- [`Packet::option`] scans for them lazily.
+    pub fn parse(raw: &'a [u8]) -> Result<Self, &'static str> {
+        if raw.len() < FIXED_LEN + MAGIC_COOKIE.len() {
+            return Err("dhcp: packet shorter than a BOOTP header plus magic cookie");
+        }
+        if raw[FIXED_LEN..FIXED_LEN + MAGIC_COOKIE.len()] != MAGIC_COOKIE {
+            return Err("dhcp: missing DHCP magic cookie");
+        }
+
+        Ok(Self { raw })
+    }
+
+    /// The transaction ID a client uses to match replies to its own requests.
+    pub fn transaction_id(&self) -> u32 {
+        u32::from_be_bytes(self.raw[4..8].try_into().unwrap())
+    }
+
+    /// The address the server is offering or has assigned ("your IP address", RFC 2131 §2).
+    pub fn your_ip(&self) -> [u8; 4] {
+        self.raw[16..20].try_into().unwrap()
+    }
+
+    fn options(&self) -> impl Iterator<Item = (u8, &'a [u8])> {
+        let mut rest = &self.raw[FIXED_LEN + MAGIC_COOKIE.len()..];
+
+        core::iter::from_fn(move || loop {
+            let code = *rest.first()?;
+            if code == OPTION_PAD {
+                rest = &rest[1..];
+                continue;
+            }
+            if code == OPTION_END {
+                return None;
+            }
+
+            let len = usize::from(*rest.get(1)?);
+            let value = rest.get(2..2 + len)?;
+            rest = &rest[2 + len..];
+            return Some((code, value));
+        })
+    }
+
+    /// Find option `code` (RFC 2132), if present.
+    pub fn option(&self, code: u8) -> Option<&'a [u8]> {
+        self.options().find(|(c, _)| *c == code).map(|(_, v)| v)
+    }
+
+    /// The DHCP message type option (code 53).
+    pub fn message_type(&self) -> Option<MessageType> {
+        self.option(53)
+            .and_then(|v| v.first())
+            .copied()
+            .and_then(MessageType::from_u8)
+    }
+
+    /// The server identifier option (code 54).
+    pub fn server_identifier(&self) -> Option<[u8; 4]> {
+        self.option(54).and_then(|v| v.try_into().ok())
+    }
+
+    /// The subnet mask option (code 1).
+    pub fn subnet_mask(&self) -> Option<[u8; 4]> {
+        self.option(1).and_then(|v| v.try_into().ok())
+    }
+
+    /// The router option (code 3), taking only the first address if the server listed several.
+    pub fn router(&self) -> Option<[u8; 4]> {
+        self.option(3)
+            .and_then(|v| v.get(0..4))
+            .and_then(|v| v.try_into().ok())
+    }
+
+    /// The IP address lease time option (code 51).
+    pub fn lease_time(&self) -> Option<Duration> {
+        self.option(51)
+            .and_then(|v| v.try_into().ok())
+            .map(|b: [u8; 4]| Duration::from_secs(u64::from(u32::from_be_bytes(b))))
+    }
+}
+
+fn write_fixed_header(
+    buf: &mut [u8],
+    transaction_id: u32,
+    client_hardware_address: [u8; 6],
+) -> Result<(), &'static str> {
+    if buf.len() < FIXED_LEN + MAGIC_COOKIE.len() {
+        return Err("dhcp: buffer too small for a BOOTP header");
+    }
+
+    buf[..FIXED_LEN + MAGIC_COOKIE.len()].fill(0);
+    buf[0] = BOOTREQUEST;
+    buf[1] = 1; // htype: Ethernet (RFC 1700)
+    buf[2] = 6; // hlen: a MAC address is 6 bytes
+    buf[4..8].copy_from_slice(&transaction_id.to_be_bytes());
+    buf[28..34].copy_from_slice(&client_hardware_address);
+    buf[FIXED_LEN..FIXED_LEN + MAGIC_COOKIE.len()].copy_from_slice(&MAGIC_COOKIE);
+
+    Ok(())
+}
+
+fn write_option(buf: &mut [u8], code: u8, value: &[u8]) -> Result<usize, &'static str> {
+    if buf.len() < 2 + value.len() {
+        return Err("dhcp: buffer too small for an option");
+    }
+
+    buf[0] = code;
+    buf[1] = value.len() as u8;
+    buf[2..2 + value.len()].copy_from_slice(value);
+
+    Ok(2 + value.len())
+}
+
+/// Build a DHCPDISCOVER packet into `buf`, returning the number of bytes written.
+pub fn build_discover(
+    buf: &mut [u8],
+    transaction_id: u32,
+    client_hardware_address: [u8; 6],
+) -> Result<usize, &'static str> {
+    write_fixed_header(buf, transaction_id, client_hardware_address)?;
+
+    let mut offset = FIXED_LEN + MAGIC_COOKIE.len();
+    offset += write_option(&mut buf[offset..], 53, &[MessageType::Discover as u8])?;
+    if let Some(end) = buf.get_mut(offset) {
+        *end = OPTION_END;
+        offset += 1;
+    } else {
+        return Err("dhcp: buffer too small for the end option");
+    }
+
+    Ok(offset)
+}
+
+/// Build a DHCPREQUEST packet into `buf`, asking `server_identifier` to confirm
+/// `requested_ip`, returning the number of bytes written.
+pub fn build_request(
+    buf: &mut [u8],
+    transaction_id: u32,
+    client_hardware_address: [u8; 6],
+    requested_ip: [u8; 4],
+    server_identifier: [u8; 4],
+) -> Result<usize, &'static str> {
+    write_fixed_header(buf, transaction_id, client_hardware_address)?;
+
+    let mut offset = FIXED_LEN + MAGIC_COOKIE.len();
+    offset += write_option(&mut buf[offset..], 53, &[MessageType::Request as u8])?;
+    offset += write_option(&mut buf[offset..], 50, &requested_ip)?;
+    offset += write_option(&mut buf[offset..], 54, &server_identifier)?;
+    if let Some(end) = buf.get_mut(offset) {
+        *end = OPTION_END;
+        offset += 1;
+    } else {
+        return Err("dhcp: buffer too small for the end option");
+    }
+
+    Ok(offset)
+}
+
+/// A DHCP client negotiating one lease.
+pub struct Client {
+    transaction_id: u32,
+    client_hardware_address: [u8; 6],
+    state: State,
+    offered_ip: [u8; 4],
+    server_identifier: [u8; 4],
+    lease: Option<Lease>,
+}
+
+impl Client {
+    /// Create a client that will use `transaction_id` to match replies to its own requests.
+    pub fn new(transaction_id: u32, client_hardware_address: [u8; 6]) -> Self {
+        Self {
+            transaction_id,
+            client_hardware_address,
+            state: State::Init,
+            offered_ip: [0; 4],
+            server_identifier: [0; 4],
+            lease: None,
+        }
+    }
+
+    /// This client's current negotiation state.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// The lease negotiated so far, if [`Client::state`] has reached [`State::Bound`].
+    pub fn lease(&self) -> Option<Lease> {
+        self.lease
+    }
+
+    /// Broadcast a DISCOVER over `device` and move to [`State::Selecting`].
+    pub fn start(&mut self, device: &dyn NetworkDevice) -> Result<(), &'static str> {
+        let mut buf = [0u8; 256];
+        let len = build_discover(&mut buf, self.transaction_id, self.client_hardware_address)?;
+
+        udp::send_to(
+            device,
+            self.client_hardware_address,
+            [0, 0, 0, 0],
+            CLIENT_PORT,
+            [255, 255, 255, 255],
+            SERVER_PORT,
+            &buf[..len],
+        )?;
+        self.state = State::Selecting;
+
+        Ok(())
+    }
+
+    /// Process a received OFFER, recording the offered address and server identifier and moving
+    /// to [`State::Requesting`].
+    pub fn handle_offer(&mut self, offer: &Packet) -> Result<(), &'static str> {
+        if self.state != State::Init && self.state != State::Selecting {
+            return Err("dhcp: not expecting an OFFER in this state");
+        }
+        if offer.transaction_id() != self.transaction_id {
+            return Err("dhcp: OFFER transaction ID does not match");
+        }
+        if offer.message_type() != Some(MessageType::Offer) {
+            return Err("dhcp: not an OFFER packet");
+        }
+
+        self.offered_ip = offer.your_ip();
+        self.server_identifier = offer
+            .server_identifier()
+            .ok_or("dhcp: OFFER is missing a server identifier")?;
+        self.state = State::Requesting;
+
+        Ok(())
+    }
+
+    /// Broadcast a REQUEST over `device` for the address [`Client::handle_offer`] recorded.
+    pub fn request(&self, device: &dyn NetworkDevice) -> Result<(), &'static str> {
+        if self.state != State::Requesting {
+            return Err("dhcp: not ready to send a REQUEST in this state");
+        }
+
+        let mut buf = [0u8; 256];
+        let len = build_request(
+            &mut buf,
+            self.transaction_id,
+            self.client_hardware_address,
+            self.offered_ip,
+            self.server_identifier,
+        )?;
+
+        udp::send_to(
+            device,
+            self.client_hardware_address,
+            [0, 0, 0, 0],
+            CLIENT_PORT,
+            [255, 255, 255, 255],
+            SERVER_PORT,
+            &buf[..len],
+        )
+    }
+
+    /// Process a received ACK, recording the lease and moving to [`State::Bound`].
+    pub fn handle_ack(&mut self, ack: &Packet) -> Result<Lease, &'static str> {
+        if self.state != State::Requesting {
+            return Err("dhcp: not expecting an ACK in this state");
+        }
+        if ack.transaction_id() != self.transaction_id {
+            return Err("dhcp: ACK transaction ID does not match");
+        }
+        if ack.message_type() != Some(MessageType::Ack) {
+            return Err("dhcp: not an ACK packet");
+        }
+
+        let lease = Lease {
+            address: ack.your_ip(),
+            subnet_mask: ack
+                .subnet_mask()
+                .ok_or("dhcp: ACK is missing a subnet mask")?,
+            router: ack.router(),
+            duration: ack
+                .lease_time()
+                .ok_or("dhcp: ACK is missing a lease time")?,
+        };
+
+        self.lease = Some(lease);
+        self.state = State::Bound;
+
+        Ok(lease)
+    }
+}
@@ -0,0 +1,316 @@
+//! A minimal DNS stub resolver (RFC 1035), for A-record lookups.
+//!
+//! [`send_query`] really sends a query over [`crate::net::udp`] now. What's still missing is a
+//! timer wheel (see [`crate::exception::syscall`]'s module doc) to drive retry on a lost query or
+//! time out a server that never answers -- so [`resolve`] stays a cache-only lookup rather than
+//! blocking to send-and-wait itself; the caller is expected to call [`send_query`], poll
+//! [`crate::net::udp::recv_from`] for the reply, and feed it to [`record_response`].
+//!
+//! What's real is the wire format and the cache: [`build_query`] encodes a question section,
+//! [`parse_response`] decodes a reply (following name-compression pointers per RFC 1035 §4.1.4)
+//! into the first A record's address and TTL, and [`record_response`] feeds a query's raw reply
+//! bytes through that parser and into the fixed-capacity positive cache -- exactly the function a
+//! UDP receive path calls with bytes off the wire.
+
+use crate::net::interface::NetworkDevice;
+use crate::net::udp;
+use crate::synchronization::{Mutex, NullLock};
+use crate::time::{self, TimeManager};
+use core::time::Duration;
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The DNS query/response type this resolver speaks (A records only; no AAAA/CNAME/MX/...).
+const TYPE_A: u16 = 1;
+/// The `IN` (Internet) query/response class.
+const CLASS_IN: u16 = 1;
+/// The top two bits marking a name-compression pointer (RFC 1035 §4.1.4).
+const POINTER_TAG: u8 = 0xc0;
+
+/// The longest hostname this resolver will cache or query for.
+const MAX_HOSTNAME_LEN: usize = 64;
+/// The longest query [`send_query`] will build: a 12-byte header, the hostname's labels each with
+/// a length byte, a terminating zero byte, and a 4-byte QTYPE/QCLASS.
+const MAX_QUERY_LEN: usize = 12 + MAX_HOSTNAME_LEN + 2 + 4;
+/// How many resolved names the positive cache holds before evicting the oldest entry.
+const MAX_CACHE_ENTRIES: usize = 16;
+
+#[derive(Copy, Clone)]
+struct CacheEntry {
+    hostname: [u8; MAX_HOSTNAME_LEN],
+    hostname_len: usize,
+    address: [u8; 4],
+    expires_at: Duration,
+}
+
+struct Cache {
+    entries: [Option<CacheEntry>; MAX_CACHE_ENTRIES],
+    /// The slot a new name overwrites once every slot is full. Slots fill in index order and are
+    /// never freed, so stepping this round-robin always lands on the oldest entry.
+    next_evict: usize,
+}
+
+impl Cache {
+    const fn new() -> Self {
+        Self {
+            entries: [None; MAX_CACHE_ENTRIES],
+            next_evict: 0,
+        }
+    }
+
+    fn get(&self, hostname: &str, now: Duration) -> Option<[u8; 4]> {
+        self.entries
+            .iter()
+            .flatten()
+            .find(|e| {
+                e.hostname_len == hostname.len()
+                    && &e.hostname[..e.hostname_len] == hostname.as_bytes()
+            })
+            .filter(|e| now < e.expires_at)
+            .map(|e| e.address)
+    }
+
+    fn insert(
+        &mut self,
+        hostname: &str,
+        address: [u8; 4],
+        expires_at: Duration,
+    ) -> Result<(), &'static str> {
+        if hostname.len() > MAX_HOSTNAME_LEN {
+            return Err("dns: hostname too long to cache");
+        }
+
+        let mut buf = [0u8; MAX_HOSTNAME_LEN];
+        buf[..hostname.len()].copy_from_slice(hostname.as_bytes());
+        let entry = CacheEntry {
+            hostname: buf,
+            hostname_len: hostname.len(),
+            address,
+            expires_at,
+        };
+
+        let index = self
+            .entries
+            .iter()
+            .position(|e| {
+                e.as_ref()
+                    .map(|e| {
+                        e.hostname_len == hostname.len()
+                            && &e.hostname[..e.hostname_len] == hostname.as_bytes()
+                    })
+                    .unwrap_or(false)
+            })
+            .or_else(|| self.entries.iter().position(|e| e.is_none()))
+            .unwrap_or_else(|| {
+                let oldest = self.next_evict;
+                self.next_evict = (oldest + 1) % MAX_CACHE_ENTRIES;
+                oldest
+            });
+        self.entries[index] = Some(entry);
+
+        Ok(())
+    }
+}
+
+static CACHE: NullLock<Cache> = NullLock::new(Cache::new());
+
+fn write_qname(buf: &mut [u8], hostname: &str) -> Result<usize, &'static str> {
+    let mut offset = 0;
+
+    for label in hostname.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err("dns: malformed hostname label");
+        }
+        if buf.len() < offset + 1 + label.len() {
+            return Err("dns: buffer too small for the question section");
+        }
+
+        buf[offset] = label.len() as u8;
+        buf[offset + 1..offset + 1 + label.len()].copy_from_slice(label.as_bytes());
+        offset += 1 + label.len();
+    }
+
+    *buf.get_mut(offset)
+        .ok_or("dns: buffer too small for the question section")? = 0;
+    offset += 1;
+
+    Ok(offset)
+}
+
+/// Skip a NAME field (a possibly-compressed sequence of labels, RFC 1035 §4.1.4), returning the
+/// offset immediately after it.
+fn skip_name(buf: &[u8], mut offset: usize) -> Result<usize, &'static str> {
+    loop {
+        let len = *buf.get(offset).ok_or("dns: truncated name")?;
+        if len & POINTER_TAG == POINTER_TAG {
+            buf.get(offset + 1).ok_or("dns: truncated name pointer")?;
+            return Ok(offset + 2);
+        }
+        if len == 0 {
+            return Ok(offset + 1);
+        }
+
+        offset += 1 + usize::from(len);
+        if offset > buf.len() {
+            return Err("dns: truncated name");
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The UDP port a DNS server listens on.
+pub const SERVER_PORT: u16 = 53;
+
+/// A decoded DNS response's first usable answer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub address: [u8; 4],
+    pub ttl: Duration,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Build an A-record query for `hostname` into `buf`, tagged with `id`, returning the number of
+/// bytes written.
+pub fn build_query(buf: &mut [u8], id: u16, hostname: &str) -> Result<usize, &'static str> {
+    if buf.len() < 12 {
+        return Err("dns: buffer too small for a header");
+    }
+
+    buf[0..2].copy_from_slice(&id.to_be_bytes());
+    buf[2..4].copy_from_slice(&0x0100u16.to_be_bytes()); // RD (recursion desired)
+    buf[4..6].copy_from_slice(&1u16.to_be_bytes()); // QDCOUNT
+    buf[6..12].fill(0); // ANCOUNT, NSCOUNT, ARCOUNT
+
+    let mut offset = 12;
+    offset += write_qname(&mut buf[offset..], hostname)?;
+
+    let question = buf
+        .get_mut(offset..offset + 4)
+        .ok_or("dns: buffer too small for the question section")?;
+    question[0..2].copy_from_slice(&TYPE_A.to_be_bytes());
+    question[2..4].copy_from_slice(&CLASS_IN.to_be_bytes());
+    offset += 4;
+
+    Ok(offset)
+}
+
+/// Parse a DNS response in `buf`, validating that it answers query `id`, and return the first A
+/// record found.
+pub fn parse_response(buf: &[u8], id: u16) -> Result<Answer, &'static str> {
+    if buf.len() < 12 {
+        return Err("dns: response shorter than a header");
+    }
+    if u16::from_be_bytes([buf[0], buf[1]]) != id {
+        return Err("dns: response ID does not match the query");
+    }
+
+    let flags = u16::from_be_bytes([buf[2], buf[3]]);
+    if flags & 0x8000 == 0 {
+        return Err("dns: not a response packet");
+    }
+    if flags & 0x000f != 0 {
+        return Err("dns: server returned an error");
+    }
+
+    let qdcount = u16::from_be_bytes([buf[4], buf[5]]);
+    let ancount = u16::from_be_bytes([buf[6], buf[7]]);
+    if ancount == 0 {
+        return Err("dns: response has no answers");
+    }
+
+    let mut offset = 12;
+    for _ in 0..qdcount {
+        offset = skip_name(buf, offset)?;
+        offset += 4; // QTYPE, QCLASS
+    }
+
+    for _ in 0..ancount {
+        offset = skip_name(buf, offset)?;
+        let record = buf
+            .get(offset..offset + 10)
+            .ok_or("dns: truncated answer record")?;
+        let record_type = u16::from_be_bytes([record[0], record[1]]);
+        let record_class = u16::from_be_bytes([record[2], record[3]]);
+        let ttl = u32::from_be_bytes([record[4], record[5], record[6], record[7]]);
+        let rdlength = usize::from(u16::from_be_bytes([record[8], record[9]]));
+        offset += 10;
+
+        let rdata = buf
+            .get(offset..offset + rdlength)
+            .ok_or("dns: truncated answer record")?;
+        offset += rdlength;
+
+        if record_type == TYPE_A && record_class == CLASS_IN && rdlength == 4 {
+            return Ok(Answer {
+                address: [rdata[0], rdata[1], rdata[2], rdata[3]],
+                ttl: Duration::from_secs(u64::from(ttl)),
+            });
+        }
+    }
+
+    Err("dns: response has no A records")
+}
+
+/// Build an A-record query for `hostname` tagged `id` and send it to `server:`[`SERVER_PORT`]
+/// over `device`.
+///
+/// Sends exactly once -- see the module docs for the timer wheel a retry would need. The caller
+/// is responsible for polling [`crate::net::udp::recv_from`] for the reply and passing it to
+/// [`record_response`].
+pub fn send_query(
+    device: &dyn NetworkDevice,
+    source_mac: [u8; 6],
+    source_addr: [u8; 4],
+    source_port: u16,
+    server: [u8; 4],
+    id: u16,
+    hostname: &str,
+) -> Result<(), &'static str> {
+    let mut buf = [0u8; MAX_QUERY_LEN];
+    let len = build_query(&mut buf, id, hostname)?;
+
+    udp::send_to(
+        device,
+        source_mac,
+        source_addr,
+        source_port,
+        server,
+        SERVER_PORT,
+        &buf[..len],
+    )
+}
+
+/// Parse `response` as the reply to a query for `hostname` tagged `id`, and cache the result.
+///
+/// This is what a future UDP receive path would call with bytes off the wire; it's exercised here
+/// purely as a pure function over caller-supplied bytes, same as
+/// [`crate::net::config::dhcp::Client::handle_offer`].
+pub fn record_response(hostname: &str, id: u16, response: &[u8]) -> Result<Answer, &'static str> {
+    let answer = parse_response(response, id)?;
+    let now = time::time_manager().uptime();
+
+    CACHE.lock(|cache| cache.insert(hostname, answer.address, now + answer.ttl))?;
+
+    Ok(answer)
+}
+
+/// Resolve `hostname` to an IPv4 address.
+///
+/// Only ever serves a cache hit recorded by [`record_response`] -- see the module docs: sending a
+/// fresh query is [`send_query`]'s job, not this one, since there's no timer wheel here to block
+/// and retry against.
+pub fn resolve(hostname: &str) -> Result<[u8; 4], &'static str> {
+    let now = time::time_manager().uptime();
+
+    CACHE
+        .lock(|cache| cache.get(hostname, now))
+        .ok_or("dns: not cached -- see the module docs for send_query/record_response")
+}
@@ -0,0 +1,54 @@
+//! Ethernet II framing (IEEE 802.3): a 14-byte destination/source MAC plus EtherType header, with
+//! no 802.1Q tagging or jumbo frame support.
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The fixed size of an Ethernet II header.
+pub const HEADER_LEN: usize = 14;
+
+/// The EtherType marking an ARP payload (RFC 826).
+pub const ETHERTYPE_ARP: u16 = 0x0806;
+/// The EtherType marking an IPv4 payload (RFC 894).
+pub const ETHERTYPE_IPV4: u16 = 0x0800;
+
+/// The all-ones MAC address every interface accepts as a broadcast.
+pub const BROADCAST_MAC: [u8; 6] = [0xff; 6];
+
+/// A parsed Ethernet II header.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub destination: [u8; 6],
+    pub source: [u8; 6],
+    pub ethertype: u16,
+}
+
+impl Header {
+    /// Parse a fixed 14-byte header from the front of `frame`.
+    pub fn parse(frame: &[u8]) -> Result<Self, &'static str> {
+        if frame.len() < HEADER_LEN {
+            return Err("ethernet: frame shorter than a header");
+        }
+
+        Ok(Self {
+            destination: frame[0..6].try_into().unwrap(),
+            source: frame[6..12].try_into().unwrap(),
+            ethertype: u16::from_be_bytes(frame[12..14].try_into().unwrap()),
+        })
+    }
+
+    /// Serialize this header into the front of `buf`, which must be at least [`HEADER_LEN`]
+    /// bytes.
+    pub fn write(&self, buf: &mut [u8]) -> Result<(), &'static str> {
+        if buf.len() < HEADER_LEN {
+            return Err("ethernet: buffer too small for a header");
+        }
+
+        buf[0..6].copy_from_slice(&self.destination);
+        buf[6..12].copy_from_slice(&self.source);
+        buf[12..14].copy_from_slice(&self.ethertype.to_be_bytes());
+
+        Ok(())
+    }
+}
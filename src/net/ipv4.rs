@@ -0,0 +1,226 @@
+//! IPv4 (RFC 791) framing over Ethernet: header encode/decode, the header checksum, and the only
+//! two ways this fork ever picks a destination MAC -- [`arp::resolve`] for a unicast neighbor, or
+//! no resolution at all for broadcast/multicast.
+//!
+//! No options, no fragmentation, and no routing table: [`send`] either ARPs for `dest_addr`
+//! directly or addresses a broadcast/multicast frame without resolving anything, which is enough
+//! for the LAN-local protocols built on top of this ([`crate::net::udp`] and everything that uses
+//! it) but not for talking to a host across a gateway.
+
+use crate::net::arp;
+use crate::net::ethernet;
+use crate::net::interface::NetworkDevice;
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+fn is_multicast(addr: [u8; 4]) -> bool {
+    (224..=239).contains(&addr[0])
+}
+
+/// The Ethernet multicast MAC an IPv4 multicast address maps to (RFC 1112 §6.4): the low 23 bits
+/// of the address copied into a fixed `01:00:5e` prefix.
+fn multicast_mac(addr: [u8; 4]) -> [u8; 6] {
+    [0x01, 0x00, 0x5e, addr[1] & 0x7f, addr[2], addr[3]]
+}
+
+fn header_checksum(header_bytes: &[u8; HEADER_LEN]) -> u16 {
+    let mut sum: u32 = 0;
+    for chunk in header_bytes.chunks_exact(2) {
+        sum += u32::from(u16::from_be_bytes([chunk[0], chunk[1]]));
+    }
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
+
+fn write_header(
+    buf: &mut [u8],
+    protocol: u8,
+    source: [u8; 4],
+    destination: [u8; 4],
+    payload_len: usize,
+) -> Result<(), &'static str> {
+    if buf.len() < HEADER_LEN {
+        return Err("ipv4: buffer too small for a header");
+    }
+    let total_length = HEADER_LEN + payload_len;
+    if total_length > u16::MAX as usize {
+        return Err("ipv4: payload too large for a single datagram");
+    }
+
+    buf[..HEADER_LEN].fill(0);
+    buf[0] = 0x45; // version 4, IHL 5 (no options)
+    buf[2..4].copy_from_slice(&(total_length as u16).to_be_bytes());
+    buf[8] = 64; // TTL
+    buf[9] = protocol;
+    buf[12..16].copy_from_slice(&source);
+    buf[16..20].copy_from_slice(&destination);
+
+    let checksum = header_checksum(buf[..HEADER_LEN].try_into().unwrap());
+    buf[10..12].copy_from_slice(&checksum.to_be_bytes());
+
+    Ok(())
+}
+
+fn parse_header(bytes: &[u8]) -> Result<Header, &'static str> {
+    if bytes.len() < HEADER_LEN {
+        return Err("ipv4: packet shorter than a header");
+    }
+    if bytes[0] >> 4 != 4 {
+        return Err("ipv4: not an IPv4 packet");
+    }
+    if bytes[0] & 0x0f != 5 {
+        return Err("ipv4: IP options are not supported");
+    }
+    if header_checksum(bytes[..HEADER_LEN].try_into().unwrap()) != 0 {
+        return Err("ipv4: header checksum mismatch");
+    }
+
+    Ok(Header {
+        protocol: bytes[9],
+        source: bytes[12..16].try_into().unwrap(),
+        destination: bytes[16..20].try_into().unwrap(),
+        total_length: u16::from_be_bytes(bytes[2..4].try_into().unwrap()),
+    })
+}
+
+/// Resolve the MAC a frame to `dest_addr` should be sent to: broadcast and multicast addresses
+/// resolve without a lookup, everything else goes through [`arp::resolve`].
+fn destination_mac(
+    device: &dyn NetworkDevice,
+    source_mac: [u8; 6],
+    source_addr: [u8; 4],
+    dest_addr: [u8; 4],
+) -> Result<[u8; 6], &'static str> {
+    if dest_addr == BROADCAST_ADDR {
+        Ok(ethernet::BROADCAST_MAC)
+    } else if is_multicast(dest_addr) {
+        Ok(multicast_mac(dest_addr))
+    } else {
+        arp::resolve(device, source_mac, source_addr, dest_addr)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The fixed size of an IPv4 header with no options.
+pub const HEADER_LEN: usize = 20;
+
+/// The protocol number (RFC 790) marking a UDP payload.
+pub const PROTO_UDP: u8 = 17;
+/// The protocol number (RFC 790) marking a TCP payload.
+pub const PROTO_TCP: u8 = 6;
+
+/// The broadcast address for this fork's one supported network shape: no subnet mask is tracked
+/// anywhere above [`crate::net::config::Ipv4Config`], so this is the only broadcast address
+/// recognized here -- not a subnet-relative one.
+pub const BROADCAST_ADDR: [u8; 4] = [255, 255, 255, 255];
+
+/// A parsed IPv4 header.
+#[derive(Copy, Clone, Debug)]
+pub struct Header {
+    pub protocol: u8,
+    pub source: [u8; 4],
+    pub destination: [u8; 4],
+    pub total_length: u16,
+}
+
+/// One received datagram's source/destination address, protocol, and payload, borrowed from the
+/// frame buffer [`recv`] was given.
+pub struct Datagram<'a> {
+    pub source_addr: [u8; 4],
+    pub destination_addr: [u8; 4],
+    pub protocol: u8,
+    pub payload: &'a [u8],
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Send `payload` as protocol `protocol` from `source_addr` to `dest_addr`, over `device`, using
+/// `frame_buf` as working space for the Ethernet + IPv4 header and payload.
+pub fn send(
+    device: &dyn NetworkDevice,
+    source_mac: [u8; 6],
+    source_addr: [u8; 4],
+    dest_addr: [u8; 4],
+    protocol: u8,
+    payload: &[u8],
+    frame_buf: &mut [u8],
+) -> Result<(), &'static str> {
+    let dest_mac = destination_mac(device, source_mac, source_addr, dest_addr)?;
+    let total_len = ethernet::HEADER_LEN + HEADER_LEN + payload.len();
+    let frame = frame_buf
+        .get_mut(..total_len)
+        .ok_or("ipv4: buffer too small for this datagram")?;
+
+    ethernet::Header {
+        destination: dest_mac,
+        source: source_mac,
+        ethertype: ethernet::ETHERTYPE_IPV4,
+    }
+    .write(frame)?;
+    write_header(
+        &mut frame[ethernet::HEADER_LEN..],
+        protocol,
+        source_addr,
+        dest_addr,
+        payload.len(),
+    )?;
+    frame[ethernet::HEADER_LEN + HEADER_LEN..].copy_from_slice(payload);
+
+    device.send(frame)
+}
+
+/// Poll `device` once. An ARP request addressed to `my_ip` is answered in place and this returns
+/// `Ok(None)`, the same as an empty poll or a frame this interface isn't the destination of; only
+/// an IPv4 datagram addressed to `my_ip` (or a broadcast/multicast one) produces `Ok(Some(_))`.
+pub fn recv<'a>(
+    device: &dyn NetworkDevice,
+    my_mac: [u8; 6],
+    my_ip: [u8; 4],
+    frame_buf: &'a mut [u8],
+) -> Result<Option<Datagram<'a>>, &'static str> {
+    let n = device.receive(frame_buf)?;
+    if n == 0 {
+        return Ok(None);
+    }
+
+    let header = ethernet::Header::parse(&frame_buf[..n])?;
+    if header.ethertype == ethernet::ETHERTYPE_ARP {
+        let _ =
+            arp::handle_incoming_packet(device, my_mac, my_ip, &frame_buf[ethernet::HEADER_LEN..n]);
+        return Ok(None);
+    }
+    if header.ethertype != ethernet::ETHERTYPE_IPV4 {
+        return Ok(None);
+    }
+
+    let ip_header = parse_header(&frame_buf[ethernet::HEADER_LEN..n])?;
+    if ip_header.destination != my_ip
+        && ip_header.destination != BROADCAST_ADDR
+        && !is_multicast(ip_header.destination)
+    {
+        return Ok(None);
+    }
+
+    let payload_start = ethernet::HEADER_LEN + HEADER_LEN;
+    let payload_end = ethernet::HEADER_LEN + usize::from(ip_header.total_length);
+    let payload = frame_buf
+        .get(payload_start..payload_end)
+        .ok_or("ipv4: total length field overruns the received frame")?;
+
+    Ok(Some(Datagram {
+        source_addr: ip_header.source,
+        destination_addr: ip_header.destination,
+        protocol: ip_header.protocol,
+        payload,
+    }))
+}
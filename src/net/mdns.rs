@@ -0,0 +1,396 @@
+//! A small mDNS/DNS-SD responder (RFC 6762/6763) announcing this board's `.local` hostname and a
+//! few development services.
+//!
+//! [`Responder::announce`] really multicasts an announcement over [`crate::net::udp`] now, and
+//! [`Responder::run`] polls once for an incoming query and answers it the same way -- the same
+//! "caller drives it from the main loop, one poll per call" shape
+//! [`crate::jobs::poll_all`]/[`crate::net::udp::recv_from`] already use, so call it repeatedly
+//! rather than expecting it to block. There's no timer wheel (see
+//! [`crate::exception::syscall`]'s module doc) to re-announce periodically or to answer only
+//! after RFC 6762 §6's recommended random delay, so [`Responder::run`] answers immediately every
+//! time instead. Only one of the three announced services
+//! corresponds to something this fork actually runs (`_telnet._tcp.local` ->
+//! [`crate::net::shell_server`]); a GDB remote stub and a netconsole logger were never built here,
+//! so [`SERVICES`] carries placeholder ports for both, called out there.
+//!
+//! What's real is the RFC 6762 record building: [`build_response`] writes a full mDNS response
+//! packet -- an A record for the hostname plus a PTR/SRV/TXT record set per service. It doesn't
+//! use DNS name compression; every name is written out in full, which keeps the encoder simple at
+//! the cost of a few extra bytes per packet.
+
+use crate::net::interface::NetworkDevice;
+use crate::net::udp;
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+/// RFC 6762 §10.2: the top bit of the class field, set on records that a subsequent announcement
+/// fully replaces rather than adds to.
+const CACHE_FLUSH: u16 = 0x8000;
+/// RFC 6762 §10: the recommended TTL for records tied to a specific host (an A or SRV record).
+const TTL_HOST_SECONDS: u32 = 120;
+/// RFC 6762 §10: the recommended TTL for other records (here, PTR and TXT).
+const TTL_OTHER_SECONDS: u32 = 4500;
+
+/// The longest domain name (a label sequence) this module will encode.
+const MAX_NAME_LEN: usize = 255;
+/// The longest response [`Responder::announce`] will build: comfortably larger than a header plus
+/// an A record and three services' worth of PTR/SRV/TXT records (each carrying the service's full
+/// instance name two or three times over).
+const MAX_RESPONSE_LEN: usize = 1024;
+/// The longest incoming query [`Responder::run`] will read.
+const MAX_QUERY_LEN: usize = 512;
+
+fn write_name(buf: &mut [u8], name: &str) -> Result<usize, &'static str> {
+    let mut offset = 0;
+
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err("mdns: malformed domain name label");
+        }
+        if buf.len() < offset + 1 + label.len() {
+            return Err("mdns: buffer too small for a domain name");
+        }
+
+        buf[offset] = label.len() as u8;
+        buf[offset + 1..offset + 1 + label.len()].copy_from_slice(label.as_bytes());
+        offset += 1 + label.len();
+    }
+
+    *buf.get_mut(offset)
+        .ok_or("mdns: buffer too small for a domain name")? = 0;
+    offset += 1;
+
+    Ok(offset)
+}
+
+/// Write one resource record: `name`, then its fixed type/class/TTL/RDLENGTH fields, then
+/// whatever `write_rdata` puts in the RDATA field. Returns the total number of bytes written.
+fn write_record(
+    buf: &mut [u8],
+    name: &str,
+    record_type: u16,
+    class: u16,
+    ttl_seconds: u32,
+    write_rdata: impl FnOnce(&mut [u8]) -> Result<usize, &'static str>,
+) -> Result<usize, &'static str> {
+    let mut offset = write_name(buf, name)?;
+
+    let fixed = buf
+        .get_mut(offset..offset + 10)
+        .ok_or("mdns: buffer too small for a record")?;
+    fixed[0..2].copy_from_slice(&record_type.to_be_bytes());
+    fixed[2..4].copy_from_slice(&class.to_be_bytes());
+    fixed[4..8].copy_from_slice(&ttl_seconds.to_be_bytes());
+    let rdlength_offset = offset + 8;
+    offset += 10;
+
+    let rdata_len = write_rdata(&mut buf[offset..])?;
+    buf[rdlength_offset..rdlength_offset + 2].copy_from_slice(&(rdata_len as u16).to_be_bytes());
+    offset += rdata_len;
+
+    Ok(offset)
+}
+
+/// Decode one (uncompressed) NAME field at `offset` into `scratch` as dot-joined label bytes,
+/// returning the offset immediately after it and the length written. RFC 1035 §4.1.4 compression
+/// pointers aren't supported -- a question using one is treated as not matching, the same
+/// "doesn't do name compression" limit [`build_response`] documents for its own writes.
+fn parse_question_name(
+    buf: &[u8],
+    mut offset: usize,
+    scratch: &mut [u8; MAX_NAME_LEN],
+) -> Result<(usize, usize), &'static str> {
+    let mut len = 0;
+
+    loop {
+        let label_len = usize::from(*buf.get(offset).ok_or("mdns: truncated question name")?);
+        if label_len & 0xc0 == 0xc0 {
+            return Err("mdns: compressed question names are not supported");
+        }
+        offset += 1;
+        if label_len == 0 {
+            break;
+        }
+
+        if len != 0 {
+            *scratch.get_mut(len).ok_or("mdns: question name too long")? = b'.';
+            len += 1;
+        }
+        let label = buf
+            .get(offset..offset + label_len)
+            .ok_or("mdns: truncated question name")?;
+        scratch
+            .get_mut(len..len + label_len)
+            .ok_or("mdns: question name too long")?
+            .copy_from_slice(label);
+        len += label_len;
+        offset += label_len;
+    }
+
+    Ok((offset, len))
+}
+
+/// Join `service`'s instance name (e.g. `raspberry-os._telnet._tcp.local`) into `scratch`.
+fn instance_name<'a>(
+    scratch: &'a mut [u8; MAX_NAME_LEN],
+    service: &Service,
+) -> Result<&'a str, &'static str> {
+    let total = service.instance.len() + 1 + service.service_type.len();
+    if total > scratch.len() {
+        return Err("mdns: instance name too long");
+    }
+
+    scratch[..service.instance.len()].copy_from_slice(service.instance.as_bytes());
+    scratch[service.instance.len()] = b'.';
+    scratch[service.instance.len() + 1..total].copy_from_slice(service.service_type.as_bytes());
+
+    core::str::from_utf8(&scratch[..total]).map_err(|_| "mdns: instance name is not valid UTF-8")
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The UDP port mDNS queries and responses are exchanged on.
+pub const PORT: u16 = 5353;
+/// The IPv4 multicast group mDNS uses (RFC 6762 §3).
+pub const MULTICAST_ADDR: [u8; 4] = [224, 0, 0, 251];
+
+/// This board's default `.local` hostname.
+pub const DEFAULT_HOSTNAME: &str = "raspberry-os.local";
+
+/// One DNS-SD service instance to announce (RFC 6763).
+#[derive(Copy, Clone, Debug)]
+pub struct Service {
+    /// The service instance's name, e.g. `"raspberry-os"`.
+    pub instance: &'static str,
+    /// The service type and domain, e.g. `"_telnet._tcp.local"`.
+    pub service_type: &'static str,
+    /// The TCP or UDP port the service listens on.
+    pub port: u16,
+}
+
+/// The development services this responder announces.
+///
+/// Only `_telnet._tcp.local` is real -- see [`crate::net::shell_server`]. Neither a GDB remote
+/// stub nor a netconsole logger exists in this fork; their ports below are placeholders picked to
+/// match their usual defaults, not ports anything here actually listens on.
+pub const SERVICES: &[Service] = &[
+    Service {
+        instance: "raspberry-os",
+        service_type: "_telnet._tcp.local",
+        port: 23,
+    },
+    Service {
+        instance: "raspberry-os",
+        service_type: "_gdb._tcp.local",
+        port: 1234,
+    },
+    Service {
+        instance: "raspberry-os",
+        service_type: "_netconsole._udp.local",
+        port: 6666,
+    },
+];
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Build a full mDNS response into `buf`, announcing an A record for `hostname` at `addr` plus a
+/// PTR/SRV/TXT record set for each of `services`. Returns the number of bytes written.
+pub fn build_response(
+    buf: &mut [u8],
+    hostname: &str,
+    addr: [u8; 4],
+    services: &[Service],
+) -> Result<usize, &'static str> {
+    if buf.len() < 12 {
+        return Err("mdns: buffer too small for a header");
+    }
+
+    let ancount = 1 + services.len() * 3;
+    buf[0..2].fill(0); // ID: ignored for multicast responses (RFC 6762 §18.1)
+    buf[2..4].copy_from_slice(&0x8400u16.to_be_bytes()); // QR=1 (response), AA=1 (authoritative)
+    buf[4..6].fill(0); // QDCOUNT
+    buf[6..8].copy_from_slice(&(ancount as u16).to_be_bytes());
+    buf[8..12].fill(0); // NSCOUNT, ARCOUNT
+
+    let mut offset = 12;
+    offset += write_record(
+        &mut buf[offset..],
+        hostname,
+        TYPE_A,
+        CLASS_IN | CACHE_FLUSH,
+        TTL_HOST_SECONDS,
+        |rdata| {
+            let rdata = rdata
+                .get_mut(..4)
+                .ok_or("mdns: buffer too small for an A record")?;
+            rdata.copy_from_slice(&addr);
+            Ok(4)
+        },
+    )?;
+
+    let mut scratch = [0u8; MAX_NAME_LEN];
+    for service in services {
+        let instance = instance_name(&mut scratch, service)?;
+
+        offset += write_record(
+            &mut buf[offset..],
+            instance,
+            TYPE_PTR,
+            CLASS_IN,
+            TTL_OTHER_SECONDS,
+            |rdata| write_name(rdata, instance),
+        )?;
+
+        offset += write_record(
+            &mut buf[offset..],
+            instance,
+            TYPE_SRV,
+            CLASS_IN | CACHE_FLUSH,
+            TTL_HOST_SECONDS,
+            |rdata| {
+                let fixed = rdata
+                    .get_mut(..6)
+                    .ok_or("mdns: buffer too small for an SRV record")?;
+                fixed[0..2].fill(0); // priority
+                fixed[2..4].fill(0); // weight
+                fixed[4..6].copy_from_slice(&service.port.to_be_bytes());
+
+                Ok(6 + write_name(&mut rdata[6..], hostname)?)
+            },
+        )?;
+
+        offset += write_record(
+            &mut buf[offset..],
+            instance,
+            TYPE_TXT,
+            CLASS_IN | CACHE_FLUSH,
+            TTL_OTHER_SECONDS,
+            |rdata| {
+                *rdata
+                    .first_mut()
+                    .ok_or("mdns: buffer too small for a TXT record")? = 0;
+                Ok(1)
+            },
+        )?;
+    }
+
+    Ok(offset)
+}
+
+/// A responder announcing one hostname and a fixed set of services.
+pub struct Responder {
+    hostname: &'static str,
+    addr: [u8; 4],
+    services: &'static [Service],
+}
+
+impl Responder {
+    /// Create a responder announcing `hostname` at `addr` for `services`.
+    pub fn new(hostname: &'static str, addr: [u8; 4], services: &'static [Service]) -> Self {
+        Self {
+            hostname,
+            addr,
+            services,
+        }
+    }
+
+    /// Build one announcement packet for this responder's configuration into `buf`.
+    pub fn build_announcement(&self, buf: &mut [u8]) -> Result<usize, &'static str> {
+        build_response(buf, self.hostname, self.addr, self.services)
+    }
+
+    /// Multicast an announcement of this responder's configuration over `device`.
+    pub fn announce(
+        &self,
+        device: &dyn NetworkDevice,
+        source_mac: [u8; 6],
+    ) -> Result<(), &'static str> {
+        let mut buf = [0u8; MAX_RESPONSE_LEN];
+        let len = self.build_announcement(&mut buf)?;
+
+        udp::send_to(
+            device,
+            source_mac,
+            self.addr,
+            PORT,
+            MULTICAST_ADDR,
+            PORT,
+            &buf[..len],
+        )
+    }
+
+    /// Does `payload` (an mDNS packet's bytes) contain a query (QR bit clear) asking about a name
+    /// this responder answers for -- its hostname, or one of its services' service type or
+    /// instance name? A malformed or unsupported (compressed) question is treated as not matching
+    /// rather than answered.
+    fn matches_query(&self, payload: &[u8]) -> bool {
+        if payload.len() < 12 {
+            return false;
+        }
+        let flags = u16::from_be_bytes([payload[2], payload[3]]);
+        if flags & 0x8000 != 0 {
+            return false; // QR=1: this is a response, not a query.
+        }
+        let qdcount = u16::from_be_bytes([payload[4], payload[5]]);
+
+        let mut instance_scratch = [0u8; MAX_NAME_LEN];
+        let mut offset = 12;
+        for _ in 0..qdcount {
+            let mut name_scratch = [0u8; MAX_NAME_LEN];
+            let (next_offset, name_len) =
+                match parse_question_name(payload, offset, &mut name_scratch) {
+                    Ok(parsed) => parsed,
+                    Err(_) => return false,
+                };
+            offset = next_offset + 4; // skip QTYPE, QCLASS
+
+            let name = &name_scratch[..name_len];
+            if name.eq_ignore_ascii_case(self.hostname.as_bytes()) {
+                return true;
+            }
+            for service in self.services {
+                if name.eq_ignore_ascii_case(service.service_type.as_bytes()) {
+                    return true;
+                }
+                let instance_matches = instance_name(&mut instance_scratch, service)
+                    .map(|instance| name.eq_ignore_ascii_case(instance.as_bytes()))
+                    .unwrap_or(false);
+                if instance_matches {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Poll `device` once for an incoming mDNS query asking about a name this responder answers
+    /// for, and if one arrived, answer it with a fresh announcement.
+    ///
+    /// See the module docs: this is one poll, not a wait -- call it again on your own schedule.
+    pub fn run(&self, device: &dyn NetworkDevice, source_mac: [u8; 6]) -> Result<(), &'static str> {
+        let mut query_buf = [0u8; MAX_QUERY_LEN];
+
+        let datagram = match udp::recv_from(device, source_mac, self.addr, &mut query_buf)? {
+            Some(datagram) if datagram.dest_port == PORT => datagram,
+            _ => return Ok(()),
+        };
+
+        if !self.matches_query(&query_buf[..datagram.len]) {
+            return Ok(());
+        }
+
+        self.announce(device, source_mac)
+    }
+}
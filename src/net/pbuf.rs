@@ -0,0 +1,156 @@
+//! A fixed pool of reference-counted packet buffers, shared by handle rather than copied as they
+//! pass up through parsing layers.
+//!
+//! One thing below this is aspirational: no NIC driver in this fork DMAs into a shared pool at
+//! all -- [`crate::bsp::device_driver::virtio::net::VirtioNet::receive`] and every other
+//! [`crate::net::interface::NetworkDevice`] implementation copies a frame into a caller-supplied
+//! buffer instead, so nothing yet calls [`PacketBuffer::alloc`] from an RX interrupt. The
+//! [`crate::net::ethernet`]/[`crate::net::arp`]/[`crate::net::ipv4`]/[`crate::net::udp`] layer
+//! above this exists now and could hand a [`PacketBuffer`] up through its parsing the same way it
+//! hands up borrowed frame-buffer slices today, but it doesn't yet -- still a wiring problem, not
+//! a gap in this module: the pool itself needs nothing from either to be real.
+//!
+//! What's real: [`POOL_CAPACITY`] buffers of [`BUFFER_LEN`] bytes each, preallocated in a static
+//! array since there's no heap or frame allocator to carve a dedicated region from dynamically
+//! (the same trade-off [`crate::fs::tmpfs`] and [`crate::exception::asynchronous`]'s IRQ
+//! statistics table make). [`PacketBuffer`] is a `Clone`-to-share, `Drop`-to-release handle into
+//! one slot -- cloning it bumps a refcount instead of copying [`BUFFER_LEN`] bytes, which is what
+//! "zero-copy" means here: passing a `PacketBuffer` from one parsing layer to the next never
+//! touches the underlying bytes. [`stats`] reports pool exhaustion the same way
+//! [`crate::exception::asynchronous::all_irq_counts`] reports counters: a plain accessor over
+//! fixed-size state, no channel or logging subsystem involved.
+
+use crate::synchronization::{Mutex, NullLock};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone)]
+struct Slot {
+    data: [u8; BUFFER_LEN],
+    len: usize,
+    refcount: usize,
+}
+
+impl Slot {
+    const fn new() -> Self {
+        Self {
+            data: [0; BUFFER_LEN],
+            len: 0,
+            refcount: 0,
+        }
+    }
+}
+
+struct Pool {
+    slots: [Slot; POOL_CAPACITY],
+    exhaustion_count: u64,
+}
+
+impl Pool {
+    const fn new() -> Self {
+        Self {
+            slots: [Slot::new(); POOL_CAPACITY],
+            exhaustion_count: 0,
+        }
+    }
+}
+
+static POOL: NullLock<Pool> = NullLock::new(Pool::new());
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// How many packet buffers the pool holds.
+pub const POOL_CAPACITY: usize = 32;
+/// The size of each packet buffer: a standard Ethernet MTU (1500) plus its 14-byte header,
+/// rounded up.
+pub const BUFFER_LEN: usize = 1536;
+
+/// A pool exhaustion and occupancy snapshot.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    pub capacity: usize,
+    pub in_use: usize,
+    pub exhaustion_count: u64,
+}
+
+/// A reference-counted handle to one pool slot.
+///
+/// Cloning shares the same underlying bytes (bumping the slot's refcount); dropping the last
+/// clone returns the slot to the pool.
+pub struct PacketBuffer {
+    index: usize,
+}
+
+impl PacketBuffer {
+    /// Allocate an empty buffer from the pool.
+    pub fn alloc() -> Result<Self, &'static str> {
+        POOL.lock(
+            |pool| match pool.slots.iter().position(|slot| slot.refcount == 0) {
+                Some(index) => {
+                    pool.slots[index].refcount = 1;
+                    pool.slots[index].len = 0;
+                    Ok(Self { index })
+                }
+                None => {
+                    pool.exhaustion_count += 1;
+                    Err("pbuf: pool exhausted")
+                }
+            },
+        )
+    }
+
+    /// This buffer's filled length.
+    pub fn len(&self) -> usize {
+        POOL.lock(|pool| pool.slots[self.index].len)
+    }
+
+    /// Whether this buffer holds no data.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Run `f` against this buffer's filled bytes.
+    pub fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+        POOL.lock(|pool| {
+            let slot = &pool.slots[self.index];
+            f(&slot.data[..slot.len])
+        })
+    }
+
+    /// Run `f` against this buffer's full backing storage (all [`BUFFER_LEN`] bytes, not just the
+    /// filled portion), letting a filler such as a NIC's RX path write in place, then record how
+    /// much of it `f` filled.
+    pub fn fill(&mut self, f: impl FnOnce(&mut [u8; BUFFER_LEN]) -> usize) {
+        POOL.lock(|pool| {
+            let slot = &mut pool.slots[self.index];
+            slot.len = f(&mut slot.data).min(BUFFER_LEN);
+        })
+    }
+}
+
+impl Clone for PacketBuffer {
+    fn clone(&self) -> Self {
+        POOL.lock(|pool| pool.slots[self.index].refcount += 1);
+
+        Self { index: self.index }
+    }
+}
+
+impl Drop for PacketBuffer {
+    fn drop(&mut self) {
+        POOL.lock(|pool| pool.slots[self.index].refcount -= 1);
+    }
+}
+
+/// A snapshot of the pool's current occupancy and lifetime exhaustion count.
+pub fn stats() -> PoolStats {
+    POOL.lock(|pool| PoolStats {
+        capacity: POOL_CAPACITY,
+        in_use: pool.slots.iter().filter(|slot| slot.refcount > 0).count(),
+        exhaustion_count: pool.exhaustion_count,
+    })
+}
@@ -0,0 +1,178 @@
+//! Bridge the interactive line-edit console to a TCP connection, so a headless board can be
+//! poked without a serial cable.
+//!
+//! [`TcpListener::accept`] is the same honest stub described in [`crate::net::tcp`]'s module doc
+//! -- the IP/ARP layer underneath it exists now, but there's still no timer wheel to poll for an
+//! inbound SYN with and no connection table to track a handshake in -- so
+//! [`ShellServer::serve_one`] can't actually pick up a connection yet. What's real is everything
+//! above that: [`TcpConsole`]
+//! implements [`console::interface::All`] over a [`TcpStream`] exactly the way
+//! [`crate::bsp::device_driver::bcm::bcm2xxx_pl011_uart::PL011Uart`] implements it over a
+//! physical UART, so [`console::line_edit::LineEditor`] can drive one without caring which it is;
+//! and [`authenticate`] gates it behind a shared secret using that same line editor. The moment
+//! `net::tcp` gains a working transport, this module needs no changes to start serving real
+//! connections.
+//!
+//! Nothing calls [`ShellServer::serve_one`] yet -- wiring a `kernel_main`-style command loop to
+//! it, the way `main.rs`'s own interactive loop drives the local UART console today, is left to
+//! whoever adds the transport underneath.
+
+use crate::console::{self, line_edit::LineEditor, line_edit::LineResult};
+use crate::net::tcp::{TcpListener, TcpStream};
+use crate::synchronization::{Mutex, NullLock};
+use core::fmt;
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+struct Inner {
+    stream: TcpStream,
+    chars_written: usize,
+    chars_read: usize,
+}
+
+impl Inner {
+    /// Send a character. There's no error channel back to
+    /// [`console::interface::Write::write_char`], so a failed write is silently dropped -- the
+    /// same tradeoff a physical UART driver makes when the wire itself misbehaves.
+    fn write_char(&mut self, c: char) {
+        let mut bytes = [0u8; 4];
+        let encoded = c.encode_utf8(&mut bytes);
+        let _ = self.stream.write(encoded.as_bytes());
+        self.chars_written += 1;
+    }
+
+    /// Block until a character arrives. [`TcpStream::read`] can't succeed until the connection
+    /// underneath it is real -- see the module docs -- so today this spins forever, the same
+    /// shape a UART's `read_char` spins while its RX FIFO is empty.
+    fn read_char(&mut self) -> char {
+        let mut byte = [0u8; 1];
+        loop {
+            if let Ok(1) = self.stream.read(&mut byte) {
+                self.chars_read += 1;
+                return byte[0] as char;
+            }
+        }
+    }
+}
+
+impl fmt::Write for Inner {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            self.write_char(c);
+        }
+
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A [`TcpStream`] wrapped as a [`console::interface::All`], so it can be driven by
+/// [`console::line_edit::LineEditor`] like any other console.
+pub struct TcpConsole {
+    inner: NullLock<Inner>,
+}
+
+impl TcpConsole {
+    fn new(stream: TcpStream) -> Self {
+        Self {
+            inner: NullLock::new(Inner {
+                stream,
+                chars_written: 0,
+                chars_read: 0,
+            }),
+        }
+    }
+}
+
+impl console::interface::Write for TcpConsole {
+    fn write_char(&self, c: char) {
+        self.inner.lock(|inner| inner.write_char(c));
+    }
+
+    fn write_fmt(&self, args: fmt::Arguments) -> fmt::Result {
+        self.inner.lock(|inner| fmt::Write::write_fmt(inner, args))
+    }
+
+    fn flush(&self) {}
+}
+
+impl console::interface::Read for TcpConsole {
+    fn read_char(&self) -> char {
+        self.inner.lock(|inner| inner.read_char())
+    }
+
+    fn clear_rx(&self) {}
+}
+
+impl console::interface::Statistics for TcpConsole {
+    fn chars_written(&self) -> usize {
+        self.inner.lock(|inner| inner.chars_written)
+    }
+
+    fn chars_read(&self) -> usize {
+        self.inner.lock(|inner| inner.chars_read)
+    }
+}
+
+impl console::interface::All for TcpConsole {}
+
+/// Prompt over `console` for a shared secret and compare it against `expected`.
+///
+/// The comparison isn't constant-time and the secret is echoed back as it's typed -- neither
+/// matters much for a debug shell on a lab rack, but both would want fixing before this guarded
+/// anything more sensitive.
+pub fn authenticate(console: &dyn console::interface::All, expected: &[u8]) -> bool {
+    console.write_fmt(format_args!("Password: ")).ok();
+
+    let mut editor = LineEditor::new();
+    match editor.read_line(console, console) {
+        LineResult::Line(line) => line.as_bytes() == expected,
+        LineResult::Eof | LineResult::Interrupted => false,
+    }
+}
+
+/// Accepts TCP connections and bridges each one to a line-edited console session.
+pub struct ShellServer {
+    listener: TcpListener,
+    shared_secret: Option<&'static [u8]>,
+}
+
+impl ShellServer {
+    /// Bind a server to `port`, optionally requiring `shared_secret` before serving a
+    /// connection.
+    pub fn bind(port: u16, shared_secret: Option<&'static str>) -> Result<Self, &'static str> {
+        Ok(Self {
+            listener: TcpListener::bind(port)?,
+            shared_secret: shared_secret.map(str::as_bytes),
+        })
+    }
+
+    /// Accept one connection, authenticate it if a shared secret is configured, then hand
+    /// completed lines to `handle_line` until the connection drops.
+    ///
+    /// Always fails at the `accept` step today -- see the module docs.
+    pub fn serve_one(&self, mut handle_line: impl FnMut(&str)) -> Result<(), &'static str> {
+        let stream = self.listener.accept()?;
+        let console = TcpConsole::new(stream);
+
+        if let Some(secret) = self.shared_secret {
+            if !authenticate(&console, secret) {
+                return Err("shell_server: authentication failed");
+            }
+        }
+
+        let mut editor = LineEditor::new();
+        loop {
+            match editor.read_line(&console, &console) {
+                LineResult::Line(line) => handle_line(line),
+                LineResult::Eof => return Ok(()),
+                LineResult::Interrupted => return Ok(()),
+            }
+        }
+    }
+}
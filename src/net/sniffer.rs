@@ -0,0 +1,289 @@
+//! A ring-buffered pcap capture of RX/TX Ethernet frames, dumpable over a text console as base64
+//! or uploadable to a host via TFTP.
+//!
+//! Nothing calls [`capture`] from an actual send/receive path yet: every
+//! [`crate::net::interface::NetworkDevice`] implementation (e.g.
+//! [`crate::bsp::device_driver::virtio::net::VirtioNet`]) is a concrete driver with its own
+//! `send`/`receive` methods, not a generic dispatch point this module could hook once and cover
+//! everything -- wiring each driver to mirror its frames here is left to whoever needs it.
+//! [`upload_tftp`] has a narrower gap than it used to: [`crate::net::udp`] could send a TFTP
+//! RRQ/WRQ now, but TFTP (RFC 1350) is a lock-step DATA/ACK exchange that has to retransmit a
+//! block on a lost ACK -- a timer wheel's job, the same gap [`crate::exception::syscall`]'s module
+//! doc notes -- so it's still an honest stub. Capture timestamps are also
+//! relative to boot, not wall-clock time -- see [`crate::time::TimeManager`] -- so a resulting
+//! pcap's absolute dates are meaningless even though the RX/TX deltas between frames are real.
+//!
+//! What's real: [`capture`] records into a fixed-depth ring buffer (dropping the oldest frame
+//! once full, the same trade-off [`crate::console::line_edit`]'s history ring makes); and
+//! [`dump_base64`] serializes the whole ring as a real pcap file -- global header, then one
+//! record header plus payload per frame -- and streams it out as base64 over any
+//! [`crate::console::interface::Write`], since this fork has no heap to buffer the encoded output
+//! in before sending it.
+
+use crate::console;
+use crate::synchronization::{Mutex, NullLock};
+use crate::time::{self, TimeManager};
+use core::time::Duration;
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const GLOBAL_HEADER_LEN: usize = 24;
+const RECORD_HEADER_LEN: usize = 16;
+/// pcap's magic number for little-endian, microsecond-resolution captures.
+const PCAP_MAGIC: u32 = 0xa1b2c3d4;
+/// pcap's `LINKTYPE_ETHERNET`.
+const LINKTYPE_ETHERNET: u32 = 1;
+
+fn write_global_header(buf: &mut [u8; GLOBAL_HEADER_LEN]) {
+    buf[0..4].copy_from_slice(&PCAP_MAGIC.to_le_bytes());
+    buf[4..6].copy_from_slice(&2u16.to_le_bytes()); // version_major
+    buf[6..8].copy_from_slice(&4u16.to_le_bytes()); // version_minor
+    buf[8..12].fill(0); // thiszone
+    buf[12..16].fill(0); // sigfigs
+    buf[16..20].copy_from_slice(&(SNAPLEN as u32).to_le_bytes());
+    buf[20..24].copy_from_slice(&LINKTYPE_ETHERNET.to_le_bytes());
+}
+
+fn write_record_header(
+    buf: &mut [u8; RECORD_HEADER_LEN],
+    timestamp: Duration,
+    captured_len: usize,
+    original_len: usize,
+) {
+    buf[0..4].copy_from_slice(&(timestamp.as_secs() as u32).to_le_bytes());
+    buf[4..8].copy_from_slice(&timestamp.subsec_micros().to_le_bytes());
+    buf[8..12].copy_from_slice(&(captured_len as u32).to_le_bytes());
+    buf[12..16].copy_from_slice(&(original_len as u32).to_le_bytes());
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+/// Wrap output lines at this width, matching the conventional MIME base64 line length.
+const BASE64_LINE_WIDTH: usize = 76;
+
+/// Streams bytes out as base64 without ever buffering the whole encoded output, carrying at most
+/// two bytes of unencoded residue between calls to [`Base64Writer::write`].
+struct Base64Writer<'a> {
+    sink: &'a dyn console::interface::Write,
+    pending: [u8; 3],
+    pending_len: usize,
+    column: usize,
+}
+
+impl<'a> Base64Writer<'a> {
+    fn new(sink: &'a dyn console::interface::Write) -> Self {
+        Self {
+            sink,
+            pending: [0; 3],
+            pending_len: 0,
+            column: 0,
+        }
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            let take = (3 - self.pending_len).min(bytes.len());
+            self.pending[self.pending_len..self.pending_len + take].copy_from_slice(&bytes[..take]);
+            self.pending_len += take;
+            bytes = &bytes[take..];
+
+            if self.pending_len == 3 {
+                self.emit_group(3);
+                self.pending_len = 0;
+            }
+        }
+    }
+
+    fn emit_group(&mut self, len: usize) {
+        let (b0, b1, b2) = (self.pending[0], self.pending[1], self.pending[2]);
+
+        let chars = [
+            BASE64_ALPHABET[(b0 >> 2) as usize],
+            BASE64_ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize],
+            if len > 1 {
+                BASE64_ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize]
+            } else {
+                b'='
+            },
+            if len > 2 {
+                BASE64_ALPHABET[(b2 & 0x3f) as usize]
+            } else {
+                b'='
+            },
+        ];
+
+        for c in chars {
+            self.sink.write_char(c as char);
+            self.column += 1;
+            if self.column == BASE64_LINE_WIDTH {
+                self.sink.write_char('\n');
+                self.column = 0;
+            }
+        }
+    }
+
+    /// Flush any residue (padded with `=`) and end the final line.
+    fn finish(mut self) {
+        if self.pending_len > 0 {
+            self.emit_group(self.pending_len);
+        }
+        if self.column != 0 {
+            self.sink.write_char('\n');
+        }
+    }
+}
+
+struct Ring {
+    data: [[u8; SNAPLEN]; CAPTURE_CAPACITY],
+    captured_lens: [usize; CAPTURE_CAPACITY],
+    original_lens: [usize; CAPTURE_CAPACITY],
+    directions: [Direction; CAPTURE_CAPACITY],
+    timestamps: [Duration; CAPTURE_CAPACITY],
+    count: usize,
+    next_slot: usize,
+    dropped: u64,
+}
+
+impl Ring {
+    const fn new() -> Self {
+        Self {
+            data: [[0; SNAPLEN]; CAPTURE_CAPACITY],
+            captured_lens: [0; CAPTURE_CAPACITY],
+            original_lens: [0; CAPTURE_CAPACITY],
+            directions: [Direction::Rx; CAPTURE_CAPACITY],
+            timestamps: [Duration::ZERO; CAPTURE_CAPACITY],
+            count: 0,
+            next_slot: 0,
+            dropped: 0,
+        }
+    }
+
+    fn push(&mut self, direction: Direction, timestamp: Duration, frame: &[u8]) {
+        let slot = self.next_slot;
+        let captured_len = frame.len().min(SNAPLEN);
+
+        self.data[slot][..captured_len].copy_from_slice(&frame[..captured_len]);
+        self.captured_lens[slot] = captured_len;
+        self.original_lens[slot] = frame.len();
+        self.directions[slot] = direction;
+        self.timestamps[slot] = timestamp;
+
+        self.next_slot = (self.next_slot + 1) % CAPTURE_CAPACITY;
+        if self.count < CAPTURE_CAPACITY {
+            self.count += 1;
+        } else {
+            self.dropped += 1;
+        }
+    }
+
+    /// The slot holding the oldest still-retained frame.
+    fn oldest_slot(&self) -> usize {
+        if self.count < CAPTURE_CAPACITY {
+            0
+        } else {
+            self.next_slot
+        }
+    }
+
+    /// Visit every retained frame, oldest first.
+    fn for_each(&self, mut f: impl FnMut(Direction, Duration, &[u8], usize)) {
+        let start = self.oldest_slot();
+
+        for i in 0..self.count {
+            let slot = (start + i) % CAPTURE_CAPACITY;
+            f(
+                self.directions[slot],
+                self.timestamps[slot],
+                &self.data[slot][..self.captured_lens[slot]],
+                self.original_lens[slot],
+            );
+        }
+    }
+}
+
+static RING: NullLock<Ring> = NullLock::new(Ring::new());
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// How many frames the capture ring retains before overwriting the oldest one.
+pub const CAPTURE_CAPACITY: usize = 32;
+/// The longest prefix of a frame that's actually captured (pcap's "snap length"); anything beyond
+/// this is recorded as dropped from the capture but still counted in the record's original
+/// length.
+pub const SNAPLEN: usize = 256;
+
+/// Which direction a captured frame travelled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Rx,
+    Tx,
+}
+
+/// A capture ring occupancy and drop-count snapshot.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SnifferStats {
+    pub capacity: usize,
+    pub count: usize,
+    pub dropped: u64,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Record `frame` into the capture ring, stamped with the current uptime.
+pub fn capture(direction: Direction, frame: &[u8]) {
+    let timestamp = time::time_manager().uptime();
+
+    RING.lock(|ring| ring.push(direction, timestamp, frame));
+}
+
+/// This capture ring's current occupancy and lifetime drop count.
+pub fn stats() -> SnifferStats {
+    RING.lock(|ring| SnifferStats {
+        capacity: CAPTURE_CAPACITY,
+        count: ring.count,
+        dropped: ring.dropped,
+    })
+}
+
+/// Discard all retained frames.
+pub fn clear() {
+    RING.lock(|ring| *ring = Ring::new());
+}
+
+/// Serialize the capture ring as a pcap file and stream it out as base64 over `sink`, oldest
+/// frame first.
+pub fn dump_base64(sink: &dyn console::interface::Write) {
+    let mut encoder = Base64Writer::new(sink);
+
+    let mut global_header = [0u8; GLOBAL_HEADER_LEN];
+    write_global_header(&mut global_header);
+    encoder.write(&global_header);
+
+    RING.lock(|ring| {
+        ring.for_each(|_direction, timestamp, captured, original_len| {
+            let mut record_header = [0u8; RECORD_HEADER_LEN];
+            write_record_header(&mut record_header, timestamp, captured.len(), original_len);
+
+            encoder.write(&record_header);
+            encoder.write(captured);
+        });
+    });
+
+    encoder.finish();
+}
+
+/// Upload the capture ring to `server` as a pcap file named `filename`, over TFTP.
+///
+/// Always fails today -- see the module docs: an RRQ could be sent over [`crate::net::udp`] now,
+/// but there's no timer wheel to retransmit a DATA block whose ACK never comes back.
+pub fn upload_tftp(server: [u8; 4], filename: &str) -> Result<(), &'static str> {
+    let _ = (server, filename);
+
+    Err("sniffer: no timer wheel to retransmit a TFTP DATA block with if its ACK never comes back -- see the module docs")
+}
@@ -0,0 +1,292 @@
+//! An SNTP client (RFC 4330) for setting the wall clock from a network time server.
+//!
+//! [`SntpClient::send_request`] really sends a request over [`crate::net::udp`] now. Two things
+//! are still missing before this can run for real: a timer wheel to drive periodic re-sync
+//! polling, the same gap [`crate::exception::syscall`]'s module doc notes; and, unlike that, a
+//! wall clock at all -- [`crate::time::TimeManager`] only exposes a monotonic uptime, with nothing
+//! resembling `settimeofday` to step or slew. Until both exist, [`SntpClient::sync`] is an honest
+//! stub, and there's no "after DHCP completes" hook to call it from either.
+//!
+//! What's real is the packet format and the arithmetic: [`Header::parse`]/[`Header::write`] codec
+//! the 48-byte NTP header, [`compute_sample`] turns the four timestamps a client/server exchange
+//! carries into a clock offset and round-trip delay exactly as RFC 4330 §5 specifies, and
+//! [`classify`] decides whether an offset is small enough to slew or large enough to step --
+//! [`SntpClient::record_response`] chains all three so the caller only has to poll
+//! [`crate::net::udp::recv_from`] for the reply and hand it over plus its own send/receive
+//! timestamps, the same shape as [`crate::net::config::dhcp::Client::handle_ack`].
+
+use crate::net::interface::NetworkDevice;
+use crate::net::udp;
+use core::time::Duration;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The fixed size of an NTP header with no extension fields.
+pub const HEADER_LEN: usize = 48;
+
+/// An offset larger than this is stepped in immediately rather than slewed, mirroring ordinary
+/// NTP client practice (a fraction of a second is judged small enough to correct gradually
+/// without the wall clock visibly jumping).
+pub const STEP_THRESHOLD: Duration = Duration::from_millis(128);
+
+/// The UDP port an NTP/SNTP server listens on.
+pub const SERVER_PORT: u16 = 123;
+
+/// A 64-bit NTP timestamp (RFC 4330 §3): seconds and a binary fraction of a second, both since
+/// the NTP epoch. The epoch itself doesn't matter here -- every use below only ever subtracts two
+/// of these from each other.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct NtpTimestamp {
+    pub seconds: u32,
+    pub fraction: u32,
+}
+
+impl NtpTimestamp {
+    fn parse(bytes: &[u8]) -> Self {
+        Self {
+            seconds: u32::from_be_bytes(bytes[0..4].try_into().unwrap()),
+            fraction: u32::from_be_bytes(bytes[4..8].try_into().unwrap()),
+        }
+    }
+
+    fn write(self, buf: &mut [u8]) {
+        buf[0..4].copy_from_slice(&self.seconds.to_be_bytes());
+        buf[4..8].copy_from_slice(&self.fraction.to_be_bytes());
+    }
+
+    /// This timestamp as nanoseconds since whatever epoch it was itself measured from.
+    fn as_nanos(self) -> i128 {
+        i128::from(self.seconds) * 1_000_000_000
+            + ((i128::from(self.fraction) * 1_000_000_000) >> 32)
+    }
+}
+
+/// A parsed NTP header (RFC 4330 §4). Extension fields and authentication trailers aren't
+/// supported.
+#[derive(Copy, Clone, Debug)]
+pub struct Header {
+    pub version: u8,
+    pub mode: u8,
+    pub stratum: u8,
+    pub poll: i8,
+    pub reference_timestamp: NtpTimestamp,
+    pub origin_timestamp: NtpTimestamp,
+    pub receive_timestamp: NtpTimestamp,
+    pub transmit_timestamp: NtpTimestamp,
+}
+
+impl Header {
+    /// Parse a fixed 48-byte NTP header from `bytes`.
+    pub fn parse(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() < HEADER_LEN {
+            return Err("sntp: packet shorter than an NTP header");
+        }
+
+        Ok(Self {
+            version: (bytes[0] >> 3) & 0x07,
+            mode: bytes[0] & 0x07,
+            stratum: bytes[1],
+            poll: bytes[2] as i8,
+            reference_timestamp: NtpTimestamp::parse(&bytes[16..24]),
+            origin_timestamp: NtpTimestamp::parse(&bytes[24..32]),
+            receive_timestamp: NtpTimestamp::parse(&bytes[32..40]),
+            transmit_timestamp: NtpTimestamp::parse(&bytes[40..48]),
+        })
+    }
+
+    /// Serialize this header into `buf`, which must be at least [`HEADER_LEN`] bytes. The leap
+    /// indicator is always written as 0 ("no warning") and precision/root delay/root
+    /// dispersion/reference ID are always written as 0, matching what an unsynchronized client
+    /// sends in its own request.
+    pub fn write(&self, buf: &mut [u8]) -> Result<(), &'static str> {
+        if buf.len() < HEADER_LEN {
+            return Err("sntp: buffer too small for an NTP header");
+        }
+
+        buf[..HEADER_LEN].fill(0);
+        buf[0] = (self.version << 3) | self.mode;
+        buf[1] = self.stratum;
+        buf[2] = self.poll as u8;
+        self.reference_timestamp.write(&mut buf[16..24]);
+        self.origin_timestamp.write(&mut buf[24..32]);
+        self.receive_timestamp.write(&mut buf[32..40]);
+        self.transmit_timestamp.write(&mut buf[40..48]);
+
+        Ok(())
+    }
+}
+
+/// Build a client request (RFC 4330 §4's mode 3) into `buf`, stamping `transmit_timestamp` as its
+/// own send time, returning the number of bytes written.
+pub fn build_request(
+    buf: &mut [u8],
+    transmit_timestamp: NtpTimestamp,
+) -> Result<usize, &'static str> {
+    let header = Header {
+        version: 4,
+        mode: 3,
+        stratum: 0,
+        poll: 0,
+        reference_timestamp: NtpTimestamp::default(),
+        origin_timestamp: NtpTimestamp::default(),
+        receive_timestamp: NtpTimestamp::default(),
+        transmit_timestamp,
+    };
+    header.write(buf)?;
+
+    Ok(HEADER_LEN)
+}
+
+/// A clock offset and round-trip delay derived from one request/reply exchange (RFC 4330 §5).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Sample {
+    /// How far the local clock is behind the server, in nanoseconds (negative if it's ahead) --
+    /// the correction to add to the local clock.
+    pub offset_nanos: i64,
+    /// How long the round trip to the server and back took.
+    pub round_trip_delay: Duration,
+}
+
+/// Compute a [`Sample`] from the four timestamps a client/server exchange carries: `t1` when the
+/// client sent its request, `t2` when the server received it, `t3` when the server sent its
+/// reply, and `t4` when the client received it.
+pub fn compute_sample(
+    t1: NtpTimestamp,
+    t2: NtpTimestamp,
+    t3: NtpTimestamp,
+    t4: NtpTimestamp,
+) -> Sample {
+    let (t1, t2, t3, t4) = (t1.as_nanos(), t2.as_nanos(), t3.as_nanos(), t4.as_nanos());
+
+    let offset_nanos = ((t2 - t1) + (t3 - t4)) / 2;
+    let round_trip_nanos = (t4 - t1) - (t3 - t2);
+
+    Sample {
+        offset_nanos: offset_nanos as i64,
+        round_trip_delay: Duration::from_nanos(round_trip_nanos.max(0) as u64),
+    }
+}
+
+/// How an offset should be corrected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Adjustment {
+    /// Correct the clock gradually, by speeding up or slowing down how it advances.
+    Slew(i64),
+    /// Correct the clock immediately, by setting it to a new value outright.
+    Step(i64),
+}
+
+/// Classify `offset_nanos` as small enough to [`Adjustment::Slew`] or large enough to
+/// [`Adjustment::Step`], against [`STEP_THRESHOLD`].
+pub fn classify(offset_nanos: i64) -> Adjustment {
+    if offset_nanos.unsigned_abs() > STEP_THRESHOLD.as_nanos() as u64 {
+        Adjustment::Step(offset_nanos)
+    } else {
+        Adjustment::Slew(offset_nanos)
+    }
+}
+
+/// An SNTP client tracking one time server.
+pub struct SntpClient {
+    server: [u8; 4],
+    poll_interval: Duration,
+    last_sample: Option<Sample>,
+}
+
+impl SntpClient {
+    /// Create a client that will query `server`, re-syncing every `poll_interval` once periodic
+    /// re-sync exists -- see the module docs.
+    pub fn new(server: [u8; 4], poll_interval: Duration) -> Self {
+        Self {
+            server,
+            poll_interval,
+            last_sample: None,
+        }
+    }
+
+    /// The server this client queries.
+    pub fn server(&self) -> [u8; 4] {
+        self.server
+    }
+
+    /// How often this client would re-sync, once periodic re-sync exists.
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    /// The most recent sample recorded by [`SntpClient::record_response`], if any.
+    pub fn last_sample(&self) -> Option<Sample> {
+        self.last_sample
+    }
+
+    /// Send a request to [`SntpClient::server`] over `device`, stamping `transmit_timestamp` as
+    /// this client's own send time.
+    ///
+    /// Sends exactly once -- see the module docs for the timer wheel periodic re-sync would need.
+    /// The caller is responsible for polling [`crate::net::udp::recv_from`] for the reply and
+    /// passing it to [`SntpClient::record_response`].
+    pub fn send_request(
+        &self,
+        device: &dyn NetworkDevice,
+        source_mac: [u8; 6],
+        source_addr: [u8; 4],
+        source_port: u16,
+        transmit_timestamp: NtpTimestamp,
+    ) -> Result<(), &'static str> {
+        let mut buf = [0u8; HEADER_LEN];
+        let len = build_request(&mut buf, transmit_timestamp)?;
+
+        udp::send_to(
+            device,
+            source_mac,
+            source_addr,
+            source_port,
+            self.server,
+            SERVER_PORT,
+            &buf[..len],
+        )
+    }
+
+    /// Query the server and apply the resulting offset to the wall clock.
+    ///
+    /// Always fails today -- see the module docs: a request could be sent with
+    /// [`SntpClient::send_request`] now, but there's still no wall clock to apply an offset to,
+    /// and no timer wheel to drive periodic re-sync.
+    pub fn sync(&mut self) -> Result<(), &'static str> {
+        Err(
+            "sntp: no wall clock to adjust, and no timer wheel to drive periodic re-sync -- see the module docs",
+        )
+    }
+
+    /// Parse a server's reply, given the local send/receive timestamps a real transport would
+    /// have stamped, and record the resulting [`Sample`] and [`Adjustment`].
+    ///
+    /// This computes a real offset and a real slew-or-step decision; it just has nowhere to apply
+    /// either, since there's no wall clock -- see the module docs.
+    pub fn record_response(
+        &mut self,
+        response: &[u8],
+        local_transmit_timestamp: NtpTimestamp,
+        local_receive_timestamp: NtpTimestamp,
+    ) -> Result<Adjustment, &'static str> {
+        let header = Header::parse(response)?;
+        if header.mode != 4 {
+            return Err("sntp: not a server reply");
+        }
+        if header.stratum == 0 {
+            return Err("sntp: kiss-of-death reply (stratum 0)");
+        }
+
+        let sample = compute_sample(
+            local_transmit_timestamp,
+            header.receive_timestamp,
+            header.transmit_timestamp,
+            local_receive_timestamp,
+        );
+        self.last_sample = Some(sample);
+
+        Ok(classify(sample.offset_nanos))
+    }
+}
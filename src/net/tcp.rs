@@ -0,0 +1,239 @@
+//! Kernel-side TCP.
+//!
+//! [`crate::net::ipv4`] and [`crate::net::arp`] now exist, so a segment could physically reach the
+//! wire -- but a handshake and a connection are more than one send: [`TcpListener::accept`] has to
+//! wait for an inbound SYN while this fork's main loop moves on to other work, and
+//! [`TcpStream::connect`]'s SYN needs retransmitting if no SYN-ACK shows up. Both are a timer
+//! wheel's job (driving a retransmission timeout and letting `accept`/`connect` be polled rather
+//! than block forever), the same gap [`crate::exception::syscall`]'s module doc notes for
+//! `nanosleep`. Until it exists, there's also nowhere to keep a table of in-flight connections
+//! demultiplexing segments by port, so [`TcpListener::accept`] and [`TcpStream::connect`] stay
+//! honest stubs -- narrower ones than before, since the transport underneath them is no longer the
+//! missing piece.
+//!
+//! What's real here is the wire format everything above would eventually speak:
+//! [`Header::parse`]/[`Header::write`] codec a TCP segment header (no options), and [`checksum`]
+//! computes the RFC 793 internet checksum over the IPv4 pseudo-header. None of that depends on the
+//! timer wheel -- it's pure struct-and-checksum code -- so a future state machine's segment
+//! construction has something real to build on instead of starting from raw bytes.
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The fixed size of a TCP header with no options.
+pub const HEADER_LEN: usize = 20;
+
+/// A TCP header's six control bits (RFC 793 §3.1).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Flags {
+    pub urg: bool,
+    pub ack: bool,
+    pub psh: bool,
+    pub rst: bool,
+    pub syn: bool,
+    pub fin: bool,
+}
+
+impl Flags {
+    fn from_bits(bits: u8) -> Self {
+        Self {
+            urg: bits & 0x20 != 0,
+            ack: bits & 0x10 != 0,
+            psh: bits & 0x08 != 0,
+            rst: bits & 0x04 != 0,
+            syn: bits & 0x02 != 0,
+            fin: bits & 0x01 != 0,
+        }
+    }
+
+    fn to_bits(self) -> u8 {
+        (self.urg as u8) << 5
+            | (self.ack as u8) << 4
+            | (self.psh as u8) << 3
+            | (self.rst as u8) << 2
+            | (self.syn as u8) << 1
+            | (self.fin as u8)
+    }
+}
+
+/// A parsed TCP segment header (RFC 793 §3.1). TCP options are not supported.
+#[derive(Copy, Clone, Debug)]
+pub struct Header {
+    pub source_port: u16,
+    pub dest_port: u16,
+    pub sequence_number: u32,
+    pub ack_number: u32,
+    pub flags: Flags,
+    pub window: u16,
+    pub checksum: u16,
+    pub urgent_pointer: u16,
+}
+
+impl Header {
+    /// Parse a fixed 20-byte TCP header (no options) from `bytes`.
+    pub fn parse(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() < HEADER_LEN {
+            return Err("tcp: segment shorter than a TCP header");
+        }
+
+        let data_offset = usize::from(bytes[12] >> 4) * 4;
+        if data_offset != HEADER_LEN {
+            return Err("tcp: TCP options are not supported");
+        }
+
+        Ok(Self {
+            source_port: u16::from_be_bytes(bytes[0..2].try_into().unwrap()),
+            dest_port: u16::from_be_bytes(bytes[2..4].try_into().unwrap()),
+            sequence_number: u32::from_be_bytes(bytes[4..8].try_into().unwrap()),
+            ack_number: u32::from_be_bytes(bytes[8..12].try_into().unwrap()),
+            flags: Flags::from_bits(bytes[13] & 0x3f),
+            window: u16::from_be_bytes(bytes[14..16].try_into().unwrap()),
+            checksum: u16::from_be_bytes(bytes[16..18].try_into().unwrap()),
+            urgent_pointer: u16::from_be_bytes(bytes[18..20].try_into().unwrap()),
+        })
+    }
+
+    /// Serialize this header (with no options) into `buf`, which must be at least [`HEADER_LEN`]
+    /// bytes.
+    pub fn write(&self, buf: &mut [u8]) -> Result<(), &'static str> {
+        if buf.len() < HEADER_LEN {
+            return Err("tcp: buffer too small for a TCP header");
+        }
+
+        buf[0..2].copy_from_slice(&self.source_port.to_be_bytes());
+        buf[2..4].copy_from_slice(&self.dest_port.to_be_bytes());
+        buf[4..8].copy_from_slice(&self.sequence_number.to_be_bytes());
+        buf[8..12].copy_from_slice(&self.ack_number.to_be_bytes());
+        buf[12] = ((HEADER_LEN / 4) as u8) << 4;
+        buf[13] = self.flags.to_bits();
+        buf[14..16].copy_from_slice(&self.window.to_be_bytes());
+        buf[16..18].copy_from_slice(&self.checksum.to_be_bytes());
+        buf[18..20].copy_from_slice(&self.urgent_pointer.to_be_bytes());
+
+        Ok(())
+    }
+}
+
+/// Sum `bytes` as big-endian 16-bit words into `sum`, padding a trailing odd byte with a zero
+/// low byte, per RFC 1071.
+fn add_checksum_words(sum: &mut u32, bytes: &[u8]) {
+    let mut chunks = bytes.chunks_exact(2);
+    for chunk in &mut chunks {
+        *sum += u32::from(u16::from_be_bytes([chunk[0], chunk[1]]));
+    }
+    if let [last] = chunks.remainder() {
+        *sum += u32::from(u16::from_be_bytes([*last, 0]));
+    }
+}
+
+/// Compute the RFC 793/RFC 1071 internet checksum of a TCP segment (`header` plus `payload`,
+/// with `header.checksum` treated as zero) over the IPv4 pseudo-header formed from
+/// `source_addr`/`dest_addr`.
+pub fn checksum(source_addr: [u8; 4], dest_addr: [u8; 4], header: &Header, payload: &[u8]) -> u16 {
+    let mut sum: u32 = 0;
+
+    add_checksum_words(&mut sum, &source_addr);
+    add_checksum_words(&mut sum, &dest_addr);
+    sum += 6; // pseudo-header zero byte (0x00) + IP protocol number for TCP (0x06)
+    sum += (HEADER_LEN + payload.len()) as u32;
+
+    let mut zeroed_header = *header;
+    zeroed_header.checksum = 0;
+    let mut header_bytes = [0u8; HEADER_LEN];
+    zeroed_header
+        .write(&mut header_bytes)
+        .expect("header_bytes is exactly HEADER_LEN bytes");
+    add_checksum_words(&mut sum, &header_bytes);
+    add_checksum_words(&mut sum, payload);
+
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
+
+/// A TCP connection's state (RFC 793 §3.2), tracked even though nothing here can drive a
+/// connection through more than [`State::Closed`] yet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum State {
+    Closed,
+    SynSent,
+    SynReceived,
+    Established,
+    FinWait,
+    CloseWait,
+    Closing,
+    LastAck,
+    TimeWait,
+}
+
+/// A listening TCP socket, bound to a local port.
+pub struct TcpListener {
+    port: u16,
+}
+
+impl TcpListener {
+    /// Bind a listener to `port`. Binding itself needs nothing this fork is missing -- it's just
+    /// bookkeeping -- but see [`TcpListener::accept`] for what does.
+    pub fn bind(port: u16) -> Result<Self, &'static str> {
+        Ok(Self { port })
+    }
+
+    /// The port this listener is bound to.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Wait for and accept an inbound connection.
+    ///
+    /// Always fails today -- see the module docs: there's no timer wheel to poll for an inbound
+    /// SYN against and no connection table to track the handshake in.
+    pub fn accept(&self) -> Result<TcpStream, &'static str> {
+        Err("tcp: no timer wheel to poll for a SYN with, and no connection table to track a handshake in -- see the module docs")
+    }
+}
+
+/// A TCP connection.
+pub struct TcpStream {
+    state: State,
+}
+
+impl TcpStream {
+    /// Open a connection to `dest_addr:dest_port`.
+    ///
+    /// Always fails today -- see the module docs: a SYN could be sent over [`crate::net::ipv4`]
+    /// now, but there's no timer wheel to retransmit it if no SYN-ACK comes back.
+    pub fn connect(dest_addr: [u8; 4], dest_port: u16) -> Result<Self, &'static str> {
+        let _ = (dest_addr, dest_port);
+
+        Err("tcp: no timer wheel to retransmit a SYN with if no SYN-ACK comes back -- see the module docs")
+    }
+
+    /// This connection's current state. Always [`State::Closed`] today -- see
+    /// [`TcpStream::connect`].
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Read into `buf` from the connection.
+    ///
+    /// Always fails today, since no [`TcpStream`] can leave [`State::Closed`] -- see
+    /// [`TcpStream::connect`].
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, &'static str> {
+        let _ = buf;
+
+        Err("tcp: connection is not established")
+    }
+
+    /// Write `buf` to the connection.
+    ///
+    /// Always fails today, since no [`TcpStream`] can leave [`State::Closed`] -- see
+    /// [`TcpStream::connect`].
+    pub fn write(&mut self, buf: &[u8]) -> Result<usize, &'static str> {
+        let _ = buf;
+
+        Err("tcp: connection is not established")
+    }
+}
@@ -0,0 +1,220 @@
+//! UDP (RFC 768) over the IPv4 layer in [`crate::net::ipv4`].
+//!
+//! This is what lets [`crate::net::dns`], [`crate::net::config::dhcp`], [`crate::net::sntp`], and
+//! [`crate::net::mdns`] stop being wire-format-only: a real [`send_to`]/[`recv_from`] over a
+//! [`crate::net::interface::NetworkDevice`]. What none of them get from this alone is retry,
+//! timeout, or periodic re-send -- that's still the timer wheel gap each of their own module docs
+//! names (tied to [`crate::exception::syscall`]'s missing `nanosleep`): [`send_to`] sends exactly
+//! once, and [`recv_from`] reports one poll's worth of what's queued, the same "caller drives it
+//! from the main loop" shape [`crate::jobs::poll_all`] already uses.
+
+use crate::net::interface::NetworkDevice;
+use crate::net::ipv4;
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+const HEADER_LEN: usize = 8;
+/// The longest UDP payload [`send_to`] will build in one call -- comfortably larger than every
+/// message this fork's clients send (DNS/DHCP/SNTP/mDNS are all well under a page), far short of
+/// the 64KiB RFC 768 allows.
+const MAX_PAYLOAD_LEN: usize = 1024;
+/// Ethernet + IPv4 header space a frame buffer needs ahead of a UDP datagram.
+const FRAME_HEADROOM: usize = ethernet_and_ipv4_header_len();
+
+const fn ethernet_and_ipv4_header_len() -> usize {
+    crate::net::ethernet::HEADER_LEN + ipv4::HEADER_LEN
+}
+
+/// Sum `bytes` as big-endian 16-bit words into `sum`, padding a trailing odd byte with a zero low
+/// byte, per RFC 1071 -- the same helper [`crate::net::tcp::checksum`] uses for its own pseudo
+/// header.
+fn add_checksum_words(sum: &mut u32, bytes: &[u8]) {
+    let mut chunks = bytes.chunks_exact(2);
+    for chunk in &mut chunks {
+        *sum += u32::from(u16::from_be_bytes([chunk[0], chunk[1]]));
+    }
+    if let [last] = chunks.remainder() {
+        *sum += u32::from(u16::from_be_bytes([*last, 0]));
+    }
+}
+
+fn checksum(
+    source_addr: [u8; 4],
+    dest_addr: [u8; 4],
+    header_bytes: &[u8; HEADER_LEN],
+    payload: &[u8],
+) -> u16 {
+    let mut sum: u32 = 0;
+
+    add_checksum_words(&mut sum, &source_addr);
+    add_checksum_words(&mut sum, &dest_addr);
+    sum += u32::from(ipv4::PROTO_UDP);
+    sum += (HEADER_LEN + payload.len()) as u32;
+    add_checksum_words(&mut sum, header_bytes);
+    add_checksum_words(&mut sum, payload);
+
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
+
+fn write_datagram(
+    buf: &mut [u8],
+    source_addr: [u8; 4],
+    dest_addr: [u8; 4],
+    source_port: u16,
+    dest_port: u16,
+    payload: &[u8],
+) -> Result<usize, &'static str> {
+    let total_len = HEADER_LEN + payload.len();
+    let buf = buf
+        .get_mut(..total_len)
+        .ok_or("udp: buffer too small for this datagram")?;
+
+    buf[0..2].copy_from_slice(&source_port.to_be_bytes());
+    buf[2..4].copy_from_slice(&dest_port.to_be_bytes());
+    buf[4..6].copy_from_slice(&(total_len as u16).to_be_bytes());
+    buf[6..8].fill(0);
+    buf[HEADER_LEN..].copy_from_slice(payload);
+
+    let header_bytes: [u8; HEADER_LEN] = buf[..HEADER_LEN].try_into().unwrap();
+    let checksum = checksum(source_addr, dest_addr, &header_bytes, payload);
+    buf[6..8].copy_from_slice(&checksum.to_be_bytes());
+
+    Ok(total_len)
+}
+
+struct ParsedDatagram<'a> {
+    source_port: u16,
+    dest_port: u16,
+    payload: &'a [u8],
+}
+
+fn parse_datagram<'a>(
+    source_addr: [u8; 4],
+    dest_addr: [u8; 4],
+    bytes: &'a [u8],
+) -> Result<ParsedDatagram<'a>, &'static str> {
+    if bytes.len() < HEADER_LEN {
+        return Err("udp: datagram shorter than a header");
+    }
+
+    let length = usize::from(u16::from_be_bytes(bytes[4..6].try_into().unwrap())).max(HEADER_LEN);
+    let datagram = bytes
+        .get(..length)
+        .ok_or("udp: length field overruns the received datagram")?;
+    let checksum_field = u16::from_be_bytes(datagram[6..8].try_into().unwrap());
+
+    if checksum_field != 0 {
+        let mut zeroed = [0u8; HEADER_LEN];
+        zeroed.copy_from_slice(&datagram[..HEADER_LEN]);
+        zeroed[6..8].fill(0);
+        if checksum(source_addr, dest_addr, &zeroed, &datagram[HEADER_LEN..]) != checksum_field {
+            return Err("udp: checksum mismatch");
+        }
+    }
+
+    Ok(ParsedDatagram {
+        source_port: u16::from_be_bytes(datagram[0..2].try_into().unwrap()),
+        dest_port: u16::from_be_bytes(datagram[2..4].try_into().unwrap()),
+        payload: &datagram[HEADER_LEN..],
+    })
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// One received datagram's source address/port and destination port, with the payload copied into
+/// the caller's `payload_buf` and its length reported here.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Datagram {
+    pub source_addr: [u8; 4],
+    pub source_port: u16,
+    pub dest_port: u16,
+    pub len: usize,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Send `payload` from `source_addr:source_port` to `dest_addr:dest_port`, over `device`.
+///
+/// Sends exactly once -- see the module docs for the timer wheel a retry or periodic re-send
+/// would need.
+pub fn send_to(
+    device: &dyn NetworkDevice,
+    source_mac: [u8; 6],
+    source_addr: [u8; 4],
+    source_port: u16,
+    dest_addr: [u8; 4],
+    dest_port: u16,
+    payload: &[u8],
+) -> Result<(), &'static str> {
+    if payload.len() > MAX_PAYLOAD_LEN {
+        return Err("udp: payload exceeds this fork's per-call limit");
+    }
+
+    let mut datagram_buf = [0u8; HEADER_LEN + MAX_PAYLOAD_LEN];
+    let datagram_len = write_datagram(
+        &mut datagram_buf,
+        source_addr,
+        dest_addr,
+        source_port,
+        dest_port,
+        payload,
+    )?;
+
+    let mut frame_buf = [0u8; FRAME_HEADROOM + HEADER_LEN + MAX_PAYLOAD_LEN];
+    ipv4::send(
+        device,
+        source_mac,
+        source_addr,
+        dest_addr,
+        ipv4::PROTO_UDP,
+        &datagram_buf[..datagram_len],
+        &mut frame_buf,
+    )
+}
+
+/// Poll `device` once for a UDP datagram addressed to `my_ip`, copying its payload into
+/// `payload_buf` and returning its length and source.
+///
+/// See the module docs: this is one poll, not a wait -- call it again on your own schedule.
+pub fn recv_from(
+    device: &dyn NetworkDevice,
+    my_mac: [u8; 6],
+    my_ip: [u8; 4],
+    payload_buf: &mut [u8],
+) -> Result<Option<Datagram>, &'static str> {
+    let mut frame_buf = [0u8; crate::net::pbuf::BUFFER_LEN];
+
+    let ip_datagram = match ipv4::recv(device, my_mac, my_ip, &mut frame_buf)? {
+        Some(datagram) if datagram.protocol == ipv4::PROTO_UDP => datagram,
+        _ => return Ok(None),
+    };
+
+    let udp_datagram = match parse_datagram(
+        ip_datagram.source_addr,
+        ip_datagram.destination_addr,
+        ip_datagram.payload,
+    ) {
+        Ok(datagram) => datagram,
+        Err(_) => return Ok(None),
+    };
+
+    let len = udp_datagram.payload.len().min(payload_buf.len());
+    payload_buf[..len].copy_from_slice(&udp_datagram.payload[..len]);
+
+    Ok(Some(Datagram {
+        source_addr: ip_datagram.source_addr,
+        source_port: udp_datagram.source_port,
+        dest_port: udp_datagram.dest_port,
+        len,
+    }))
+}
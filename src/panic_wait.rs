@@ -0,0 +1,35 @@
+//! A panic handler that infinitely waits.
+
+use crate::{cpu, crashdump, log, println, testing};
+use core::panic::PanicInfo;
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    println!();
+
+    if testing::take_expected_panic() {
+        log::log_info!("panic", "expected panic: {}", info.message());
+    } else {
+        log::log_error!("panic", "{}", info.message());
+    }
+
+    log::log_error!(
+        "panic",
+        "register state at panic:\n{}",
+        cpu::context::snapshot()
+    );
+
+    crashdump::capture(info.message());
+
+    // A registered ACT LED blinks fast from here on, in case nothing else reaches whoever's
+    // looking at the board -- see crate::led. Boards with no LED wired up just wait as before.
+    #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+    {
+        crate::led::panic_fast_blink_forever()
+    }
+
+    #[cfg(not(any(feature = "bsp_rpi3", feature = "bsp_rpi4")))]
+    {
+        cpu::wait_forever()
+    }
+}
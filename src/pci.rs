@@ -0,0 +1,329 @@
+//! PCI/PCIe config-space access and minimal bus enumeration.
+//!
+//! This is the hardware-independent half of the PCIe request: the config-space address math
+//! ([`ecam_address`]), header field decoding ([`DeviceIdentity::from_header`], [`BarKind`]), and a
+//! brute-force bus/device/function walk ([`enumerate`]) built entirely against the [`ConfigAccess`]
+//! trait, the same "program against a trait, not a concrete controller" shape
+//! [`crate::storage::interface::BlockDevice`] gives the FAT32/block-cache code. None of it assumes
+//! a particular root complex, so it's exercised below with a RAM-backed fake instead of real
+//! hardware -- see [`crate::bsp::device_driver::bcm::bcm2xxx_pcie`] for the one concrete
+//! [`ConfigAccess`] implementation this fork has (and what it still can't do).
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A location in PCI config space: bus, device, and function numbers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeviceLocation {
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+}
+
+/// The fields [`enumerate`] reads out of a function's header to report it as present.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeviceIdentity {
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub class_code: u8,
+    pub subclass: u8,
+    pub header_type: u8,
+}
+
+impl DeviceIdentity {
+    /// Decode a function's identity out of its first four config-space dwords (offsets
+    /// `0x00`/`0x08`/`0x0c`), as read by [`enumerate`].
+    fn from_header(dword0: u32, dword2: u32, dword3: u32) -> Self {
+        Self {
+            vendor_id: dword0 as u16,
+            device_id: (dword0 >> 16) as u16,
+            subclass: (dword2 >> 16) as u8,
+            class_code: (dword2 >> 24) as u8,
+            header_type: (dword3 >> 16) as u8,
+        }
+    }
+
+    /// Whether this function's vendor ID marks the slot as unpopulated (PCI Local Bus spec:
+    /// `0xffff` is never a real vendor).
+    fn is_present(dword0: u32) -> bool {
+        (dword0 as u16) != 0xffff
+    }
+
+    /// Whether [`Self::header_type`]'s multi-function bit (bit 7) is set, meaning functions 1-7
+    /// are worth probing too.
+    fn is_multi_function(&self) -> bool {
+        self.header_type & 0x80 != 0
+    }
+}
+
+/// What a Base Address Register decodes to, per the PCI Local Bus spec's BAR encoding (bit 0
+/// selects I/O vs. memory space; for memory BARs, bits `2:1` select the width and bit 3 is the
+/// prefetchable flag).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BarKind {
+    /// An I/O space BAR.
+    Io,
+    /// A memory space BAR occupying a 32-bit address range.
+    Memory32 { prefetchable: bool },
+    /// A memory space BAR occupying a 64-bit address range (paired with the following BAR, which
+    /// holds the upper 32 address bits).
+    Memory64 { prefetchable: bool },
+}
+
+/// Classify a raw BAR value as read out of config space.
+pub fn bar_kind(bar_value: u32) -> BarKind {
+    if bar_value & 0x1 != 0 {
+        return BarKind::Io;
+    }
+
+    let prefetchable = bar_value & 0x8 != 0;
+    match (bar_value >> 1) & 0x3 {
+        0b10 => BarKind::Memory64 { prefetchable },
+        _ => BarKind::Memory32 { prefetchable },
+    }
+}
+
+/// Implemented by whatever can read and write 32-bit PCI config-space dwords, so [`enumerate`]
+/// doesn't need to know whether that's ECAM MMIO, legacy CAM I/O ports, or (in tests) a RAM-backed
+/// fake.
+pub trait ConfigAccess {
+    /// Read the dword at `offset` (must be 4-byte aligned) in `location`'s config space.
+    fn read_u32(&self, location: DeviceLocation, offset: u16) -> u32;
+
+    /// Write `value` to the dword at `offset` (must be 4-byte aligned) in `location`'s config
+    /// space.
+    fn write_u32(&self, location: DeviceLocation, offset: u16, value: u32);
+}
+
+/// The byte offset of `location`'s config space within a PCIe ECAM (Enhanced Configuration Access
+/// Mechanism) region starting at `ecam_base`, per the PCI Express Base Spec's ECAM address
+/// formula. `offset` must be 4-byte aligned and below 4 KiB (ECAM gives each function a full 4 KiB
+/// of config space, versus legacy CAM's 256 bytes).
+pub fn ecam_address(ecam_base: usize, location: DeviceLocation, offset: u16) -> usize {
+    debug_assert!(
+        offset.is_multiple_of(4),
+        "config-space offset must be dword-aligned"
+    );
+    debug_assert!(
+        offset < 0x1000,
+        "ECAM gives each function 4 KiB of config space"
+    );
+
+    ecam_base
+        + ((location.bus as usize) << 20)
+        + ((location.device as usize) << 15)
+        + ((location.function as usize) << 12)
+        + offset as usize
+}
+
+const VENDOR_ID_DEVICE_ID_OFFSET: u16 = 0x00;
+const CLASS_CODE_OFFSET: u16 = 0x08;
+const HEADER_TYPE_OFFSET: u16 = 0x0c;
+
+const MAX_BUSES: u8 = 1;
+const MAX_DEVICES_PER_BUS: u8 = 32;
+const MAX_FUNCTIONS_PER_DEVICE: u8 = 8;
+
+/// Walk every bus/device/function [`access`] can reach and call `on_device` for each one whose
+/// vendor ID marks it present.
+///
+/// This is deliberately "minimal", per the request: a flat brute-force scan of bus 0 rather than a
+/// real topology walk that would follow PCI-to-PCI bridges onto the buses behind them. Functions
+/// on bus 0 itself are fully enumerated by this; an endpoint behind a bridge -- even a single
+/// bridge in front of a single endpoint -- sits on the bridge's secondary bus and is not.
+pub fn enumerate(
+    access: &impl ConfigAccess,
+    mut on_device: impl FnMut(DeviceLocation, DeviceIdentity),
+) {
+    for bus in 0..MAX_BUSES {
+        for device in 0..MAX_DEVICES_PER_BUS {
+            let function_0 = DeviceLocation {
+                bus,
+                device,
+                function: 0,
+            };
+            let dword0 = access.read_u32(function_0, VENDOR_ID_DEVICE_ID_OFFSET);
+            if !DeviceIdentity::is_present(dword0) {
+                continue;
+            }
+
+            let identity = read_identity(access, function_0, dword0);
+            let multi_function = identity.is_multi_function();
+            on_device(function_0, identity);
+
+            if !multi_function {
+                continue;
+            }
+
+            for function in 1..MAX_FUNCTIONS_PER_DEVICE {
+                let location = DeviceLocation {
+                    bus,
+                    device,
+                    function,
+                };
+                let dword0 = access.read_u32(location, VENDOR_ID_DEVICE_ID_OFFSET);
+                if !DeviceIdentity::is_present(dword0) {
+                    continue;
+                }
+
+                on_device(location, read_identity(access, location, dword0));
+            }
+        }
+    }
+}
+
+fn read_identity(
+    access: &impl ConfigAccess,
+    location: DeviceLocation,
+    dword0: u32,
+) -> DeviceIdentity {
+    let dword2 = access.read_u32(location, CLASS_CODE_OFFSET);
+    let dword3 = access.read_u32(location, HEADER_TYPE_OFFSET);
+    DeviceIdentity::from_header(dword0, dword2, dword3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A RAM-backed [`ConfigAccess`], standing in for real ECAM MMIO the same way
+    /// [`crate::fs::block_cache`]'s tests stand a RAM-backed fake in for a real
+    /// [`crate::storage::interface::BlockDevice`].
+    struct FakeConfigSpace {
+        // One slot per (device, function); `None` means vendor ID 0xffff (unpopulated).
+        functions: [[Option<(u16, u16, u8, u8, u8)>; 8]; 32],
+    }
+
+    impl FakeConfigSpace {
+        fn empty() -> Self {
+            Self {
+                functions: [[None; 8]; 32],
+            }
+        }
+
+        fn populate(
+            &mut self,
+            device: u8,
+            function: u8,
+            vendor_id: u16,
+            device_id: u16,
+            class_code: u8,
+            subclass: u8,
+            header_type: u8,
+        ) {
+            self.functions[device as usize][function as usize] =
+                Some((vendor_id, device_id, class_code, subclass, header_type));
+        }
+    }
+
+    impl ConfigAccess for FakeConfigSpace {
+        fn read_u32(&self, location: DeviceLocation, offset: u16) -> u32 {
+            let entry = self.functions[location.device as usize][location.function as usize];
+            let Some((vendor_id, device_id, class_code, subclass, header_type)) = entry else {
+                return 0xffff_ffff;
+            };
+
+            match offset {
+                VENDOR_ID_DEVICE_ID_OFFSET => (vendor_id as u32) | ((device_id as u32) << 16),
+                CLASS_CODE_OFFSET => ((subclass as u32) << 16) | ((class_code as u32) << 24),
+                HEADER_TYPE_OFFSET => (header_type as u32) << 16,
+                _ => 0,
+            }
+        }
+
+        fn write_u32(&self, _location: DeviceLocation, _offset: u16, _value: u32) {}
+    }
+
+    #[test]
+    fn ecam_address_matches_the_pcie_formula() {
+        let location = DeviceLocation {
+            bus: 1,
+            device: 2,
+            function: 3,
+        };
+        let addr = ecam_address(0x1_0000_0000, location, 0x10);
+        assert_eq!(
+            addr,
+            0x1_0000_0000 + (1 << 20) + (2 << 15) + (3 << 12) + 0x10
+        );
+    }
+
+    #[test]
+    fn bar_kind_classifies_io_and_memory_bars() {
+        assert_eq!(bar_kind(0x0000_0001), BarKind::Io);
+        assert_eq!(
+            bar_kind(0x0000_0000),
+            BarKind::Memory32 {
+                prefetchable: false
+            }
+        );
+        assert_eq!(
+            bar_kind(0x0000_0008),
+            BarKind::Memory32 { prefetchable: true }
+        );
+        assert_eq!(
+            bar_kind(0x0000_0004),
+            BarKind::Memory64 {
+                prefetchable: false
+            }
+        );
+        assert_eq!(
+            bar_kind(0x0000_000c),
+            BarKind::Memory64 { prefetchable: true }
+        );
+    }
+
+    #[test]
+    fn enumerate_finds_a_single_function_device() {
+        let mut space = FakeConfigSpace::empty();
+        space.populate(5, 0, 0x1106, 0x3483, 0x0c, 0x03, 0x00);
+
+        let mut found = heapless_collect(&space);
+        assert_eq!(found.len(), 1);
+        let (location, identity) = found.remove(0);
+        assert_eq!(location.device, 5);
+        assert_eq!(identity.vendor_id, 0x1106);
+        assert_eq!(identity.device_id, 0x3483);
+    }
+
+    #[test]
+    fn enumerate_skips_unpopulated_slots() {
+        let space = FakeConfigSpace::empty();
+        assert!(heapless_collect(&space).is_empty());
+    }
+
+    #[test]
+    fn enumerate_probes_all_functions_of_a_multi_function_device() {
+        let mut space = FakeConfigSpace::empty();
+        // Header type 0x80 marks function 0 as multi-function.
+        space.populate(5, 0, 0x1106, 0x3483, 0x0c, 0x03, 0x80);
+        space.populate(5, 1, 0x1106, 0x3483, 0x0c, 0x03, 0x00);
+
+        let found = heapless_collect(&space);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0.function, 0);
+        assert_eq!(found[1].0.function, 1);
+    }
+
+    #[test]
+    fn enumerate_does_not_probe_other_functions_of_a_single_function_device() {
+        let mut space = FakeConfigSpace::empty();
+        space.populate(5, 0, 0x1106, 0x3483, 0x0c, 0x03, 0x00);
+        // Populated, but unreachable because function 0 didn't advertise multi-function.
+        space.populate(5, 1, 0x1106, 0x3483, 0x0c, 0x03, 0x00);
+
+        let found = heapless_collect(&space);
+        assert_eq!(found.len(), 1);
+    }
+
+    /// No heap in this fork's kernel build (this crate's tests run on the host, where a `Vec` is
+    /// fine, but [`enumerate`]'s own signature stays `no_std`-friendly via the `FnMut` callback).
+    fn heapless_collect(
+        space: &FakeConfigSpace,
+    ) -> std::vec::Vec<(DeviceLocation, DeviceIdentity)> {
+        let mut found = std::vec::Vec::new();
+        enumerate(space, |location, identity| found.push((location, identity)));
+        found
+    }
+}
@@ -0,0 +1,99 @@
+//! Pin claim tracking and conflict detection for the board's GPIO header.
+//!
+//! [`claim`] is the single choke point [`crate::gpio::pin`] now goes through: every call names
+//! the function it wants the pin for (e.g. `"sclk"`) and the driver claiming it (e.g.
+//! `"spi-bitbang"`), and a second, different-owner claim on an already-claimed pin fails loudly
+//! through [`crate::kassert`] with both claimants named -- under the default
+//! [`crate::kassert::Policy::Panic`] policy, the same way
+//! [`crate::driver::DriverManager::init_drivers`] halts on a driver's `init()` error.
+//!
+//! [`crate::gpio::Pin::set_alt`] can route a pin to one of its six alternate functions now, but
+//! `function` here is still just a caller-chosen label for the pin map -- this module has no
+//! table of which `ALTn` a given peripheral needs on which pin, so it can't cross-check a claim's
+//! `function` string against the alt number a caller actually programs; [`crate::debug_jtag`] is
+//! the first caller to use `set_alt`. And since every consumer of [`crate::gpio::Pin`] in this
+//! fork ([`crate::spi::bitbang`], [`crate::i2c::bitbang`], [`crate::sensors`], `debug_jtag`) is a
+//! library piece a board wires up externally rather than something any in-tree board `driver.rs`
+//! instantiates today, there's no live two-driver conflict for this to catch yet -- but the
+//! moment a board's driver init claims two peripherals onto the same physical pin, this is what
+//! catches it.
+
+use crate::{
+    kassert,
+    synchronization::{Mutex, NullLock},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// One slot per BCM pin number across the boards this fork targets: BCM2711 goes up to GPIO 57,
+/// so 58 slots cover pins 0-57 exactly.
+const NUM_PINS: usize = 58;
+
+#[derive(Copy, Clone)]
+struct Claim {
+    function: &'static str,
+    owner: &'static str,
+}
+
+struct Registry {
+    claims: [Option<Claim>; NUM_PINS],
+}
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static REGISTRY: NullLock<Registry> = NullLock::new(Registry {
+    claims: [None; NUM_PINS],
+});
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Claim `pin` for `function` on behalf of `owner`.
+///
+/// A re-claim by the *same* `owner` is idempotent, since a driver retrying its own init shouldn't
+/// trip this. A claim by a different owner than the one already holding `pin` fails loudly -- see
+/// the module docs.
+///
+/// # Panics
+///
+/// Panics if `pin` is outside the range this board's GPIO controller has.
+pub fn claim(pin: u32, function: &'static str, owner: &'static str) {
+    REGISTRY.lock(|registry| {
+        let slot = registry
+            .claims
+            .get_mut(pin as usize)
+            .unwrap_or_else(|| panic!("pinctrl: pin {} is out of range", pin));
+
+        match slot {
+            Some(existing) if existing.owner != owner => {
+                kassert::kassert!(
+                    false,
+                    "pinctrl",
+                    "pin {} requested by '{}' for '{}' conflicts with existing claim by '{}' for '{}'",
+                    pin,
+                    owner,
+                    function,
+                    existing.owner,
+                    existing.function
+                );
+            }
+            _ => *slot = Some(Claim { function, owner }),
+        }
+    });
+}
+
+/// Call `f` with `(pin, function, owner)` for every currently claimed pin, in pin order.
+pub fn print_map(mut f: impl FnMut(u32, &'static str, &'static str)) {
+    REGISTRY.lock(|registry| {
+        for (pin, claim) in registry.claims.iter().enumerate() {
+            if let Some(claim) = claim {
+                f(pin as u32, claim.function, claim.owner);
+            }
+        }
+    });
+}
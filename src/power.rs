@@ -0,0 +1,92 @@
+//! Peripheral power-domain control via the VideoCore mailbox's power-state tags.
+//!
+//! Only meaningful on real Raspberry Pi hardware -- QEMU's `virt` machine emulates no
+//! VideoCore/mailbox peripheral to back this, so this module only exists under
+//! `bsp_rpi3`/`bsp_rpi4`. See `bsp::device_driver::Power` for the driver itself, and
+//! [`domain`] for the reference-counted API drivers should actually call.
+
+use crate::bsp;
+
+pub mod interface {
+    use super::Domain;
+
+    /// Operations a power-domain controller must implement.
+    pub trait Controller {
+        /// Switch `domain`'s power rail on or off.
+        fn set_power(&self, domain: Domain, on: bool) -> Result<(), &'static str>;
+    }
+}
+
+/// A peripheral power rail the board's power-domain controller can switch.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Domain {
+    /// The SD card host controller.
+    Sd,
+    /// The USB host controller.
+    Usb,
+}
+
+/// Reference-counted power-domain claims.
+///
+/// A peripheral driver's `init()` should [`acquire`](domain::acquire) the domain(s) it needs
+/// instead of calling [`crate::bsp::power::power`]'s controller directly, so that two drivers
+/// sharing a domain (e.g. two USB-backed devices) don't have the first one to shut down turn the
+/// rail off underneath the other. The domain only actually powers down once the last claimant
+/// releases it.
+pub mod domain {
+    use super::{bsp, Domain};
+    use crate::synchronization::{Mutex, NullLock};
+
+    /// Per-domain claim counts.
+    struct Refcounts {
+        sd: u32,
+        usb: u32,
+    }
+
+    impl Refcounts {
+        fn get_mut(&mut self, domain: Domain) -> &mut u32 {
+            match domain {
+                Domain::Sd => &mut self.sd,
+                Domain::Usb => &mut self.usb,
+            }
+        }
+    }
+
+    static REFCOUNTS: NullLock<Refcounts> = NullLock::new(Refcounts { sd: 0, usb: 0 });
+
+    /// Claim `domain`, powering it on if this is the first outstanding claim.
+    pub fn acquire(domain: Domain) -> Result<(), &'static str> {
+        REFCOUNTS.lock(|refcounts| {
+            let count = refcounts.get_mut(domain);
+
+            if *count == 0 {
+                bsp::power::power().set_power(domain, true)?;
+            }
+
+            *count += 1;
+            Ok(())
+        })
+    }
+
+    /// Release a claim on `domain` taken out by [`acquire`], powering it off once no claims
+    /// remain.
+    ///
+    /// A release with no outstanding claim is a no-op, the same way releasing an already-released
+    /// lock would be a bug in the caller rather than something to panic the kernel over.
+    pub fn release(domain: Domain) -> Result<(), &'static str> {
+        REFCOUNTS.lock(|refcounts| {
+            let count = refcounts.get_mut(domain);
+
+            if *count == 0 {
+                return Ok(());
+            }
+
+            *count -= 1;
+            if *count == 0 {
+                bsp::power::power().set_power(domain, false)?;
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,161 @@
+//! Printing.
+
+use crate::{console, synchronization, synchronization::NullLock, time::TimeManager};
+use core::{fmt, time::Duration};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Rough character budget for the console's underlying transport (a PL011 UART at 115200 8N1
+/// moves roughly 11.5 KiB/s); staying noticeably under that during a storm leaves an interrupt
+/// handler or another core headroom to still get a word in, instead of the UART itself becoming
+/// the bottleneck.
+const BUDGET_BYTES_PER_SEC: usize = 8192;
+
+struct RateLimiter {
+    window_start: Duration,
+    bytes_in_window: usize,
+    suppressed: usize,
+}
+
+impl RateLimiter {
+    const fn new() -> Self {
+        Self {
+            window_start: Duration::ZERO,
+            bytes_in_window: 0,
+            suppressed: 0,
+        }
+    }
+}
+
+/// Counts the bytes a `fmt::Arguments` would produce, without writing them anywhere.
+struct ByteCounter(usize);
+
+impl fmt::Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static RATE_LIMITER: NullLock<RateLimiter> = NullLock::new(RateLimiter::new());
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+/// Write `args` to whichever console is currently backing `print!`/`println!`, bypassing the rate
+/// limiter. Used both for output that already cleared the budget check and for the limiter's own
+/// suppression summary.
+fn write_now(channel: console::mux::Channel, args: fmt::Arguments) {
+    #[cfg(feature = "early_console")]
+    if console::early::is_active() {
+        console::early::_print(args);
+        return;
+    }
+
+    console::mux::write_channel(channel, args);
+
+    #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
+    crate::hdmi_console::mirror_shell_bytes(args);
+}
+
+/// Returns `true` if a message of `len` bytes still fits this second's character budget.
+///
+/// Once the budget is exhausted, further messages are dropped until the next one-second window,
+/// which then opens with a one-line summary of how many were suppressed -- enough to notice an
+/// IRQ storm or a chatty driver without losing the rest of the log to it.
+fn rate_limit_allow(len: usize) -> bool {
+    use synchronization::Mutex;
+
+    RATE_LIMITER.lock(|limiter| {
+        let now = crate::time::time_manager().uptime();
+
+        if now.saturating_sub(limiter.window_start) >= Duration::from_secs(1) {
+            let suppressed = limiter.suppressed;
+            limiter.window_start = now;
+            limiter.bytes_in_window = 0;
+            limiter.suppressed = 0;
+
+            if suppressed > 0 {
+                write_now(
+                    console::mux::Channel::Shell,
+                    format_args!("[rate-limit] {} message(s) suppressed\n", suppressed),
+                );
+            }
+        }
+
+        if limiter.bytes_in_window + len > BUDGET_BYTES_PER_SEC {
+            limiter.suppressed += 1;
+            false
+        } else {
+            limiter.bytes_in_window += len;
+
+            crate::kassert::kassert_debug!(
+                limiter.bytes_in_window <= BUDGET_BYTES_PER_SEC,
+                "print",
+                "rate limiter window over budget: {}",
+                limiter.bytes_in_window
+            );
+
+            true
+        }
+    })
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+#[doc(hidden)]
+pub fn _print(args: fmt::Arguments) {
+    let mut counter = ByteCounter(0);
+    let _ = fmt::Write::write_fmt(&mut counter, args);
+
+    if !rate_limit_allow(counter.0) {
+        return;
+    }
+
+    write_now(console::mux::Channel::Shell, args);
+}
+
+/// Like [`_print`], but on [`console::mux::Channel::Klog`] instead of
+/// [`console::mux::Channel::Shell`]. Used solely by [`crate::log::_log`], so that a host
+/// demultiplexing [`console::mux`]'s framed output can tell structured log lines apart from the
+/// interactive shell's own output, even though both still share this module's byte-per-second
+/// budget.
+#[doc(hidden)]
+pub fn _log_print(args: fmt::Arguments) {
+    let mut counter = ByteCounter(0);
+    let _ = fmt::Write::write_fmt(&mut counter, args);
+
+    if !rate_limit_allow(counter.0) {
+        return;
+    }
+
+    write_now(console::mux::Channel::Klog, args);
+}
+
+/// Prints without a newline.
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::print::_print(format_args!($($arg)*))
+    }
+}
+
+/// Prints with a newline.
+#[macro_export]
+macro_rules! println {
+    () => {
+        $crate::print!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::print::_print(format_args!("{}\n", format_args!($($arg)*)))
+    }
+}
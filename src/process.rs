@@ -0,0 +1,36 @@
+//! Process isolation.
+//!
+//! Three things block a real [`spawn_elf`], and only one of them has closed since this module was
+//! first sketched out. [`crate::fs::fat32`] and [`crate::fs::tmpfs`] now exist and can serve real
+//! file bytes, so "no filesystem to load an ELF binary's segments from" is no longer accurate --
+//! but there is still no VFS/mount table to route a `path: &str` through to one of them (see
+//! `crate::fs`'s module docs: every filesystem in this fork is reached by its own concrete type,
+//! not a generic lookup). The other two gaps are unchanged: per-process page tables
+//! (`crate::memory::mmu` only flips an "enabled" flag; it doesn't walk or build translation
+//! tables) and EL0/EL1 privilege separation (`crate::exception`'s own docs: `VBAR_EL1` is never
+//! programmed in this fork, so there is no vector to take a trap from EL0 in the first place).
+//!
+//! Building a page-table walker and an EL0 entry/exit path by hand, with no way to boot this
+//! kernel under emulation from this environment to confirm a translation table or an `ERET` is
+//! actually correct, is exactly the kind of change this fork declines to ship unverified (the
+//! same judgment call as the undocumented-MMIO-sequence drivers under `crate::bsp`). So
+//! [`spawn_elf`] stays parked: closed out against the two real remaining blockers rather than
+//! carrying forward a claim about a filesystem gap that no longer exists.
+
+pub mod signal;
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Load `path` as an ELF binary and schedule it as a fresh process with its own page tables,
+/// file-descriptor table, and thread.
+///
+/// Blocked, not merely unwritten -- see the module docs for the two prerequisites (a page-table
+/// walker and an EL0 entry/exit path) this fork won't hand-roll without a way to verify either
+/// actually works.
+pub fn spawn_elf(path: &str) -> Result<(), &'static str> {
+    let _ = path;
+
+    Err("process: spawn_elf is blocked on per-process page tables and EL0 entry/exit, neither of which this fork can verify without a bootable target")
+}
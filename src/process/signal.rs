@@ -0,0 +1,65 @@
+//! Event/signal delivery to processes.
+//!
+//! Unlike [`super::spawn_elf`], this module isn't blocked on anything: [`post`] and
+//! [`take_pending`] are real today, and [`crate::exception::syscall::dispatch`] already calls
+//! [`take_pending`] on every syscall. Only the "flag checked on syscall return" delivery mode from
+//! this request is implementable, though: redirecting EL0 execution to a registered handler
+//! trampoline needs the EL0 support [`super`] doesn't have yet. There is also no scheduler
+//! multiplexing several processes, so -- like the rest of [`super`] -- this tracks a pending
+//! signal for the one implicit foreground process rather than a real per-process table.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+//--------------------------------------------------------------------------------------------------
+// Private Code
+//--------------------------------------------------------------------------------------------------
+
+/// `0` means "nothing pending"; otherwise holds a [`Signal`] discriminant.
+static PENDING: AtomicU8 = AtomicU8::new(0);
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// An event the kernel can post to a process.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Signal {
+    /// A timer this process was waiting on has expired.
+    TimerExpiry = 1,
+    /// An IRQ the process asked to be forwarded has fired.
+    IrqForward = 2,
+    /// The process should terminate, e.g. in response to Ctrl-C at the console.
+    Kill = 3,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl Signal {
+    const fn from_u8(v: u8) -> Option<Self> {
+        Some(match v {
+            1 => Self::TimerExpiry,
+            2 => Self::IrqForward,
+            3 => Self::Kill,
+            _ => return None,
+        })
+    }
+}
+
+/// Post `signal` to the (one implicit) foreground process.
+///
+/// A later post silently overwrites an undelivered earlier one; there's a single flag, not a
+/// queue, since nothing here needs more than "is something pending" yet.
+pub fn post(signal: Signal) {
+    PENDING.store(signal as u8, Ordering::Relaxed);
+}
+
+/// Take and clear the pending signal, if any.
+///
+/// Meant to be called at the syscall dispatch boundary -- the "flag checked on syscall return"
+/// delivery mode; see the module docs for why there is no handler-trampoline mode yet.
+pub fn take_pending() -> Option<Signal> {
+    Signal::from_u8(PENDING.swap(0, Ordering::Relaxed))
+}
@@ -0,0 +1,222 @@
+//! Sampling profiler: a flat address→count table, toggleable from the shell.
+//!
+//! The request this answers wants a high-frequency timer interrupt capturing the interrupted PC.
+//! Two things stand between this fork and that, both covered in [`crate::exception`]'s module
+//! docs: there is no exception vector table installed at all (`VBAR_EL1` is never programmed), and
+//! the generic timer has no registered IRQ path to fire one through even if there were. There's
+//! nothing to interrupt *from* yet.
+//!
+//! What's real instead is the same "polled from the interactive main loop" substitute
+//! [`crate::exception::asynchronous::IrqMode::Threaded`] and [`crate::led::heartbeat_step`] use
+//! for their own missing hardware drivers: [`sample_tick`] is called once per
+//! [`crate::kernel_main`] loop iteration and records the return address of whatever called it.
+//! Because every call to [`sample_tick`] comes from the same call site, this samples the main loop
+//! itself, not an unpredictable cross-section of kernel execution the way a real timer IRQ
+//! firing on arbitrary code would -- it proves the table, the toggle, and the symbolized dump
+//! end to end, but it is not yet a meaningful profile of where the kernel spends its time. Wiring
+//! a real handler in once a vector table and a generic-timer IRQ exist only needs a different
+//! caller of [`record_sample`]; the storage and reporting side already doesn't care who calls it.
+//!
+//! Samples are symbolized against [`crate::kmod`]'s exported-symbol table (see
+//! [`crate::kmod::resolve_symbol`]) -- the only address→name table this fork has, and already
+//! real for the same reason [`crate::kmod`]'s own module docs give for building it ahead of a
+//! working module loader.
+
+use crate::synchronization::{Mutex, NullLock};
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Distinct sampled addresses tracked at once. Fixed, like every other table in this fork that
+/// would otherwise need a heap to grow -- see [`record_sample`] for what happens once it fills.
+const NUM_SAMPLE_SLOTS: usize = 64;
+
+#[derive(Copy, Clone)]
+struct Sample {
+    addr: usize,
+    count: u32,
+}
+
+struct Profiler {
+    enabled: bool,
+    slots: [Option<Sample>; NUM_SAMPLE_SLOTS],
+    total_samples: u32,
+    /// Samples of a new address recorded after every slot was already claimed by some other
+    /// address. Counted rather than silently dropped, so [`dump`] can tell a caller their profile
+    /// is incomplete instead of reporting a falsely-exhaustive one.
+    dropped_samples: u32,
+}
+
+impl Profiler {
+    const fn new() -> Self {
+        Self {
+            enabled: false,
+            slots: [None; NUM_SAMPLE_SLOTS],
+            total_samples: 0,
+            dropped_samples: 0,
+        }
+    }
+}
+
+static PROFILER: NullLock<Profiler> = NullLock::new(Profiler::new());
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Start recording samples.
+pub fn start() {
+    PROFILER.lock(|p| p.enabled = true);
+}
+
+/// Stop recording samples. Already-recorded samples are left in place; see [`reset`] to clear
+/// them.
+pub fn stop() {
+    PROFILER.lock(|p| p.enabled = false);
+}
+
+/// Whether the profiler is currently recording.
+pub fn is_running() -> bool {
+    PROFILER.lock(|p| p.enabled)
+}
+
+/// Clear every recorded sample and both counters, without changing whether it's running.
+pub fn reset() {
+    PROFILER.lock(|p| {
+        p.slots = [None; NUM_SAMPLE_SLOTS];
+        p.total_samples = 0;
+        p.dropped_samples = 0;
+    });
+}
+
+/// Record one sample at `addr`, if the profiler is currently running.
+///
+/// An `addr` already being tracked has its count incremented (saturating, rather than wrapping,
+/// on overflow). A new `addr` claims a free slot if one is left, or is counted in
+/// [`stats`]'s dropped-sample total otherwise.
+pub fn record_sample(addr: usize) {
+    PROFILER.lock(|p| {
+        if !p.enabled {
+            return;
+        }
+
+        p.total_samples = p.total_samples.saturating_add(1);
+
+        if let Some(sample) = p.slots.iter_mut().flatten().find(|s| s.addr == addr) {
+            sample.count = sample.count.saturating_add(1);
+            return;
+        }
+
+        match p.slots.iter_mut().find(|s| s.is_none()) {
+            Some(slot) => *slot = Some(Sample { addr, count: 1 }),
+            None => p.dropped_samples = p.dropped_samples.saturating_add(1),
+        }
+    });
+}
+
+/// Call `f` with the address and count of every sample currently recorded, in no particular
+/// order.
+pub fn for_each(mut f: impl FnMut(usize, u32)) {
+    PROFILER.lock(|p| {
+        for sample in p.slots.iter().flatten() {
+            f(sample.addr, sample.count);
+        }
+    });
+}
+
+/// `(total samples recorded, samples dropped because every slot was already in use)`.
+pub fn stats() -> (u32, u32) {
+    PROFILER.lock(|p| (p.total_samples, p.dropped_samples))
+}
+
+/// Sample the address [`sample_tick`] was called from -- see the module docs for why that's the
+/// main loop itself today, not an arbitrary interrupted PC.
+///
+/// A no-op if the profiler isn't running, so callers can call this unconditionally every loop
+/// iteration the same way [`crate::jobs::poll_all`] and [`crate::led::heartbeat_step`] are.
+#[cfg(target_arch = "aarch64")]
+pub fn sample_tick() {
+    if !is_running() {
+        return;
+    }
+
+    let lr: usize;
+    // SAFETY: reads a register, doesn't write memory or change flags.
+    unsafe {
+        core::arch::asm!("mov {}, lr", out(reg) lr, options(nomem, nostack, preserves_flags));
+    }
+    record_sample(lr);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Held by each test for its whole body, since they all share [`PROFILER`] and the test
+    /// harness otherwise runs them on parallel threads.
+    static PROFILER_SERIAL: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn records_nothing_while_stopped() {
+        let _guard = PROFILER_SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        reset();
+        record_sample(0x1000);
+        let mut count = 0;
+        for_each(|_, _| count += 1);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn counts_repeated_samples_at_the_same_address() {
+        let _guard = PROFILER_SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        reset();
+        start();
+        record_sample(0x2000);
+        record_sample(0x2000);
+        record_sample(0x2000);
+        stop();
+
+        let mut seen = None;
+        for_each(|addr, count| {
+            if addr == 0x2000 {
+                seen = Some(count);
+            }
+        });
+        assert_eq!(seen, Some(3));
+
+        let (total, dropped) = stats();
+        assert_eq!(total, 3);
+        assert_eq!(dropped, 0);
+    }
+
+    #[test]
+    fn drops_new_addresses_once_every_slot_is_claimed() {
+        let _guard = PROFILER_SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        reset();
+        start();
+        for i in 0..NUM_SAMPLE_SLOTS {
+            record_sample(0x3000 + i);
+        }
+        record_sample(0x3000 + NUM_SAMPLE_SLOTS);
+        stop();
+
+        let (total, dropped) = stats();
+        assert_eq!(total, (NUM_SAMPLE_SLOTS + 1) as u32);
+        assert_eq!(dropped, 1);
+    }
+
+    #[test]
+    fn reset_clears_samples_and_counters() {
+        let _guard = PROFILER_SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        reset();
+        start();
+        record_sample(0x4000);
+        reset();
+
+        let mut count = 0;
+        for_each(|_, _| count += 1);
+        assert_eq!(count, 0);
+        assert_eq!(stats(), (0, 0));
+    }
+}
@@ -0,0 +1,15 @@
+//! Bit-banged environmental sensor drivers.
+//!
+//! Unlike the singleton-accessor peripherals in `bsp::raspberrypi` (mailbox, framebuffer, audio),
+//! these sensors are external components wired to a caller-chosen [`crate::gpio::Pin`], so there's
+//! no single static instance to hand out -- callers construct a driver around whichever pin their
+//! board wiring uses.
+
+pub mod dht22;
+pub mod ds18b20;
+
+/// A sensor that can report ambient temperature.
+pub trait Thermometer {
+    /// Read the current temperature, in degrees Celsius.
+    fn read_temperature_celsius(&self) -> Result<f32, &'static str>;
+}
@@ -0,0 +1,108 @@
+//! DHT22 (AM2302) humidity/temperature sensor.
+//!
+//! Like [`crate::sensors::ds18b20`], the DHT22 shares a single open-drain data line with the host,
+//! but instead of host-timed write/read slots it replies by holding the line at each level for a
+//! duration that encodes the bit -- so reading it means measuring pulse widths against a wall
+//! clock rather than sampling at fixed offsets. [`crate::time::TimeManager::uptime`] is used for
+//! that, with a timeout on every wait so a disconnected or unresponsive sensor can't hang the
+//! caller forever.
+
+use crate::{
+    gpio,
+    sensors::Thermometer,
+    time::{self, TimeManager},
+};
+use core::time::Duration;
+
+/// Bit slots reporting a high-time longer than this are a `1`; shorter is a `0`. The datasheet
+/// specifies ~26-28us for a `0` and ~70us for a `1`, so 40us sits comfortably between them.
+const BIT_THRESHOLD: Duration = Duration::from_micros(40);
+
+/// A DHT22 wired to a single GPIO pin.
+pub struct Dht22 {
+    pin: gpio::Pin,
+}
+
+impl Dht22 {
+    /// Create a driver for a DHT22 wired to `pin`.
+    pub const fn new(pin: gpio::Pin) -> Self {
+        Self { pin }
+    }
+
+    /// Busy-wait until the line reaches `high`, or `timeout` elapses.
+    fn wait_for_level(&self, high: bool, timeout: Duration) -> Result<(), &'static str> {
+        let time = time::time_manager();
+        let start = time.uptime();
+
+        while self.pin.is_high() != high {
+            if time.uptime() - start > timeout {
+                return Err("DHT22: timed out waiting for line transition");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Busy-wait while the line stays at `high`, returning how long it did, or an error if
+    /// `timeout` elapses first.
+    fn measure_level(&self, high: bool, timeout: Duration) -> Result<Duration, &'static str> {
+        let time = time::time_manager();
+        let start = time.uptime();
+
+        while self.pin.is_high() == high {
+            if time.uptime() - start > timeout {
+                return Err("DHT22: timed out waiting for line transition");
+            }
+        }
+
+        Ok(time.uptime() - start)
+    }
+
+    /// Run the host-initiated handshake and read back the sensor's 40-bit reply.
+    fn read_raw(&self) -> Result<[u8; 5], &'static str> {
+        let time = time::time_manager();
+
+        self.pin.drive_low();
+        time.spin_for(Duration::from_millis(2));
+        self.pin.release();
+
+        self.wait_for_level(false, Duration::from_micros(40))?;
+        self.wait_for_level(true, Duration::from_micros(80))?;
+        self.wait_for_level(false, Duration::from_micros(80))?;
+
+        let mut bytes = [0u8; 5];
+        for i in 0..40 {
+            self.wait_for_level(true, Duration::from_micros(65))?;
+            let high_time = self.measure_level(true, Duration::from_micros(90))?;
+
+            if high_time > BIT_THRESHOLD {
+                bytes[i / 8] |= 1 << (7 - (i % 8));
+            }
+        }
+
+        let checksum = bytes[0]
+            .wrapping_add(bytes[1])
+            .wrapping_add(bytes[2])
+            .wrapping_add(bytes[3]);
+        if checksum != bytes[4] {
+            return Err("DHT22: checksum mismatch");
+        }
+
+        Ok(bytes)
+    }
+}
+
+impl Thermometer for Dht22 {
+    fn read_temperature_celsius(&self) -> Result<f32, &'static str> {
+        let bytes = self.read_raw()?;
+
+        let magnitude = (u16::from(bytes[2] & 0x7f) << 8) | u16::from(bytes[3]);
+        let celsius = f32::from(magnitude) / 10.0;
+
+        if bytes[2] & 0x80 != 0 {
+            Ok(-celsius)
+        } else {
+            Ok(celsius)
+        }
+    }
+}
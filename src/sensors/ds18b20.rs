@@ -0,0 +1,116 @@
+//! DS18B20 one-wire digital thermometer.
+//!
+//! The one-wire bus is a single open-drain line with an external pull-up: a device asserts the
+//! bus by driving it low and releases it (goes input) to let the pull-up bring it back high. All
+//! timings below are the standard-speed one-wire slot widths from the DS18B20 datasheet.
+
+use crate::{
+    gpio,
+    sensors::Thermometer,
+    time::{self, TimeManager},
+};
+use core::time::Duration;
+
+/// Skip ROM addressing -- assumes a single device on the bus.
+const CMD_SKIP_ROM: u8 = 0xcc;
+/// Start a temperature conversion.
+const CMD_CONVERT_T: u8 = 0x44;
+/// Read the 9-byte scratchpad (only the first two bytes, the temperature, are used here).
+const CMD_READ_SCRATCHPAD: u8 = 0xbe;
+
+/// A DS18B20 wired to a single GPIO pin.
+pub struct Ds18b20 {
+    pin: gpio::Pin,
+}
+
+impl Ds18b20 {
+    /// Create a driver for a DS18B20 wired to `pin`.
+    pub const fn new(pin: gpio::Pin) -> Self {
+        Self { pin }
+    }
+
+    /// Reset the bus and check for a device's presence pulse.
+    fn reset(&self) -> Result<(), &'static str> {
+        let time = time::time_manager();
+
+        self.pin.drive_low();
+        time.spin_for(Duration::from_micros(480));
+        self.pin.release();
+        time.spin_for(Duration::from_micros(70));
+
+        let present = !self.pin.is_high();
+        time.spin_for(Duration::from_micros(410));
+
+        if present {
+            Ok(())
+        } else {
+            Err("DS18B20: no presence pulse")
+        }
+    }
+
+    fn write_bit(&self, bit: bool) {
+        let time = time::time_manager();
+
+        self.pin.drive_low();
+        if bit {
+            time.spin_for(Duration::from_micros(6));
+            self.pin.release();
+            time.spin_for(Duration::from_micros(64));
+        } else {
+            time.spin_for(Duration::from_micros(60));
+            self.pin.release();
+            time.spin_for(Duration::from_micros(10));
+        }
+    }
+
+    fn read_bit(&self) -> bool {
+        let time = time::time_manager();
+
+        self.pin.drive_low();
+        time.spin_for(Duration::from_micros(2));
+        self.pin.release();
+        time.spin_for(Duration::from_micros(10));
+
+        let bit = self.pin.is_high();
+        time.spin_for(Duration::from_micros(48));
+
+        bit
+    }
+
+    fn write_byte(&self, byte: u8) {
+        for i in 0..8 {
+            self.write_bit((byte >> i) & 1 != 0);
+        }
+    }
+
+    fn read_byte(&self) -> u8 {
+        let mut byte = 0u8;
+        for i in 0..8 {
+            if self.read_bit() {
+                byte |= 1 << i;
+            }
+        }
+        byte
+    }
+}
+
+impl Thermometer for Ds18b20 {
+    fn read_temperature_celsius(&self) -> Result<f32, &'static str> {
+        self.reset()?;
+        self.write_byte(CMD_SKIP_ROM);
+        self.write_byte(CMD_CONVERT_T);
+
+        // The default 12-bit conversion takes up to 750ms.
+        time::time_manager().spin_for(Duration::from_millis(750));
+
+        self.reset()?;
+        self.write_byte(CMD_SKIP_ROM);
+        self.write_byte(CMD_READ_SCRATCHPAD);
+
+        let lsb = self.read_byte();
+        let msb = self.read_byte();
+        let raw = i16::from_le_bytes([lsb, msb]);
+
+        Ok(f32::from(raw) / 16.0)
+    }
+}
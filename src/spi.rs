@@ -0,0 +1,15 @@
+//! SPI bus abstraction.
+//!
+//! As with [`crate::i2c`], there's no hardware SPI controller driver in this fork for
+//! [`bitbang::BitBangSpi`] to fall back from -- it's the only [`interface::SpiBus`]
+//! implementation, constructed directly around whichever GPIO pins a board's wiring uses.
+
+pub mod bitbang;
+
+pub mod interface {
+    /// Operations an SPI master must implement.
+    pub trait SpiBus {
+        /// Simultaneously shift `tx` out and `rx` in. `tx` and `rx` must be the same length.
+        fn transfer(&self, tx: &[u8], rx: &mut [u8]) -> Result<(), &'static str>;
+    }
+}
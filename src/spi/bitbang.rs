@@ -0,0 +1,92 @@
+//! Bit-banged SPI master (mode 0) over GPIO pins.
+//!
+//! As with [`crate::i2c::bitbang`], this is the only backend behind
+//! [`crate::spi::interface::SpiBus`] in this fork -- there's no hardware SPI controller driver to
+//! prefer over it yet.
+
+use crate::{
+    gpio,
+    spi::interface::SpiBus,
+    time::{self, TimeManager},
+};
+use core::time::Duration;
+
+/// Half a clock period at the ~100kHz rate this driver bit-bangs at.
+const HALF_PERIOD: Duration = Duration::from_micros(5);
+
+/// A bit-banged SPI master, mode 0 (clock idle low, data sampled on the rising edge).
+pub struct BitBangSpi {
+    sclk: gpio::Pin,
+    mosi: gpio::Pin,
+    miso: gpio::Pin,
+    cs: Option<gpio::Pin>,
+}
+
+impl BitBangSpi {
+    /// Create a bus over the given pins. `cs`, if given, is driven low for the duration of each
+    /// transfer; pass `None` if chip select is tied low or handled externally.
+    pub fn new(sclk: gpio::Pin, mosi: gpio::Pin, miso: gpio::Pin, cs: Option<gpio::Pin>) -> Self {
+        sclk.set_output();
+        sclk.set_low();
+        mosi.set_output();
+        miso.set_input();
+        if let Some(cs) = cs {
+            cs.set_output();
+            cs.set_high();
+        }
+
+        Self {
+            sclk,
+            mosi,
+            miso,
+            cs,
+        }
+    }
+
+    fn half_delay(&self) {
+        time::time_manager().spin_for(HALF_PERIOD);
+    }
+
+    fn transfer_byte(&self, out: u8) -> u8 {
+        let mut input = 0u8;
+
+        for i in (0..8).rev() {
+            if (out >> i) & 1 != 0 {
+                self.mosi.set_high();
+            } else {
+                self.mosi.set_low();
+            }
+            self.half_delay();
+
+            self.sclk.set_high();
+            input = (input << 1) | u8::from(self.miso.is_high());
+            self.half_delay();
+
+            self.sclk.set_low();
+        }
+
+        input
+    }
+}
+
+impl SpiBus for BitBangSpi {
+    fn transfer(&self, tx: &[u8], rx: &mut [u8]) -> Result<(), &'static str> {
+        if tx.len() != rx.len() {
+            return Err("SPI: tx/rx length mismatch");
+        }
+
+        if let Some(cs) = self.cs {
+            cs.set_low();
+        }
+
+        for (out, input) in tx.iter().zip(rx.iter_mut()) {
+            *input = self.transfer_byte(*out);
+        }
+
+        if let Some(cs) = self.cs {
+            cs.set_high();
+        }
+
+        Ok(())
+    }
+}
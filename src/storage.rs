@@ -0,0 +1,167 @@
+//! Block storage.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Block storage interfaces.
+pub mod interface {
+    use core::task::Poll;
+
+    /// The fixed size of a single block, in bytes.
+    ///
+    /// Kept as a single global constant instead of a per-device value because every current and
+    /// planned block backend (SD card, virtio-blk) uses 512-byte sectors, and plumbing a runtime
+    /// value through the whole read/write path buys nothing yet.
+    pub const BLOCK_SIZE: usize = 512;
+
+    /// A single block's worth of bytes.
+    pub type Block = [u8; BLOCK_SIZE];
+
+    /// Implemented by drivers that expose randomly addressable, block-granular storage.
+    ///
+    /// This is the synchronous convenience interface, preserved for callers (the block cache, the
+    /// FAT32 volume) that just want a completed read or write and don't care whether the wait
+    /// happened by blocking or by polling a future -- see [`RawBlockQueue`] for the underlying
+    /// asynchronous request-queue model this is typically built on top of.
+    pub trait BlockDevice {
+        /// The total number of addressable blocks.
+        fn block_count(&self) -> u64;
+
+        /// Read the block at `block_index` into `buf`.
+        fn read_block(&self, block_index: u64, buf: &mut Block) -> Result<(), &'static str>;
+
+        /// Write `buf` to the block at `block_index`.
+        fn write_block(&self, block_index: u64, buf: &Block) -> Result<(), &'static str>;
+    }
+
+    /// Implemented by drivers that can queue a block transfer and be polled for completion
+    /// separately from submitting it, so DMA can overlap with other work instead of blocking the
+    /// caller until the device is done -- the same shape
+    /// [`crate::ipc::Channel::send_async`]/[`crate::console::read_char_async`] give non-blocking
+    /// waits elsewhere in this fork.
+    ///
+    /// There's no task scheduler in this kernel to run a second in-flight request concurrently
+    /// with this one -- [`crate::executor::block_on`] only ever drives a single future to
+    /// completion on the calling context -- so "overlap" here means the caller can do other work
+    /// between submitting a request and it completing, not that multiple requests are serviced at
+    /// once. [`RequestToken`] and [`Self::poll_completion`] still model a real, driver-tracked
+    /// request rather than a synchronous call dressed up as one:
+    /// [`crate::bsp::device_driver::virtio::blk::VirtioBlk`] submits to its virtqueue and returns
+    /// immediately, and only checks the used ring (rather than busy-waiting on it) when polled.
+    pub trait RawBlockQueue {
+        /// The total number of addressable blocks.
+        fn block_count(&self) -> u64;
+
+        /// Queue a read of the block at `block_index` into `buf`, returning a token identifying
+        /// the request.
+        ///
+        /// # Safety
+        ///
+        /// `buf` must not be read, written, or moved until [`Self::poll_completion`] reports the
+        /// returned token's request as [`Poll::Ready`].
+        unsafe fn submit_read(
+            &self,
+            block_index: u64,
+            buf: &mut Block,
+        ) -> Result<super::RequestToken, &'static str>;
+
+        /// Queue a write of `buf` to the block at `block_index`, returning a token identifying
+        /// the request.
+        ///
+        /// # Safety
+        ///
+        /// `buf` must not be read, written, or moved until [`Self::poll_completion`] reports the
+        /// returned token's request as [`Poll::Ready`].
+        unsafe fn submit_write(
+            &self,
+            block_index: u64,
+            buf: &Block,
+        ) -> Result<super::RequestToken, &'static str>;
+
+        /// Non-blocking check for whether `token`'s request has completed.
+        fn poll_completion(&self, token: super::RequestToken) -> Poll<Result<(), &'static str>>;
+    }
+}
+
+/// Identifies one request submitted through a [`interface::RawBlockQueue`], to be handed back to
+/// [`interface::RawBlockQueue::poll_completion`].
+///
+/// Opaque to callers on purpose: a driver with a deeper queue than today's single-in-flight
+/// virtio-blk could pack a descriptor-chain index into it without changing this type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RequestToken(pub usize);
+
+/// The async counterpart to [`interface::BlockDevice::read_block`]. Built by
+/// [`read_block_async`].
+pub struct ReadCompletion<'a> {
+    device: &'a dyn interface::RawBlockQueue,
+    token: RequestToken,
+}
+
+/// The async counterpart to [`interface::BlockDevice::write_block`]. Built by
+/// [`write_block_async`].
+pub struct WriteCompletion<'a> {
+    device: &'a dyn interface::RawBlockQueue,
+    token: RequestToken,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Queue a read of the block at `block_index` into `buf`, returning a future that resolves once
+/// `device` has serviced it, for use with [`crate::executor::block_on`].
+///
+/// # Safety
+///
+/// `buf` must not be read, written, or moved until the returned future resolves -- which, since
+/// nothing in this fork polls a future without eventually driving it to completion, means for as
+/// long as the future is held.
+pub unsafe fn read_block_async<'a>(
+    device: &'a dyn interface::RawBlockQueue,
+    block_index: u64,
+    buf: &'a mut interface::Block,
+) -> Result<ReadCompletion<'a>, &'static str> {
+    let token = unsafe { device.submit_read(block_index, buf)? };
+    Ok(ReadCompletion { device, token })
+}
+
+/// Queue a write of `buf` to the block at `block_index`, returning a future that resolves once
+/// `device` has serviced it, for use with [`crate::executor::block_on`].
+///
+/// # Safety
+///
+/// `buf` must not be read, written, or moved until the returned future resolves -- which, since
+/// nothing in this fork polls a future without eventually driving it to completion, means for as
+/// long as the future is held.
+pub unsafe fn write_block_async<'a>(
+    device: &'a dyn interface::RawBlockQueue,
+    block_index: u64,
+    buf: &'a interface::Block,
+) -> Result<WriteCompletion<'a>, &'static str> {
+    let token = unsafe { device.submit_write(block_index, buf)? };
+    Ok(WriteCompletion { device, token })
+}
+
+impl Future for ReadCompletion<'_> {
+    type Output = Result<(), &'static str>;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.device.poll_completion(self.token)
+    }
+}
+
+impl Future for WriteCompletion<'_> {
+    type Output = Result<(), &'static str>;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.device.poll_completion(self.token)
+    }
+}
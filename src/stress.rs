@@ -0,0 +1,139 @@
+//! A feature-gated stress exerciser for the locking and timer subsystems.
+//!
+//! The request this answers asks for quite a bit more than this fork can actually do, so here is
+//! what's in and why:
+//!
+//! - **No separate `stress` binary.** This is a single-`[[bin]]` crate (see `Cargo.toml`) whose
+//!   one binary owns the boot assembly in [`cpu::boot`](crate::cpu::boot); a second `[[bin]]`
+//!   would need its own copy of that boot path and linker script for no real benefit. Instead,
+//!   this is a normal module gated behind the `stress` feature and wired up as a console command
+//!   in `main.rs`, the same way `lsblk` and `pinmap` are.
+//! - **No cross-core lock contention.** There's no SMP boot path anywhere in
+//!   [`cpu::boot`](crate::cpu::boot) -- only the primary core ever leaves the reset vector, and
+//!   [`NullLock`](crate::synchronization::NullLock)'s own docs already say it's "not suitable for
+//!   multicore contexts". [`lock_churn`] instead hammers a [`NullLock`] from the one core this
+//!   kernel actually runs on, which still catches a critical section that forgets to cover part
+//!   of its invariant -- just not a true cross-core race.
+//! - **No software-injected interrupts.** The GICv3 driver
+//!   ([`bsp::device_driver::arm::gicv3`](crate::bsp::device_driver::arm::gicv3)) only exposes
+//!   enabling a line and reading/acknowledging `IAR1`; nothing here programs `ICC_SGI1R_EL1` to
+//!   raise a software-generated interrupt on demand. [`timer_churn`] gets closest to "interrupts
+//!   at random intervals" with [`time::sleep_async`](crate::time::sleep_async) futures at random
+//!   delays, some run to completion and some polled once and dropped. A `Sleep` only compares
+//!   uptime against its deadline, so no hardware timer interrupt is armed or cancelled by this.
+//! - **No allocator churn.** This kernel has no heap and never pulls in `alloc` (see
+//!   `src/memory.rs`), so there is nothing for an allocator-churn test to exercise. Omitted
+//!   entirely rather than faked.
+//! - **No hardware RNG.** "Random intervals" are produced by [`Lcg`], a small linear-congruential
+//!   generator seeded from [`TimeManager::uptime`](crate::time::TimeManager::uptime) -- good
+//!   enough to avoid falling into a fixed timing pattern, not meant to be statistically strong.
+//!
+//! Failures are reported the same way the rest of the kernel reports them: through
+//! [`kassert::kassert!`](crate::kassert::kassert), so a stress run obeys whatever
+//! [`kassert::Policy`](crate::kassert::Policy) the kernel is currently set to.
+
+use crate::{
+    executor, kassert, log,
+    synchronization::{Mutex, NullLock},
+    time::{self, TimeManager},
+};
+use core::time::Duration;
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A minimal linear-congruential generator, standing in for a hardware RNG this board doesn't
+/// have. Not suitable for anything beyond jittering loop timing.
+struct Lcg(u64);
+
+impl Lcg {
+    fn new(seed: u64) -> Self {
+        // Force the seed odd. (With the `+ 1` increment in `next`, a zero seed would not stick at
+        // zero anyway.)
+        Self(seed | 1)
+    }
+
+    /// Return the next pseudo-random value, and advance the generator.
+    fn next(&mut self) -> u64 {
+        // Constants from Numerical Recipes.
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1);
+        self.0
+    }
+
+    /// Return a pseudo-random duration in `[0, max)`.
+    fn next_duration(&mut self, max: Duration) -> Duration {
+        let max_nanos = max.as_nanos().max(1) as u64;
+        Duration::from_nanos(self.next() % max_nanos)
+    }
+}
+
+static COUNTER: NullLock<u64> = NullLock::new(0);
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Hammer [`COUNTER`] through [`NullLock`], checking that every acquisition is reflected exactly
+/// once. See the module docs for why this is single-core, not cross-core, contention.
+pub fn lock_churn(iterations: usize) {
+    let before = COUNTER.lock(|c| *c);
+
+    for _ in 0..iterations {
+        COUNTER.lock(|c| *c += 1);
+    }
+
+    let after = COUNTER.lock(|c| *c);
+    kassert::kassert!(
+        after == before + iterations as u64,
+        "stress",
+        "lock_churn lost an update: expected {}, got {}",
+        before + iterations as u64,
+        after
+    );
+}
+
+/// Arm and cancel [`time::sleep_async`] futures at pseudo-random intervals, some driven to
+/// completion and some dropped early, to shake out arm/cancel races in the timer and executor.
+pub fn timer_churn(rounds: usize) {
+    let mut rng = Lcg::new(time::time_manager().uptime().as_nanos() as u64);
+    let mut completed = 0;
+
+    for i in 0..rounds {
+        let delay = rng.next_duration(Duration::from_micros(500));
+
+        if i % 2 == 0 {
+            executor::block_on(time::sleep_async(delay));
+            completed += 1;
+        } else {
+            // Poll it once, so it fixes its deadline, then drop it before it resolves -- a cancel
+            // racing the deadline passing.
+            let mut sleep = time::sleep_async(delay);
+            executor::block_on(core::future::poll_fn(|cx| {
+                let _ = core::future::Future::poll(core::pin::Pin::new(&mut sleep), cx);
+                core::task::Poll::Ready(())
+            }));
+        }
+    }
+
+    let expected_completed = (rounds + 1) / 2;
+    kassert::kassert!(
+        completed == expected_completed,
+        "stress",
+        "timer_churn completed an unexpected number of sleeps: expected {}, got {}",
+        expected_completed,
+        completed
+    );
+}
+
+/// Run every stress exerciser once and log a summary. Intended to be invoked from the interactive
+/// console's `stress` command.
+pub fn run() {
+    log::log_info!("stress", "lock_churn: starting");
+    lock_churn(10_000);
+    log::log_info!("stress", "lock_churn: done");
+
+    log::log_info!("stress", "timer_churn: starting");
+    timer_churn(64);
+    log::log_info!("stress", "timer_churn: done");
+}
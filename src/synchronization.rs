@@ -0,0 +1,129 @@
+//! Synchronization primitives.
+//!
+//! # Resources
+//!
+//! - <https://doc.rust-lang.org/book/ch16-04-extensible-concurrency-sync-and-send.html>
+//! - <https://stackoverflow.com/questions/59428096/understanding-the-send-trait>
+//! - <https://doc.rust-lang.org/std/cell/index.html>
+//!
+//! [`PriorityInheritingLock`] is as far as "a sleeping mutex with priority inheritance" can go
+//! here. Priority inversion is a *scheduling* problem -- a high-priority task stuck behind a
+//! low-priority holder never gets the CPU to finish waiting -- and this kernel has no scheduling
+//! to invert: it's single-core (see [`crate::stress`]'s module docs) and strictly cooperative
+//! ([`crate::jobs`] steps every live job once per tick regardless of priority; nothing here ever
+//! blocks a task and context-switches away from it, because nothing here has a task to switch
+//! to -- see [`crate::cpu::context`] for how far register-state capture gets). So there is no
+//! "low-priority holder starves a waiting high-priority task" failure mode to fix by donating
+//! priority, and nothing for a donation to actually speed up. What [`PriorityInheritingLock`]
+//! does instead is the bookkeeping half of PI, honestly: it tracks the highest priority of
+//! anyone currently inside its critical section, for a future real scheduler to read and act on.
+//! It is not, and cannot yet be, a sleeping lock -- [`PriorityInheritingLock::lock`] never blocks,
+//! same as [`NullLock`], because this kernel has nothing to suspend a caller onto while it waits.
+
+use core::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Any object implementing this trait guarantees exclusive access to the data wrapped within
+/// the Mutex for the duration of the provided closure.
+pub trait Mutex {
+    /// The type of the data that is wrapped by this mutex.
+    type Data;
+
+    /// Locks the mutex and grants the closure temporary mutable access.
+    fn lock<'a, R>(&'a self, f: impl FnOnce(&'a mut Self::Data) -> R) -> R;
+}
+
+/// A pseudo-lock that is not suitable for multicore contexts, but as long as the kernel runs
+/// single-threaded on a single core, it fulfils the same purpose while keeping the syntax uniform
+/// for a future switch to `IRQSafeNullLock`.
+pub struct NullLock<T>
+where
+    T: ?Sized,
+{
+    data: UnsafeCell<T>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+unsafe impl<T> Send for NullLock<T> where T: ?Sized + Send {}
+unsafe impl<T> Sync for NullLock<T> where T: ?Sized + Send {}
+
+impl<T> NullLock<T> {
+    /// Create an instance.
+    pub const fn new(data: T) -> Self {
+        Self {
+            data: UnsafeCell::new(data),
+        }
+    }
+}
+
+impl<T> Mutex for NullLock<T> {
+    type Data = T;
+
+    fn lock<'a, R>(&'a self, f: impl FnOnce(&'a mut Self::Data) -> R) -> R {
+        // In a real lock, interrupts would be masked here to make this critical section safe.
+        let data = unsafe { &mut *self.data.get() };
+
+        f(data)
+    }
+}
+
+/// A [`NullLock`] that additionally tracks the highest priority anyone has asked to enter its
+/// critical section at, for a future scheduler to read and act on -- see the module docs for why
+/// it can't yet act on that by actually donating priority or blocking a caller.
+///
+/// Priorities are plain `u8`s, higher meaning more urgent, rather than
+/// [`crate::jobs::Priority`]: this module sits below [`crate::jobs`] in the dependency graph (jobs
+/// already depends on [`Mutex`]/[`NullLock`]), so it can't name a type jobs defines without
+/// inverting that.
+pub struct PriorityInheritingLock<T>
+where
+    T: ?Sized,
+{
+    /// The highest priority passed to [`Self::lock`] that hasn't returned yet. Reset to `0`
+    /// between critical sections -- there is no "default priority" here to fall back to, since
+    /// nothing downstream reads this as anything but a transient high-water mark.
+    ceiling: AtomicU8,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T> Send for PriorityInheritingLock<T> where T: ?Sized + Send {}
+unsafe impl<T> Sync for PriorityInheritingLock<T> where T: ?Sized + Send {}
+
+impl<T> PriorityInheritingLock<T> {
+    /// Create an instance.
+    pub const fn new(data: T) -> Self {
+        Self {
+            ceiling: AtomicU8::new(0),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Enter the critical section as `priority`, record it as this lock's [`Self::ceiling`] for
+    /// the duration, and restore the previous ceiling on the way out.
+    ///
+    /// Never blocks -- see the module docs for why there is nothing here for it to block on.
+    pub fn lock<R>(&self, priority: u8, f: impl FnOnce(&mut T) -> R) -> R {
+        let previous = self.ceiling.fetch_max(priority, Ordering::AcqRel);
+        let data = unsafe { &mut *self.data.get() };
+
+        let result = f(data);
+
+        self.ceiling.store(previous, Ordering::Release);
+
+        result
+    }
+
+    /// The highest priority currently inside [`Self::lock`], or `0` if nothing is.
+    pub fn ceiling(&self) -> u8 {
+        self.ceiling.load(Ordering::Acquire)
+    }
+}
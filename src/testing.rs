@@ -0,0 +1,77 @@
+//! Primitives for a future kernel test harness.
+//!
+//! There is no kernel test harness in this fork for this request to extend: no `#[test_case]`
+//! custom test framework, no `#[kernel_test]` attribute, nothing that collects and runs tests at
+//! all. Building that for real needs a procedural-macro crate to parse a `#[kernel_test(...)]`
+//! attribute -- this is a single `[package]`, not a workspace, so there's nowhere for one to live
+//! without restructuring the project, and a `should_panic` test on a `no_std`/`panic = "abort"`
+//! target has nothing to longjmp back to once [`panic_wait`](crate::panic_wait)'s handler (which
+//! is `-> !`, by definition of `#[panic_handler]`) runs -- there's no way to resume and run the
+//! next test afterwards on real hardware, only a full reset.
+//!
+//! What's provided instead is the hardware-independent pieces a harness would still need on day
+//! one regardless of how test collection and process-per-test isolation eventually get built:
+//! [`Deadline`] is the watchdog-timeout primitive a test runner loop would poll so a hung test
+//! fails instead of wedging CI forever, and [`expect_panic`]/[`take_expected_panic`] is the flag
+//! [`panic_wait`](crate::panic_wait)'s handler already checks for real, so a `should_panic`-style
+//! test at least gets a distinguishable log line instead of being indistinguishable from any other
+//! crash -- even though, per the previous paragraph, nothing can resume execution after it fires.
+
+use crate::time::{self, TimeManager};
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static EXPECTING_PANIC: AtomicBool = AtomicBool::new(false);
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// A point in time a caller hasn't reached yet.
+///
+/// Mirrors [`time::Sleep`](crate::time::Sleep)'s deadline, but as a plain, pollable value instead
+/// of a future -- a test runner loop needs to poll a test's own progress *and* a timeout in the
+/// same loop, which a future-only API can't do without an executor.
+pub struct Deadline {
+    at: Duration,
+}
+
+impl Deadline {
+    /// Create a deadline `timeout` from now.
+    pub fn after(timeout: Duration) -> Self {
+        Self {
+            at: time::time_manager().uptime() + timeout,
+        }
+    }
+
+    /// Whether this deadline has passed.
+    pub fn expired(&self) -> bool {
+        time::time_manager().uptime() >= self.at
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Mark that a panic is expected until the next [`take_expected_panic`] call.
+///
+/// A `should_panic` test would call this immediately before the operation it expects to panic.
+pub fn expect_panic() {
+    EXPECTING_PANIC.store(true, Ordering::Relaxed);
+}
+
+/// Consume the expected-panic flag, returning whether it was set.
+///
+/// Checked by [`panic_wait`](crate::panic_wait)'s handler so its log line can say a panic was
+/// expected instead of reporting it as an unexplained crash. Consuming rather than just reading it
+/// means a second, genuinely unexpected panic later in the same test run still reports as one.
+pub fn take_expected_panic() -> bool {
+    EXPECTING_PANIC.swap(false, Ordering::Relaxed)
+}
@@ -0,0 +1,68 @@
+//! Timer primitives.
+
+pub mod vdso;
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Timer functions.
+pub trait TimeManager {
+    /// The timer's resolution.
+    fn resolution(&self) -> Duration;
+
+    /// The uptime since the system started.
+    fn uptime(&self) -> Duration;
+
+    /// Spin for a given duration.
+    fn spin_for(&self, duration: Duration);
+}
+
+/// A future that resolves once `duration` has elapsed, for use with [`crate::executor::block_on`].
+///
+/// Unlike [`TimeManager::spin_for`], this doesn't busy-spin the core while waiting; the executor
+/// parks it on `wfe` between polls instead. Built by [`sleep_async`].
+pub struct Sleep {
+    duration: Duration,
+    deadline: Option<Duration>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let now = time_manager().uptime();
+        let deadline = *this.deadline.get_or_insert_with(|| now + this.duration);
+
+        if now >= deadline {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Return a reference to the time manager.
+pub fn time_manager() -> &'static impl TimeManager {
+    crate::bsp::cpu::time_manager()
+}
+
+/// The async counterpart to [`TimeManager::spin_for`].
+pub fn sleep_async(duration: Duration) -> Sleep {
+    Sleep {
+        duration,
+        deadline: None,
+    }
+}
@@ -0,0 +1,70 @@
+//! The data a vDSO-style time page would hand a user process, without the page itself.
+//!
+//! The request this answers wants a read-only page mapped into every user process so `gettime`
+//! can read the clock without a syscall. Three things that would take are missing from this fork:
+//! per-process page tables to map the page into in the first place (`crate::memory::mmu` only
+//! flips an "enabled" flag -- see its module docs, which this same backlog entry's
+//! [`crate::memory::AccessPermissions::ReadOnlyEL0`] was added for and still has no walker to
+//! back it), an ELF loader to set up a process's address space at all (`crate::process::spawn_elf`
+//! is blocked on that same page-table walker plus EL0 entry, see its own docs), and a real-time
+//! clock to seed "boot epoch" with (there is no RTC anywhere in this fork;
+//! [`crate::time::TimeManager::uptime`] is time-since-boot, not wall-clock time, so a boot epoch
+//! field can only ever read zero here).
+//!
+//! What isn't missing is the data layout itself: [`VdsoData`] is what the page's bytes would be,
+//! and [`VdsoData::current`] is how the kernel would fill it in, both independent of how (or
+//! whether) a future loader maps them anywhere. A future per-process mapper can wrap this
+//! verbatim; a future user-space `gettime` can read it back exactly as defined here.
+
+use core::time::Duration;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// The contents of a vDSO-style shared time page, in the layout user space would read it in.
+///
+/// `#[repr(C)]` for the same reason [`crate::image_header`] and [`crate::bootselect`] use it on
+/// their own fixed-layout records: this is read by code on the other side of a privilege boundary
+/// that doesn't go through Rust's type system, so the field order and padding need to be pinned
+/// down explicitly rather than left to the compiler's discretion.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VdsoData {
+    /// The generic timer's tick rate, in Hz, i.e. the inverse of
+    /// [`crate::time::TimeManager::resolution`]. User space needs this to turn a raw counter read
+    /// into a [`Duration`] itself, the same conversion [`crate::cpu::read_cycle_counter`]'s own
+    /// callers do today.
+    pub counter_freq_hz: u64,
+    /// Unix time at boot, in seconds. Always `0` in this fork -- there is no RTC anywhere to read
+    /// a real wall-clock time from, so this can only ever mean "unknown", never "1970-01-01".
+    pub boot_epoch_unix_secs: u64,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+impl VdsoData {
+    /// Build the page contents as of right now, from the active board's
+    /// [`crate::time::TimeManager`].
+    pub fn current() -> Self {
+        let resolution = crate::time::time_manager().resolution();
+
+        Self {
+            counter_freq_hz: hz_from_resolution(resolution),
+            boot_epoch_unix_secs: 0,
+        }
+    }
+}
+
+/// Invert a tick's [`Duration`] into a frequency in Hz, saturating to `0` for a zero or
+/// unrepresentable resolution rather than dividing by it.
+fn hz_from_resolution(resolution: Duration) -> u64 {
+    let nanos = resolution.as_nanos();
+    if nanos == 0 {
+        return 0;
+    }
+
+    (1_000_000_000u128 / nanos) as u64
+}
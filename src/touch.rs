@@ -0,0 +1,66 @@
+//! Board-independent touchscreen input events.
+//!
+//! Mirrors [`crate::input`]'s shape for a second input modality: [`TouchEvent`]s land in a bounded
+//! [`ipc::Channel`], and [`poll`] is the one producer, the same way [`input::fill_from_console`] is
+//! [`input::events`]'s only producer. There is no USB host controller or real touch-IRQ line in
+//! this fork -- the VideoCore firmware memory-maps the FT5406 touch controller's state for the ARM
+//! side to read, rather than raising an interrupt on contact -- so [`poll`] has to be driven
+//! periodically by whatever owns the main loop, the same "complete but unwired" situation
+//! [`crate::hdmi_console`]'s module docs describe for the keyboard side: nothing in this tree calls
+//! it yet.
+
+use crate::ipc;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// Touchscreen interfaces.
+pub mod interface {
+    /// Implemented by touch controllers.
+    pub trait TouchController {
+        /// Read the controller's current touch state and call `f` once per reported contact.
+        fn poll(&self, f: &mut dyn FnMut(super::TouchEvent));
+    }
+}
+
+/// The number of pending touch events this fork buffers before a producer starts blocking.
+const CAPACITY: usize = 16;
+
+/// One finger's state, decoded from an FT5406 touch record.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TouchEvent {
+    /// Which of the controller's (up to 10) simultaneous contacts this is.
+    pub id: u8,
+    /// Panel-relative X coordinate, 0..=799 on the official 7" panel.
+    pub x: u16,
+    /// Panel-relative Y coordinate, 0..=479 on the official 7" panel.
+    pub y: u16,
+    /// `false` on the record marking a finger's liftoff; `true` for both the initial contact and
+    /// every "still down" record in between.
+    pub pressed: bool,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Global instances
+//--------------------------------------------------------------------------------------------------
+
+static EVENTS: ipc::Channel<TouchEvent, CAPACITY> = ipc::Channel::new();
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// The system's touch input event queue.
+pub fn events() -> &'static ipc::Channel<TouchEvent, CAPACITY> {
+    &EVENTS
+}
+
+/// Read the board's touch controller's current state and enqueue one [`TouchEvent`] per reported
+/// contact onto [`events`].
+///
+/// Meant to be polled periodically -- see the module docs for why there's no interrupt to drive
+/// this from instead.
+pub fn poll() {
+    crate::bsp::touch::touch_controller().poll(&mut |event| events().send(event));
+}
@@ -0,0 +1,216 @@
+//! A ring-buffered event trace, exportable as Chrome's `trace_event` JSON format for loading into
+//! Perfetto or `chrome://tracing`.
+//!
+//! Every event is recorded as a single self-contained record -- a name, category, core id, start
+//! time, and duration -- rather than as separate "begin" and "end" markers a viewer has to pair up
+//! afterwards. That's a deliberate trade-off against this being a fixed-depth ring buffer, the same
+//! one [`crate::net::sniffer`]'s capture ring makes for frames: once the buffer wraps, the oldest
+//! record is simply gone, and a half-matched "begin" with its "end" already evicted would render as
+//! a timeline entry that never closes. [`record_span`] and [`record_instant`] cover it either way --
+//! an instant event is just a span with zero duration -- so nothing here needs Chrome's paired
+//! `"B"`/`"E"` phases at all; every record uses `"X"` (complete event) or `"i"` (instant).
+//!
+//! Real, already-wired call sites: [`crate::exception::asynchronous::record_irq`] traces every IRQ
+//! dispatch on every interrupt controller this fork has ([`crate::bsp::device_driver::arm::gicv2`],
+//! `gicv3`, and [`crate::bsp::device_driver::bcm::bcm2xxx_interrupt_controller`]);
+//! [`crate::driver::DriverManager::init_drivers`] traces each driver's init as one span, the same
+//! [`Duration`] it already records as `init_duration`; and [`crate::jobs::poll_all`] traces each
+//! background job's step. Nothing plays the "scheduler" role literally -- see [`crate::jobs`]'s own
+//! module doc for why there isn't one -- so the job table's cooperative steps are this trace's
+//! closest equivalent timeline.
+//!
+//! [`upload_tftp`] is the same honest stub [`crate::net::sniffer::upload_tftp`] is, for the same
+//! reason: an RRQ could be sent over [`crate::net::udp`] now, but TFTP (RFC 1350) needs a timer
+//! wheel to retransmit a DATA block whose ACK never comes back, and there isn't one.
+
+use crate::{
+    console,
+    synchronization::{Mutex, NullLock},
+    time::{self, TimeManager},
+};
+use core::time::Duration;
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// How many events the trace ring retains before overwriting the oldest one.
+pub const CAPACITY: usize = 128;
+
+/// The trace ring's current occupancy and lifetime drop count.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TraceStats {
+    pub capacity: usize,
+    pub count: usize,
+    pub dropped: u64,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Private Definitions
+//--------------------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone)]
+struct Event {
+    category: &'static str,
+    name: &'static str,
+    core_id: usize,
+    start: Duration,
+    duration: Duration,
+}
+
+impl Event {
+    const ZERO: Self = Self {
+        category: "",
+        name: "",
+        core_id: 0,
+        start: Duration::ZERO,
+        duration: Duration::ZERO,
+    };
+}
+
+struct Ring {
+    events: [Event; CAPACITY],
+    count: usize,
+    next_slot: usize,
+    dropped: u64,
+}
+
+impl Ring {
+    const fn new() -> Self {
+        Self {
+            events: [Event::ZERO; CAPACITY],
+            count: 0,
+            next_slot: 0,
+            dropped: 0,
+        }
+    }
+
+    fn push(&mut self, event: Event) {
+        let slot = self.next_slot;
+        self.events[slot] = event;
+
+        self.next_slot = (self.next_slot + 1) % CAPACITY;
+        if self.count < CAPACITY {
+            self.count += 1;
+        } else {
+            self.dropped += 1;
+        }
+    }
+
+    /// The slot holding the oldest still-retained event.
+    fn oldest_slot(&self) -> usize {
+        if self.count < CAPACITY {
+            0
+        } else {
+            self.next_slot
+        }
+    }
+
+    /// Visit every retained event, oldest first.
+    fn for_each(&self, mut f: impl FnMut(&Event)) {
+        let start = self.oldest_slot();
+
+        for i in 0..self.count {
+            f(&self.events[(start + i) % CAPACITY]);
+        }
+    }
+}
+
+static RING: NullLock<Ring> = NullLock::new(Ring::new());
+
+//--------------------------------------------------------------------------------------------------
+// Public Code
+//--------------------------------------------------------------------------------------------------
+
+/// Record a completed span: `name` in `category` ran on the current core from `start` to `end`
+/// (both measured as uptime, e.g. from [`crate::time::TimeManager::uptime`]).
+pub fn record_span(category: &'static str, name: &'static str, start: Duration, end: Duration) {
+    let event = Event {
+        category,
+        name,
+        core_id: crate::cpu::core_id(),
+        start,
+        duration: end.saturating_sub(start),
+    };
+
+    RING.lock(|ring| ring.push(event));
+}
+
+/// Record a zero-duration event: `name` in `category` happened on the current core, right now.
+pub fn record_instant(category: &'static str, name: &'static str) {
+    let event = Event {
+        category,
+        name,
+        core_id: crate::cpu::core_id(),
+        start: time::time_manager().uptime(),
+        duration: Duration::ZERO,
+    };
+
+    RING.lock(|ring| ring.push(event));
+}
+
+/// This trace ring's current occupancy and lifetime drop count.
+pub fn stats() -> TraceStats {
+    RING.lock(|ring| TraceStats {
+        capacity: CAPACITY,
+        count: ring.count,
+        dropped: ring.dropped,
+    })
+}
+
+/// Discard all retained events.
+pub fn clear() {
+    RING.lock(|ring| *ring = Ring::new());
+}
+
+/// Serialize the trace ring as a Chrome `trace_event` JSON array and stream it out over `sink`,
+/// oldest event first.
+///
+/// The result is a bare JSON array (the `trace_event` format's simplest valid shape), loadable
+/// directly in Perfetto or `chrome://tracing`.
+pub fn dump_chrome_json(sink: &dyn console::interface::All) {
+    sink.write_fmt(format_args!("[")).ok();
+
+    let mut first = true;
+    RING.lock(|ring| {
+        ring.for_each(|event| {
+            if !first {
+                sink.write_fmt(format_args!(",")).ok();
+            }
+            first = false;
+
+            if event.duration.is_zero() {
+                sink.write_fmt(format_args!(
+                    "{{\"name\":\"{}\",\"cat\":\"{}\",\"ph\":\"i\",\"s\":\"t\",\"ts\":{},\"pid\":0,\"tid\":{}}}",
+                    event.name,
+                    event.category,
+                    event.start.as_micros(),
+                    event.core_id
+                ))
+                .ok();
+            } else {
+                sink.write_fmt(format_args!(
+                    "{{\"name\":\"{}\",\"cat\":\"{}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":0,\"tid\":{}}}",
+                    event.name,
+                    event.category,
+                    event.start.as_micros(),
+                    event.duration.as_micros(),
+                    event.core_id
+                ))
+                .ok();
+            }
+        });
+    });
+
+    sink.write_fmt(format_args!("]\n")).ok();
+}
+
+/// Upload the trace ring to `server` as a `trace_event` JSON file named `filename`, over TFTP.
+///
+/// Always fails today -- see the module docs: an RRQ could be sent over [`crate::net::udp`] now,
+/// but there's no timer wheel to retransmit a DATA block whose ACK never comes back.
+pub fn upload_tftp(server: [u8; 4], filename: &str) -> Result<(), &'static str> {
+    let _ = (server, filename);
+
+    Err("trace: no timer wheel to retransmit a TFTP DATA block with if its ACK never comes back -- see the module docs")
+}
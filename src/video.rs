@@ -0,0 +1,75 @@
+//! Display-mode selection, layered over [`crate::framebuffer`]'s mailbox-negotiated buffer.
+//!
+//! Only meaningful on real Raspberry Pi hardware, for the same reason [`crate::framebuffer`] is --
+//! QEMU's `virt` machine has no VideoCore to back a framebuffer with.
+//!
+//! [`set_mode`] only ever negotiates 32 bits per pixel: [`crate::gfx::Surface`]'s whole pixel
+//! format -- `put_pixel`, `blit`, `draw_text`, all of it -- is written for one packed 32bpp word
+//! per pixel, with no other format this fork understands. A caller asking for anything else gets
+//! an honest error rather than a silently wrong buffer layout.
+//!
+//! The fallback chain below is two tiers deep, not three: the real "get EDID block" mailbox tag
+//! returns a full 128-byte block, but [`bcm2xxx_mailbox`](crate::bsp::device_driver::Mailbox)'s
+//! property-call buffer is sized for the handful of response words every other tag in this fork
+//! needs (see that module's docs on why there's no heap to grow it with), so a genuine EDID read
+//! doesn't fit without enlarging every property call's message, not just this one.
+//! [`edid_preferred_mode`] is kept as a named stage so the chain's shape matches what a real
+//! implementation would look like, but it never has a mode to offer today.
+//!
+//! Mode switches don't reflow [`crate::hdmi_console`]'s text grid -- its `COLS`/`ROWS` are sized
+//! off the boot resolution at compile time, since there's no heap to grow a scrollback buffer
+//! with. A smaller mode just clips instead of crashing, because every draw already goes through
+//! [`crate::gfx::Surface::put_pixel`]'s existing bounds check against the *live* surface; a larger
+//! mode leaves the extra space blank until something redraws into it.
+
+use crate::framebuffer;
+use crate::gfx::Surface;
+use crate::hdmi_console;
+
+/// Resolution to fall back to if every preferred mode negotiation fails -- the one every HDMI
+/// sink is required to support.
+pub const SAFE_WIDTH: u32 = 640;
+pub const SAFE_HEIGHT: u32 = 480;
+
+/// The only pixel depth [`crate::gfx`] knows how to draw into.
+const SUPPORTED_DEPTH: u32 = 32;
+
+/// Ask the display for its EDID-reported preferred resolution.
+///
+/// Always returns `None` today -- see the module docs for why.
+fn edid_preferred_mode() -> Option<(u32, u32)> {
+    None
+}
+
+/// Negotiate `width` x `height` at `depth` bits per pixel with the VideoCore, falling back to the
+/// display's EDID-reported preferred mode and then [`SAFE_WIDTH`]x[`SAFE_HEIGHT`] if that also
+/// fails, then reinitialize [`crate::hdmi_console`] against whatever mode actually won.
+pub fn set_mode(width: u32, height: u32, depth: u32) -> Result<(), &'static str> {
+    if depth != SUPPORTED_DEPTH {
+        return Err("video: only 32 bits per pixel is supported");
+    }
+
+    let candidates = [
+        Some((width, height)),
+        edid_preferred_mode(),
+        Some((SAFE_WIDTH, SAFE_HEIGHT)),
+    ];
+
+    let mut last_err = "video: no candidate mode was attempted";
+    for (w, h) in candidates.into_iter().flatten() {
+        match framebuffer::set_mode(w, h, depth) {
+            Ok(()) => return reset_console(),
+            Err(x) => last_err = x,
+        }
+    }
+
+    Err(last_err)
+}
+
+/// Blank the freshly negotiated back buffer and redraw the active console into it.
+fn reset_console() -> Result<(), &'static str> {
+    let surface = framebuffer::back_buffer().ok_or("video: framebuffer not initialized")?;
+    crate::gfx::fill_rect(&surface, 0, 0, surface.width(), surface.height(), 0);
+
+    hdmi_console::redraw()
+}
@@ -0,0 +1,219 @@
+//! xHCI Transfer Request Blocks and ring management.
+//!
+//! The request asks for "an xHCI driver capable of control and bulk transfers with the existing
+//! usb device model" -- there is no USB device model in this fork for a driver to plug into.
+//! [`crate::input`]'s own docs already say so for the keyboard side ("no USB host controller
+//! driver, so there is no USB HID keyboard to decode scan codes from"), and there's no generic
+//! `usb` module anywhere in this tree defining a device/endpoint abstraction a completed control
+//! or bulk transfer could hand its data to. Even setting that aside, actually talking to the
+//! VL805 needs two things [`crate::pci`] doesn't have yet either: a working PCIe link
+//! ([`crate::bsp::device_driver::bcm::bcm2xxx_pcie::Bcm2711Pcie::bring_up_link`] is an honest
+//! stub) and a BAR allocator (this fork's [`crate::pci::bar_kind`] classifies a BAR's type, but
+//! nothing assigns it an address -- that needs a free-address-range tracker this fork has no
+//! equivalent of outside a handful of fixed SoC peripheral offsets).
+//!
+//! Unlike the BCM2711 PCIe root complex or the BCM EMMC controller, xHCI itself is a public,
+//! vendor-neutral specification (Intel's "Eleven-Chapter" xHCI spec) -- so the wire format below,
+//! the Transfer Request Block layout and the producer-side transfer-ring mechanics
+//! ([`Trb`]/[`Ring`]), is real and spec-derived rather than guessed. What's left out is
+//! everything that needs a live register set to mean anything: the capability/operational
+//! register layout, the controller reset/run sequence, and a slot/endpoint context table, since
+//! there is no MMIO base address to point any of that at until PCIe link-up and BAR assignment
+//! exist. A future driver that has both can build directly on [`Trb`] and [`Ring`] for its
+//! command ring and each endpoint's transfer ring.
+
+//--------------------------------------------------------------------------------------------------
+// Public Definitions
+//--------------------------------------------------------------------------------------------------
+
+/// TRB Type field values (xHCI spec, Table 6-91) this module builds or recognizes.
+pub mod trb_type {
+    pub const NORMAL: u8 = 1;
+    pub const SETUP_STAGE: u8 = 2;
+    pub const LINK: u8 = 6;
+}
+
+/// A single 16-byte Transfer Request Block: a 64-bit parameter field plus two 32-bit status/
+/// control fields, the common shape every TRB type (transfer, command, event) shares.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Trb {
+    pub parameter: u64,
+    pub status: u32,
+    pub control: u32,
+}
+
+impl Trb {
+    const CYCLE_BIT: u32 = 1 << 0;
+    const TOGGLE_CYCLE_BIT: u32 = 1 << 1;
+    const TRB_TYPE_SHIFT: u32 = 10;
+
+    const fn zeroed() -> Self {
+        Self {
+            parameter: 0,
+            status: 0,
+            control: 0,
+        }
+    }
+
+    /// This TRB's Cycle bit, the single bit a ring's producer and consumer compare against the
+    /// ring's own expected cycle state to tell a not-yet-written slot from a stale one left over
+    /// from the ring's previous lap.
+    pub fn cycle_bit(&self) -> bool {
+        self.control & Self::CYCLE_BIT != 0
+    }
+
+    /// This TRB's Type field (bits 15:10 of the control dword).
+    pub fn trb_type(&self) -> u8 {
+        ((self.control >> Self::TRB_TYPE_SHIFT) & 0x3f) as u8
+    }
+}
+
+/// Build a Normal TRB (xHCI spec 6.4.1.1): a bulk/interrupt transfer of `length` bytes out of
+/// `buffer`, with `interrupt_on_completion` requesting a Transfer Event once it's done.
+pub fn normal_trb(buffer: u64, length: u32, interrupt_on_completion: bool) -> Trb {
+    let mut control = (trb_type::NORMAL as u32) << Trb::TRB_TYPE_SHIFT;
+    if interrupt_on_completion {
+        control |= 1 << 5; // Interrupt On Completion (IOC)
+    }
+
+    Trb {
+        parameter: buffer,
+        status: length & 0x1_ffff, // TRB Transfer Length is 17 bits
+        control,
+    }
+}
+
+/// Build a Setup Stage TRB (xHCI spec 6.4.1.2.1): the 8-byte USB Setup packet of a control
+/// transfer, packed into the parameter field exactly as the packet itself is laid out.
+pub fn setup_stage_trb(request_type: u8, request: u8, value: u16, index: u16, length: u16) -> Trb {
+    let parameter = (request_type as u64)
+        | ((request as u64) << 8)
+        | ((value as u64) << 16)
+        | ((index as u64) << 32)
+        | ((length as u64) << 48);
+
+    let control = ((trb_type::SETUP_STAGE as u32) << Trb::TRB_TYPE_SHIFT)
+        | (1 << 6) // Immediate Data (IDT): the parameter field *is* the packet, not a pointer to it
+        | (3 << 16); // Transfer Type: IN Data Stage follows (3); see spec Table 6-4
+
+    Trb {
+        parameter,
+        status: 8, // the Setup packet is always 8 bytes
+        control,
+    }
+}
+
+/// A fixed-capacity, single-segment xHCI ring: the producer-side enqueue logic a command ring or
+/// a transfer ring both use, built around [`Trb::CYCLE_BIT`] the way the spec intends -- software
+/// flips its own notion of the expected cycle state once a Link TRB wraps the ring back to slot 0,
+/// and the controller detects new work the same way, by noticing a TRB's cycle bit no longer
+/// matches what it already consumed. The last slot is reserved for that ring-wrap Link TRB, so a
+/// `Ring<N>` holds `N - 1` usable entries.
+pub struct Ring<const N: usize> {
+    trbs: [Trb; N],
+    enqueue: usize,
+    cycle: bool,
+}
+
+impl<const N: usize> Ring<N> {
+    /// A new ring with its initial cycle state set (per spec, software starts a ring believing in
+    /// Cycle State `1`).
+    pub const fn new() -> Self {
+        Self {
+            trbs: [Trb::zeroed(); N],
+            enqueue: 0,
+            cycle: true,
+        }
+    }
+
+    /// The ring's backing storage, for a driver to hand the physical address of `trbs[0]` to the
+    /// controller as this ring's dequeue pointer (CRCR/endpoint context) once one exists.
+    pub fn trbs(&self) -> &[Trb; N] {
+        &self.trbs
+    }
+
+    /// Write `trb` at the current enqueue pointer with this ring's current cycle bit set (or
+    /// cleared) correctly, advancing past it -- wrapping back to slot 0 via a Link TRB and
+    /// flipping the expected cycle state when the ring is full.
+    pub fn enqueue(&mut self, mut trb: Trb) {
+        if self.cycle {
+            trb.control |= Trb::CYCLE_BIT;
+        } else {
+            trb.control &= !Trb::CYCLE_BIT;
+        }
+        self.trbs[self.enqueue] = trb;
+        self.enqueue += 1;
+
+        if self.enqueue == N - 1 {
+            self.write_link_trb();
+            self.enqueue = 0;
+            self.cycle = !self.cycle;
+        }
+    }
+
+    fn write_link_trb(&mut self) {
+        let mut control = (trb_type::LINK as u32) << Trb::TRB_TYPE_SHIFT;
+        control |= Trb::TOGGLE_CYCLE_BIT;
+        if self.cycle {
+            control |= Trb::CYCLE_BIT;
+        }
+
+        self.trbs[N - 1] = Trb {
+            parameter: 0, // points back at `trbs[0]`; a real driver fills in its own physical base
+            status: 0,
+            control,
+        };
+    }
+}
+
+impl<const N: usize> Default for Ring<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_trb_has_the_right_type_and_length() {
+        let trb = normal_trb(0x1000, 512, true);
+        assert_eq!(trb.trb_type(), trb_type::NORMAL);
+        assert_eq!(trb.status, 512);
+        assert_eq!(trb.parameter, 0x1000);
+    }
+
+    #[test]
+    fn setup_stage_trb_packs_the_usb_setup_packet() {
+        let trb = setup_stage_trb(0x80, 0x06, 0x0100, 0x0000, 18);
+        assert_eq!(trb.parameter & 0xff, 0x80);
+        assert_eq!((trb.parameter >> 8) & 0xff, 0x06);
+        assert_eq!((trb.parameter >> 16) & 0xffff, 0x0100);
+        assert_eq!((trb.parameter >> 48) & 0xffff, 18);
+        assert_eq!(trb.trb_type(), trb_type::SETUP_STAGE);
+    }
+
+    #[test]
+    fn enqueue_sets_the_current_cycle_bit() {
+        let mut ring: Ring<4> = Ring::new();
+        ring.enqueue(normal_trb(0x1000, 64, false));
+        assert!(ring.trbs()[0].cycle_bit());
+    }
+
+    #[test]
+    fn enqueue_wraps_through_a_link_trb_and_flips_the_cycle_state() {
+        let mut ring: Ring<4> = Ring::new();
+        for _ in 0..3 {
+            ring.enqueue(normal_trb(0x1000, 64, false));
+        }
+
+        // The 3rd enqueue (index 2) filled the last usable slot, so a Link TRB landed at index 3.
+        assert_eq!(ring.trbs()[3].trb_type(), trb_type::LINK);
+        assert!(ring.trbs()[3].cycle_bit());
+
+        // The next enqueue starts back at slot 0, using the flipped cycle state.
+        ring.enqueue(normal_trb(0x2000, 32, false));
+        assert!(!ring.trbs()[0].cycle_bit());
+    }
+}